@@ -207,7 +207,7 @@ cmd_start() {
     echo_color $GREEN "✅ Vite is ready"
     
     # Start Tauri with developer-specific name
-    start_named_process "${DEV_PROCESS_PREFIX}.tauri.${DEVELOPER}" "cargo run --no-default-features" "$PROJECT_DIR/src-tauri"
+    start_named_process "${DEV_PROCESS_PREFIX}.tauri.${DEVELOPER}" "cargo run --no-default-features --features debug-tools" "$PROJECT_DIR/src-tauri"
     
     echo_color $PURPLE "🎉 Blink development environment is ready!"
     echo_color $BLUE "📊 Use 'pnpm run dev:status' to check status"
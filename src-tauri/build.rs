@@ -1,3 +1,15 @@
 fn main() {
+    // Short commit hash shown in the About window; "unknown" outside a git checkout
+    let build_hash = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=BLINK_BUILD_HASH={}", build_hash);
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+
     tauri_build::build()
-}
\ No newline at end of file
+}
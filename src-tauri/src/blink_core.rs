@@ -0,0 +1,180 @@
+//! Tauri-free core API for embedding Blink's note storage.
+//!
+//! Everything here works on a notes directory directly, without an `AppHandle`,
+//! managed state or windows, so the same logic can back the desktop app, a CLI,
+//! tests and server builds. Enabled with the `core-api` feature.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::modules::audit_log::{record_audit, AuditAction, AuditEntry, AuditSource};
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::formatting::apply_save_transforms;
+use crate::modules::maintenance::verify_vault_at;
+use crate::modules::metadata_export::note_metadata;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::workspace_settings::effective_config;
+use crate::utils::{generate_slug, generate_unique_slug, uuid_from_slug};
+
+pub use crate::error::{BlinkError, BlinkResult};
+pub use crate::modules::command_manifest::{command_manifest, CommandParam, CommandSpec};
+pub use crate::modules::maintenance::VaultVerifyReport;
+pub use crate::modules::metadata_export::NoteMetadata;
+pub use crate::types::config::{AppConfig, FormattingConfig};
+pub use crate::types::note::{CreateNoteRequest, Note, UpdateNoteRequest};
+
+/// An open notes directory
+pub struct Vault {
+    config: AppConfig,
+    notes_dir: PathBuf,
+    storage: FileNotesStorage,
+    notes: HashMap<String, Note>,
+}
+
+impl Vault {
+    /// Open the vault at `notes_dir` with default settings
+    pub async fn open(notes_dir: impl AsRef<Path>) -> BlinkResult<Self> {
+        let mut config = AppConfig::default();
+        config.storage.notes_directory = Some(notes_dir.as_ref().to_string_lossy().to_string());
+        config.storage.use_custom_directory = true;
+        Self::open_with_config(config).await
+    }
+
+    /// Open the vault described by `config`. Workspace settings stored in the
+    /// vault are applied on top, exactly as in the app.
+    pub async fn open_with_config(config: AppConfig) -> BlinkResult<Self> {
+        let config = effective_config(&config);
+        let notes_dir = get_configured_notes_directory(&config).map_err(BlinkError::Config)?;
+        let storage = FileNotesStorage::new(&config).map_err(BlinkError::Storage)?;
+        let notes = storage.load_notes().await.map_err(BlinkError::Storage)?;
+
+        Ok(Self { config, notes_dir, storage, notes })
+    }
+
+    pub fn notes_dir(&self) -> &Path {
+        &self.notes_dir
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    /// All notes in manual order; notes without a position come last, newest first
+    pub fn list_notes(&self) -> Vec<Note> {
+        let mut notes: Vec<Note> = self.notes.values().cloned().collect();
+        notes.sort_by(crate::types::note::note_order);
+        notes
+    }
+
+    pub fn get_note(&self, id: &str) -> Option<&Note> {
+        self.notes.get(id)
+    }
+
+    /// Create a note at the end of the list
+    pub async fn create_note(&mut self, request: CreateNoteRequest) -> BlinkResult<Note> {
+        self.ensure_writable()?;
+
+        let max_position = self.notes.values().filter_map(|n| n.position).max().unwrap_or(-1);
+        let existing_slugs: HashSet<String> = self.notes.values().map(|n| generate_slug(&n.title)).collect();
+        let slug = generate_unique_slug(&request.title, &existing_slugs);
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let note = Note {
+            id: uuid_from_slug(&slug),
+            title: request.title,
+            content: apply_save_transforms(&request.content, &self.config.formatting),
+            created_at: now.clone(),
+            updated_at: now,
+            tags: request.tags,
+            position: Some(max_position + 1),
+            icon: None,
+        };
+
+        self.storage.save_note(&note).await.map_err(BlinkError::Storage)?;
+        record_audit(&self.config, AuditEntry::new(
+            AuditAction::Create, AuditSource::Api, Some(&note.id), format!("Created \"{}\"", note.title),
+        ));
+        self.notes.insert(note.id.clone(), note.clone());
+        Ok(note)
+    }
+
+    /// Update a note's title, content and/or tags
+    pub async fn update_note(&mut self, id: &str, request: UpdateNoteRequest) -> BlinkResult<Note> {
+        self.ensure_writable()?;
+
+        let formatting = self.config.formatting.clone();
+        let note = self.notes.get_mut(id).ok_or_else(|| BlinkError::NoteNotFound { id: id.to_string() })?;
+        if let Some(title) = request.title {
+            note.title = title;
+        }
+        if let Some(content) = request.content {
+            note.content = apply_save_transforms(&content, &formatting);
+        }
+        if let Some(tags) = request.tags {
+            note.tags = tags;
+        }
+        note.updated_at = chrono::Utc::now().to_rfc3339();
+
+        let updated = note.clone();
+        self.storage.save_note(&updated).await.map_err(BlinkError::Storage)?;
+        record_audit(&self.config, AuditEntry::new(
+            AuditAction::Update, AuditSource::Api, Some(id), format!("Updated \"{}\"", updated.title),
+        ));
+        Ok(updated)
+    }
+
+    /// Delete a note and its file
+    pub async fn delete_note(&mut self, id: &str) -> BlinkResult<()> {
+        self.ensure_writable()?;
+
+        let note = self.notes.remove(id).ok_or_else(|| BlinkError::NoteNotFound { id: id.to_string() })?;
+        self.storage.delete_note(id).await.map_err(BlinkError::Storage)?;
+        record_audit(&self.config, AuditEntry::new(
+            AuditAction::Delete, AuditSource::Api, Some(id), format!("Deleted \"{}\"", note.title),
+        ));
+        Ok(())
+    }
+
+    /// Structured metadata (links, attachments, tags...) for a note
+    pub fn metadata(&self, id: &str) -> Option<NoteMetadata> {
+        self.notes.get(id).map(note_metadata)
+    }
+
+    /// Compare the files on disk with the index
+    pub fn verify(&self) -> BlinkResult<VaultVerifyReport> {
+        verify_vault_at(&self.notes_dir, self.config.storage.follow_symlinks).map_err(BlinkError::Storage)
+    }
+
+    fn ensure_writable(&self) -> BlinkResult<()> {
+        if self.config.read_only {
+            Err(BlinkError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_vault_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut vault = Vault::open(temp_dir.path()).await.unwrap();
+
+        let note = vault.create_note(CreateNoteRequest {
+            title: "Groceries".to_string(),
+            content: "- milk".to_string(),
+            tags: vec!["home".to_string()],
+        }).await.unwrap();
+
+        let reopened = Vault::open(temp_dir.path()).await.unwrap();
+        let loaded = reopened.get_note(&note.id).expect("note persisted");
+        assert_eq!(loaded.content, "- milk");
+
+        vault.delete_note(&note.id).await.unwrap();
+        assert!(vault.get_note(&note.id).is_none());
+    }
+}
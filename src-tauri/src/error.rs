@@ -29,6 +29,18 @@ pub enum BlinkError {
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
     
+    #[error("Vault is open in read-only mode")]
+    ReadOnly,
+    
+    #[error("Confirmation required for '{0}'")]
+    ConfirmationRequired(String),
+    
+    #[error("Offline mode is on; {0} is disabled")]
+    Offline(String),
+    
+    #[error("Note is too large to save ({0}); resend with allowLarge to save it anyway")]
+    NoteTooLarge(String),
+    
     #[error("Menu error: {0}")]
     Menu(String),
     
@@ -1,15 +1,359 @@
 use crate::error::{BlinkError, BlinkResult};
+use crate::modules::database;
+use crate::modules::date_format::format_rfc3339;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::workspace_settings::load_workspace_settings;
+use crate::services::window_service::{set_follow_mode, FOLLOW_WINDOW_LABEL};
+use crate::state::{NotesMenuState, OpenRecentMenuState, RecentNotesState, WindowMenuState};
 use crate::types::{note::Note, window::DetachedWindow};
 use crate::{log_error, log_info};
-use std::collections::HashMap;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use std::collections::{HashMap, HashSet};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::{AppHandle, Manager, Emitter};
 
+/// Recent notes listed in the Notes menu, after pinned ones
+pub const RECENT_NOTES_MENU_LIMIT: usize = 15;
+/// Notes with this tag are always listed in the Notes menu
+pub const PINNED_TAG: &str = "pinned";
+/// Notes listed under a saved filter, unless the filter sets its own limit
+pub const SAVED_FILTER_MENU_LIMIT: usize = 10;
+/// Items above the saved filters: New Note, Open Note…, Open Recent, a
+/// separator, Show Main Window, Toggle Follow Window and another separator
+const NOTES_MENU_HEADER_LEN: usize = 7;
+
+/// One note item in the Notes menu
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotesMenuEntry {
+    pub id: String,
+    pub label: String,
+}
+
+/// A saved filter in the Notes menu, with the notes it matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedFilterMenu {
+    pub name: String,
+    pub entries: Vec<NotesMenuEntry>,
+}
+
+/// The live Notes submenu, kept so it can be updated in place
+pub struct NotesMenu {
+    submenu: Submenu<tauri::Wry>,
+    /// Items below the fixed header and saved filters, in menu order
+    items: Vec<(NotesMenuEntry, MenuItem<tauri::Wry>)>,
+    /// Saved filter submenus, right below the header
+    filters: Vec<(SavedFilterMenu, Submenu<tauri::Wry>)>,
+    filters_separator: Option<PredefinedMenuItem<tauri::Wry>>,
+}
+
+impl NotesMenu {
+    /// Bring the submenu in line with `entries`, touching only what changed
+    pub fn sync(&mut self, app: &AppHandle, entries: Vec<NotesMenuEntry>) -> BlinkResult<()> {
+        let unchanged = self.items
+            .iter()
+            .zip(&entries)
+            .take_while(|((old, _), new)| old.id == new.id)
+            .count();
+
+        for ((old, item), new) in self.items.iter_mut().zip(&entries).take(unchanged) {
+            if old.label != new.label {
+                item.set_text(&new.label).map_err(|e| BlinkError::Menu(e.to_string()))?;
+                old.label = new.label.clone();
+            }
+        }
+        for (_, item) in self.items.drain(unchanged..) {
+            self.submenu.remove(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        for entry in entries.into_iter().skip(unchanged) {
+            let item = MenuItem::with_id(app, &entry.id, &entry.label, true, None::<&str>)
+                .map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.submenu.append(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.items.push((entry, item));
+        }
+        Ok(())
+    }
+
+    /// Replace the saved filter submenus when their names or matches changed
+    pub fn sync_filters(&mut self, app: &AppHandle, filters: Vec<SavedFilterMenu>) -> BlinkResult<()> {
+        if self.filters.len() == filters.len() && self.filters.iter().zip(&filters).all(|((old, _), new)| old == new) {
+            return Ok(());
+        }
+
+        for (_, submenu) in self.filters.drain(..) {
+            self.submenu.remove(&submenu).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        if let Some(separator) = self.filters_separator.take() {
+            self.submenu.remove(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        if filters.is_empty() {
+            return Ok(());
+        }
+
+        for (i, filter) in filters.into_iter().enumerate() {
+            let submenu = Submenu::new(app, &filter.name, true)
+                .map_err(|e| BlinkError::Menu(e.to_string()))?;
+            if filter.entries.is_empty() {
+                let item = MenuItem::new(app, "No Matching Notes", false, None::<&str>)
+                    .map_err(|e| BlinkError::Menu(e.to_string()))?;
+                submenu.append(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+            }
+            for entry in &filter.entries {
+                let item = MenuItem::with_id(app, &entry.id, &entry.label, true, None::<&str>)
+                    .map_err(|e| BlinkError::Menu(e.to_string()))?;
+                submenu.append(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+            }
+            self.submenu.insert(&submenu, NOTES_MENU_HEADER_LEN + i)
+                .map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.filters.push((filter, submenu));
+        }
+
+        let separator = PredefinedMenuItem::separator(app)
+            .map_err(|e| BlinkError::Menu(e.to_string()))?;
+        self.submenu.insert(&separator, NOTES_MENU_HEADER_LEN + self.filters.len())
+            .map_err(|e| BlinkError::Menu(e.to_string()))?;
+        self.filters_separator = Some(separator);
+        Ok(())
+    }
+}
+
+/// The live Open Recent submenu: recently opened notes above "Clear Menu"
+pub struct OpenRecentMenu {
+    submenu: Submenu<tauri::Wry>,
+    items: Vec<(NotesMenuEntry, MenuItem<tauri::Wry>)>,
+    clear_item: MenuItem<tauri::Wry>,
+}
+
+impl OpenRecentMenu {
+    /// Replace the listed notes when they changed. The list is short, so there is nothing to diff.
+    pub fn sync(&mut self, app: &AppHandle, entries: Vec<NotesMenuEntry>) -> BlinkResult<()> {
+        if self.items.len() == entries.len() && self.items.iter().zip(&entries).all(|((old, _), new)| old == new) {
+            return Ok(());
+        }
+
+        for (_, item) in self.items.drain(..) {
+            self.submenu.remove(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        for (i, entry) in entries.into_iter().enumerate() {
+            let item = MenuItem::with_id(app, &entry.id, &entry.label, true, None::<&str>)
+                .map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.submenu.insert(&item, i).map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.items.push((entry, item));
+        }
+        self.clear_item
+            .set_enabled(!self.items.is_empty())
+            .map_err(|e| BlinkError::Menu(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// An open note window listed in the Window menu
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMenuEntry {
+    pub label: String,
+    pub title: String,
+    pub focused: bool,
+}
+
+/// The live Window submenu; its note window items are replaced on every change
+pub struct WindowMenu {
+    submenu: Submenu<tauri::Wry>,
+    items: Vec<CheckMenuItem<tauri::Wry>>,
+    separator: Option<PredefinedMenuItem<tauri::Wry>>,
+}
+
+impl WindowMenu {
+    /// Replace the listed note windows. The list is short, so there is nothing to diff.
+    pub fn sync(&mut self, app: &AppHandle, entries: Vec<WindowMenuEntry>) -> BlinkResult<()> {
+        for item in self.items.drain(..) {
+            self.submenu.remove(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        if let Some(separator) = self.separator.take() {
+            self.submenu.remove(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let separator = PredefinedMenuItem::separator(app)
+            .map_err(|e| BlinkError::Menu(e.to_string()))?;
+        self.submenu.append(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
+        self.separator = Some(separator);
+
+        for entry in entries {
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("focus-window-{}", entry.label),
+                &entry.title,
+                true,
+                entry.focused,
+                None::<&str>,
+            )
+            .map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.submenu.append(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+            self.items.push(item);
+        }
+        Ok(())
+    }
+}
+
+/// Open note windows by title, marking the focused one
+pub fn window_menu_entries(
+    app: &AppHandle,
+    detached_windows: &HashMap<String, DetachedWindow>,
+    notes: &HashMap<String, Note>,
+) -> Vec<WindowMenuEntry> {
+    let mut entries: Vec<WindowMenuEntry> = detached_windows
+        .iter()
+        .filter(|(label, _)| label.starts_with("note-"))
+        .filter_map(|(label, w)| {
+            let window = app.get_webview_window(label)?;
+            let title = notes
+                .get(&w.note_id)
+                .map(|n| n.display_title())
+                .unwrap_or_else(|| "Untitled Note".to_string());
+            Some(WindowMenuEntry {
+                label: label.clone(),
+                title,
+                focused: window.is_focused().unwrap_or(false),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.label.cmp(&b.label)));
+    entries
+}
+
+fn is_pinned(note: &Note) -> bool {
+    note.tags.iter().any(|t| t.trim_start_matches('#').eq_ignore_ascii_case(PINNED_TAG))
+}
+
+/// Pinned notes, then the most recent ones, then "More Notes…" when some are left out.
+/// `recent_ids` comes from the database; notes it doesn't know yet are ordered by `updated_at`.
+pub fn notes_menu_entries(
+    notes: &HashMap<String, Note>,
+    detached_windows: &HashMap<String, DetachedWindow>,
+    recent_ids: &[String],
+) -> Vec<NotesMenuEntry> {
+    let open: HashSet<&str> = detached_windows.values().map(|w| w.note_id.as_str()).collect();
+    let entry = |note: &Note| {
+        let marker = if open.contains(note.id.as_str()) { "•" } else { " " };
+        NotesMenuEntry {
+            id: format!("open-note-{}", note.id),
+            label: format!("{} {}", marker, note.display_title()),
+        }
+    };
+    // Recent notes say when they were last edited
+    let recent_entry = |note: &Note| {
+        let mut e = entry(note);
+        e.label = format!("{} — {}", e.label, format_rfc3339(&note.updated_at, None));
+        e
+    };
+
+    let mut pinned: Vec<&Note> = notes.values().filter(|n| is_pinned(n)).collect();
+    pinned.sort_by(|a, b| a.title.cmp(&b.title));
+
+    let mut by_updated: Vec<&Note> = notes.values().collect();
+    by_updated.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    let mut seen: HashSet<&str> = pinned.iter().map(|n| n.id.as_str()).collect();
+    let recent: Vec<&Note> = recent_ids
+        .iter()
+        .filter_map(|id| notes.get(id))
+        .chain(by_updated)
+        .filter(|n| seen.insert(n.id.as_str()))
+        .take(RECENT_NOTES_MENU_LIMIT)
+        .collect();
+
+    let mut entries: Vec<NotesMenuEntry> = pinned
+        .into_iter()
+        .map(entry)
+        .chain(recent.into_iter().map(recent_entry))
+        .collect();
+    if entries.len() < notes.len() {
+        entries.push(NotesMenuEntry {
+            id: "note-palette-more".to_string(),
+            label: format!("  More Notes… ({})", notes.len() - entries.len()),
+        });
+    }
+    entries
+}
+
+/// Open Recent items for the tracked notes that are still in the vault
+pub fn open_recent_entries(open_recent_ids: &[String], notes: &HashMap<String, Note>) -> Vec<NotesMenuEntry> {
+    open_recent_ids
+        .iter()
+        .filter_map(|id| notes.get(id))
+        .map(|note| NotesMenuEntry {
+            id: format!("open-recent-{}", note.id),
+            label: note.display_title(),
+        })
+        .collect()
+}
+
+/// Menu entries for the notes a saved filter matched, skipping any not loaded
+pub fn saved_filter_menus(results: &[(String, Vec<String>)], notes: &HashMap<String, Note>) -> Vec<SavedFilterMenu> {
+    results
+        .iter()
+        .map(|(name, ids)| SavedFilterMenu {
+            name: name.clone(),
+            entries: ids
+                .iter()
+                .filter_map(|id| notes.get(id))
+                .map(|note| NotesMenuEntry {
+                    id: format!("open-note-{}", note.id),
+                    label: note.display_title(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Each of the vault's saved filters with the IDs of the notes it matches,
+/// queried from the database; empty when there are none or it can't be read
+pub async fn saved_filter_results(app: &AppHandle) -> Vec<(String, Vec<String>)> {
+    let config = app.state::<crate::ConfigState>();
+    let Ok(notes_dir) = get_configured_notes_directory(&*config.lock().await) else {
+        return Vec::new();
+    };
+    let filters = load_workspace_settings(&notes_dir).saved_filters;
+    if filters.is_empty() {
+        return Vec::new();
+    }
+    let Ok(db) = database::initialize_database(&notes_dir) else {
+        return Vec::new();
+    };
+
+    let now = chrono::Utc::now();
+    filters
+        .into_iter()
+        .map(|filter| {
+            let limit = filter.limit.unwrap_or(SAVED_FILTER_MENU_LIMIT);
+            let ids = db.filter_note_ids(&filter, now, limit).unwrap_or_else(|e| {
+                log_error!("MENU", "Saved filter \"{}\" failed: {}", filter.name, e);
+                Vec::new()
+            });
+            (filter.name, ids)
+        })
+        .collect()
+}
+
+/// Most recently updated note IDs from the vault database; empty when it can't be read
+pub async fn recent_note_ids(app: &AppHandle, limit: usize) -> Vec<String> {
+    let config = app.state::<crate::ConfigState>();
+    let notes_dir = get_configured_notes_directory(&*config.lock().await);
+    notes_dir
+        .ok()
+        .and_then(|dir| database::initialize_database(&dir).ok())
+        .and_then(|db| db.get_recent_note_ids(limit).ok())
+        .unwrap_or_default()
+}
+
 /// Build the application menu with all items
 pub fn build_app_menu(
     app: &AppHandle,
     detached_windows: &HashMap<String, DetachedWindow>,
     notes: &HashMap<String, Note>,
+    recent_ids: &[String],
+    open_recent_ids: &[String],
+    filter_results: &[(String, Vec<String>)],
 ) -> BlinkResult<Menu<tauri::Wry>> {
     let menu = Menu::new(app).map_err(|e| BlinkError::Menu(e.to_string()))?;
 
@@ -18,11 +362,16 @@ pub fn build_app_menu(
     // Edit menu
     let edit_menu = build_edit_submenu(app)?;
     // Notes menu
-    let notes_menu = build_notes_submenu(app, detached_windows, notes)?;
+    let notes_menu = build_notes_submenu(
+        app,
+        notes_menu_entries(notes, detached_windows, recent_ids),
+        open_recent_entries(open_recent_ids, notes),
+        saved_filter_menus(filter_results, notes),
+    )?;
     // Developer menu
     let developer_menu = build_developer_submenu(app)?;
     // Window menu
-    let window_menu = build_window_submenu(app)?;
+    let window_menu = build_window_submenu(app, window_menu_entries(app, detached_windows, notes))?;
 
     menu.append(&app_menu)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
@@ -42,10 +391,16 @@ fn build_app_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
     let app_menu = Submenu::new(app, "Blink", true)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     
-    let about_item = MenuItem::new(app, "About Blink", true, None::<&str>)
+    let about_item = MenuItem::with_id(app, "about", "About Blink", true, None::<&str>)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     let separator = PredefinedMenuItem::separator(app)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let settings_item = MenuItem::with_id(app, "settings", "Settings…", true, Some("Cmd+,"))
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let vault_item = MenuItem::with_id(app, "vault-picker", "Switch Vault…", true, None::<&str>)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let settings_separator = PredefinedMenuItem::separator(app)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
     let services_item = MenuItem::new(app, "Services", true, None::<&str>)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     let separator2 = PredefinedMenuItem::separator(app)
@@ -63,6 +418,9 @@ fn build_app_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
 
     app_menu.append(&about_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
     app_menu.append(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    app_menu.append(&settings_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    app_menu.append(&vault_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    app_menu.append(&settings_separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
     app_menu.append(&services_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
     app_menu.append(&separator2).map_err(|e| BlinkError::Menu(e.to_string()))?;
     app_menu.append(&hide_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
@@ -106,55 +464,76 @@ fn build_edit_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
 
 fn build_notes_submenu(
     app: &AppHandle,
-    detached_windows: &HashMap<String, DetachedWindow>,
-    notes: &HashMap<String, Note>,
+    entries: Vec<NotesMenuEntry>,
+    open_recent: Vec<NotesMenuEntry>,
+    filters: Vec<SavedFilterMenu>,
 ) -> BlinkResult<Submenu<tauri::Wry>> {
     let notes_menu = Submenu::new(app, "Notes", true)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     
     let new_note_item = MenuItem::with_id(app, "new-note", "New Note", true, Some("Cmd+Ctrl+Alt+Shift+N"))
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let open_note_item = MenuItem::with_id(app, "note-palette", "Open Note…", true, Some("Cmd+Shift+O"))
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let open_recent_menu = build_open_recent_submenu(app, open_recent)?;
     let separator = PredefinedMenuItem::separator(app)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     let show_main_window_item = MenuItem::with_id(app, "show-main-window", "Show Main Window", true, None::<&str>)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let follow_item = MenuItem::with_id(app, "follow-active-note", "Toggle Follow Window", true, None::<&str>)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
     let separator2 = PredefinedMenuItem::separator(app)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
 
     notes_menu.append(&new_note_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    notes_menu.append(&open_note_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    notes_menu.append(&open_recent_menu).map_err(|e| BlinkError::Menu(e.to_string()))?;
     notes_menu.append(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
     notes_menu.append(&show_main_window_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    notes_menu.append(&follow_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
     notes_menu.append(&separator2).map_err(|e| BlinkError::Menu(e.to_string()))?;
 
-    // Add all notes to the menu
-    let mut notes_vec: Vec<(&String, &Note)> = notes.iter().collect();
-    notes_vec.sort_by(|a, b| match (a.1.position, b.1.position) {
-        (Some(pos_a), Some(pos_b)) => pos_a.cmp(&pos_b),
-        (Some(_), None) => std::cmp::Ordering::Less,
-        (None, Some(_)) => std::cmp::Ordering::Greater,
-        (None, None) => std::cmp::Ordering::Equal,
-    });
-
-    for (note_id, note) in notes_vec.iter() {
-        let is_open = detached_windows.values().any(|w| &w.note_id == *note_id);
-        let title = if note.title.is_empty() {
-            "Untitled Note".to_string()
-        } else {
-            note.title.clone()
-        };
-        let menu_title = if is_open {
-            format!("• {}", title)
-        } else {
-            format!("  {}", title)
-        };
-        let item = MenuItem::with_id(app, format!("open-note-{}", note_id), menu_title, true, None::<&str>)
-            .map_err(|e| BlinkError::Menu(e.to_string()))?;
-        notes_menu.append(&item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    // Saved filters, then pinned and recent notes; the rest are reachable through "Open Note…"
+    let mut live_menu = NotesMenu {
+        submenu: notes_menu.clone(),
+        items: Vec::new(),
+        filters: Vec::new(),
+        filters_separator: None,
+    };
+    live_menu.sync_filters(app, filters)?;
+    live_menu.sync(app, entries)?;
+    if let Some(state) = app.try_state::<NotesMenuState>() {
+        *state.lock().unwrap() = Some(live_menu);
     }
 
     Ok(notes_menu)
 }
 
+fn build_open_recent_submenu(
+    app: &AppHandle,
+    entries: Vec<NotesMenuEntry>,
+) -> BlinkResult<Submenu<tauri::Wry>> {
+    let open_recent_menu = Submenu::new(app, "Open Recent", true)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+
+    let separator = PredefinedMenuItem::separator(app)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+    let clear_item = MenuItem::with_id(app, "clear-recent-notes", "Clear Menu", true, None::<&str>)
+        .map_err(|e| BlinkError::Menu(e.to_string()))?;
+
+    open_recent_menu.append(&separator).map_err(|e| BlinkError::Menu(e.to_string()))?;
+    open_recent_menu.append(&clear_item).map_err(|e| BlinkError::Menu(e.to_string()))?;
+
+    // Notes go above the separator, most recently opened first
+    let mut live_menu = OpenRecentMenu { submenu: open_recent_menu.clone(), items: Vec::new(), clear_item };
+    live_menu.sync(app, entries)?;
+    if let Some(state) = app.try_state::<OpenRecentMenuState>() {
+        *state.lock().unwrap() = Some(live_menu);
+    }
+
+    Ok(open_recent_menu)
+}
+
 fn build_developer_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
     let developer_menu = Submenu::new(app, "Developer", true)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
@@ -176,7 +555,10 @@ fn build_developer_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>>
     Ok(developer_menu)
 }
 
-fn build_window_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
+fn build_window_submenu(
+    app: &AppHandle,
+    entries: Vec<WindowMenuEntry>,
+) -> BlinkResult<Submenu<tauri::Wry>> {
     let window_menu = Submenu::new(app, "Window", true)
         .map_err(|e| BlinkError::Menu(e.to_string()))?;
     
@@ -216,6 +598,13 @@ fn build_window_submenu(app: &AppHandle) -> BlinkResult<Submenu<tauri::Wry>> {
     window_menu.append(&separator3).map_err(|e| BlinkError::Menu(e.to_string()))?;
     window_menu.append(&bring_all_to_front).map_err(|e| BlinkError::Menu(e.to_string()))?;
 
+    // Open note windows go at the end, like the document list in other Mac apps
+    let mut live_menu = WindowMenu { submenu: window_menu.clone(), items: Vec::new(), separator: None };
+    live_menu.sync(app, entries)?;
+    if let Some(state) = app.try_state::<WindowMenuState>() {
+        *state.lock().unwrap() = Some(live_menu);
+    }
+
     Ok(window_menu)
 }
 
@@ -225,22 +614,96 @@ pub async fn update_app_menu(
     detached_windows: tauri::State<'_, crate::state::DetachedWindowsState>,
     notes: tauri::State<'_, crate::state::NotesState>,
 ) -> Result<(), String> {
+    let recent_ids = recent_note_ids(&app, RECENT_NOTES_MENU_LIMIT * 2).await;
+    let filter_results = saved_filter_results(&app).await;
+    let open_recent_ids = open_recent_ids(&app, &recent_ids);
     let windows_lock = detached_windows.lock().await;
     let notes_lock = notes.lock().await;
     
-    let menu = build_app_menu(&app, &*windows_lock, &*notes_lock)
+    // Update the live submenus in place once the menu has been built
+    let notes_menu = app.try_state::<NotesMenuState>();
+    let window_menu = app.try_state::<WindowMenuState>();
+    if let (Some(notes_menu), Some(window_menu)) = (notes_menu, window_menu) {
+        let mut notes_menu = notes_menu.lock().unwrap();
+        let mut window_menu = window_menu.lock().unwrap();
+        if let (Some(notes_menu), Some(window_menu)) = (notes_menu.as_mut(), window_menu.as_mut()) {
+            if let Some(state) = app.try_state::<OpenRecentMenuState>() {
+                if let Some(open_recent) = state.lock().unwrap().as_mut() {
+                    open_recent
+                        .sync(&app, open_recent_entries(&open_recent_ids, &notes_lock))
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            notes_menu
+                .sync_filters(&app, saved_filter_menus(&filter_results, &notes_lock))
+                .map_err(|e| e.to_string())?;
+            notes_menu
+                .sync(&app, notes_menu_entries(&notes_lock, &windows_lock, &recent_ids))
+                .map_err(|e| e.to_string())?;
+            return window_menu
+                .sync(&app, window_menu_entries(&app, &windows_lock, &notes_lock))
+                .map_err(|e| e.to_string());
+        }
+    }
+    
+    let menu = build_app_menu(&app, &*windows_lock, &*notes_lock, &recent_ids, &open_recent_ids, &filter_results)
         .map_err(|e| e.to_string())?;
     app.set_menu(menu).map_err(|e| format!("Failed to update menu: {}", e))?;
     
     Ok(())
 }
 
+/// The Open Recent list, seeding the tracker from the index's recent notes
+/// the first time there are any
+fn open_recent_ids(app: &AppHandle, recent_ids: &[String]) -> Vec<String> {
+    let Some(state) = app.try_state::<RecentNotesState>() else { return Vec::new() };
+    let mut recent = state.lock().unwrap();
+    recent.seed(recent_ids);
+    recent.ids().to_vec()
+}
+
+/// Re-list recently opened notes under Open Recent, e.g. after a note is opened
+pub async fn refresh_open_recent_menu(app: &AppHandle) {
+    let open_recent_ids = match app.try_state::<RecentNotesState>() {
+        Some(state) => state.lock().unwrap().ids().to_vec(),
+        None => return,
+    };
+    let notes = app.state::<crate::state::NotesState>();
+    let entries = open_recent_entries(&open_recent_ids, &*notes.lock().await);
+
+    if let Some(state) = app.try_state::<OpenRecentMenuState>() {
+        if let Some(open_recent) = state.lock().unwrap().as_mut() {
+            if let Err(e) = open_recent.sync(app, entries) {
+                log_error!("MENU", "Failed to refresh Open Recent menu: {}", e);
+            }
+        }
+    }
+}
+
+/// Re-list open note windows in the Window menu, e.g. after focus moves
+pub async fn refresh_window_menu(app: &AppHandle) {
+    let detached_windows = app.state::<crate::state::DetachedWindowsState>();
+    let notes = app.state::<crate::state::NotesState>();
+    let windows_lock = detached_windows.lock().await;
+    let notes_lock = notes.lock().await;
+    let entries = window_menu_entries(app, &windows_lock, &notes_lock);
+
+    if let Some(state) = app.try_state::<WindowMenuState>() {
+        if let Some(window_menu) = state.lock().unwrap().as_mut() {
+            if let Err(e) = window_menu.sync(app, entries) {
+                log_error!("MENU", "Failed to refresh Window menu: {}", e);
+            }
+        }
+    }
+}
+
 /// Handle menu events
 pub fn handle_menu_event(app: &AppHandle, menu_id: &str) {
-    use crate::modules::windows::{force_main_window_visible, create_detached_window};
-    use crate::types::window::CreateDetachedWindowRequest;
-    use crate::DetachedWindowsState;
-    use crate::state::NotesState;
+    use crate::modules::about::open_about_window;
+    use crate::modules::settings_window::open_settings_window;
+    use crate::modules::vault_picker::open_vault_picker;
+    use crate::modules::note_palette::{focus_or_detach_note, open_note_palette};
+    use crate::modules::windows::{force_main_window_visible, reveal_note_in_app};
     
     log_info!("MENU", "Menu event received: {}", menu_id);
 
@@ -249,6 +712,33 @@ pub fn handle_menu_event(app: &AppHandle, menu_id: &str) {
             log_info!("MENU", "Quit menu item selected");
             app.exit(0);
         }
+        "about" => {
+            log_info!("MENU", "About menu item selected");
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open_about_window(app_handle).await {
+                    log_error!("MENU", "❌ Failed to open About window: {}", e);
+                }
+            });
+        }
+        "settings" => {
+            log_info!("MENU", "Settings menu item selected");
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open_settings_window(app_handle, None).await {
+                    log_error!("MENU", "❌ Failed to open settings window: {}", e);
+                }
+            });
+        }
+        "vault-picker" => {
+            log_info!("MENU", "Switch Vault menu item selected");
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open_vault_picker(app_handle).await {
+                    log_error!("MENU", "❌ Failed to open vault picker: {}", e);
+                }
+            });
+        }
         "minimize" => {
             log_info!("MENU", "Minimize menu item selected");
             if let Some(window) = app.get_webview_window("main") {
@@ -306,43 +796,68 @@ pub fn handle_menu_event(app: &AppHandle, menu_id: &str) {
                 }
             });
         }
+        "note-palette" | "note-palette-more" => {
+            log_info!("MENU", "Open Note… menu item selected");
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open_note_palette(app_handle).await {
+                    log_error!("MENU", "❌ Failed to open note palette: {}", e);
+                }
+            });
+        }
+        id if id.starts_with("focus-window-") => {
+            let label = id.strip_prefix("focus-window-").unwrap_or("").to_string();
+            if let Some(window) = app.get_webview_window(&label) {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+                log_info!("MENU", "Focused window {}", label);
+            } else {
+                log_error!("MENU", "❌ Window {} not found", label);
+            }
+
+            // Move the checkmark to the window that now has focus
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                refresh_window_menu(&app_handle).await;
+            });
+        }
+        "follow-active-note" => {
+            let enabled = app.get_webview_window(FOLLOW_WINDOW_LABEL).is_none();
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = set_follow_mode(&app_handle, enabled).await {
+                    log_error!("MENU", "❌ Failed to toggle follow window: {}", e);
+                }
+            });
+        }
+        id if id.starts_with("open-recent-") => {
+            let note_id = id.strip_prefix("open-recent-").unwrap_or("").to_string();
+            let app_handle = app.clone();
+
+            // Same as reveal_note: its window if it has one, otherwise the main list
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = reveal_note_in_app(&app_handle, &note_id).await {
+                    log_error!("MENU", "❌ Failed to reveal recent note {}: {}", note_id, e);
+                }
+            });
+        }
+        "clear-recent-notes" => {
+            log_info!("MENU", "Clear Menu selected, forgetting recent notes");
+            app.state::<RecentNotesState>().lock().unwrap().clear();
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                refresh_open_recent_menu(&app_handle).await;
+            });
+        }
         id if id.starts_with("open-note-") => {
             let note_id = id.strip_prefix("open-note-").unwrap_or("").to_string();
             let app_handle = app.clone();
 
-            // Open the note in a floating window
+            // Focus the note's window, or open it in a floating window
             tauri::async_runtime::spawn(async move {
-                let detached_windows = app_handle.state::<DetachedWindowsState>();
-                let windows_lock = detached_windows.lock().await;
-
-                // Check if window already exists for this note
-                if let Some((window_label, _)) = windows_lock
-                    .iter()
-                    .find(|(_, w)| w.note_id == note_id)
-                {
-                    // Window exists, just focus it
-                    if let Some(window) = app_handle.get_webview_window(window_label) {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
-                } else {
-                    // Create new window
-                    drop(windows_lock);
-                    let notes = app_handle.state::<NotesState>();
-                    let request = CreateDetachedWindowRequest {
-                        note_id: note_id.clone(),
-                        x: None,
-                        y: None,
-                        width: None,
-                        height: None,
-                    };
-                    let _ = create_detached_window(
-                        request,
-                        app_handle.clone(),
-                        detached_windows.clone(),
-                        notes.clone(),
-                    )
-                    .await;
+                if let Err(e) = focus_or_detach_note(&app_handle, note_id).await {
+                    log_error!("MENU", "❌ Failed to open note: {}", e);
                 }
             });
         }
@@ -1,5 +1,6 @@
 pub mod menu_handler;
 pub mod shortcut_handler;
+pub mod window_chord;
 pub mod window_handler;
 
 pub use menu_handler::*;
@@ -1,7 +1,10 @@
 use crate::error::{BlinkError, BlinkResult};
 use crate::types::window::{DetachedWindowsState, ToggleState};
 use crate::{log_debug, log_error, log_info};
-use crate::state::NotesState;
+use crate::services::window_service::WindowService;
+use crate::handlers::window_chord::{handle_chord_key, start_window_chord};
+use crate::modules::reading_mode::handle_reading_key;
+use crate::modules::screen_capture::capture_region_from_shortcut;
 use tauri::{AppHandle, Manager, Emitter};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
@@ -18,6 +21,9 @@ pub fn register_global_shortcuts(app: &AppHandle) -> BlinkResult<()> {
     // Register Hyperkey+B for window chord mode
     register_window_chord_shortcut(app)?;
 
+    // Register Hyperkey+S for screen capture
+    register_screen_capture_shortcut(app)?;
+
     // Register Ctrl+Opt+Shift+1-9 for note deployment
     register_note_deployment_shortcuts(app)?;
 
@@ -99,6 +105,30 @@ fn register_window_chord_shortcut(
     Ok(())
 }
 
+fn register_screen_capture_shortcut(
+    app: &AppHandle,
+) -> BlinkResult<()> {
+    let manager = app.global_shortcut();
+    let hyperkey_s = Shortcut::new(
+        Some(Modifiers::SUPER | Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
+        Code::KeyS,
+    );
+
+    // Unregister if exists
+    let _ = manager.unregister(hyperkey_s.clone());
+
+    manager
+        .register(hyperkey_s)
+        .map_err(|e| BlinkError::GlobalShortcut(format!("Failed to register Hyperkey+S: {}", e)))?;
+
+    log_info!(
+        "STARTUP",
+        "✅ Successfully registered global shortcut: Cmd+Ctrl+Alt+Shift+S (Screen capture)"
+    );
+
+    Ok(())
+}
+
 fn register_note_deployment_shortcuts(
     app: &AppHandle,
 ) -> BlinkResult<()> {
@@ -213,6 +243,16 @@ pub fn handle_global_shortcut(app: &AppHandle, shortcut: &Shortcut, event: Short
         return;
     }
 
+    // Keys captured by an armed Hyperkey+B chord
+    if handle_chord_key(app, shortcut) {
+        return;
+    }
+
+    // Page keys, registered only while a reading window has focus
+    if handle_reading_key(app, shortcut) {
+        return;
+    }
+
     // Define shortcuts for comparison
     let hyperkey_n = Shortcut::new(
         Some(Modifiers::SUPER | Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
@@ -229,6 +269,11 @@ pub fn handle_global_shortcut(app: &AppHandle, shortcut: &Shortcut, event: Short
         Code::KeyB,
     );
 
+    let hyperkey_s = Shortcut::new(
+        Some(Modifiers::SUPER | Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
+        Code::KeyS,
+    );
+
     let simple_shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyN);
 
     log_debug!("SHORTCUT-HANDLER", "Checking which shortcut was pressed...");
@@ -239,6 +284,8 @@ pub fn handle_global_shortcut(app: &AppHandle, shortcut: &Shortcut, event: Short
         handle_hover_mode_shortcut(app);
     } else if shortcut == &hyperkey_b {
         handle_window_chord_shortcut(app);
+    } else if shortcut == &hyperkey_s {
+        handle_screen_capture_shortcut(app);
     } else if shortcut == &simple_shortcut {
         handle_simple_new_note_shortcut(app);
     } else {
@@ -264,7 +311,7 @@ fn handle_new_note_shortcut(app: &AppHandle) {
     }
 }
 
-fn handle_hover_mode_shortcut(app: &AppHandle) {
+pub(crate) fn handle_hover_mode_shortcut(app: &AppHandle) {
     use crate::modules::windows::toggle_all_windows_hover;
 
     log_info!(
@@ -275,10 +322,10 @@ fn handle_hover_mode_shortcut(app: &AppHandle) {
     let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
         let detached_windows = app_handle.state::<DetachedWindowsState>();
-        let notes = app_handle.state::<NotesState>();
+        let window_service = app_handle.state::<WindowService>();
         let toggle_state = app_handle.state::<ToggleState>();
 
-        match toggle_all_windows_hover(app_handle.clone(), detached_windows, notes, toggle_state)
+        match toggle_all_windows_hover(app_handle.clone(), detached_windows, window_service, toggle_state)
             .await
         {
             Ok(visible) => log_info!(
@@ -300,17 +347,15 @@ fn handle_window_chord_shortcut(app: &AppHandle) {
         "SHORTCUT-HANDLER",
         "🔥 HYPERKEY+B TRIGGERED! Entering window chord mode..."
     );
-    match app.emit("chord-window-mode", ()) {
-        Ok(_) => log_info!(
-            "SHORTCUT-HANDLER",
-            "✅ Successfully emitted chord-window-mode event"
-        ),
-        Err(e) => log_error!(
-            "SHORTCUT-HANDLER",
-            "❌ Failed to emit chord-window-mode event: {}",
-            e
-        ),
-    }
+    start_window_chord(app);
+}
+
+fn handle_screen_capture_shortcut(app: &AppHandle) {
+    log_info!(
+        "SHORTCUT-HANDLER",
+        "🔥 HYPERKEY+S TRIGGERED! Capturing a screen region into a new note..."
+    );
+    capture_region_from_shortcut(app);
 }
 
 fn handle_simple_new_note_shortcut(app: &AppHandle) {
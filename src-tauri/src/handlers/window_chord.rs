@@ -0,0 +1,239 @@
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut};
+
+use crate::state::WindowChordState;
+use crate::{log_debug, log_error, log_info};
+
+/// How long the chord waits for its second key
+pub const CHORD_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DIGIT_KEYS: [Code; 9] = [
+    Code::Digit1, Code::Digit2, Code::Digit3, Code::Digit4, Code::Digit5,
+    Code::Digit6, Code::Digit7, Code::Digit8, Code::Digit9,
+];
+
+const NUMPAD_KEYS: [Code; 9] = [
+    Code::Numpad1, Code::Numpad2, Code::Numpad3, Code::Numpad4, Code::Numpad5,
+    Code::Numpad6, Code::Numpad7, Code::Numpad8, Code::Numpad9,
+];
+
+/// What the second key of Hyperkey+B does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordAction {
+    /// Open or focus the window of the Nth note (0-based), like Ctrl+Opt+Shift+N
+    DeployNote(usize),
+    GatherWindows,
+    ToggleHover,
+    Cancel,
+}
+
+impl ChordAction {
+    fn name(&self) -> String {
+        match self {
+            ChordAction::DeployNote(index) => format!("deploy-note-{}", index + 1),
+            ChordAction::GatherWindows => "gather-windows".to_string(),
+            ChordAction::ToggleHover => "toggle-hover".to_string(),
+            ChordAction::Cancel => "cancel".to_string(),
+        }
+    }
+}
+
+/// The action for a key pressed while the chord is armed
+pub fn chord_action(code: Code) -> Option<ChordAction> {
+    if let Some(index) = DIGIT_KEYS.iter().chain(NUMPAD_KEYS.iter()).position(|key| *key == code) {
+        return Some(ChordAction::DeployNote(index % DIGIT_KEYS.len()));
+    }
+    match code {
+        Code::KeyG => Some(ChordAction::GatherWindows),
+        Code::KeyH => Some(ChordAction::ToggleHover),
+        Code::Escape => Some(ChordAction::Cancel),
+        _ => None,
+    }
+}
+
+/// Keys registered as global shortcuts, without modifiers, only while armed
+fn chord_keys() -> Vec<Code> {
+    DIGIT_KEYS
+        .iter()
+        .chain(NUMPAD_KEYS.iter())
+        .copied()
+        .chain([Code::KeyG, Code::KeyH, Code::Escape])
+        .collect()
+}
+
+/// Idle, or armed and waiting for a key. Each arming gets a new generation so
+/// the timeout of an earlier chord can't end a later one.
+#[derive(Debug, Default)]
+pub struct WindowChord {
+    armed: Option<u64>,
+    generation: u64,
+}
+
+impl WindowChord {
+    /// Start waiting for a key. Returns the generation to expire later, or
+    /// `None` when a chord is already waiting.
+    pub fn arm(&mut self) -> Option<u64> {
+        if self.armed.is_some() {
+            return None;
+        }
+        self.generation += 1;
+        self.armed = Some(self.generation);
+        Some(self.generation)
+    }
+
+    /// A key arrived. Returns false when nothing was waiting for it.
+    pub fn complete(&mut self) -> bool {
+        self.armed.take().is_some()
+    }
+
+    /// The timeout fired. Returns false when that chord already finished.
+    pub fn expire(&mut self, generation: u64) -> bool {
+        if self.armed == Some(generation) {
+            self.armed = None;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChordPhase {
+    Armed,
+    Completed,
+    Cancelled,
+    TimedOut,
+}
+
+/// Payload of `window-chord`, so the frontend can show and hide its hint
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowChordEvent {
+    pub phase: ChordPhase,
+    pub action: Option<String>,
+    pub timeout_ms: u64,
+}
+
+fn emit_chord(app: &AppHandle, phase: ChordPhase, action: Option<ChordAction>) {
+    let payload = WindowChordEvent {
+        phase,
+        action: action.map(|a| a.name()),
+        timeout_ms: CHORD_TIMEOUT.as_millis() as u64,
+    };
+    if let Err(e) = app.emit("window-chord", &payload) {
+        log_error!("SHORTCUT", "Failed to emit window-chord event: {}", e);
+    }
+}
+
+fn register_chord_keys(app: &AppHandle) {
+    let manager = app.global_shortcut();
+    for code in chord_keys() {
+        if let Err(e) = manager.register(Shortcut::new(None, code)) {
+            log_debug!("SHORTCUT", "Could not capture {:?} for the window chord: {}", code, e);
+        }
+    }
+}
+
+fn unregister_chord_keys(app: &AppHandle) {
+    let manager = app.global_shortcut();
+    for code in chord_keys() {
+        let _ = manager.unregister(Shortcut::new(None, code));
+    }
+}
+
+/// Hyperkey+B: capture the next key system-wide, so the chord works even when
+/// no Blink window has focus
+pub fn start_window_chord(app: &AppHandle) {
+    let generation = app.state::<WindowChordState>().lock().unwrap().arm();
+    let Some(generation) = generation else {
+        log_debug!("SHORTCUT", "Window chord already waiting for a key");
+        return;
+    };
+
+    register_chord_keys(app);
+    emit_chord(app, ChordPhase::Armed, None);
+    log_info!("SHORTCUT", "Window chord armed, waiting {}s for a key", CHORD_TIMEOUT.as_secs());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(CHORD_TIMEOUT).await;
+        let expired = app.state::<WindowChordState>().lock().unwrap().expire(generation);
+        if expired {
+            unregister_chord_keys(&app);
+            emit_chord(&app, ChordPhase::TimedOut, None);
+            log_info!("SHORTCUT", "Window chord timed out");
+        }
+    });
+}
+
+/// Handle a key captured by an armed chord. Returns false for any other shortcut.
+pub fn handle_chord_key(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    if !shortcut.mods.is_empty() {
+        return false;
+    }
+    let Some(action) = chord_action(shortcut.key) else { return false };
+    if !app.state::<WindowChordState>().lock().unwrap().complete() {
+        return false;
+    }
+    unregister_chord_keys(app);
+    log_info!("SHORTCUT", "Window chord: {}", action.name());
+
+    match action {
+        ChordAction::DeployNote(index) => {
+            if let Err(e) = app.emit("deploy-note-window", index) {
+                log_error!("SHORTCUT", "Failed to emit deploy-note-window event: {}", e);
+            }
+        }
+        ChordAction::GatherWindows => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::modules::windows::gather_all_windows_to_main_screen(app).await {
+                    log_error!("SHORTCUT", "Failed to gather windows: {}", e);
+                }
+            });
+        }
+        ChordAction::ToggleHover => crate::handlers::shortcut_handler::handle_hover_mode_shortcut(app),
+        ChordAction::Cancel => {
+            emit_chord(app, ChordPhase::Cancelled, None);
+            return true;
+        }
+    }
+    emit_chord(app, ChordPhase::Completed, Some(action));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chord_action() {
+        assert_eq!(chord_action(Code::Digit1), Some(ChordAction::DeployNote(0)));
+        assert_eq!(chord_action(Code::Numpad9), Some(ChordAction::DeployNote(8)));
+        assert_eq!(chord_action(Code::KeyG), Some(ChordAction::GatherWindows));
+        assert_eq!(chord_action(Code::KeyH), Some(ChordAction::ToggleHover));
+        assert_eq!(chord_action(Code::Escape), Some(ChordAction::Cancel));
+        assert_eq!(chord_action(Code::Digit0), None);
+        assert!(chord_keys().into_iter().all(|code| chord_action(code).is_some()));
+    }
+
+    #[test]
+    fn test_chord_state_machine() {
+        let mut chord = WindowChord::default();
+        assert!(!chord.complete());
+
+        let first = chord.arm().unwrap();
+        assert_eq!(chord.arm(), None);
+        assert!(chord.complete());
+        assert!(!chord.complete());
+
+        // The first chord's timeout must not end the second
+        let second = chord.arm().unwrap();
+        assert!(!chord.expire(first));
+        assert!(chord.expire(second));
+        assert!(!chord.complete());
+    }
+}
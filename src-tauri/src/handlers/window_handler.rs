@@ -5,11 +5,8 @@ use std::collections::HashMap;
 use std::path::Path;
 use tauri::{AppHandle, Manager};
 
-/// Load spatial positioning data for a specific note
-pub async fn load_spatial_data(note_id: &str) -> Option<DetachedWindow> {
-    use crate::modules::storage::get_default_notes_directory;
-    
-    let notes_dir = get_default_notes_directory().ok()?;
+/// Load spatial positioning data for a specific note from the vault at `notes_dir`
+pub async fn load_spatial_data(notes_dir: &Path, note_id: &str) -> Option<DetachedWindow> {
     let spatial_file = notes_dir.join("spatial_positions.json");
     
     if !spatial_file.exists() {
@@ -22,13 +19,8 @@ pub async fn load_spatial_data(note_id: &str) -> Option<DetachedWindow> {
     spatial_data.get(note_id).cloned()
 }
 
-/// Save spatial positioning data for a specific note
-#[allow(dead_code)]
-pub async fn save_spatial_data(note_id: &str, window: &DetachedWindow) -> BlinkResult<()> {
-    use crate::modules::storage::get_default_notes_directory;
-    
-    let notes_dir = get_default_notes_directory()
-        .map_err(|e| BlinkError::Storage(format!("Failed to get notes directory: {}", e)))?;
+/// Save spatial positioning data for a specific note in the vault at `notes_dir`
+pub async fn save_spatial_data(notes_dir: &Path, note_id: &str, window: &DetachedWindow) -> BlinkResult<()> {
     let spatial_file = notes_dir.join("spatial_positions.json");
     
     // Load existing spatial data
@@ -56,10 +48,10 @@ pub async fn save_spatial_data(note_id: &str, window: &DetachedWindow) -> BlinkR
 
 /// Save window position (currently unused - handled by frontend with debouncing)
 #[allow(dead_code)]
-pub async fn save_window_position(note_id: String, x: f64, y: f64) -> BlinkResult<()> {
-    if let Some(mut window_data) = load_spatial_data(&note_id).await {
+pub async fn save_window_position(notes_dir: &Path, note_id: String, x: f64, y: f64) -> BlinkResult<()> {
+    if let Some(mut window_data) = load_spatial_data(notes_dir, &note_id).await {
         window_data.position = (x, y);
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     } else {
         // Create new spatial data if none exists
         let window_data = DetachedWindow {
@@ -71,18 +63,20 @@ pub async fn save_window_position(note_id: String, x: f64, y: f64) -> BlinkResul
             opacity: 1.0,
             is_shaded: false,
             original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
         };
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     }
     Ok(())
 }
 
 /// Save window size (currently unused - handled by frontend with debouncing)
 #[allow(dead_code)]
-pub async fn save_window_size(note_id: String, width: f64, height: f64) -> BlinkResult<()> {
-    if let Some(mut window_data) = load_spatial_data(&note_id).await {
+pub async fn save_window_size(notes_dir: &Path, note_id: String, width: f64, height: f64) -> BlinkResult<()> {
+    if let Some(mut window_data) = load_spatial_data(notes_dir, &note_id).await {
         window_data.size = (width, height);
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     } else {
         // Create new spatial data if none exists
         let window_data = DetachedWindow {
@@ -94,8 +88,10 @@ pub async fn save_window_size(note_id: String, width: f64, height: f64) -> Blink
             opacity: 1.0,
             is_shaded: false,
             original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
         };
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     }
     Ok(())
 }
@@ -110,38 +106,7 @@ pub fn apply_initial_window_settings(app: &AppHandle, config: &crate::types::con
     );
 
     if let Some(window) = app.get_webview_window("main") {
-        log_info!("STARTUP", "🪟 Found main window, forcing it to be visible...");
-
-        // Make sure window is visible
-        if let Err(e) = window.show() {
-            log_error!("STARTUP", "Failed to show window: {}", e);
-        } else {
-            log_info!("STARTUP", "✅ Window.show() called successfully");
-        }
-
-        // Center the window
-        if let Err(e) = window.center() {
-            log_error!("STARTUP", "Failed to center window: {}", e);
-        } else {
-            log_info!("STARTUP", "✅ Window.center() called successfully");
-        }
-
-        // Set proper size (match tauri.conf.json defaults)
-        if let Err(e) = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
-            width: 1000,
-            height: 700,
-        })) {
-            log_error!("STARTUP", "Failed to set window size: {}", e);
-        } else {
-            log_info!("STARTUP", "✅ Window.set_size() called successfully");
-        }
-
-        // Set focus
-        if let Err(e) = window.set_focus() {
-            log_error!("STARTUP", "Failed to set window focus: {}", e);
-        } else {
-            log_info!("STARTUP", "✅ Window.set_focus() called successfully");
-        }
+        // Visibility, size and position are applied by `apply_startup_behavior`
 
         // Set always on top
         if let Err(e) = window.set_always_on_top(config.always_on_top) {
@@ -11,6 +11,9 @@ mod state;
 mod types;
 mod utils;
 
+#[cfg(feature = "core-api")]
+pub mod blink_core;
+
 #[cfg(test)]
 mod tests;
 
@@ -24,7 +27,28 @@ pub use state::{
     ConfigState,
     DetachedWindowsState,
     ToggleState,
+    CursorDeployState,
+    NoteEditLockState,
     ModifiedStateTrackerState,
+    VaultLockState,
+    DragPreviewCacheState,
+    DragSessionState,
+    WindowPoolState,
+    ConfirmationTokenState,
+    UrlMetadataCacheState,
+    NotesMenuState,
+    WindowMenuState,
+    OpenRecentMenuState,
+    AudioCaptureState,
+    WindowRevealState,
+    NoteCommandGuardState,
+    ConfigReloadState,
+    FollowModeState,
+    AutosaveState,
+    UndoHistoryState,
+    WindowChordState,
+    ReadingModeState,
+    RecentNotesState,
 };
 
 // Re-export from modules for backward compatibility
@@ -34,6 +58,59 @@ pub use modules::{
     storage::{get_default_notes_directory, get_configured_notes_directory, 
              get_config, update_config, get_detached_windows},
     windows::*,
+    display_layouts::*,
+    vault_lock::*,
+    maintenance::*,
+    workspace_settings::*,
+    note_locks::*,
+    metadata_export::*,
+    drag_preview::*,
+    drag_session::*,
+    native_drag::*,
+    audit_log::*,
+    confirmation::*,
+    duplicates::*,
+    tag_routing::*,
+    url_metadata::*,
+    network_policy::*,
+    note_palette::*,
+    about::*,
+    settings_window::*,
+    note_counts::*,
+    attachments::*,
+    pasted_images::*,
+    gist::*,
+    ics::*,
+    weekly_review::*,
+    assistant::*,
+    audio_capture::*,
+    note_icons::*,
+    window_dock::*,
+    window_theme::*,
+    debug_tools::*,
+    vault_health::*,
+    date_format::*,
+    config_reload::*,
+    window_capture::*,
+    archive_policy::*,
+    note_styles::*,
+    window_events::*,
+    sticky_notes::*,
+    autosave::*,
+    safe_mode::get_safe_mode,
+    undo::*,
+    reading_mode::{get_reading_page, open_reading_mode},
+    related_notes::get_related_notes,
+    power_mode::{get_power_mode, set_power_mode},
+    screen_capture::capture_screen_to_note,
+    tag_export::export_tagged_notes,
+    find_replace::find_and_replace,
+    annotations::{add_annotation, delete_annotation, list_annotations, resolve_annotation},
+    window_arrangements::{apply_window_arrangement, delete_window_arrangement, list_window_arrangements, save_window_arrangement},
+    vault_picker::{list_recent_vaults, open_vault_picker, set_vault_pinned, switch_vault},
+    normalized_export::export_note_normalized,
+    command_manifest::get_command_manifest,
+    window_commands::{get_follow_active_note, set_follow_active_note},
     file_operations::*,
     system_commands::*,
     test_commands::*,
@@ -78,44 +155,11 @@ async fn reregister_global_shortcuts(app: tauri::AppHandle) -> Result<String, St
     }
 }
 
-// Main entry point
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    use modules::logging::init_file_logging;
-    use modules::modified_state_tracker::ModifiedStateTracker;
-    use startup::{setup_app, build_shortcut_handler, build_menu_handler};
-    
-    // Initialize file logging
-    match init_file_logging() {
-        Ok(log_path) => {
-            log_info!("STARTUP", "File logging initialized at: {}", log_path.display());
-        },
-        Err(e) => {
-            eprintln!("Failed to initialize file logging: {}", e);
-        }
-    }
-    
-    // Initialize with empty states - data will be loaded after app starts
-    let notes_state = NotesState::new(HashMap::new());
-    let config_state = ConfigState::new(AppConfig::default());
-    let detached_windows_state = DetachedWindowsState::new(HashMap::new());
-    let modified_state_tracker = ModifiedStateTrackerState::new();
-
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_clipboard_manager::init())
-        .plugin({
-            tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(build_shortcut_handler())
-                .build()
-        })
-        .manage(notes_state)
-        .manage(config_state)
-        .manage(detached_windows_state)
-        .manage(ToggleState::new(false))
-        .manage(modified_state_tracker)
-        .invoke_handler(tauri::generate_handler![
+/// The invoke handler for every command, plus `$extra`. Test and debug commands
+/// are only passed in with the `debug-tools` feature.
+macro_rules! blink_invoke_handler {
+    ($($extra:ident),* $(,)?) => {
+        tauri::generate_handler![
             // Note operations
             get_notes,
             get_note,
@@ -123,20 +167,117 @@ pub fn run() {
             update_note,
             delete_note,
             reorder_notes,
+            move_notes,
+            undo_last_operation,
+            get_undo_history,
+            normalize_positions,
             get_notes_directory,
+            acquire_note_edit_lock,
+            release_note_edit_lock,
+            get_note_edit_lock,
             
             // File operations
             import_notes_from_directory,
             import_single_file,
             export_note_to_file,
             export_all_notes_to_directory,
+            export_note_metadata,
+            export_vault_metadata,
+            find_duplicate_notes,
             set_notes_directory,
             reload_notes_from_directory,
             get_current_notes_directory,
             
+            // Vault lock
+            get_vault_lock_status,
+            force_take_vault_lock,
+            set_read_only,
+            
+            // Vault maintenance
+            repair_note_ids,
+            rebuild_index,
+            verify_vault,
+            compact_vault,
+            get_vault_health,
+            format_timestamp,
+            resolve_config_reload,
+            set_follow_active_note,
+            get_follow_active_note,
+            capture_note_window,
+            run_archive_policies,
+            get_note_css,
+            set_note_css,
+            get_window_history,
+            create_sticky,
+            schedule_autosave,
+            flush_note,
+            get_safe_mode,
+            open_reading_mode,
+            get_reading_page,
+            get_related_notes,
+            set_power_mode,
+            get_power_mode,
+            capture_screen_to_note,
+            export_tagged_notes,
+            find_and_replace,
+            add_annotation,
+            resolve_annotation,
+            delete_annotation,
+            list_annotations,
+            save_window_arrangement,
+            apply_window_arrangement,
+            list_window_arrangements,
+            delete_window_arrangement,
+            open_vault_picker,
+            list_recent_vaults,
+            switch_vault,
+            set_vault_pinned,
+            export_note_normalized,
+            get_command_manifest,
+            get_audit_log,
+            request_confirmation_token,
+            
             // Config operations
             get_config,
             update_config,
+            get_effective_config,
+            get_workspace_settings,
+            update_workspace_settings,
+            get_tag_routing_rules,
+            set_tag_routing_rules,
+            resolve_url_metadata,
+            get_network_policy,
+            set_offline_mode,
+            fuzzy_find_notes,
+            open_note_palette,
+            select_palette_note,
+            get_about_info,
+            copy_diagnostics,
+            open_about_window,
+            startup::onboarding::complete_onboarding,
+            open_settings_window,
+            get_tag_counts,
+            suggest_tags,
+            get_folder_counts,
+            gc_attachments,
+            attach_dropped_file,
+            save_pasted_image,
+            publish_note_to_gist,
+            unpublish_note_gist,
+            get_gist_publication,
+            create_note_from_ics,
+            generate_weekly_review,
+            run_assistant_action,
+            start_audio_capture,
+            stop_audio_capture,
+            set_note_icon,
+            dock_window,
+            set_dock_expanded,
+            undock_window,
+            get_system_theme,
+            get_window_theme,
+            set_window_theme,
+            get_debug_capabilities,
             
             // Window operations
             toggle_window_visibility,
@@ -149,11 +290,16 @@ pub fn run() {
             reload_main_window,
             create_detached_window,
             close_detached_window,
+            detach_note_at_cursor,
             focus_detached_window,
+            reveal_note,
             get_detached_windows,
+            snap_detached_window,
             update_detached_window_position,
             update_detached_window_size,
+            get_display_fingerprint,
             toggle_window_shade,
+            set_window_visible_on_all_workspaces,
             toggle_main_window_shade,
             restore_detached_windows,
             clear_all_detached_windows,
@@ -161,24 +307,24 @@ pub fn run() {
             force_all_windows_opaque,
             gather_all_windows_to_main_screen,
             recreate_missing_windows,
-            test_detached_window_creation,
             get_window_state_truth,
             list_all_windows,
-            create_test_window,
-            test_window_events,
-            force_create_detached_window,
             cleanup_stale_windows,
             cleanup_destroyed_window,
-            force_close_test_window,
             cleanup_stale_hybrid_windows,
             
             // Drag and drop operations
             create_drag_ghost,
             update_drag_ghost_position,
             destroy_drag_ghost,
+            get_note_drag_preview,
             create_hybrid_drag_window,
             show_hybrid_drag_window,
             update_hybrid_drag_position,
+            begin_hybrid_drag_session,
+            queue_hybrid_drag_positions,
+            end_hybrid_drag_session,
+            begin_native_file_drag,
             close_hybrid_drag_window,
             finalize_hybrid_drag_window,
             
@@ -191,18 +337,103 @@ pub fn run() {
             update_app_menu,
             reregister_global_shortcuts,
             
-            // Test and debug operations
-            test_emit_new_note,
-            test_database_migration,
-            test_window_creation,
+            // Logs
             get_log_file_path,
             get_recent_logs,
-        ])
+            $($extra,)*
+        ]
+    };
+}
+
+// Main entry point
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    use modules::logging::init_file_logging;
+    use modules::modified_state_tracker::ModifiedStateTracker;
+    use startup::{setup_app, build_shortcut_handler, build_menu_handler, build_window_event_handler};
+    
+    // Initialize file logging
+    match init_file_logging() {
+        Ok(log_path) => {
+            log_info!("STARTUP", "File logging initialized at: {}", log_path.display());
+        },
+        Err(e) => {
+            eprintln!("Failed to initialize file logging: {}", e);
+        }
+    }
+
+    // Decided before setup so nothing it turns off gets started
+    modules::safe_mode::init_safe_mode();
+    
+    // Initialize with empty states - data will be loaded after app starts
+    let notes_state = NotesState::new(HashMap::new());
+    let config_state = ConfigState::new(AppConfig::default());
+    let detached_windows_state = DetachedWindowsState::new(HashMap::new());
+    let modified_state_tracker = ModifiedStateTrackerState::new();
+
+    #[cfg(feature = "debug-tools")]
+    let invoke_handler = blink_invoke_handler![
+        test_emit_new_note,
+        test_database_migration,
+        test_window_creation,
+        test_detached_window_creation,
+        create_test_window,
+        test_window_events,
+        force_create_detached_window,
+        force_close_test_window,
+    ];
+    #[cfg(not(feature = "debug-tools"))]
+    let invoke_handler = blink_invoke_handler![];
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
+        .plugin({
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(build_shortcut_handler())
+                .build()
+        })
+        .manage(notes_state)
+        .manage(config_state)
+        .manage(detached_windows_state)
+        .manage(ToggleState::new(false))
+        .manage(CursorDeployState::new(Default::default()))
+        .manage(NoteEditLockState::new(HashMap::new()))
+        .manage(modified_state_tracker)
+        .manage(VaultLockState::new(Default::default()))
+        .manage(DragPreviewCacheState::new(HashMap::new()))
+        .manage(DragSessionState::new(HashMap::new()))
+        .manage(WindowPoolState::new(Default::default()))
+        .manage(ConfirmationTokenState::new(HashMap::new()))
+        .manage(UrlMetadataCacheState::new(HashMap::new()))
+        .manage(NotesMenuState::new(None))
+        .manage(WindowMenuState::new(None))
+        .manage(OpenRecentMenuState::new(None))
+        .manage(AudioCaptureState::new(None))
+        .manage(WindowRevealState::new(Default::default()))
+        .manage(NoteCommandGuardState::default())
+        .manage(ConfigReloadState::new(None))
+        .manage(FollowModeState::default())
+        .manage(AutosaveState::default())
+        .manage(UndoHistoryState::default())
+        .manage(WindowChordState::default())
+        .manage(ReadingModeState::default())
+        .manage(RecentNotesState::default())
+        .invoke_handler(invoke_handler)
         .on_menu_event(build_menu_handler())
+        .on_window_event(build_window_event_handler())
         .setup(|app| {
             setup_app(app)?;
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Queued edits go to disk while the vault lock is still ours
+            tauri::RunEvent::ExitRequested { .. } => modules::autosave::flush_all_on_exit(app),
+            tauri::RunEvent::Exit => modules::vault_lock::release_vault_lock_on_exit(app),
+            _ => {}
+        });
 }
\ No newline at end of file
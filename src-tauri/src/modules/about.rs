@@ -0,0 +1,109 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::modules::database::get_database_path;
+use crate::modules::logging::{get_log_file_path, get_recent_logs};
+use crate::modules::maintenance::database_size;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::window::{ConfigState, NotesState};
+use crate::log_info;
+
+/// Label of the About window
+pub const ABOUT_WINDOW_LABEL: &str = "about";
+/// Log lines included in copied diagnostics
+const DIAGNOSTIC_LOG_LINES: usize = 200;
+
+/// What the About window shows
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AboutInfo {
+    pub version: String,
+    /// Short git commit the app was built from
+    pub build_hash: String,
+    pub notes_directory: String,
+    pub note_count: usize,
+    pub database_bytes: u64,
+    pub log_file: String,
+    pub platform: String,
+    pub arch: String,
+}
+
+/// Version, build and vault details for the About window
+#[tauri::command]
+pub async fn get_about_info(
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+) -> Result<AboutInfo, String> {
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let note_count = notes.lock().await.len();
+
+    Ok(AboutInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        build_hash: env!("BLINK_BUILD_HASH").to_string(),
+        notes_directory: notes_dir.to_string_lossy().to_string(),
+        note_count,
+        database_bytes: database_size(&get_database_path(&notes_dir)),
+        log_file: get_log_file_path(None).await?,
+        platform: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+    })
+}
+
+/// Plain-text report for bug reports: the About details plus recent log lines
+pub fn format_diagnostics(info: &AboutInfo, logs: &str) -> String {
+    format!(
+        "Blink {} ({})\nPlatform: {} {}\nNotes directory: {}\nNotes: {}\nDatabase: {} bytes\nLog file: {}\n\n--- Recent logs ---\n{}\n",
+        info.version,
+        info.build_hash,
+        info.platform,
+        info.arch,
+        info.notes_directory,
+        info.note_count,
+        info.database_bytes,
+        info.log_file,
+        logs,
+    )
+}
+
+/// Copy diagnostics to the clipboard and return them
+#[tauri::command]
+pub async fn copy_diagnostics(
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+) -> Result<String, String> {
+    let info = get_about_info(config, notes).await?;
+    let logs = get_recent_logs(Some(DIAGNOSTIC_LOG_LINES)).await?;
+    let diagnostics = format_diagnostics(&info, &logs);
+
+    app.clipboard()
+        .write_text(diagnostics.clone())
+        .map_err(|e| format!("Failed to copy diagnostics: {}", e))?;
+    log_info!("ABOUT", "Copied diagnostics ({} bytes)", diagnostics.len());
+    Ok(diagnostics)
+}
+
+/// Show the About window, creating it on first use
+#[tauri::command]
+pub async fn open_about_window(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(ABOUT_WINDOW_LABEL) {
+        window.show().map_err(|e| format!("Failed to show About window: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus About window: {}", e))?;
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(&app, ABOUT_WINDOW_LABEL, WebviewUrl::App("index.html?about=true".into()))
+        .title("About Blink")
+        .inner_size(380.0, 440.0)
+        .center()
+        .resizable(false)
+        .minimizable(false)
+        .maximizable(false)
+        .build()
+        .map_err(|e| format!("Failed to create About window: {}", e))?;
+    Ok(())
+}
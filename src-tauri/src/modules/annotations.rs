@@ -0,0 +1,383 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::{ConfigState, NotesState};
+use crate::{log_info, log_warn, VaultLockState};
+
+/// Characters of surrounding text kept with an anchor to find it again after edits
+const ANCHOR_CONTEXT_CHARS: usize = 32;
+
+/// The text a thread is attached to. The quote and its context are matched
+/// against the current content, so anchors survive edits elsewhere in the note;
+/// `offset` only breaks ties between identical quotes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAnchor {
+    pub quote: String,
+    pub prefix: String,
+    pub suffix: String,
+    /// Where the quote started when the thread was made, in UTF-16 units
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationComment {
+    pub id: String,
+    pub author: Option<String>,
+    pub body: String,
+    pub created_at: String,
+}
+
+/// A comment thread on part of a note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    pub id: String,
+    pub anchor: TextAnchor,
+    pub comments: Vec<AnnotationComment>,
+    #[serde(default)]
+    pub resolved: bool,
+    pub resolved_at: Option<String>,
+    pub created_at: String,
+}
+
+/// An annotation with where its anchor is in the note now
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacedAnnotation {
+    #[serde(flatten)]
+    pub annotation: Annotation,
+    /// UTF-16 range of the quote in the current content, `None` once the
+    /// quoted text is gone
+    pub range: Option<(usize, usize)>,
+}
+
+/// Where a note's threads are stored. Note ids become file names, so ids that
+/// could reach outside `.blink/annotations` are refused.
+fn annotations_path(notes_dir: &Path, note_id: &str) -> Result<PathBuf, String> {
+    if note_id.is_empty() || note_id.contains(['/', '\\']) || note_id.contains("..") {
+        return Err(format!("Invalid note id: {}", note_id));
+    }
+    Ok(notes_dir.join(".blink").join("annotations").join(format!("{}.json", note_id)))
+}
+
+/// Threads on a note, oldest first. Notes are plain markdown, so they live in
+/// `.blink/annotations/<note id>.json` rather than in the note file.
+pub fn load_annotations(notes_dir: &Path, note_id: &str) -> Vec<Annotation> {
+    let path = match annotations_path(notes_dir, note_id) {
+        Ok(path) => path,
+        Err(e) => {
+            log_warn!("ANNOTATIONS", "{}", e);
+            return Vec::new();
+        }
+    };
+    if !path.exists() {
+        return Vec::new();
+    }
+    match fs::read_to_string(&path).map(|json| serde_json::from_str(&json)) {
+        Ok(Ok(annotations)) => annotations,
+        Ok(Err(e)) => {
+            log_warn!("ANNOTATIONS", "Ignoring invalid annotations {:?}: {}", path, e);
+            Vec::new()
+        }
+        Err(e) => {
+            log_warn!("ANNOTATIONS", "Failed to read annotations {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+fn save_annotations(notes_dir: &Path, note_id: &str, annotations: &[Annotation]) -> Result<(), String> {
+    let path = annotations_path(notes_dir, note_id)?;
+    if annotations.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove annotations: {}", e))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create annotations directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(annotations)
+        .map_err(|e| format!("Failed to serialize annotations: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save annotations: {}", e))
+}
+
+/// Byte index of a UTF-16 offset, `None` past the end or inside a character
+fn byte_index(content: &str, utf16_offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (index, c) in content.char_indices() {
+        if units == utf16_offset {
+            return Some(index);
+        }
+        if units > utf16_offset {
+            return None;
+        }
+        units += c.len_utf16();
+    }
+    (units == utf16_offset).then_some(content.len())
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// Anchor the UTF-16 range `start..end` of `content`
+pub fn anchor_for_range(content: &str, start: usize, end: usize) -> Result<TextAnchor, String> {
+    let (Some(from), Some(to)) = (byte_index(content, start), byte_index(content, end)) else {
+        return Err("Selection is outside the note".to_string());
+    };
+    if from >= to {
+        return Err("Select some text to annotate".to_string());
+    }
+    let before = &content[..from];
+    let prefix: String = before.chars().rev().take(ANCHOR_CONTEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+    let suffix: String = content[to..].chars().take(ANCHOR_CONTEXT_CHARS).collect();
+    Ok(TextAnchor { quote: content[from..to].to_string(), prefix, suffix, offset: start })
+}
+
+/// Find the anchor in `content`, returning its UTF-16 range. Of several copies
+/// of the quote, the one whose surroundings match the saved context best wins,
+/// then the one nearest the original offset.
+pub fn locate_anchor(content: &str, anchor: &TextAnchor) -> Option<(usize, usize)> {
+    if anchor.quote.is_empty() {
+        return None;
+    }
+    let score = |from: usize, to: usize| {
+        let before = content[..from].chars().rev().zip(anchor.prefix.chars().rev()).take_while(|(a, b)| a == b).count();
+        let after = content[to..].chars().zip(anchor.suffix.chars()).take_while(|(a, b)| a == b).count();
+        before + after
+    };
+
+    content
+        .match_indices(&anchor.quote)
+        .map(|(from, quote)| {
+            let start = utf16_len(&content[..from]);
+            (score(from, from + quote.len()), start.abs_diff(anchor.offset), start)
+        })
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)))
+        .map(|(_, _, start)| (start, start + utf16_len(&anchor.quote)))
+}
+
+fn comment(author: Option<String>, body: &str) -> Result<AnnotationComment, String> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err("Comment is empty".to_string());
+    }
+    Ok(AnnotationComment {
+        id: uuid::Uuid::new_v4().to_string(),
+        author: author.filter(|a| !a.trim().is_empty()),
+        body: body.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// The notes directory, once the caller may annotate
+async fn writable_notes_dir(
+    window: &tauri::Window,
+    config: &ConfigState,
+    vault_lock: &VaultLockState,
+) -> Result<PathBuf, String> {
+    authorize(window, Capability::WriteNotes)?;
+    ensure_vault_writable(config, vault_lock).await?;
+    let config_lock = config.lock().await;
+    get_configured_notes_directory(&config_lock)
+}
+
+async fn note_content(notes: &NotesState, note_id: &str) -> Result<String, String> {
+    notes
+        .lock()
+        .await
+        .get(note_id)
+        .map(|note| note.content.clone())
+        .ok_or_else(|| format!("Note not found: {}", note_id))
+}
+
+fn place(content: &str, annotation: Annotation) -> PlacedAnnotation {
+    let range = locate_anchor(content, &annotation.anchor);
+    PlacedAnnotation { annotation, range }
+}
+
+/// Start a thread on the UTF-16 range `start..end` of a note, or reply to
+/// `thread_id`. The note's markdown is never touched.
+#[tauri::command]
+pub async fn add_annotation(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    body: String,
+    start: Option<usize>,
+    end: Option<usize>,
+    thread_id: Option<String>,
+    author: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<PlacedAnnotation, String> {
+    let notes_dir = writable_notes_dir(&window, &config, &vault_lock).await?;
+    let content = note_content(&notes, &note_id).await?;
+    let comment = comment(author, &body)?;
+    let mut annotations = load_annotations(&notes_dir, &note_id);
+
+    let annotation = match thread_id {
+        Some(thread_id) => {
+            let thread = annotations
+                .iter_mut()
+                .find(|a| a.id == thread_id)
+                .ok_or_else(|| format!("Annotation not found: {}", thread_id))?;
+            thread.comments.push(comment);
+            // Replying reopens a resolved thread
+            thread.resolved = false;
+            thread.resolved_at = None;
+            thread.clone()
+        }
+        None => {
+            let (Some(start), Some(end)) = (start, end) else {
+                return Err("A new annotation needs a start and end".to_string());
+            };
+            let annotation = Annotation {
+                id: uuid::Uuid::new_v4().to_string(),
+                anchor: anchor_for_range(&content, start, end)?,
+                created_at: comment.created_at.clone(),
+                comments: vec![comment],
+                resolved: false,
+                resolved_at: None,
+            };
+            annotations.push(annotation.clone());
+            annotation
+        }
+    };
+
+    save_annotations(&notes_dir, &note_id, &annotations)?;
+    let _ = app.emit("annotations-changed", &note_id);
+    log_info!("ANNOTATIONS", "Comment added to {} on {}", annotation.id, note_id);
+    Ok(place(&content, annotation))
+}
+
+/// Resolve a thread, or reopen it with `resolved: false`
+#[tauri::command]
+pub async fn resolve_annotation(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    annotation_id: String,
+    resolved: bool,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<PlacedAnnotation, String> {
+    let notes_dir = writable_notes_dir(&window, &config, &vault_lock).await?;
+    let content = note_content(&notes, &note_id).await?;
+    let mut annotations = load_annotations(&notes_dir, &note_id);
+    let annotation = annotations
+        .iter_mut()
+        .find(|a| a.id == annotation_id)
+        .ok_or_else(|| format!("Annotation not found: {}", annotation_id))?;
+    annotation.resolved = resolved;
+    annotation.resolved_at = resolved.then(|| chrono::Utc::now().to_rfc3339());
+    let annotation = annotation.clone();
+
+    save_annotations(&notes_dir, &note_id, &annotations)?;
+    let _ = app.emit("annotations-changed", &note_id);
+    Ok(place(&content, annotation))
+}
+
+/// Delete a thread and its comments
+#[tauri::command]
+pub async fn delete_annotation(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    annotation_id: String,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<(), String> {
+    let notes_dir = writable_notes_dir(&window, &config, &vault_lock).await?;
+    note_content(&notes, &note_id).await?;
+    let mut annotations = load_annotations(&notes_dir, &note_id);
+    let count = annotations.len();
+    annotations.retain(|a| a.id != annotation_id);
+    if annotations.len() == count {
+        return Err(format!("Annotation not found: {}", annotation_id));
+    }
+    save_annotations(&notes_dir, &note_id, &annotations)?;
+    let _ = app.emit("annotations-changed", &note_id);
+    Ok(())
+}
+
+/// Threads on a note, placed against its current content. Resolved threads
+/// are left out unless `include_resolved` is set.
+#[tauri::command]
+pub async fn list_annotations(
+    note_id: String,
+    include_resolved: Option<bool>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<Vec<PlacedAnnotation>, String> {
+    let content = note_content(&notes, &note_id).await?;
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let include_resolved = include_resolved.unwrap_or(false);
+    Ok(load_annotations(&notes_dir, &note_id)
+        .into_iter()
+        .filter(|a| include_resolved || !a.resolved)
+        .map(|a| place(&content, a))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_ids_cannot_leave_the_annotations_directory() {
+        let notes_dir = Path::new("/vault");
+        assert_eq!(
+            annotations_path(notes_dir, "abc-123").unwrap(),
+            Path::new("/vault/.blink/annotations/abc-123.json")
+        );
+        for note_id in ["", "../../x", "a/b", "a\\b", ".."] {
+            assert!(annotations_path(notes_dir, note_id).is_err(), "{:?}", note_id);
+        }
+    }
+
+    #[test]
+    fn test_anchor_survives_edits_around_it() {
+        let content = "Intro\nThe launch is on Friday.\n";
+        let anchor = anchor_for_range(content, 23, 29).unwrap();
+        assert_eq!(anchor.quote, "Friday");
+        assert_eq!(anchor.prefix, "Intro\nThe launch is on ");
+
+        let edited = "# Plan\nIntro\nThe launch is on Friday.\n";
+        assert_eq!(locate_anchor(edited, &anchor), Some((30, 36)));
+        assert_eq!(locate_anchor("Intro\nThe launch is on Monday.\n", &anchor), None);
+    }
+
+    #[test]
+    fn test_repeated_quote_is_told_apart_by_context() {
+        let content = "todo: call Ana\ndone: call Ana\n";
+        let anchor = anchor_for_range(content, 26, 29).unwrap();
+        assert_eq!(anchor.quote, "Ana");
+        let edited = format!("notes\n{}", content);
+        assert_eq!(locate_anchor(&edited, &anchor), Some((32, 35)));
+    }
+
+    #[test]
+    fn test_offsets_are_utf16() {
+        let content = "🚀 ship it";
+        let anchor = anchor_for_range(content, 3, 7).unwrap();
+        assert_eq!(anchor.quote, "ship");
+        assert_eq!(locate_anchor(content, &anchor), Some((3, 7)));
+        assert!(anchor_for_range(content, 1, 4).is_err());
+        assert!(anchor_for_range(content, 3, 3).is_err());
+    }
+}
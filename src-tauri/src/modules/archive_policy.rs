@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::audit_log::{record_audit, AuditAction, AuditEntry, AuditSource};
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::maintenance::record_maintenance_run;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::modules::workspace_settings::load_workspace_settings;
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::types::workspace::{ArchiveAction, ArchivePolicy};
+use crate::{log_error, log_info, VaultLockState};
+
+/// Tag given to archived notes
+pub const ARCHIVED_TAG: &str = "archived";
+
+/// A note an archive policy matched
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveCandidate {
+    pub note_id: String,
+    pub title: String,
+    /// The policy's tag, `None` for a policy covering every note
+    pub tag: Option<String>,
+    pub days_untouched: i64,
+}
+
+/// Result of `run_archive_policies`; also the payload of `archive-suggested`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveReport {
+    pub dry_run: bool,
+    pub archived: Vec<ArchiveCandidate>,
+    pub suggested: Vec<ArchiveCandidate>,
+}
+
+pub(crate) fn has_tag(note: &Note, tag: &str) -> bool {
+    let tag = tag.trim().trim_start_matches('#');
+    note.tags.iter().any(|t| t.trim().trim_start_matches('#').eq_ignore_ascii_case(tag))
+}
+
+/// Notes `policy` applies to as of `now`, oldest first. Archived notes and notes
+/// whose timestamp doesn't parse are never matched.
+pub fn select_for_archive<'a>(
+    policy: &ArchivePolicy,
+    notes: impl IntoIterator<Item = &'a Note>,
+    now: DateTime<Utc>,
+) -> Vec<ArchiveCandidate> {
+    if !policy.enabled {
+        return Vec::new();
+    }
+    let mut matched: Vec<ArchiveCandidate> = notes
+        .into_iter()
+        .filter(|note| !has_tag(note, ARCHIVED_TAG))
+        .filter(|note| policy.tag.as_deref().map_or(true, |tag| has_tag(note, tag)))
+        .filter_map(|note| {
+            let updated = DateTime::parse_from_rfc3339(&note.updated_at).ok()?;
+            let days_untouched = (now - updated.with_timezone(&Utc)).num_days();
+            (days_untouched > policy.older_than_days as i64).then(|| ArchiveCandidate {
+                note_id: note.id.clone(),
+                title: note.title.clone(),
+                tag: policy.tag.clone(),
+                days_untouched,
+            })
+        })
+        .collect();
+    matched.sort_by(|a, b| b.days_untouched.cmp(&a.days_untouched).then_with(|| a.note_id.cmp(&b.note_id)));
+    matched
+}
+
+/// Match every policy against `notes`. A note matched by several policies is
+/// listed once, under the first one that archives it if any does.
+pub fn plan_archive(policies: &[ArchivePolicy], notes: &HashMap<String, Note>, now: DateTime<Utc>) -> ArchiveReport {
+    let mut report = ArchiveReport::default();
+    let mut seen = std::collections::HashSet::new();
+    for action in [ArchiveAction::Archive, ArchiveAction::Suggest] {
+        for policy in policies.iter().filter(|p| p.action == action) {
+            for candidate in select_for_archive(policy, notes.values(), now) {
+                if !seen.insert(candidate.note_id.clone()) {
+                    continue;
+                }
+                match action {
+                    ArchiveAction::Archive => report.archived.push(candidate),
+                    ArchiveAction::Suggest => report.suggested.push(candidate),
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Apply the vault's archive policies. Archiving adds the `archived` tag and
+/// leaves `updatedAt` alone, so the note keeps its age.
+pub async fn apply_archive_policies(app: &AppHandle, dry_run: bool) -> Result<ArchiveReport, String> {
+    let config = app.state::<ConfigState>();
+    let notes = app.state::<NotesState>();
+    let config_snapshot = config.lock().await.clone();
+    let notes_dir = get_configured_notes_directory(&config_snapshot)?;
+    let policies = load_workspace_settings(&notes_dir).archive_policies;
+    if policies.is_empty() {
+        return Ok(ArchiveReport { dry_run, ..Default::default() });
+    }
+
+    let mut notes_lock = notes.lock().await;
+    let mut report = plan_archive(&policies, &notes_lock, Utc::now());
+    report.dry_run = dry_run;
+
+    if !dry_run && !report.archived.is_empty() {
+        for candidate in &report.archived {
+            if let Some(note) = notes_lock.get_mut(&candidate.note_id) {
+                note.tags.push(ARCHIVED_TAG.to_string());
+            }
+        }
+        FileNotesStorage::new(&config_snapshot)?.save_all_notes(&notes_lock).await?;
+
+        for candidate in &report.archived {
+            record_audit(&config_snapshot, AuditEntry::new(
+                AuditAction::Update,
+                AuditSource::Maintenance,
+                Some(&candidate.note_id),
+                format!("Archived \"{}\" after {} days untouched", candidate.title, candidate.days_untouched),
+            ));
+            if let Some(note) = notes_lock.get(&candidate.note_id) {
+                let _ = app.emit("note-updated", note);
+            }
+        }
+        record_maintenance_run(&notes_dir, "archive_policies");
+    }
+    drop(notes_lock);
+
+    if !dry_run && !report.suggested.is_empty() {
+        let _ = app.emit("archive-suggested", &report);
+    }
+    log_info!(
+        "MAINTENANCE",
+        "Archive policies{}: {} archived, {} suggested",
+        if dry_run { " (dry run)" } else { "" },
+        report.archived.len(),
+        report.suggested.len()
+    );
+    Ok(report)
+}
+
+/// Run the policies once startup has loaded the notes. A vault we can't write
+/// to is only checked.
+pub async fn run_archive_policies_at_startup(app: &AppHandle) {
+    let writable = ensure_vault_writable(&app.state::<ConfigState>(), &app.state::<VaultLockState>())
+        .await
+        .is_ok();
+    if let Err(e) = apply_archive_policies(app, !writable).await {
+        log_error!("MAINTENANCE", "Archive policies failed: {}", e);
+    }
+}
+
+/// Archive (or suggest archiving) notes as the vault's archive policies say.
+/// With `dry_run`, nothing is changed or emitted; the report lists what would be.
+#[tauri::command]
+pub async fn run_archive_policies(
+    window: tauri::Window,
+    app: AppHandle,
+    dry_run: Option<bool>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<ArchiveReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        ensure_vault_writable(&config, &vault_lock).await?;
+    }
+    apply_archive_policies(&app, dry_run).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, tags: &[&str], updated_at: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            position: None,
+            icon: None,
+        }
+    }
+
+    fn policy(tag: Option<&str>, older_than_days: u32, action: ArchiveAction) -> ArchivePolicy {
+        ArchivePolicy { tag: tag.map(|t| t.to_string()), older_than_days, action, enabled: true }
+    }
+
+    #[test]
+    fn test_select_for_archive() {
+        let now = DateTime::parse_from_rfc3339("2024-06-30T12:00:00Z").unwrap().with_timezone(&Utc);
+        let notes = vec![
+            note("old-meeting", &["#Meetings"], "2024-03-01T09:00:00Z"),
+            note("older-meeting", &["meetings"], "2024-01-01T09:00:00Z"),
+            note("recent-meeting", &["meetings"], "2024-06-20T09:00:00Z"),
+            note("archived-meeting", &["meetings", "archived"], "2024-01-01T09:00:00Z"),
+            note("old-idea", &["ideas"], "2024-01-01T09:00:00Z"),
+            note("broken", &["meetings"], "yesterday"),
+        ];
+
+        let matched = select_for_archive(&policy(Some("meetings"), 90, ArchiveAction::Archive), &notes, now);
+        let ids: Vec<&str> = matched.iter().map(|c| c.note_id.as_str()).collect();
+        assert_eq!(ids, ["older-meeting", "old-meeting"]);
+        assert_eq!(matched[1].days_untouched, 121);
+
+        let mut disabled = policy(None, 1, ArchiveAction::Archive);
+        disabled.enabled = false;
+        assert!(select_for_archive(&disabled, &notes, now).is_empty());
+    }
+
+    #[test]
+    fn test_archiving_policies_win_over_suggestions() {
+        let now = DateTime::parse_from_rfc3339("2024-06-30T12:00:00Z").unwrap().with_timezone(&Utc);
+        let notes: HashMap<String, Note> = [
+            note("standup", &["meetings"], "2024-01-01T09:00:00Z"),
+            note("idea", &["ideas"], "2024-01-01T09:00:00Z"),
+        ]
+        .into_iter()
+        .map(|n| (n.id.clone(), n))
+        .collect();
+
+        let policies = vec![
+            policy(None, 30, ArchiveAction::Suggest),
+            policy(Some("meetings"), 30, ArchiveAction::Archive),
+        ];
+        let report = plan_archive(&policies, &notes, now);
+        assert_eq!(report.archived.iter().map(|c| c.note_id.as_str()).collect::<Vec<_>>(), ["standup"]);
+        assert_eq!(report.suggested.iter().map(|c| c.note_id.as_str()).collect::<Vec<_>>(), ["idea"]);
+    }
+}
@@ -0,0 +1,229 @@
+use std::future::Future;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::types::window::{ConfigState, NotesState};
+use crate::log_info;
+
+/// What the assistant should do with a note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AssistantAction {
+    Summarize,
+    Rewrite,
+    SuggestTitle,
+}
+
+/// Result of `run_assistant_action`. Nothing is written to the note; the
+/// frontend decides whether to apply it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssistantOutput {
+    pub action: AssistantAction,
+    /// The summary, the rewritten text, or the best title
+    pub text: String,
+    /// All title candidates for `suggest-title`; empty otherwise
+    pub suggestions: Vec<String>,
+}
+
+/// A language model backend. Providers only turn text into text; note lookup,
+/// offline checks and feature gating happen in `run_assistant_action`.
+pub trait AssistantProvider {
+    fn summarize(&self, text: &str) -> impl Future<Output = Result<String, String>> + Send;
+    fn rewrite(&self, text: &str) -> impl Future<Output = Result<String, String>> + Send;
+    fn suggest_titles(&self, text: &str) -> impl Future<Output = Result<Vec<String>, String>> + Send;
+}
+
+/// Split a model reply into title candidates, dropping list markers and quotes
+pub fn parse_title_suggestions(reply: &str) -> Vec<String> {
+    let list_marker = Regex::new(r"^(?:\d+[.)]|[-*•])\s+").unwrap();
+    reply
+        .lines()
+        .map(|line| {
+            list_marker
+                .replace(line.trim(), "")
+                .trim_matches(|c| c == '"' || c == '\'')
+                .trim()
+                .to_string()
+        })
+        .filter(|title| !title.is_empty())
+        .take(5)
+        .collect()
+}
+
+/// Run `action` on `text` with any provider
+pub async fn run_action<P: AssistantProvider>(
+    provider: &P,
+    action: AssistantAction,
+    text: &str,
+) -> Result<AssistantOutput, String> {
+    let (text, suggestions) = match action {
+        AssistantAction::Summarize => (provider.summarize(text).await?, Vec::new()),
+        AssistantAction::Rewrite => (provider.rewrite(text).await?, Vec::new()),
+        AssistantAction::SuggestTitle => {
+            let titles = provider.suggest_titles(text).await?;
+            let best = titles.first().cloned().ok_or("The assistant did not suggest a title")?;
+            (best, titles)
+        }
+    };
+    Ok(AssistantOutput { action, text, suggestions })
+}
+
+#[cfg(feature = "assistant")]
+mod http {
+    use std::time::Duration;
+    use serde_json::{json, Value};
+
+    use super::{parse_title_suggestions, AssistantProvider};
+    use crate::types::config::AssistantConfig;
+
+    pub const ASSISTANT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Talks to an OpenAI-compatible `chat/completions` endpoint
+    pub struct HttpAssistantProvider {
+        pub client: reqwest::Client,
+        pub config: AssistantConfig,
+    }
+
+    impl HttpAssistantProvider {
+        async fn complete(&self, instructions: &str, text: &str) -> Result<String, String> {
+            let body = json!({
+                "model": self.config.model,
+                "messages": [
+                    { "role": "system", "content": instructions },
+                    { "role": "user", "content": text },
+                ],
+            });
+            let mut request = self
+                .client
+                .post(&self.config.endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+            if let Some(key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+                request = request.bearer_auth(key);
+            }
+
+            let response = request.send().await.map_err(|e| format!("Failed to reach the assistant: {}", e))?;
+            let status = response.status();
+            let text = response.text().await.map_err(|e| format!("Failed to read assistant response: {}", e))?;
+            if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+                return Err("The assistant endpoint rejected the API key".to_string());
+            }
+            if !status.is_success() {
+                return Err(format!("Assistant request failed ({}): {}", status, text));
+            }
+
+            let reply: Value = serde_json::from_str(&text)
+                .map_err(|e| format!("Unexpected assistant response: {}", e))?;
+            reply["choices"][0]["message"]["content"]
+                .as_str()
+                .map(|content| content.trim().to_string())
+                .ok_or_else(|| "The assistant response had no message".to_string())
+        }
+    }
+
+    impl AssistantProvider for HttpAssistantProvider {
+        async fn summarize(&self, text: &str) -> Result<String, String> {
+            self.complete("Summarize this note in a few sentences of markdown. Reply with the summary only.", text).await
+        }
+
+        async fn rewrite(&self, text: &str) -> Result<String, String> {
+            self.complete(
+                "Rewrite this markdown note to be clearer and more concise. Keep its structure, links and meaning. Reply with the note only.",
+                text,
+            )
+            .await
+        }
+
+        async fn suggest_titles(&self, text: &str) -> Result<Vec<String>, String> {
+            let reply = self.complete("Suggest up to five short titles for this note, one per line, best first.", text).await?;
+            Ok(parse_title_suggestions(&reply))
+        }
+    }
+}
+
+#[cfg(feature = "assistant")]
+async fn run_with_configured_provider(
+    action: AssistantAction,
+    text: &str,
+    config: &ConfigState,
+) -> Result<AssistantOutput, String> {
+    use crate::modules::network_policy::NetworkPolicy;
+
+    let policy = NetworkPolicy::current(config).await;
+    let client = policy.http_client("the assistant", http::ASSISTANT_TIMEOUT)?;
+    let assistant_config = config
+        .lock()
+        .await
+        .assistant
+        .clone()
+        .ok_or("Set an assistant endpoint in settings first")?;
+
+    let provider = http::HttpAssistantProvider { client, config: assistant_config };
+    run_action(&provider, action, text).await
+}
+
+#[cfg(not(feature = "assistant"))]
+async fn run_with_configured_provider(
+    _action: AssistantAction,
+    _text: &str,
+    _config: &ConfigState,
+) -> Result<AssistantOutput, String> {
+    Err("This build of Blink does not include the assistant".to_string())
+}
+
+/// Summarize, rewrite or suggest a title for a note with the configured provider
+#[tauri::command]
+pub async fn run_assistant_action(
+    note_id: String,
+    action: AssistantAction,
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+) -> Result<AssistantOutput, String> {
+    let content = notes
+        .lock()
+        .await
+        .get(&note_id)
+        .map(|note| note.content.clone())
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+    if content.trim().is_empty() {
+        return Err("The note is empty".to_string());
+    }
+
+    let output = run_with_configured_provider(action, &content, &config).await?;
+    log_info!("ASSISTANT", "Ran {:?} on {}", action, note_id);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoProvider;
+
+    impl AssistantProvider for EchoProvider {
+        async fn summarize(&self, text: &str) -> Result<String, String> {
+            Ok(format!("summary of {}", text))
+        }
+
+        async fn rewrite(&self, text: &str) -> Result<String, String> {
+            Ok(text.to_uppercase())
+        }
+
+        async fn suggest_titles(&self, _text: &str) -> Result<Vec<String>, String> {
+            Ok(parse_title_suggestions("1. \"Launch plan\"\n2) Beta checklist\n\n- Q3 goals"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_action() {
+        let summary = run_action(&EchoProvider, AssistantAction::Summarize, "notes").await.unwrap();
+        assert_eq!(summary.text, "summary of notes");
+        assert!(summary.suggestions.is_empty());
+
+        let titles = run_action(&EchoProvider, AssistantAction::SuggestTitle, "notes").await.unwrap();
+        assert_eq!(titles.text, "Launch plan");
+        assert_eq!(titles.suggestions, vec!["Launch plan", "Beta checklist", "Q3 goals"]);
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::confirmation::require_confirmation;
+use crate::modules::file_operations::allowed_roots;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::state::ConfirmationTokenState;
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::utils::paths::ensure_within;
+use crate::VaultLockState;
+use crate::{log_error, log_info, log_warn};
+
+/// Folder in the vault holding attachments. It is visible so other markdown
+/// editors can resolve the links too.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// A file in the attachment store
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredAttachment {
+    /// SHA-256 of the content; also the file name
+    pub hash: String,
+    /// Path relative to the vault root, as used in markdown links
+    pub relative_path: String,
+    pub size: u64,
+    /// True when identical content was already stored
+    pub deduplicated: bool,
+}
+
+/// A file dropped onto a note, ready to insert at the cursor
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedAttachment {
+    pub markdown: String,
+    /// Path relative to the vault root
+    pub relative_path: String,
+    /// True when the link points at the file where it was instead of a copy
+    pub linked: bool,
+}
+
+/// Files dropped onto a note window from the desktop, sent to that window to insert
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedAttachments {
+    pub window_label: String,
+    pub note_id: String,
+    pub attachments: Vec<DroppedAttachment>,
+    /// Dropped files that couldn't be attached
+    pub failed: Vec<String>,
+}
+
+/// An attachment with how many notes link to it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentUsage {
+    pub relative_path: String,
+    pub size: u64,
+    pub references: usize,
+}
+
+/// Result of `gc_attachments`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentGcReport {
+    pub dry_run: bool,
+    pub total_files: usize,
+    pub total_bytes: u64,
+    /// Attachments no note links to
+    pub unreferenced: Vec<AttachmentUsage>,
+    pub reclaimable_bytes: u64,
+    pub removed_files: usize,
+}
+
+pub fn attachments_dir(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(ATTACHMENTS_DIR)
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Store `bytes` under their content hash. Storing the same content again
+/// returns the existing file instead of writing a copy.
+pub fn store_attachment(notes_dir: &Path, bytes: &[u8], extension: &str) -> Result<StoredAttachment, String> {
+    let hash = content_hash(bytes);
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    let file_name = if extension.is_empty() { hash.clone() } else { format!("{}.{}", hash, extension) };
+
+    let dir = attachments_dir(notes_dir);
+    let path = dir.join(&file_name);
+    let deduplicated = path.exists();
+    if !deduplicated {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+        // Write then rename so a crash never leaves a truncated file under a valid hash
+        let temp_path = dir.join(format!(".{}.tmp", file_name));
+        fs::write(&temp_path, bytes).map_err(|e| format!("Failed to write attachment: {}", e))?;
+        fs::rename(&temp_path, &path).map_err(|e| format!("Failed to store attachment: {}", e))?;
+    }
+
+    Ok(StoredAttachment {
+        hash,
+        relative_path: format!("{}/{}", ATTACHMENTS_DIR, file_name),
+        size: bytes.len() as u64,
+        deduplicated,
+    })
+}
+
+/// `target` relative to `base`, with `/` separators so links work on every
+/// platform. `None` when the two share no root, like another drive on Windows.
+pub fn relative_link(base: &Path, target: &Path) -> Option<String> {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+    let parts: Vec<String> = std::iter::repeat("..".to_string())
+        .take(base.len() - common)
+        .chain(target[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()))
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Markdown for a dropped file: images are embedded, anything else is linked
+fn dropped_file_markdown(name: &str, link: &str) -> String {
+    const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+    let is_image = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map_or(false, |ext| IMAGE_EXTENSIONS.iter().any(|x| ext.eq_ignore_ascii_case(x)));
+    let link = link.replace(' ', "%20").replace('(', "%28").replace(')', "%29");
+    if is_image {
+        format!("![{}]({})", name, link)
+    } else {
+        format!("[{}]({})", name, link)
+    }
+}
+
+/// File names of the attachments a note links to, without duplicates
+pub fn linked_attachments(content: &str) -> Vec<String> {
+    let link_re = Regex::new(&format!(r"{}/([0-9a-f]{{64}}(?:\.[A-Za-z0-9]+)?)", ATTACHMENTS_DIR)).unwrap();
+    let mut linked: Vec<String> = link_re
+        .captures_iter(content)
+        .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
+        .collect();
+    linked.sort_unstable();
+    linked.dedup();
+    linked
+}
+
+/// How many notes link to each attachment file name. Counting from content keeps
+/// the numbers right after edits made outside the app.
+pub fn count_references<'a>(notes: impl IntoIterator<Item = &'a Note>) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for note in notes {
+        for name in linked_attachments(&note.content) {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Every stored attachment with its reference count
+pub fn attachment_usage(notes_dir: &Path, references: &HashMap<String, usize>) -> Vec<AttachmentUsage> {
+    let Ok(entries) = fs::read_dir(attachments_dir(notes_dir)) else { return Vec::new() };
+    let mut usage: Vec<AttachmentUsage> = entries
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            Some(AttachmentUsage {
+                relative_path: format!("{}/{}", ATTACHMENTS_DIR, name),
+                size: e.metadata().map(|m| m.len()).unwrap_or(0),
+                references: references.get(&name).copied().unwrap_or(0),
+            })
+        })
+        .collect();
+    usage.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    usage
+}
+
+/// Report attachments no note links to and, unless `dry_run` (the default),
+/// delete them. Deleting needs a confirmation token.
+#[tauri::command]
+pub async fn gc_attachments(
+    window: tauri::Window,
+    dry_run: Option<bool>,
+    confirmation_token: Option<String>,
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+    vault_lock: State<'_, VaultLockState>,
+    confirmations: State<'_, ConfirmationTokenState>,
+) -> Result<AttachmentGcReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let dry_run = dry_run.unwrap_or(true);
+    if !dry_run {
+        ensure_vault_writable(&config, &vault_lock).await?;
+        require_confirmation(&confirmations, confirmation_token.as_deref(), "gc_attachments").await?;
+    }
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let references = count_references(notes.lock().await.values());
+    let usage = attachment_usage(&notes_dir, &references);
+
+    let mut report = AttachmentGcReport {
+        dry_run,
+        total_files: usage.len(),
+        total_bytes: usage.iter().map(|u| u.size).sum(),
+        ..Default::default()
+    };
+    report.unreferenced = usage.into_iter().filter(|u| u.references == 0).collect();
+    report.reclaimable_bytes = report.unreferenced.iter().map(|u| u.size).sum();
+
+    if !dry_run {
+        for attachment in &report.unreferenced {
+            match fs::remove_file(notes_dir.join(&attachment.relative_path)) {
+                Ok(()) => report.removed_files += 1,
+                Err(e) => log_warn!("ATTACHMENTS", "Failed to remove {}: {}", attachment.relative_path, e),
+            }
+        }
+    }
+
+    log_info!(
+        "ATTACHMENTS",
+        "{} unreferenced of {} attachments, {} bytes reclaimable{}",
+        report.unreferenced.len(),
+        report.total_files,
+        report.reclaimable_bytes,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    Ok(report)
+}
+
+/// A file dragged onto a note. It is copied into the attachment store, or with
+/// `attachments.linkDroppedFiles` linked where it is, and the link is always
+/// relative to the vault root, so the vault keeps working on another machine
+/// and tag routing can move the note without breaking it. Files already in
+/// the vault are linked in place either way.
+#[tauri::command]
+pub async fn attach_dropped_file(
+    window: tauri::Window,
+    note_id: String,
+    path: String,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<DroppedAttachment, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let source = ensure_within(Path::new(&path), &allowed_roots(&*config.lock().await))?;
+    attach_file(&config, &vault_lock, &note_id, &source).await
+}
+
+/// Attach every file dropped onto a note window and send the links to it.
+/// Paths come from the OS drop, so they aren't confined like the command's.
+pub async fn attach_dropped_files(app: &AppHandle, window_label: &str, note_id: &str, paths: Vec<PathBuf>) {
+    let config = app.state::<ConfigState>();
+    let vault_lock = app.state::<VaultLockState>();
+    let mut dropped = DroppedAttachments {
+        window_label: window_label.to_string(),
+        note_id: note_id.to_string(),
+        attachments: Vec::new(),
+        failed: Vec::new(),
+    };
+    for path in paths {
+        match attach_file(&config, &vault_lock, note_id, &path).await {
+            Ok(attachment) => dropped.attachments.push(attachment),
+            Err(e) => {
+                log_warn!("ATTACHMENTS", "Failed to attach dropped file {}: {}", path.display(), e);
+                dropped.failed.push(path.to_string_lossy().to_string());
+            }
+        }
+    }
+    if let Err(e) = app.emit("attachments-dropped", &dropped) {
+        log_error!("ATTACHMENTS", "Failed to emit attachments-dropped event: {}", e);
+    }
+}
+
+async fn attach_file(
+    config: &ConfigState,
+    vault_lock: &VaultLockState,
+    note_id: &str,
+    source: &Path,
+) -> Result<DroppedAttachment, String> {
+    let path = source.to_string_lossy().to_string();
+    if !source.is_file() {
+        return Err(format!("{} is not a file", path));
+    }
+    let (notes_dir, link_files) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.attachments.link_dropped_files)
+    };
+    let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let vault = fs::canonicalize(&notes_dir).map_err(|e| format!("Failed to resolve notes directory: {}", e))?;
+    let target = fs::canonicalize(source).map_err(|e| format!("Failed to resolve {}: {}", path, e))?;
+    if link_files || target.starts_with(&vault) {
+        match relative_link(&vault, &target) {
+            Some(relative_path) => {
+                log_info!("ATTACHMENTS", "Linked dropped file {} into {}", path, note_id);
+                return Ok(DroppedAttachment {
+                    markdown: dropped_file_markdown(&name, &relative_path),
+                    relative_path,
+                    linked: true,
+                });
+            }
+            None => log_warn!("ATTACHMENTS", "No relative path from the vault to {}, copying it instead", path),
+        }
+    }
+
+    ensure_vault_writable(config, vault_lock).await?;
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let attachment = store_attachment(&notes_dir, &bytes, extension)?;
+    log_info!(
+        "ATTACHMENTS",
+        "Copied dropped file {} into {} as {}{}",
+        path,
+        note_id,
+        attachment.relative_path,
+        if attachment.deduplicated { ", already stored" } else { "" }
+    );
+    Ok(DroppedAttachment {
+        markdown: dropped_file_markdown(&name, &attachment.relative_path),
+        relative_path: attachment.relative_path,
+        linked: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn note(id: &str, content: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: content.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: vec![],
+            position: None,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_same_content_is_stored_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = store_attachment(temp_dir.path(), b"image bytes", "PNG").unwrap();
+        let second = store_attachment(temp_dir.path(), b"image bytes", ".png").unwrap();
+
+        assert!(!first.deduplicated);
+        assert!(second.deduplicated);
+        assert_eq!(first.relative_path, second.relative_path);
+        assert!(first.relative_path.ends_with(".png"));
+        assert_eq!(fs::read_dir(attachments_dir(temp_dir.path())).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_reference_counts() {
+        let temp_dir = TempDir::new().unwrap();
+        let used = store_attachment(temp_dir.path(), b"used", "png").unwrap();
+        let unused = store_attachment(temp_dir.path(), b"unused", "jpg").unwrap();
+
+        let link = format!("![shot]({})", used.relative_path);
+        let notes = [note("a", &format!("{}\n{}", link, link)), note("b", &link), note("c", "no images")];
+        let references = count_references(notes.iter());
+        let usage = attachment_usage(temp_dir.path(), &references);
+
+        let find = |path: &str| usage.iter().find(|u| u.relative_path == path).unwrap().references;
+        assert_eq!(find(&used.relative_path), 2);
+        assert_eq!(find(&unused.relative_path), 0);
+    }
+
+    #[test]
+    fn test_relative_links() {
+        let vault = Path::new("/home/me/vault");
+        assert_eq!(relative_link(vault, Path::new("/home/me/vault/attachments/a.png")).unwrap(), "attachments/a.png");
+        assert_eq!(relative_link(vault, Path::new("/home/me/Documents/spec.pdf")).unwrap(), "../Documents/spec.pdf");
+        assert_eq!(relative_link(vault, Path::new("relative/file.txt")), None);
+    }
+
+    #[test]
+    fn test_dropped_file_markdown() {
+        assert_eq!(dropped_file_markdown("Shot.PNG", "attachments/abc.png"), "![Shot.PNG](attachments/abc.png)");
+        assert_eq!(dropped_file_markdown("my spec (v2).pdf", "../docs/my spec (v2).pdf"), "[my spec (v2).pdf](../docs/my%20spec%20%28v2%29.pdf)");
+    }
+}
@@ -0,0 +1,364 @@
+use std::future::Future;
+use std::io::Cursor;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::modules::attachments::store_attachment;
+use crate::modules::commands::append_to_note;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::network_policy::NetworkPolicy;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::state::AudioCaptureState;
+use crate::types::config::TranscriptionConfig;
+use crate::types::window::{ConfigState, NotesState};
+use crate::VaultLockState;
+use crate::{log_error, log_info, log_warn};
+
+const TRANSCRIPTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A recording in progress. The cpal stream is not `Send`, so it lives on its
+/// own thread until `stop` is signalled.
+pub struct AudioRecording {
+    pub note_id: String,
+    pub started_at: Instant,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<Result<Vec<u8>, String>>,
+}
+
+/// What the capture slot holds
+pub enum AudioCapture {
+    /// Claimed while the microphone opens, so a second start is refused
+    Starting { note_id: String },
+    Recording(AudioRecording),
+}
+
+impl AudioCapture {
+    fn note_id(&self) -> &str {
+        match self {
+            AudioCapture::Starting { note_id } => note_id,
+            AudioCapture::Recording(recording) => &recording.note_id,
+        }
+    }
+}
+
+/// Claim the capture slot for `note_id`. Done before anything awaits, so two
+/// starts can't both find it empty.
+fn reserve_capture(slot: &mut Option<AudioCapture>, note_id: &str) -> Result<(), String> {
+    if let Some(capture) = slot.as_ref() {
+        return Err(format!("Already recording a memo for {}", capture.note_id()));
+    }
+    *slot = Some(AudioCapture::Starting { note_id: note_id.to_string() });
+    Ok(())
+}
+
+/// Take the recording in progress, leaving one that is still starting in place
+fn take_recording(slot: &mut Option<AudioCapture>) -> Result<AudioRecording, String> {
+    match slot.take() {
+        Some(AudioCapture::Recording(recording)) => Ok(recording),
+        Some(starting) => {
+            *slot = Some(starting);
+            Err("Recording is still starting".to_string())
+        }
+        None => Err("Not recording".to_string()),
+    }
+}
+
+/// Result of `stop_audio_capture`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceMemo {
+    pub note_id: String,
+    pub relative_path: String,
+    pub duration_secs: f64,
+    /// Filled in later by the transcription provider, if one is configured
+    pub transcribing: bool,
+}
+
+/// Speech-to-text backend for voice memos
+pub trait TranscriptionProvider {
+    fn transcribe(&self, wav: Vec<u8>) -> impl Future<Output = Result<String, String>> + Send;
+}
+
+/// Posts the memo to an OpenAI-compatible `audio/transcriptions` endpoint
+pub struct HttpTranscriptionProvider {
+    pub client: reqwest::Client,
+    pub config: TranscriptionConfig,
+}
+
+impl TranscriptionProvider for HttpTranscriptionProvider {
+    async fn transcribe(&self, wav: Vec<u8>) -> Result<String, String> {
+        let file = reqwest::multipart::Part::bytes(wav)
+            .file_name("memo.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| format!("Failed to build transcription request: {}", e))?;
+        let form = reqwest::multipart::Form::new()
+            .text("model", self.config.model.clone())
+            .part("file", file);
+
+        let mut request = self.client.post(&self.config.endpoint).multipart(form);
+        if let Some(key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.map_err(|e| format!("Failed to reach the transcription service: {}", e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| format!("Failed to read transcription: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Transcription failed ({}): {}", status, body));
+        }
+
+        let reply: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| format!("Unexpected transcription response: {}", e))?;
+        reply["text"]
+            .as_str()
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| "The transcription response had no text".to_string())
+    }
+}
+
+fn stream_error(e: cpal::StreamError) {
+    log_error!("AUDIO", "Microphone stream error: {}", e);
+}
+
+/// Record from the default microphone until `stop` fires, then return WAV bytes.
+/// `ready` reports whether recording actually started.
+fn record_until_stopped(stop: mpsc::Receiver<()>, ready: mpsc::Sender<Result<(), String>>) -> Result<Vec<u8>, String> {
+    let started = (|| -> Result<_, String> {
+        let device = cpal::default_host().default_input_device().ok_or("No microphone found")?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| format!("Microphone is unavailable: {}", e))?;
+        let sample_format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+        let samples: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = samples.clone();
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    sink.lock().unwrap().extend(data.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+                },
+                stream_error,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |data: &[i16], _: &cpal::InputCallbackInfo| sink.lock().unwrap().extend_from_slice(data),
+                stream_error,
+                None,
+            ),
+            cpal::SampleFormat::U16 => device.build_input_stream(
+                &config,
+                move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                    sink.lock().unwrap().extend(data.iter().map(|&s| (s as i32 - 32768) as i16));
+                },
+                stream_error,
+                None,
+            ),
+            other => return Err(format!("Unsupported microphone sample format: {:?}", other)),
+        }
+        .map_err(|e| format!("Failed to open microphone: {}", e))?;
+        stream.play().map_err(|e| format!("Failed to start recording: {}", e))?;
+        Ok((stream, config, samples))
+    })();
+
+    let (stream, config, samples) = match started {
+        Ok(started) => {
+            let _ = ready.send(Ok(()));
+            started
+        }
+        Err(e) => {
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+
+    // A dropped sender also ends the recording
+    let _ = stop.recv();
+    drop(stream);
+
+    let spec = hound::WavSpec {
+        channels: config.channels,
+        sample_rate: config.sample_rate.0,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut wav = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut wav, spec).map_err(|e| format!("Failed to encode recording: {}", e))?;
+    for sample in samples.lock().unwrap().iter() {
+        writer.write_sample(*sample).map_err(|e| format!("Failed to encode recording: {}", e))?;
+    }
+    writer.finalize().map_err(|e| format!("Failed to encode recording: {}", e))?;
+    Ok(wav.into_inner())
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Start recording a voice memo for a note
+#[tauri::command]
+pub async fn start_audio_capture(
+    window: tauri::Window,
+    note_id: String,
+    capture: State<'_, AudioCaptureState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    reserve_capture(&mut capture.lock().unwrap(), &note_id)?;
+
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = std::thread::spawn(move || record_until_stopped(stop_rx, ready_tx));
+    let started = tauri::async_runtime::spawn_blocking(move || ready_rx.recv())
+        .await
+        .map_err(|e| format!("Failed to start recording: {}", e))
+        .and_then(|ready| ready.map_err(|_| "Recording stopped unexpectedly".to_string()))
+        .and_then(|started| started);
+    if let Err(e) = started {
+        *capture.lock().unwrap() = None;
+        return Err(e);
+    }
+
+    *capture.lock().unwrap() = Some(AudioCapture::Recording(AudioRecording {
+        note_id: note_id.clone(),
+        started_at: Instant::now(),
+        stop: stop_tx,
+        thread,
+    }));
+    log_info!("AUDIO", "Recording voice memo for {}", note_id);
+    Ok(())
+}
+
+/// Stop recording, store the memo as an attachment and link it from the note.
+/// With a transcription provider configured, the text is appended when ready.
+#[tauri::command]
+pub async fn stop_audio_capture(
+    window: tauri::Window,
+    app: AppHandle,
+    capture: State<'_, AudioCaptureState>,
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<VoiceMemo, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let recording = take_recording(&mut capture.lock().unwrap())?;
+    let duration_secs = recording.started_at.elapsed().as_secs_f64();
+    let _ = recording.stop.send(());
+    let wav = tauri::async_runtime::spawn_blocking(move || recording.thread.join())
+        .await
+        .map_err(|e| format!("Failed to finish recording: {}", e))?
+        .map_err(|_| "The recording thread crashed".to_string())??;
+
+    // The microphone is released either way; only the memo needs a writable vault
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let (notes_dir, transcription) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.transcription.clone())
+    };
+    let attachment = store_attachment(&notes_dir, &wav, "wav")?;
+    let link = format!("[🎙 Voice memo {}]({})", format_duration(duration_secs), attachment.relative_path);
+    append_to_note(&app, &recording.note_id, &link, &notes, &config, &modified_tracker).await?;
+    log_info!("AUDIO", "Attached {:.1}s voice memo to {}", duration_secs, recording.note_id);
+
+    let provider = match transcription {
+        Some(transcription) => NetworkPolicy::current(&config)
+            .await
+            .http_client("voice memo transcription", TRANSCRIPTION_TIMEOUT)
+            .map(|client| HttpTranscriptionProvider { client, config: transcription })
+            .map_err(|e| log_warn!("AUDIO", "Not transcribing: {}", e))
+            .ok(),
+        None => None,
+    };
+    let transcribing = provider.is_some();
+    if let Some(provider) = provider {
+        let note_id = recording.note_id.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let text = match provider.transcribe(wav).await {
+                Ok(text) if !text.is_empty() => text,
+                Ok(_) => return,
+                Err(e) => {
+                    log_warn!("AUDIO", "Transcription failed: {}", e);
+                    return;
+                }
+            };
+            let quoted = text.lines().map(|line| format!("> {}", line)).collect::<Vec<_>>().join("\n");
+            let result = append_to_note(
+                &app,
+                &note_id,
+                &quoted,
+                &app.state::<NotesState>(),
+                &app.state::<ConfigState>(),
+                &app.state::<ModifiedStateTracker>(),
+            )
+            .await;
+            if let Err(e) = result {
+                log_warn!("AUDIO", "Failed to append transcript to {}: {}", note_id, e);
+            }
+        });
+    }
+
+    Ok(VoiceMemo {
+        note_id: recording.note_id,
+        relative_path: attachment.relative_path,
+        duration_secs,
+        transcribing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording(note_id: &str) -> AudioRecording {
+        let (stop, _) = mpsc::channel();
+        AudioRecording {
+            note_id: note_id.to_string(),
+            started_at: Instant::now(),
+            stop,
+            thread: std::thread::spawn(|| Ok(Vec::new())),
+        }
+    }
+
+    #[test]
+    fn test_second_start_is_refused_while_starting() {
+        let mut slot = None;
+        assert!(reserve_capture(&mut slot, "a").is_ok());
+        let err = reserve_capture(&mut slot, "b").unwrap_err();
+        assert!(err.contains("for a"));
+    }
+
+    #[test]
+    fn test_stop_waits_for_a_started_recording() {
+        let mut slot = None;
+        assert_eq!(take_recording(&mut slot).err().as_deref(), Some("Not recording"));
+
+        reserve_capture(&mut slot, "a").unwrap();
+        assert!(take_recording(&mut slot).is_err());
+        assert!(matches!(slot, Some(AudioCapture::Starting { .. })));
+
+        slot = Some(AudioCapture::Recording(recording("a")));
+        assert!(reserve_capture(&mut slot, "b").is_err());
+        assert_eq!(take_recording(&mut slot).unwrap().note_id, "a");
+        assert!(slot.is_none());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0.4), "0:00");
+        assert_eq!(format_duration(65.2), "1:05");
+    }
+}
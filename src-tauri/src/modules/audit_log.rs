@@ -0,0 +1,215 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::config::AppConfig;
+use crate::types::window::ConfigState;
+use crate::log_warn;
+
+/// Kind of mutation recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Reorder,
+    Rename,
+}
+
+/// Where a mutation came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSource {
+    /// A frontend command
+    Command,
+    Cli,
+    /// The embedded core API
+    Api,
+    Shortcut,
+    Import,
+    Maintenance,
+}
+
+/// One line of `.blink/audit.log`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub action: AuditAction,
+    pub source: AuditSource,
+    /// Affected note; `None` for vault-wide actions like reordering
+    pub note_id: Option<String>,
+    /// Window label or other caller detail, when known
+    pub actor: Option<String>,
+    pub summary: String,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, source: AuditSource, note_id: Option<&str>, summary: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            action,
+            source,
+            note_id: note_id.map(|id| id.to_string()),
+            actor: None,
+            summary: summary.into(),
+        }
+    }
+
+    pub fn with_actor(mut self, actor: &str) -> Self {
+        self.actor = Some(actor.to_string());
+        self
+    }
+}
+
+/// Filter for `get_audit_log`; every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    pub note_id: Option<String>,
+    pub action: Option<AuditAction>,
+    pub source: Option<AuditSource>,
+    /// Only entries at or after this RFC 3339 timestamp
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub fn audit_log_path(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(".blink").join("audit.log")
+}
+
+/// Append an entry to the vault's audit log
+pub fn append_audit_entry(notes_dir: &Path, entry: &AuditEntry) -> Result<(), String> {
+    let path = audit_log_path(notes_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Record a mutation in the configured vault. Failures are logged, never returned:
+/// auditing must not make the mutation itself fail.
+pub fn record_audit(config: &AppConfig, entry: AuditEntry) {
+    if config.read_only {
+        return;
+    }
+    let result = get_configured_notes_directory(config).and_then(|dir| append_audit_entry(&dir, &entry));
+    if let Err(e) = result {
+        log_warn!("AUDIT", "Failed to record {:?} of {:?}: {}", entry.action, entry.note_id, e);
+    }
+}
+
+/// Apply a filter to entries in log order, returning the newest first
+pub fn filter_audit_entries(entries: Vec<AuditEntry>, filter: &AuditFilter) -> Vec<AuditEntry> {
+    let since = filter
+        .since
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
+
+    let matching = entries.into_iter().rev().filter(|entry| {
+        filter.note_id.as_ref().map_or(true, |id| entry.note_id.as_ref() == Some(id))
+            && filter.action.map_or(true, |action| entry.action == action)
+            && filter.source.map_or(true, |source| entry.source == source)
+            && since.map_or(true, |since| {
+                chrono::DateTime::parse_from_rfc3339(&entry.timestamp).map_or(false, |t| t >= since)
+            })
+    });
+
+    match filter.limit {
+        Some(limit) => matching.take(limit).collect(),
+        None => matching.collect(),
+    }
+}
+
+/// Read the vault's audit log. Malformed lines are skipped.
+pub fn read_audit_log(notes_dir: &Path) -> Result<Vec<AuditEntry>, String> {
+    let path = audit_log_path(notes_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read audit log: {}", e))?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Get audit log entries, newest first
+#[tauri::command]
+pub async fn get_audit_log(
+    filter: Option<AuditFilter>,
+    config: State<'_, ConfigState>,
+) -> Result<Vec<AuditEntry>, String> {
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let entries = read_audit_log(&notes_dir)?;
+    Ok(filter_audit_entries(entries, &filter.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(action: AuditAction, note_id: &str, timestamp: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: timestamp.to_string(),
+            action,
+            source: AuditSource::Command,
+            note_id: Some(note_id.to_string()),
+            actor: None,
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = AuditEntry::new(AuditAction::Create, AuditSource::Api, Some("a"), "Created");
+        let second = AuditEntry::new(AuditAction::Delete, AuditSource::Command, Some("a"), "Deleted").with_actor("main");
+        append_audit_entry(temp_dir.path(), &first).unwrap();
+        append_audit_entry(temp_dir.path(), &second).unwrap();
+
+        assert_eq!(read_audit_log(temp_dir.path()).unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn test_filter_newest_first() {
+        let entries = vec![
+            entry(AuditAction::Create, "a", "2024-01-01T00:00:00+00:00"),
+            entry(AuditAction::Update, "b", "2024-01-02T00:00:00+00:00"),
+            entry(AuditAction::Update, "a", "2024-01-03T00:00:00+00:00"),
+            entry(AuditAction::Delete, "a", "2024-01-04T00:00:00+00:00"),
+        ];
+
+        let filter = AuditFilter { note_id: Some("a".to_string()), ..Default::default() };
+        let result = filter_audit_entries(entries.clone(), &filter);
+        assert_eq!(result.iter().map(|e| e.action).collect::<Vec<_>>(),
+            vec![AuditAction::Delete, AuditAction::Update, AuditAction::Create]);
+
+        let filter = AuditFilter {
+            action: Some(AuditAction::Update),
+            since: Some("2024-01-02T12:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let result = filter_audit_entries(entries.clone(), &filter);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].note_id.as_deref(), Some("a"));
+
+        let filter = AuditFilter { limit: Some(2), ..Default::default() };
+        assert_eq!(filter_audit_entries(entries, &filter).len(), 2);
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::commands::apply_note_update;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::power_mode::power_scaled;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::{Note, UpdateNoteRequest};
+use crate::types::window::{ConfigState, NotesState};
+use crate::{log_debug, log_error, log_info, AutosaveState, VaultLockState};
+
+/// Idle time after the last edit before a note is saved, as the editors used to wait
+pub const AUTOSAVE_DELAY: Duration = Duration::from_secs(30);
+
+/// In low power mode edits wait longer, so a burst of typing is one write
+pub const LOW_POWER_AUTOSAVE_DELAY: Duration = Duration::from_secs(120);
+
+/// How often `autosave-pending` is emitted while a save is waiting
+const COUNTDOWN_INTERVAL: Duration = Duration::from_secs(1);
+const LOW_POWER_COUNTDOWN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Idle time before an edit is saved in the current power mode
+pub fn autosave_delay() -> Duration {
+    power_scaled(AUTOSAVE_DELAY, LOW_POWER_AUTOSAVE_DELAY)
+}
+
+/// An edit waiting to be written
+#[derive(Debug)]
+pub struct PendingSave {
+    pub request: UpdateNoteRequest,
+    /// Label of the window the edit came from
+    pub writer: String,
+    pub due: Instant,
+}
+
+/// Payload of `autosave-pending`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutosavePending {
+    pub note_id: String,
+    pub remaining_ms: u64,
+}
+
+/// Payload of `autosave-failed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutosaveFailed {
+    pub note_id: String,
+    pub error: String,
+}
+
+/// Split pending saves into countdowns to report and the notes now due
+pub fn countdowns(pending: &HashMap<String, PendingSave>, now: Instant) -> (Vec<AutosavePending>, Vec<String>) {
+    let mut waiting = Vec::new();
+    let mut due = Vec::new();
+    for (note_id, save) in pending {
+        match save.due.checked_duration_since(now) {
+            Some(remaining) if !remaining.is_zero() => waiting.push(AutosavePending {
+                note_id: note_id.clone(),
+                remaining_ms: remaining.as_millis() as u64,
+            }),
+            _ => due.push(note_id.clone()),
+        }
+    }
+    waiting.sort_by(|a, b| a.note_id.cmp(&b.note_id));
+    due.sort();
+    (waiting, due)
+}
+
+async fn save_pending(app: &AppHandle, note_id: String, pending: PendingSave) -> Result<Option<Note>, String> {
+    let config = app.state::<ConfigState>();
+    ensure_vault_writable(&config, &app.state::<VaultLockState>()).await?;
+    apply_note_update(
+        app,
+        &pending.writer,
+        note_id,
+        pending.request,
+        &app.state::<NotesState>(),
+        &config,
+        &app.state::<ModifiedStateTracker>(),
+    )
+    .await
+}
+
+async fn save_or_report(app: &AppHandle, note_id: String, pending: PendingSave) -> Result<Option<Note>, String> {
+    let result = save_pending(app, note_id.clone(), pending).await;
+    if let Err(error) = &result {
+        log_error!("AUTOSAVE", "Failed to save note {}: {}", note_id, error);
+        let _ = app.emit("autosave-failed", &AutosaveFailed { note_id, error: error.clone() });
+    }
+    result
+}
+
+/// Report countdowns for waiting saves every second (every few in low power
+/// mode) and write the ones that are due
+pub fn start_autosave_ticker(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(power_scaled(COUNTDOWN_INTERVAL, LOW_POWER_COUNTDOWN_INTERVAL)).await;
+            let due: Vec<(String, PendingSave)> = {
+                let state = app.state::<AutosaveState>();
+                let mut pending = state.lock().unwrap();
+                let (waiting, due) = countdowns(&pending, Instant::now());
+                for countdown in &waiting {
+                    let _ = app.emit("autosave-pending", countdown);
+                }
+                due.into_iter().filter_map(|id| pending.remove_entry(&id)).collect()
+            };
+            for (note_id, save) in due {
+                log_debug!("AUTOSAVE", "Saving note {} after {}s idle", note_id, autosave_delay().as_secs());
+                let _ = save_or_report(&app, note_id, save).await;
+            }
+        }
+    });
+}
+
+/// Write every queued edit before the app exits; the ticker won't get
+/// another turn, and edits still waiting on it would be lost
+pub fn flush_all_on_exit(app: &AppHandle) {
+    let pending: Vec<(String, PendingSave)> = app.state::<AutosaveState>().lock().unwrap().drain().collect();
+    if pending.is_empty() {
+        return;
+    }
+    log_info!("AUTOSAVE", "Saving {} queued edits before exit", pending.len());
+    tauri::async_runtime::block_on(async {
+        for (note_id, save) in pending {
+            let _ = save_or_report(app, note_id, save).await;
+        }
+    });
+}
+
+/// Queue an edit to be saved once the note has been idle for `autosave_delay()`.
+/// Each edit replaces the queued one and restarts the countdown.
+#[tauri::command]
+pub async fn schedule_autosave(
+    window: tauri::Window,
+    app: AppHandle,
+    id: String,
+    request: UpdateNoteRequest,
+    autosave: State<'_, AutosaveState>,
+) -> Result<AutosavePending, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let delay = autosave_delay();
+    let save = PendingSave { request, writer: window.label().to_string(), due: Instant::now() + delay };
+    autosave.lock().unwrap().insert(id.clone(), save);
+
+    let countdown = AutosavePending { note_id: id, remaining_ms: delay.as_millis() as u64 };
+    let _ = app.emit("autosave-pending", &countdown);
+    Ok(countdown)
+}
+
+/// Save a note's queued edit right away. Returns the saved note, or `None`
+/// when nothing was waiting.
+#[tauri::command]
+pub async fn flush_note(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    autosave: State<'_, AutosaveState>,
+) -> Result<Option<Note>, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let save = autosave.lock().unwrap().remove(&note_id);
+    let Some(save) = save else { return Ok(None) };
+    save_or_report(&app, note_id, save).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(due: Instant) -> PendingSave {
+        PendingSave {
+            request: UpdateNoteRequest { title: None, content: Some("draft".to_string()), tags: None, base_hash: None, allow_large: false },
+            writer: "note-a".to_string(),
+            due,
+        }
+    }
+
+    #[test]
+    fn test_countdowns() {
+        let now = Instant::now();
+        let saves = HashMap::from([
+            ("b".to_string(), pending(now + Duration::from_millis(2500))),
+            ("a".to_string(), pending(now + Duration::from_secs(10))),
+            ("c".to_string(), pending(now)),
+            ("d".to_string(), pending(now - Duration::from_secs(1))),
+        ]);
+
+        let (waiting, due) = countdowns(&saves, now);
+        assert_eq!(
+            waiting,
+            [
+                AutosavePending { note_id: "a".to_string(), remaining_ms: 10_000 },
+                AutosavePending { note_id: "b".to_string(), remaining_ms: 2_500 },
+            ]
+        );
+        assert_eq!(due, ["c", "d"]);
+    }
+}
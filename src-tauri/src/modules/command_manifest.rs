@@ -0,0 +1,417 @@
+use serde::Serialize;
+
+/// A command as written in the registry below: Rust names and types, exactly
+/// as in its signature, minus the state and handles Tauri injects
+struct RegisteredCommand {
+    name: &'static str,
+    params: &'static [(&'static str, &'static str)],
+    returns: &'static str,
+}
+
+/// One argument of a command, as passed to `invoke`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandParam {
+    /// The camelCase key Tauri expects
+    pub name: String,
+    pub rust_type: String,
+    pub ts_type: String,
+    /// `Option` arguments may be left out
+    pub optional: bool,
+}
+
+/// A registered command with its arguments and what it resolves to. Every
+/// command fails with a `String` error, so only the success type is given.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSpec {
+    pub name: String,
+    pub params: Vec<CommandParam>,
+    pub returns: String,
+    pub ts_returns: String,
+}
+
+macro_rules! command_registry {
+    ($registry:ident { $($name:ident($($param:ident: $ty:ty),* $(,)?) -> $ret:ty;)* }) => {
+        const $registry: &[RegisteredCommand] = &[$(RegisteredCommand {
+            name: stringify!($name),
+            params: &[$((stringify!($param), stringify!($ty))),*],
+            returns: stringify!($ret),
+        }),*];
+    };
+}
+
+// Every command in `blink_invoke_handler!`, in the same order. A new command
+// goes in both places; `test_registry_matches_invoke_handler` catches misses.
+command_registry!(COMMANDS {
+    // Note operations
+    get_notes(include_sticky: Option<bool>) -> Vec<Note>;
+    get_note(id: String) -> Option<Note>;
+    create_note(request: CreateNoteRequest) -> Note;
+    update_note(id: String, request: UpdateNoteRequest) -> Option<Note>;
+    delete_note(id: String) -> bool;
+    reorder_notes(note_ids: Vec<String>) -> ();
+    move_notes(note_ids: Vec<String>, target_index: usize) -> Vec<String>;
+    undo_last_operation() -> Option<UndoSummary>;
+    get_undo_history() -> Vec<UndoSummary>;
+    normalize_positions() -> usize;
+    get_notes_directory() -> String;
+    acquire_note_edit_lock(note_id: String) -> NoteEditLockStatus;
+    release_note_edit_lock(note_id: String) -> bool;
+    get_note_edit_lock(note_id: String) -> NoteEditLockStatus;
+
+    // File operations
+    import_notes_from_directory(directory_path: String) -> Vec<Note>;
+    import_single_file(file_path: String) -> Note;
+    export_note_to_file(note_id: String, file_path: String, wrap_column: Option<usize>) -> ();
+    export_all_notes_to_directory(directory_path: String, wrap_column: Option<usize>) -> Vec<String>;
+    export_note_metadata(note_id: String, output_path: Option<String>) -> NoteMetadata;
+    export_vault_metadata(output_path: Option<String>) -> VaultMetadata;
+    find_duplicate_notes(threshold: Option<f64>) -> Vec<DuplicateGroup>;
+    set_notes_directory(directory_path: String) -> ();
+    reload_notes_from_directory() -> Vec<Note>;
+    get_current_notes_directory() -> String;
+
+    // Vault lock
+    get_vault_lock_status() -> VaultLockStatus;
+    force_take_vault_lock() -> VaultLockStatus;
+    set_read_only(enabled: bool, persist: Option<bool>) -> ();
+
+    // Vault maintenance
+    repair_note_ids(dry_run: Option<bool>) -> IdRepairReport;
+    rebuild_index() -> IndexRebuildReport;
+    verify_vault(full: Option<bool>) -> VaultVerifyReport;
+    compact_vault(
+        dry_run: Option<bool>,
+        retention_days: Option<u64>,
+        confirmation_token: Option<String>,
+    ) -> VaultCompactReport;
+    get_vault_health() -> VaultHealth;
+    format_timestamp(ts: String, style: Option<DateStyle>) -> String;
+    resolve_config_reload(accept: bool) -> ();
+    set_follow_active_note(enabled: bool) -> ();
+    get_follow_active_note() -> bool;
+    capture_note_window(label: String, path: String) -> String;
+    run_archive_policies(dry_run: Option<bool>) -> ArchiveReport;
+    get_note_css(note_id: String) -> Option<String>;
+    set_note_css(note_id: String, css: Option<String>) -> ();
+    get_window_history(note_id: Option<String>, limit: Option<usize>) -> Vec<WindowLogRecord>;
+    create_sticky(color: Option<String>, text: Option<String>) -> DetachedWindow;
+    schedule_autosave(id: String, request: UpdateNoteRequest) -> AutosavePending;
+    flush_note(note_id: String) -> Option<Note>;
+    get_safe_mode() -> bool;
+    open_reading_mode(note_id: String) -> ();
+    get_reading_page() -> ReadingPage;
+    get_related_notes(note_id: String, limit: Option<usize>) -> Vec<RelatedNote>;
+    set_power_mode(mode: PowerMode) -> PowerMode;
+    get_power_mode() -> PowerMode;
+    capture_screen_to_note(mode: CaptureMode, note_id: Option<String>) -> Option<ScreenCapture>;
+    export_tagged_notes(tag: String, output_dir: String, format: ExportFormat) -> TagExportReport;
+    find_and_replace(query: String, replacement: String, options: Option<FindReplaceOptions>) -> FindReplaceReport;
+    add_annotation(
+        note_id: String,
+        body: String,
+        start: Option<usize>,
+        end: Option<usize>,
+        thread_id: Option<String>,
+        author: Option<String>,
+    ) -> PlacedAnnotation;
+    resolve_annotation(note_id: String, annotation_id: String, resolved: bool) -> PlacedAnnotation;
+    delete_annotation(note_id: String, annotation_id: String) -> ();
+    list_annotations(note_id: String, include_resolved: Option<bool>) -> Vec<PlacedAnnotation>;
+    save_window_arrangement(name: String, display_preset: Option<bool>) -> WindowArrangement;
+    apply_window_arrangement(name: String) -> ();
+    list_window_arrangements() -> Vec<WindowArrangement>;
+    delete_window_arrangement(name: String) -> ();
+    open_vault_picker() -> ();
+    list_recent_vaults() -> Vec<VaultEntry>;
+    switch_vault(path: String) -> usize;
+    set_vault_pinned(path: String, pinned: bool) -> ();
+    export_note_normalized(
+        note_id: String,
+        output_path: Option<String>,
+        attachments: Option<AttachmentPaths>,
+    ) -> NormalizedExport;
+    get_command_manifest() -> Vec<CommandSpec>;
+    get_audit_log(filter: Option<AuditFilter>) -> Vec<AuditEntry>;
+    request_confirmation_token(action: String) -> ConfirmationToken;
+
+    // Config operations
+    get_config() -> AppConfig;
+    update_config(new_config: AppConfig) -> AppConfig;
+    get_effective_config() -> AppConfig;
+    get_workspace_settings() -> WorkspaceSettings;
+    update_workspace_settings(settings: WorkspaceSettings) -> WorkspaceSettings;
+    get_tag_routing_rules() -> Vec<TagRoutingRule>;
+    set_tag_routing_rules(rules: Vec<TagRoutingRule>) -> Vec<TagRoutingRule>;
+    resolve_url_metadata(url: String) -> UrlMetadata;
+    get_network_policy() -> NetworkPolicy;
+    set_offline_mode(offline: bool) -> NetworkPolicy;
+    fuzzy_find_notes(query: String, limit: Option<usize>) -> Vec<NoteMatch>;
+    open_note_palette() -> ();
+    select_palette_note(note_id: String) -> ();
+    get_about_info() -> AboutInfo;
+    copy_diagnostics() -> String;
+    open_about_window() -> ();
+    complete_onboarding(onboarding_config: AppConfig) -> AppConfig;
+    open_settings_window(section: Option<String>) -> ();
+    get_tag_counts() -> Vec<NoteCount>;
+    suggest_tags(prefix: String, limit: Option<usize>) -> Vec<TagSuggestion>;
+    get_folder_counts() -> Vec<NoteCount>;
+    gc_attachments(dry_run: Option<bool>, confirmation_token: Option<String>) -> AttachmentGcReport;
+    attach_dropped_file(note_id: String, path: String) -> DroppedAttachment;
+    save_pasted_image(note_id: String, bytes: Vec<u8>, mime: String) -> PastedImage;
+    publish_note_to_gist(note_id: String, public: bool, token: String) -> GistPublication;
+    unpublish_note_gist(note_id: String, token: String) -> ();
+    get_gist_publication(note_id: String) -> Option<GistPublication>;
+    create_note_from_ics(path_or_text: String) -> Note;
+    generate_weekly_review(week: Option<String>) -> Note;
+    run_assistant_action(note_id: String, action: AssistantAction) -> AssistantOutput;
+    start_audio_capture(note_id: String) -> ();
+    stop_audio_capture() -> VoiceMemo;
+    set_note_icon(note_id: String, emoji: Option<String>) -> Note;
+    dock_window(window_label: String, edge: DockEdge) -> DetachedWindow;
+    set_dock_expanded(window_label: String, expanded: bool) -> DetachedWindow;
+    undock_window(window_label: String) -> DetachedWindow;
+    get_system_theme() -> WindowTheme;
+    get_window_theme(note_id: String) -> Option<WindowTheme>;
+    set_window_theme(note_id: String, theme: Option<WindowTheme>) -> ();
+    get_debug_capabilities() -> DebugCapabilities;
+
+    // Window operations
+    toggle_window_visibility() -> bool;
+    set_window_opacity(opacity: f64) -> ();
+    set_window_always_on_top(always_on_top: bool) -> ();
+    toggle_all_windows_hover() -> bool;
+    set_window_focus() -> ();
+    force_main_window_visible() -> ();
+    debug_webview_state() -> String;
+    reload_main_window() -> ();
+    create_detached_window(request: CreateDetachedWindowRequest) -> DetachedWindow;
+    close_detached_window(note_id: String) -> bool;
+    detach_note_at_cursor(note_id: String) -> DetachedWindow;
+    focus_detached_window(note_id: String) -> bool;
+    reveal_note(note_id: String) -> RevealTarget;
+    get_detached_windows() -> HashMap<String, DetachedWindow>;
+    snap_detached_window(window_label: String, x: f64, y: f64) -> Option<(f64, f64)>;
+    update_detached_window_position(window_label: String, x: f64, y: f64) -> Option<(f64, f64)>;
+    update_detached_window_size(window_label: String, width: f64, height: f64) -> ();
+    get_display_fingerprint() -> String;
+    toggle_window_shade(window_label: String) -> bool;
+    set_window_visible_on_all_workspaces(window_label: String, visible: bool) -> ();
+    toggle_main_window_shade() -> bool;
+    restore_detached_windows() -> Vec<String>;
+    clear_all_detached_windows(confirmation_token: Option<String>) -> i32;
+    debug_all_windows_state() -> String;
+    force_all_windows_opaque() -> String;
+    gather_all_windows_to_main_screen() -> String;
+    recreate_missing_windows() -> String;
+    get_window_state_truth() -> String;
+    list_all_windows() -> Vec<String>;
+    cleanup_stale_windows() -> usize;
+    cleanup_destroyed_window(note_id: String) -> ();
+    cleanup_stale_hybrid_windows() -> String;
+
+    // Drag and drop operations
+    create_drag_ghost(note_title: String, x: f64, y: f64) -> ();
+    update_drag_ghost_position(x: f64, y: f64) -> ();
+    destroy_drag_ghost() -> ();
+    get_note_drag_preview(note_id: String) -> DragPreview;
+    create_hybrid_drag_window(note_id: String, x: f64, y: f64, hidden: Option<bool>) -> String;
+    show_hybrid_drag_window(window_label: String, x: f64, y: f64) -> ();
+    update_hybrid_drag_position(window_label: String, x: f64, y: f64) -> ();
+    begin_hybrid_drag_session(
+        window_label: String,
+        offset_x: Option<f64>,
+        offset_y: Option<f64>,
+        track_cursor: Option<bool>,
+    ) -> ();
+    queue_hybrid_drag_positions(window_label: String, positions: Vec<(f64, f64)>) -> ();
+    end_hybrid_drag_session(window_label: String) -> Option<(f64, f64)>;
+    begin_native_file_drag(note_id: String) -> NativeFileDrag;
+    close_hybrid_drag_window(window_label: String) -> ();
+    finalize_hybrid_drag_window(window_label: String, note_id: String) -> DetachedWindow;
+
+    // System operations
+    open_system_settings() -> ();
+    open_directory_in_finder(directory_path: String) -> ();
+    open_directory_dialog() -> Option<String>;
+
+    // Menu and shortcuts
+    update_app_menu() -> ();
+    reregister_global_shortcuts() -> String;
+
+    // Logs
+    get_log_file_path(category: Option<String>) -> String;
+    get_recent_logs(lines: Option<usize>) -> String;
+});
+
+#[cfg(feature = "debug-tools")]
+command_registry!(DEBUG_COMMANDS {
+    test_emit_new_note() -> String;
+    test_database_migration() -> String;
+    test_window_creation() -> String;
+    test_detached_window_creation() -> String;
+    create_test_window() -> ();
+    test_window_events() -> ();
+    force_create_detached_window(note_id: String) -> ();
+    force_close_test_window() -> String;
+});
+
+/// Types as one string, however `stringify!` spaced them
+fn tidy_type(ty: &str) -> String {
+    ty.split_whitespace().collect::<String>().replace(',', ", ")
+}
+
+/// Split `A, B<C, D>` at its top-level commas
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(list[start..].trim());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// The TypeScript type a Rust type crosses the bridge as. Structs and enums
+/// keep their names, which the interfaces in `tauri-api.ts` share.
+pub fn ts_type(rust: &str) -> String {
+    let rust = rust.trim();
+    if let Some(inner) = rust.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        let items: Vec<String> = split_top_level(inner).into_iter().map(ts_type).collect();
+        return if items.is_empty() { "void".to_string() } else { format!("[{}]", items.join(", ")) };
+    }
+    if let Some((outer, inner)) = rust.strip_suffix('>').and_then(|r| r.split_once('<')) {
+        let args: Vec<&str> = split_top_level(inner);
+        let outer = outer.rsplit("::").next().unwrap_or(outer);
+        return match (outer, args.as_slice()) {
+            ("Option", [inner]) => format!("{} | null", ts_type(inner)),
+            ("Vec", ["u8"]) => "number[]".to_string(),
+            ("Vec" | "HashSet" | "BTreeSet", [inner]) => {
+                let inner = ts_type(inner);
+                if inner.contains('|') { format!("({})[]", inner) } else { format!("{}[]", inner) }
+            }
+            ("HashMap" | "BTreeMap", [key, value]) => format!("Record<{}, {}>", ts_type(key), ts_type(value)),
+            _ => outer.to_string(),
+        };
+    }
+    match rust.rsplit("::").next().unwrap_or(rust) {
+        "String" | "str" | "&str" | "PathBuf" => "string".to_string(),
+        "bool" => "boolean".to_string(),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" | "f32" | "f64" => {
+            "number".to_string()
+        }
+        "Value" => "unknown".to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.trim_start_matches('_').chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+fn spec(command: &RegisteredCommand) -> CommandSpec {
+    let returns = tidy_type(command.returns);
+    CommandSpec {
+        name: command.name.to_string(),
+        params: command
+            .params
+            .iter()
+            .map(|(name, ty)| {
+                let rust_type = tidy_type(ty);
+                CommandParam {
+                    name: camel_case(name),
+                    ts_type: ts_type(&rust_type),
+                    optional: rust_type.starts_with("Option<"),
+                    rust_type,
+                }
+            })
+            .collect(),
+        ts_returns: ts_type(&returns),
+        returns,
+    }
+}
+
+/// Every command this build registers
+pub fn command_manifest() -> Vec<CommandSpec> {
+    #[cfg(feature = "debug-tools")]
+    let registered = COMMANDS.iter().chain(DEBUG_COMMANDS);
+    #[cfg(not(feature = "debug-tools"))]
+    let registered = COMMANDS.iter();
+    registered.map(spec).collect()
+}
+
+/// List every command with its argument and return types, for generating
+/// typed bindings and checking calls before they're made
+#[tauri::command]
+pub async fn get_command_manifest() -> Result<Vec<CommandSpec>, String> {
+    Ok(command_manifest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Command names between `start` and `end` in lib.rs
+    fn handler_names(start: &str, end: &str) -> Vec<String> {
+        let source = include_str!("../lib.rs");
+        let list = &source[source.find(start).unwrap() + start.len()..];
+        let list = &list[..list.find(end).unwrap()];
+        list.lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .map(|line| line.rsplit("::").next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_registry_matches_invoke_handler() {
+        let registered: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+        assert_eq!(registered, handler_names("tauri::generate_handler![", "$($extra,)*"));
+
+        let debug = handler_names("let invoke_handler = blink_invoke_handler![", "];");
+        assert_eq!(debug.len(), 8);
+        assert!(debug.iter().all(|name| !registered.contains(&name.as_str())));
+    }
+
+    #[test]
+    fn test_ts_types() {
+        assert_eq!(ts_type("Option<Vec<String>>"), "string[] | null");
+        assert_eq!(ts_type("Vec<Option<Note>>"), "(Note | null)[]");
+        assert_eq!(ts_type("HashMap<String, DetachedWindow>"), "Record<string, DetachedWindow>");
+        assert_eq!(ts_type("Option<(f64, f64)>"), "[number, number] | null");
+        assert_eq!(ts_type("()"), "void");
+        assert_eq!(ts_type("Vec<u8>"), "number[]");
+    }
+
+    #[test]
+    fn test_specs_use_invoke_argument_names() {
+        let manifest = command_manifest();
+        let export = manifest.iter().find(|c| c.name == "export_note_normalized").unwrap();
+        let params: Vec<(&str, bool)> = export.params.iter().map(|p| (p.name.as_str(), p.optional)).collect();
+        assert_eq!(params, [("noteId", false), ("outputPath", true), ("attachments", true)]);
+        assert_eq!(export.params[2].ts_type, "AttachmentPaths | null");
+        assert_eq!(export.ts_returns, "NormalizedExport");
+    }
+}
@@ -2,13 +2,24 @@ use tauri::{State, AppHandle, Emitter};
 use std::collections::HashSet;
 
 use crate::types::{
-    note::{Note, CreateNoteRequest, UpdateNoteRequest},
+    note::{assign_positions, move_positions, note_order, Note, CreateNoteRequest, UpdateNoteRequest},
     window::{NotesState, ConfigState},
 };
 use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::formatting::apply_save_transforms;
+use crate::modules::note_sync::{compute_text_delta, publish_note_sync, NoteSyncPayload};
+use crate::modules::workspace_settings::effective_config;
+use crate::modules::windows::refresh_note_window_titles;
 use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::sticky_notes::is_sticky;
+use crate::modules::note_limits::check_note_size;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::audit_log::{record_audit, AuditAction, AuditEntry, AuditSource};
+use crate::modules::undo::{record_undo, UndoKind};
+use crate::{UndoHistoryState, VaultLockState};
 use crate::utils::{generate_unique_slug, uuid_from_slug};
 use crate::{log_info, log_error, log_debug};
+use crate::modules::window_capabilities::{authorize, Capability};
 
 /// Helper function to save all notes using FileNotesStorage
 async fn save_all_notes_using_file_storage(
@@ -36,13 +47,19 @@ pub async fn get_notes_directory(config: State<'_, ConfigState>) -> Result<Strin
     Ok(notes_dir.to_string_lossy().to_string())
 }
 
-/// Get all notes, sorted by position (manual ordering)
+/// Get all notes, sorted by position (manual ordering). Stickies are left out
+/// unless `include_sticky` is set.
 #[tauri::command]
-pub async fn get_notes(notes: State<'_, NotesState>) -> Result<Vec<Note>, String> {
+pub async fn get_notes(include_sticky: Option<bool>, notes: State<'_, NotesState>) -> Result<Vec<Note>, String> {
     log_info!("GET_NOTES", "🔍 Frontend requested notes list");
     
     let notes_lock = notes.lock().await;
-    let mut notes_vec: Vec<Note> = notes_lock.values().cloned().collect();
+    let include_sticky = include_sticky.unwrap_or(false);
+    let mut notes_vec: Vec<Note> = notes_lock
+        .values()
+        .filter(|note| include_sticky || !is_sticky(note))
+        .cloned()
+        .collect();
     
     log_info!("GET_NOTES", "📋 Found {} notes in memory", notes_vec.len());
     for note in &notes_vec {
@@ -50,16 +67,7 @@ pub async fn get_notes(notes: State<'_, NotesState>) -> Result<Vec<Note>, String
         log_debug!("GET_NOTES", "  - {} ({}) pos={:?}", note.title, id_display, note.position);
     }
     
-    // Sort by position (ascending), with None values at the end
-    // For notes without position, maintain original order (don't sort by updated_at)
-    notes_vec.sort_by(|a, b| {
-        match (a.position, b.position) {
-            (Some(pos_a), Some(pos_b)) => pos_a.cmp(&pos_b),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Equal, // Maintain original order
-        }
-    });
+    notes_vec.sort_by(note_order);
     
     log_info!("GET_NOTES", "✅ Returning {} notes to frontend (sorted by position)", notes_vec.len());
     Ok(notes_vec)
@@ -75,12 +83,17 @@ pub async fn get_note(id: String, notes: State<'_, NotesState>) -> Result<Option
 /// Create a new note
 #[tauri::command]
 pub async fn create_note(
+    window: tauri::Window,
     app: AppHandle,
     request: CreateNoteRequest,
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
     modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
 ) -> Result<Note, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
     
@@ -110,6 +123,7 @@ pub async fn create_note(
         updated_at: now,
         tags: request.tags,
         position: Some(max_position + 1),
+        icon: None,
     };
     
     notes_lock.insert(note.id.clone(), note.clone());
@@ -121,6 +135,9 @@ pub async fn create_note(
     modified_tracker.initialize_note(&note).await;
     
     log_info!("NOTES", "Created note: {} ({})", note.title, note.id);
+    record_audit(&config_lock, AuditEntry::new(
+        AuditAction::Create, AuditSource::Command, Some(&note.id), format!("Created \"{}\"", note.title),
+    ));
     
     // Emit event to all windows for synchronization
     app.emit("note-created", &note).unwrap_or_else(|e| {
@@ -130,19 +147,123 @@ pub async fn create_note(
     Ok(note)
 }
 
+/// Create a note generated by a command (meeting notes, reviews) at the end of
+/// the list, optionally inside `folder`, and announce it like `create_note` does
+pub(crate) async fn insert_generated_note(
+    app: &AppHandle,
+    request: CreateNoteRequest,
+    folder: Option<&str>,
+    notes: &NotesState,
+    config: &ConfigState,
+    modified_tracker: &ModifiedStateTracker,
+) -> Result<Note, String> {
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+
+    let max_position = notes_lock.values().filter_map(|n| n.position).max().unwrap_or(-1);
+    let existing_slugs: HashSet<String> = notes_lock.values()
+        .map(|n| crate::utils::generate_slug(&n.title))
+        .collect();
+    let id = uuid_from_slug(&generate_unique_slug(&request.title, &existing_slugs));
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let note = Note {
+        id,
+        title: request.title,
+        content: request.content,
+        created_at: now.clone(),
+        updated_at: now,
+        tags: request.tags,
+        position: Some(max_position + 1),
+        icon: None,
+    };
+
+    FileNotesStorage::new(&config_lock)?.save_note_in(&note, folder).await?;
+
+    notes_lock.insert(note.id.clone(), note.clone());
+    modified_tracker.initialize_note(&note).await;
+
+    log_info!("NOTES", "Created note: {} ({})", note.title, note.id);
+    record_audit(&config_lock, AuditEntry::new(
+        AuditAction::Create, AuditSource::Command, Some(&note.id), format!("Created \"{}\"", note.title),
+    ));
+    app.emit("note-created", &note).unwrap_or_else(|e| {
+        log_error!("NOTES", "Failed to emit note-created event: {}", e);
+    });
+
+    Ok(note)
+}
+
+/// Append a block of markdown to a note from the backend (voice memo links,
+/// transcripts) and tell the windows showing it
+pub(crate) async fn append_to_note(
+    app: &AppHandle,
+    note_id: &str,
+    markdown: &str,
+    notes: &NotesState,
+    config: &ConfigState,
+    modified_tracker: &ModifiedStateTracker,
+) -> Result<Note, String> {
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+    let note = notes_lock.get_mut(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let separator = if note.content.trim_end().is_empty() { "" } else { "\n\n" };
+    note.content = format!("{}{}{}\n", note.content.trim_end(), separator, markdown.trim());
+    note.updated_at = chrono::Utc::now().to_rfc3339();
+    let updated_note = note.clone();
+
+    save_note_using_file_storage(&updated_note, &config_lock).await?;
+    modified_tracker.update_content_hash(note_id, &updated_note.content).await;
+    app.emit("note-updated", &updated_note).unwrap_or_else(|e| {
+        log_error!("NOTES", "Failed to emit note-updated event: {}", e);
+    });
+    Ok(updated_note)
+}
+
 /// Update an existing note
 #[tauri::command]
 pub async fn update_note(
     app: AppHandle,
+    window: tauri::Window,
     id: String,
     request: UpdateNoteRequest,
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
     modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Option<Note>, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    apply_note_update(&app, window.label(), id, request, &notes, &config, &modified_tracker).await
+}
+
+/// Apply an edit to a note and tell the other windows. `writer` is the label
+/// of the window the edit came from.
+pub(crate) async fn apply_note_update(
+    app: &AppHandle,
+    writer: &str,
+    id: String,
+    mut request: UpdateNoteRequest,
+    notes: &NotesState,
+    config: &ConfigState,
+    modified_tracker: &ModifiedStateTracker,
 ) -> Result<Option<Note>, String> {
+    // Turn away pathological pastes before they reach formatting, hashing and disk
+    if let Some(content) = request.content.as_deref().filter(|_| !request.allow_large) {
+        check_note_size(content)?;
+    }
+    
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
     
+    // Run format-on-save transforms before hashing so formatting-only edits settle.
+    // Formatting may be overridden per vault, so use the effective config here.
+    if let Some(content) = request.content.take() {
+        let formatting = effective_config(&config_lock).formatting;
+        request.content = Some(apply_save_transforms(&content, &formatting));
+    }
+    
     if let Some(note) = notes_lock.get_mut(&id) {
         // Check if content has actually changed
         let content_changed = if let Some(ref new_content) = request.content {
@@ -157,6 +278,16 @@ pub async fn update_note(
         
         // Only update if something actually changed
         if content_changed || title_changed || tags_changed {
+            let previous_content = note.content.clone();
+            let previous_title = note.title.clone();
+            let previous_hash = modified_tracker.get_content_hash(&id).await;
+            // Last writer wins, but tell the other windows their version was overwritten
+            let conflict = content_changed
+                && modified_tracker.is_stale_base(&id, request.base_hash.as_deref()).await;
+            if conflict {
+                log_info!("NOTES", "⚠️ Concurrent edit on note {} from {}, last writer wins", id, writer);
+            }
+            
             if let Some(title) = request.title {
                 note.title = title;
             }
@@ -176,6 +307,7 @@ pub async fn update_note(
                 save_note_using_file_storage(&updated_note, &config_lock).await?;
                 // Update the content hash after successful save
                 modified_tracker.update_content_hash(&id, &updated_note.content).await;
+                modified_tracker.record_writer(&id, writer).await;
                 modified_tracker.clear_modified(&id).await;
             } else if title_changed || tags_changed {
                 // For title/tags only changes, still save but log differently
@@ -183,11 +315,51 @@ pub async fn update_note(
                 save_note_using_file_storage(&updated_note, &config_lock).await?;
             }
             
+            if title_changed {
+                record_audit(&config_lock, AuditEntry::new(
+                    AuditAction::Rename, AuditSource::Command, Some(&id),
+                    format!("Renamed \"{}\" to \"{}\"", previous_title, updated_note.title),
+                ).with_actor(writer));
+            }
+            if content_changed || tags_changed {
+                let mut changes = Vec::new();
+                if content_changed {
+                    changes.push(format!("content {} → {} chars", previous_content.chars().count(), updated_note.content.chars().count()));
+                }
+                if tags_changed {
+                    changes.push(format!("tags [{}]", updated_note.tags.join(", ")));
+                }
+                record_audit(&config_lock, AuditEntry::new(
+                    AuditAction::Update, AuditSource::Command, Some(&id),
+                    format!("Updated {}", changes.join(", ")),
+                ).with_actor(writer));
+            }
+            
             // Emit event to all windows for synchronization
             app.emit("note-updated", &updated_note).unwrap_or_else(|e| {
                 log_error!("NOTES", "Failed to emit note-updated event: {}", e);
             });
             
+            // Push the content delta to the other windows showing this note.
+            // Release the note/config locks first; publishing reads the window state.
+            drop(config_lock);
+            drop(notes_lock);
+            if content_changed {
+                let payload = NoteSyncPayload {
+                    note_id: id.clone(),
+                    source_window: Some(writer.to_string()),
+                    previous_hash,
+                    content_hash: ModifiedStateTracker::compute_content_hash(&updated_note.content),
+                    delta: compute_text_delta(&previous_content, &updated_note.content),
+                    conflict,
+                    updated_at: updated_note.updated_at.clone(),
+                };
+                publish_note_sync(app, &payload).await;
+            }
+            if title_changed {
+                refresh_note_window_titles(app, &updated_note).await;
+            }
+            
             Ok(Some(updated_note))
         } else {
             log_debug!("NOTES", "No changes detected for note: {} ({})", note.title, note.id);
@@ -202,17 +374,24 @@ pub async fn update_note(
 /// Delete a note
 #[tauri::command]
 pub async fn delete_note(
+    window: tauri::Window,
     app: AppHandle,
     id: String, 
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
     modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
 ) -> Result<bool, String> {
+    authorize(&window, Capability::DeleteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
-    let removed = notes_lock.remove(&id).is_some();
+    let removed_note = notes_lock.remove(&id);
+    let removed = removed_note.is_some();
     
-    if removed {
+    if let Some(note) = removed_note {
         // Delete using file storage (this handles everything including index updates)
         let file_storage = FileNotesStorage::new(&config_lock)?;
         file_storage.delete_note(&id).await?;
@@ -221,6 +400,10 @@ pub async fn delete_note(
         modified_tracker.remove_note(&id).await;
         
         log_info!("NOTES", "Deleted note: {}", id);
+        record_audit(&config_lock, AuditEntry::new(
+            AuditAction::Delete, AuditSource::Command, Some(&id), format!("Deleted \"{}\"", note.title),
+        ));
+        record_undo(&history, UndoKind::Delete, format!("Delete \"{}\"", note.title), vec![note]);
         
         // Emit event to all windows for synchronization
         app.emit("note-deleted", &id).unwrap_or_else(|e| {
@@ -236,16 +419,26 @@ pub async fn delete_note(
 /// Update note positions for manual reordering
 #[tauri::command]
 pub async fn reorder_notes(
+    window: tauri::Window,
     note_ids: Vec<String>,
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
 ) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
     
     // Update positions based on the order in note_ids
+    let mut before = Vec::new();
     for (index, note_id) in note_ids.iter().enumerate() {
         if let Some(note) = notes_lock.get_mut(note_id) {
+            if note.position != Some(index as i32) {
+                before.push(note.clone());
+            }
             note.position = Some(index as i32);
         }
     }
@@ -253,10 +446,79 @@ pub async fn reorder_notes(
     // Save all notes since multiple positions changed
     save_all_notes_using_file_storage(&notes_lock, &config_lock).await?;
     log_info!("NOTES", "Reordered {} notes", note_ids.len());
+    record_audit(&config_lock, AuditEntry::new(
+        AuditAction::Reorder, AuditSource::Command, None, format!("Reordered {} notes", note_ids.len()),
+    ));
+    record_undo(&history, UndoKind::Reorder, format!("Reorder {} notes", before.len()), before);
     
     Ok(())
 }
 
+/// Move a selection of notes, adjacent or not, to `target_index` in the list
+/// of the other notes. Usually only the moved notes get new positions.
+#[tauri::command]
+pub async fn move_notes(
+    window: tauri::Window,
+    note_ids: Vec<String>,
+    target_index: usize,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
+) -> Result<Vec<String>, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+
+    let snapshot = notes_lock.clone();
+    let changed = move_positions(&mut notes_lock, &note_ids, target_index);
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+    save_all_notes_using_file_storage(&notes_lock, &config_lock).await?;
+    log_info!("NOTES", "Moved {} notes to index {}, {} positions changed", note_ids.len(), target_index, changed.len());
+    record_audit(&config_lock, AuditEntry::new(
+        AuditAction::Reorder, AuditSource::Command, None, format!("Moved {} notes", note_ids.len()),
+    ));
+    let before = changed.iter().filter_map(|id| snapshot.get(id).cloned()).collect();
+    record_undo(&history, UndoKind::Reorder, format!("Move {} notes", note_ids.len()), before);
+
+    Ok(changed)
+}
+
+/// Give every note an explicit position, keeping the current list order
+#[tauri::command]
+pub async fn normalize_positions(
+    window: tauri::Window,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
+) -> Result<usize, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+
+    let snapshot = notes_lock.clone();
+    let changed = assign_positions(&mut notes_lock);
+    if changed.is_empty() {
+        return Ok(0);
+    }
+    save_all_notes_using_file_storage(&notes_lock, &config_lock).await?;
+    log_info!("NOTES", "Normalized positions, {} notes moved", changed.len());
+    record_audit(&config_lock, AuditEntry::new(
+        AuditAction::Reorder, AuditSource::Command, None, format!("Normalized positions of {} notes", changed.len()),
+    ));
+    let before = changed.iter().filter_map(|id| snapshot.get(id).cloned()).collect();
+    record_undo(&history, UndoKind::Reorder, "Normalize note positions".to_string(), before);
+
+    Ok(changed.len())
+}
+
 /// Test database migration (temporary command for testing)
 #[tauri::command]
 pub async fn test_database_migration(
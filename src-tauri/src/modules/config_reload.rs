@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::date_format::set_date_format;
+use crate::modules::file_storage::{has_note_extension, set_note_extensions};
+use crate::modules::power_mode::is_low_power;
+use crate::modules::storage::{get_configured_notes_directory, get_notes_directory, save_config_to_disk};
+use crate::modules::vault_lock::switch_vault_lock;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::startup::onboarding::validate_shortcut;
+use crate::types::config::AppConfig;
+use crate::types::window::ConfigState;
+use crate::{log_error, log_info, log_warn, ConfigReloadState};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Editors save in several steps; wait for them to settle before reading
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Payload of `config-reload-failed`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadError {
+    pub path: String,
+    pub error: String,
+}
+
+/// Payload of `config-reload-pending`: an edit that waits for `resolve_config_reload`
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingConfigReload {
+    pub changes: Vec<String>,
+}
+
+/// Reject values the app can't run with
+pub fn validate_config(config: &AppConfig) -> Result<(), String> {
+    if !(config.opacity > 0.0 && config.opacity <= 1.0) {
+        return Err(format!("opacity must be between 0 and 1, got {}", config.opacity));
+    }
+    if !(config.window.width > 0.0 && config.window.height > 0.0) {
+        return Err("window width and height must be positive".to_string());
+    }
+    validate_shortcut(&config.shortcuts.toggle_visibility)?;
+    if !(1..=100).contains(&config.attachments.jpeg_quality) {
+        return Err(format!("attachments.jpegQuality must be 1-100, got {}", config.attachments.jpeg_quality));
+    }
+    if config.attachments.max_image_dimension == 0 {
+        return Err("attachments.maxImageDimension must be positive".to_string());
+    }
+    config.new_windows.validate()?;
+    let extensions = &config.storage.note_extensions;
+    if extensions.is_empty() || extensions.iter().any(|ext| ext.trim_start_matches('.').is_empty()) {
+        return Err("storage.noteExtensions must list at least one extension".to_string());
+    }
+    if !has_note_extension(Path::new(&format!("note.{}", config.storage.default_extension.trim_start_matches('.'))), extensions) {
+        return Err(format!("storage.defaultExtension must be one of storage.noteExtensions, got {}", config.storage.default_extension));
+    }
+    if config.storage.use_custom_directory {
+        match config.storage.notes_directory.as_deref() {
+            Some(dir) if Path::new(dir).is_absolute() => {}
+            Some(dir) => return Err(format!("storage.notesDirectory must be an absolute path: {}", dir)),
+            None => return Err("storage.notesDirectory is required when useCustomDirectory is set".to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Parse and validate the contents of `config.json`
+pub fn parse_config(json: &str) -> Result<AppConfig, String> {
+    let config: AppConfig = serde_json::from_str(json).map_err(|e| format!("Invalid config: {}", e))?;
+    validate_config(&config)?;
+    Ok(config)
+}
+
+/// Changed settings that move or reopen the vault, so the user has to confirm them
+pub fn changes_needing_confirmation(old: &AppConfig, new: &AppConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.storage.notes_directory != new.storage.notes_directory
+        || old.storage.use_custom_directory != new.storage.use_custom_directory
+    {
+        changes.push("storage.notesDirectory".to_string());
+    }
+    if old.read_only != new.read_only {
+        changes.push("readOnly".to_string());
+    }
+    changes
+}
+
+fn same_config(a: &AppConfig, b: &AppConfig) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Make `new_config` the running config. Settings saves and edits to the file
+/// both come through here; `source` says which, for the log.
+pub(crate) async fn apply_config(app: &AppHandle, new_config: AppConfig, source: &str) -> Result<(), String> {
+    let config = app.state::<ConfigState>();
+    let mut config_lock = config.lock().await;
+    let old_config = std::mem::replace(&mut *config_lock, new_config.clone());
+    drop(config_lock);
+
+    set_date_format(&new_config.date_format);
+    crate::modules::logging::set_log_levels(&new_config.logging);
+    set_note_extensions(&new_config.storage);
+    if old_config.startup.launch_at_login != new_config.startup.launch_at_login {
+        crate::startup::sync_launch_at_login(app, new_config.startup.launch_at_login);
+    }
+    if !changes_needing_confirmation(&old_config, &new_config).is_empty() && !new_config.read_only {
+        let notes_dir = get_configured_notes_directory(&new_config)?;
+        switch_vault_lock(app, &notes_dir).await?;
+    }
+
+    let _ = app.emit("config-reloaded", &new_config);
+    log_info!("CONFIG", "Applied config {}", source);
+    Ok(())
+}
+
+/// Read the config file after an external edit and apply it, or report why not
+async fn reload_config(app: &AppHandle, path: &Path) {
+    let result = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config: {}", e))
+        .and_then(|json| parse_config(&json));
+    let new_config = match result {
+        Ok(config) => config,
+        Err(error) => {
+            log_warn!("CONFIG", "Ignoring edited config: {}", error);
+            let payload = ConfigReloadError { path: path.to_string_lossy().to_string(), error };
+            let _ = app.emit("config-reload-failed", &payload);
+            return;
+        }
+    };
+
+    let current = app.state::<ConfigState>().lock().await.clone();
+    // Our own saves land here too
+    if same_config(&current, &new_config) {
+        return;
+    }
+
+    let changes = changes_needing_confirmation(&current, &new_config);
+    if !changes.is_empty() {
+        log_info!("CONFIG", "Config edit needs confirmation: {:?}", changes);
+        *app.state::<ConfigReloadState>().lock().unwrap() = Some(new_config);
+        let _ = app.emit("config-reload-pending", &PendingConfigReload { changes });
+        return;
+    }
+
+    if let Err(e) = apply_config(app, new_config, "edited on disk").await {
+        log_error!("CONFIG", "Failed to apply edited config: {}", e);
+    }
+}
+
+/// The running watcher and the directory it watches, kept so low power mode
+/// can pause it
+static CONFIG_WATCHER: Mutex<Option<(RecommendedWatcher, PathBuf)>> = Mutex::new(None);
+
+/// Watch `config.json` for edits made outside Blink
+pub fn start_config_watcher(app: AppHandle) -> Result<(), String> {
+    let dir = get_notes_directory()?;
+    let path: PathBuf = dir.join(CONFIG_FILE_NAME);
+
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| format!("Failed to create config watcher: {}", e))?;
+    // Watch the directory: editors often replace the file rather than write to it
+    if is_low_power() {
+        log_info!("CONFIG", "Low power mode, config watcher starts paused");
+    } else {
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {:?}: {}", dir, e))?;
+        log_info!("CONFIG", "Watching {:?} for edits", path);
+    }
+    *CONFIG_WATCHER.lock().unwrap() = Some((watcher, dir));
+
+    std::thread::spawn(move || {
+        let touches_config = |event: &notify::Result<notify::Event>| {
+            event.as_ref().is_ok_and(|e| e.paths.iter().any(|p| p.file_name() == path.file_name()))
+        };
+        while let Ok(event) = rx.recv() {
+            if !touches_config(&event) {
+                continue;
+            }
+            while rx.recv_timeout(SETTLE_DELAY).is_ok() {}
+            tauri::async_runtime::block_on(reload_config(&app, &path));
+        }
+    });
+    Ok(())
+}
+
+/// Stop or restart watching the config file. On resume the file is read once,
+/// so edits made while paused aren't missed.
+pub fn set_config_watcher_paused(app: &AppHandle, paused: bool) {
+    let mut guard = CONFIG_WATCHER.lock().unwrap();
+    let Some((watcher, dir)) = guard.as_mut() else { return };
+    let result = if paused { watcher.unwatch(dir) } else { watcher.watch(dir, RecursiveMode::NonRecursive) };
+    if let Err(e) = result {
+        log_warn!("CONFIG", "Failed to {} config watcher: {}", if paused { "pause" } else { "resume" }, e);
+        return;
+    }
+    if paused {
+        log_info!("CONFIG", "Paused config watcher");
+        return;
+    }
+
+    let path = dir.join(CONFIG_FILE_NAME);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        reload_config(&app, &path).await;
+    });
+    log_info!("CONFIG", "Resumed config watcher");
+}
+
+/// Apply or discard a config edit that changed the notes directory. Discarding
+/// writes the running config back so the file matches it again.
+#[tauri::command]
+pub async fn resolve_config_reload(
+    window: tauri::Window,
+    app: AppHandle,
+    accept: bool,
+    config: State<'_, ConfigState>,
+    pending: State<'_, ConfigReloadState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::ManageVault)?;
+    let Some(new_config) = pending.lock().unwrap().take() else {
+        return Err("No config edit is waiting for confirmation".to_string());
+    };
+
+    if accept {
+        apply_config(&app, new_config, "edited on disk").await
+    } else {
+        let current = config.lock().await.clone();
+        save_config_to_disk(&current).await?;
+        log_info!("CONFIG", "Discarded config edit");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_validates() {
+        let mut config = AppConfig::default();
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).is_ok());
+
+        assert!(parse_config("{ \"opacity\": ").unwrap_err().starts_with("Invalid config"));
+
+        config.opacity = 1.5;
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).unwrap_err().contains("opacity"));
+
+        config.opacity = 0.9;
+        config.shortcuts.toggle_visibility = "Hyper+?".to_string();
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).is_err());
+
+        config.shortcuts.toggle_visibility = AppConfig::default().shortcuts.toggle_visibility;
+        config.storage.note_extensions = vec!["md".to_string(), ".TXT".to_string()];
+        config.storage.default_extension = "txt".to_string();
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).is_ok());
+        config.storage.default_extension = "markdown".to_string();
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).unwrap_err().contains("defaultExtension"));
+    }
+
+    #[test]
+    fn test_new_window_settings_are_validated() {
+        let mut config = AppConfig::default();
+        config.new_windows.width = 200.0;
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).unwrap_err().contains("newWindows"));
+
+        config.new_windows = crate::types::config::default_new_windows();
+        config.new_windows.max_cascade = 0;
+        assert!(parse_config(&serde_json::to_string(&config).unwrap()).unwrap_err().contains("maxCascade"));
+
+        config.new_windows.max_cascade = 3;
+        assert_eq!(config.new_windows.cascade_offset(1), 30.0);
+        assert_eq!(config.new_windows.cascade_offset(3), 0.0);
+    }
+
+    #[test]
+    fn test_directory_changes_need_confirmation() {
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.opacity = 0.8;
+        new.shortcuts.toggle_visibility = "CommandOrControl+Shift+J".to_string();
+        assert!(changes_needing_confirmation(&old, &new).is_empty());
+
+        new.storage.use_custom_directory = true;
+        new.storage.notes_directory = Some("/tmp/vault".to_string());
+        assert_eq!(changes_needing_confirmation(&old, &new), ["storage.notesDirectory"]);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::{BlinkError, BlinkResult};
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::state::ConfirmationTokenState;
+use crate::log_info;
+
+/// How long an issued token stays valid
+const CONFIRMATION_TOKEN_TTL: Duration = Duration::from_secs(30);
+
+/// Actions that must be confirmed with a token before they run
+pub const DESTRUCTIVE_ACTIONS: &[&str] = &["clear_all_detached_windows", "compact_vault", "gc_attachments"];
+
+/// What a window must be allowed to do to ask for a token for `action`.
+/// Clearing detached windows only touches saved window state.
+pub fn action_capability(action: &str) -> Capability {
+    match action {
+        "clear_all_detached_windows" => Capability::WriteConfig,
+        _ => Capability::ManageVault,
+    }
+}
+
+/// An issued, not yet used confirmation
+#[derive(Debug, Clone)]
+pub struct PendingConfirmation {
+    pub action: String,
+    pub expires_at: Instant,
+}
+
+/// Token returned to the frontend, to be passed back with the destructive call
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationToken {
+    pub token: String,
+    pub action: String,
+    pub expires_in_ms: u64,
+}
+
+/// Issue a single-use token for `action`, dropping any expired ones
+pub fn issue_token(
+    pending: &mut HashMap<String, PendingConfirmation>,
+    action: &str,
+    now: Instant,
+) -> BlinkResult<ConfirmationToken> {
+    if !DESTRUCTIVE_ACTIONS.contains(&action) {
+        return Err(BlinkError::InvalidOperation(format!("'{}' does not take a confirmation token", action)));
+    }
+    pending.retain(|_, p| p.expires_at > now);
+
+    let token = uuid::Uuid::new_v4().to_string();
+    pending.insert(token.clone(), PendingConfirmation {
+        action: action.to_string(),
+        expires_at: now + CONFIRMATION_TOKEN_TTL,
+    });
+    Ok(ConfirmationToken {
+        token,
+        action: action.to_string(),
+        expires_in_ms: CONFIRMATION_TOKEN_TTL.as_millis() as u64,
+    })
+}
+
+/// Check and consume a token. A token is spent even when it was issued for another action.
+pub fn consume_token(
+    pending: &mut HashMap<String, PendingConfirmation>,
+    token: Option<&str>,
+    action: &str,
+    now: Instant,
+) -> BlinkResult<()> {
+    let confirmation = token
+        .and_then(|t| pending.remove(t))
+        .ok_or_else(|| BlinkError::ConfirmationRequired(action.to_string()))?;
+
+    if confirmation.action != action || confirmation.expires_at <= now {
+        return Err(BlinkError::ConfirmationRequired(action.to_string()));
+    }
+    Ok(())
+}
+
+/// Guard for destructive commands: fails unless `token` was issued for `action`
+pub async fn require_confirmation(
+    tokens: &ConfirmationTokenState,
+    token: Option<&str>,
+    action: &str,
+) -> Result<(), String> {
+    let mut pending = tokens.lock().await;
+    consume_token(&mut pending, token, action, Instant::now())?;
+    log_info!("CONFIRM", "Confirmed destructive action '{}'", action);
+    Ok(())
+}
+
+/// Request a short-lived token that must accompany a destructive command
+#[tauri::command]
+pub async fn request_confirmation_token(
+    window: tauri::Window,
+    action: String,
+    tokens: State<'_, ConfirmationTokenState>,
+) -> Result<ConfirmationToken, String> {
+    authorize(&window, action_capability(&action))?;
+    let mut pending = tokens.lock().await;
+    Ok(issue_token(&mut pending, &action, Instant::now())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_single_use() {
+        let mut pending = HashMap::new();
+        let now = Instant::now();
+        let issued = issue_token(&mut pending, "compact_vault", now).unwrap();
+
+        assert!(consume_token(&mut pending, Some(&issued.token), "compact_vault", now).is_ok());
+        assert!(consume_token(&mut pending, Some(&issued.token), "compact_vault", now).is_err());
+    }
+
+    #[test]
+    fn test_token_is_bound_to_action_and_expires() {
+        let mut pending = HashMap::new();
+        let now = Instant::now();
+
+        let issued = issue_token(&mut pending, "compact_vault", now).unwrap();
+        assert!(consume_token(&mut pending, Some(&issued.token), "clear_all_detached_windows", now).is_err());
+
+        let issued = issue_token(&mut pending, "compact_vault", now).unwrap();
+        let later = now + CONFIRMATION_TOKEN_TTL + Duration::from_secs(1);
+        assert!(consume_token(&mut pending, Some(&issued.token), "compact_vault", later).is_err());
+
+        assert!(consume_token(&mut pending, None, "compact_vault", now).is_err());
+        assert!(issue_token(&mut pending, "get_notes", now).is_err());
+    }
+
+    #[test]
+    fn test_tokens_need_the_action_capability() {
+        use crate::modules::window_capabilities::WindowRole;
+
+        for action in DESTRUCTIVE_ACTIONS {
+            assert!(WindowRole::Main.allows(action_capability(action)));
+            assert!(!WindowRole::Note.allows(action_capability(action)));
+        }
+        assert!(WindowRole::Settings.allows(action_capability("compact_vault")));
+    }
+}
@@ -2,9 +2,13 @@ use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+use crate::modules::related_notes::term_counts;
+use crate::types::workspace::SavedFilter;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NoteRecord {
     pub id: String,
@@ -17,6 +21,69 @@ pub struct NoteRecord {
     pub file_hash: String,
 }
 
+impl NoteRecord {
+    /// Whether any indexed field differs, so the row needs rewriting
+    fn differs_from(&self, other: &NoteRecord) -> bool {
+        self.file_hash != other.file_hash
+            || self.title != other.title
+            || self.position != other.position
+            || self.file_path != other.file_path
+            || self.tags != other.tags
+            || self.updated_at != other.updated_at
+    }
+}
+
+/// Rows to write and delete to bring the index in line with the vault
+#[derive(Debug, Default)]
+pub struct IndexDiff {
+    pub upserts: Vec<NoteRecord>,
+    pub deletes: Vec<String>,
+}
+
+impl IndexDiff {
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.deletes.is_empty()
+    }
+}
+
+/// Compare the indexed rows with the notes in memory. Rows are only deleted
+/// when their note is gone from memory and has no file on disk.
+pub fn diff_index(existing: Vec<NoteRecord>, desired: Vec<NoteRecord>, on_disk: &HashSet<String>) -> IndexDiff {
+    let mut existing: HashMap<String, NoteRecord> = existing.into_iter().map(|r| (r.id.clone(), r)).collect();
+    let mut diff = IndexDiff::default();
+    for record in desired {
+        match existing.remove(&record.id) {
+            Some(current) if !current.differs_from(&record) => {}
+            _ => diff.upserts.push(record),
+        }
+    }
+    diff.deletes = existing.into_keys().filter(|id| !on_disk.contains(id)).collect();
+    diff
+}
+
+/// A row of the content cache: a note file's text and what it was read at
+#[derive(Debug, Clone)]
+pub struct CachedContent {
+    pub file_hash: String,
+    /// Modification time in milliseconds since the epoch
+    pub mtime: i64,
+    pub size: i64,
+    pub content: String,
+}
+
+/// A row of the file stat cache: what a note file's body hashed to when it
+/// had this modification time and size
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStat {
+    pub stem: String,
+    /// Modification time in milliseconds since the epoch
+    pub mtime: i64,
+    pub size: i64,
+    pub body_hash: String,
+    /// When the file was hashed, in milliseconds since the epoch
+    pub checked_at: i64,
+}
+
 pub struct NotesDatabase {
     conn: Mutex<Connection>,
 }
@@ -138,6 +205,85 @@ impl NotesDatabase {
             [],
         )?;
         
+        // Text recognized in attachments, keyed by file name. Kept across index
+        // rebuilds because OCR is slow to redo.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachment_text (
+                file_name TEXT PRIMARY KEY NOT NULL,
+                text TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Emoji icons chosen per note; markdown files have nowhere to keep them
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_icons (
+                note_id TEXT PRIMARY KEY NOT NULL,
+                icon TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Raw text of each note file as last read, so a cold start can skip
+        // reading files whose modification time and size are unchanged
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_content (
+                file_path TEXT PRIMARY KEY NOT NULL,
+                file_hash TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                content TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Body hash of each note file by modification time and size, so vault
+        // verification only reads files that changed since they were hashed
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS file_stats (
+                file_path TEXT PRIMARY KEY NOT NULL,
+                stem TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                body_hash TEXT NOT NULL,
+                checked_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Other paths that lead to a note's file (symlinks, hard links), so
+        // deletes can remove them and scans don't mistake them for notes
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_aliases (
+                alias_path TEXT PRIMARY KEY NOT NULL,
+                note_id TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Counts of the distinctive words in each note, written with the search
+        // text, for scoring related notes by term overlap
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_terms (
+                note_id TEXT NOT NULL,
+                term TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                PRIMARY KEY (note_id, term)
+            )",
+            [],
+        )?;
+        
+        // Full-text search over titles and content. Search is optional, so a
+        // SQLite build without FTS5 must not prevent the index from opening.
+        if let Err(e) = conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(id UNINDEXED, title, content)",
+            [],
+        ) {
+            log::warn!("Full-text search unavailable: {}", e);
+        }
+        
         // Check current schema and migrate if needed
         Self::migrate_schema(&conn)?;
         
@@ -257,13 +403,328 @@ impl NotesDatabase {
         Ok(())
     }
     
+    /// Apply an index diff in one transaction
+    pub fn apply_index_diff(&self, diff: &IndexDiff) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for note in &diff.upserts {
+            tx.execute(
+                "INSERT OR REPLACE INTO notes 
+                 (id, title, file_path, created_at, updated_at, tags, position, file_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    note.id,
+                    note.title,
+                    note.file_path,
+                    note.created_at.to_rfc3339(),
+                    note.updated_at.to_rfc3339(),
+                    serde_json::to_string(&note.tags)?,
+                    note.position,
+                    note.file_hash,
+                ],
+            )?;
+        }
+        for id in &diff.deletes {
+            tx.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+            let _ = tx.execute("DELETE FROM notes_fts WHERE id = ?1", params![id]);
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// The whole content cache, keyed by path relative to the vault
+    pub fn get_cached_contents(&self) -> Result<HashMap<String, CachedContent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT file_path, file_hash, mtime, size, content FROM note_content")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, CachedContent {
+                file_hash: row.get(1)?,
+                mtime: row.get(2)?,
+                size: row.get(3)?,
+                content: row.get(4)?,
+            }))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+    
+    /// Cache the text of one note file as it was just written
+    pub fn cache_content(&self, file_path: &str, cached: &CachedContent) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO note_content (file_path, file_hash, mtime, size, content)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![file_path, cached.file_hash, cached.mtime, cached.size, cached.content],
+        )?;
+        Ok(())
+    }
+    
+    /// Write fresh cache rows and drop the rows of files that are gone
+    pub fn update_cached_contents(&self, fresh: &[(String, CachedContent)], present: &HashSet<String>) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (path, cached) in fresh {
+            tx.execute(
+                "INSERT OR REPLACE INTO note_content (file_path, file_hash, mtime, size, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![path, cached.file_hash, cached.mtime, cached.size, cached.content],
+            )?;
+        }
+        let stale: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT file_path FROM note_content")?;
+            let paths = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            paths.into_iter().filter(|p| !present.contains(p)).collect()
+        };
+        for path in stale {
+            tx.execute("DELETE FROM note_content WHERE file_path = ?1", params![path])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// The whole file stat cache, keyed by path relative to the vault
+    pub fn get_file_stats(&self) -> Result<HashMap<String, FileStat>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT file_path, stem, mtime, size, body_hash, checked_at FROM file_stats")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, FileStat {
+                stem: row.get(1)?,
+                mtime: row.get(2)?,
+                size: row.get(3)?,
+                body_hash: row.get(4)?,
+                checked_at: row.get(5)?,
+            }))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+    
+    /// Write freshly hashed files and drop the rows of files that are gone
+    pub fn update_file_stats(&self, fresh: &[(String, FileStat)], present: &HashSet<String>) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (path, stat) in fresh {
+            tx.execute(
+                "INSERT OR REPLACE INTO file_stats (file_path, stem, mtime, size, body_hash, checked_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![path, stat.stem, stat.mtime, stat.size, stat.body_hash, stat.checked_at],
+            )?;
+        }
+        let stale: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT file_path FROM file_stats")?;
+            let paths = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            paths.into_iter().filter(|p| !present.contains(p)).collect()
+        };
+        for path in stale {
+            tx.execute("DELETE FROM file_stats WHERE file_path = ?1", params![path])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Store a value in the metadata table
+    pub fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value, updated_at) VALUES (?1, ?2, ?3)",
+            params![key, value, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+    
+    /// A metadata value and when it was set
+    pub fn get_metadata(&self, key: &str) -> Result<Option<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.query_row(
+            "SELECT value, updated_at FROM metadata WHERE key = ?1",
+            params![key],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?)
+    }
+    
     /// Delete a note by ID
     pub fn delete_note(&self, id: &str) -> Result<bool> {
         let conn = self.conn.lock().unwrap();
         let rows_affected = conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM note_terms WHERE note_id = ?1", params![id])?;
+        let _ = conn.execute("DELETE FROM notes_fts WHERE id = ?1", params![id]);
         Ok(rows_affected > 0)
     }
     
+    /// Insert or replace the searchable text of a note, and the term counts
+    /// related notes are scored with
+    pub fn upsert_fts(&self, id: &str, title: &str, content: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM note_terms WHERE note_id = ?1", params![id])?;
+        for (term, count) in term_counts(&format!("{}\n{}", title, content)) {
+            tx.execute(
+                "INSERT INTO note_terms (note_id, term, count) VALUES (?1, ?2, ?3)",
+                params![id, term, count as i64],
+            )?;
+        }
+        tx.commit()?;
+        
+        conn.execute("DELETE FROM notes_fts WHERE id = ?1", params![id])?;
+        conn.execute(
+            "INSERT INTO notes_fts (id, title, content) VALUES (?1, ?2, ?3)",
+            params![id, title, content],
+        )?;
+        Ok(())
+    }
+    
+    /// Term counts of every indexed note, keyed by note ID
+    pub fn get_all_note_terms(&self) -> Result<HashMap<String, HashMap<String, usize>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT note_id, term, count FROM note_terms")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        let mut terms: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        for row in rows {
+            let (note_id, term, count) = row?;
+            terms.entry(note_id).or_default().insert(term, count.max(0) as usize);
+        }
+        Ok(terms)
+    }
+    
+    /// Store text recognized in an attachment
+    pub fn set_attachment_text(&self, file_name: &str, text: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO attachment_text (file_name, text, updated_at) VALUES (?1, ?2, ?3)",
+            params![file_name, text, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+    
+    /// Recognized text of the given attachments, skipping ones without any
+    pub fn get_attachment_text(&self, file_names: &[String]) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT text FROM attachment_text WHERE file_name = ?1")?;
+        let mut texts = Vec::new();
+        for file_name in file_names {
+            if let Some(text) = stmt.query_row(params![file_name], |row| row.get::<_, String>(0)).optional()? {
+                texts.push(text);
+            }
+        }
+        Ok(texts)
+    }
+    
+    /// Set or clear a note's icon
+    pub fn set_note_icon(&self, note_id: &str, icon: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        match icon {
+            Some(icon) => conn.execute(
+                "INSERT OR REPLACE INTO note_icons (note_id, icon, updated_at) VALUES (?1, ?2, ?3)",
+                params![note_id, icon, Utc::now().to_rfc3339()],
+            )?,
+            None => conn.execute("DELETE FROM note_icons WHERE note_id = ?1", params![note_id])?,
+        };
+        Ok(())
+    }
+    
+    /// Icons of all notes that have one
+    pub fn get_note_icons(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT note_id, icon FROM note_icons")?;
+        let icons = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(icons)
+    }
+    
+    /// Replace all recorded aliases with `(alias path, note id)` pairs
+    pub fn set_note_aliases(&self, aliases: &[(String, String)]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM note_aliases", [])?;
+        for (alias_path, note_id) in aliases {
+            tx.execute(
+                "INSERT OR REPLACE INTO note_aliases (alias_path, note_id) VALUES (?1, ?2)",
+                params![alias_path, note_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Alias paths recorded for a note
+    pub fn get_note_aliases(&self, note_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT alias_path FROM note_aliases WHERE note_id = ?1 ORDER BY alias_path")?;
+        let aliases = stmt.query_map(params![note_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(aliases)
+    }
+    
+    /// Remove every indexed note, including full-text entries
+    pub fn clear_index(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM notes", [])?;
+        conn.execute("DELETE FROM note_content", [])?;
+        conn.execute("DELETE FROM note_aliases", [])?;
+        conn.execute("DELETE FROM note_terms", [])?;
+        let _ = conn.execute("DELETE FROM notes_fts", []);
+        Ok(())
+    }
+    
+    /// Rebuild the database file to reclaim free pages
+    pub fn vacuum(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        // Merge full-text segments first so VACUUM can release their pages
+        let _ = conn.execute("INSERT INTO notes_fts (notes_fts) VALUES ('optimize')", []);
+        conn.execute_batch("VACUUM; PRAGMA optimize;")?;
+        Ok(())
+    }
+    
+    /// Number of notes per tag (case-insensitive), most used first
+    pub fn tag_counts(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT lower(trim(tag.value)) AS name, COUNT(DISTINCT notes.id)
+             FROM notes, json_each(notes.tags) AS tag
+             WHERE trim(tag.value) != ''
+             GROUP BY name
+             ORDER BY 2 DESC, name ASC"
+        )?;
+        let counts = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+    
+    /// Tags starting with `prefix` (case-insensitive), with how many notes use
+    /// each and when one of them was last updated. A leading `#` is ignored on
+    /// both the prefix and the stored tags.
+    pub fn tags_with_prefix(&self, prefix: &str) -> Result<Vec<(String, i64, String)>> {
+        let prefix = prefix.trim().trim_start_matches('#');
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT name, COUNT(DISTINCT id), MAX(updated_at)
+             FROM (SELECT lower(ltrim(trim(tag.value), '#')) AS name, notes.id AS id, notes.updated_at AS updated_at
+                   FROM notes, json_each(notes.tags) AS tag)
+             WHERE name != '' AND substr(name, 1, length(?1)) = lower(?1)
+             GROUP BY name"
+        )?;
+        let rows = stmt.query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+    
+    /// Number of notes per folder; `""` is the vault root
+    pub fn folder_counts(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        // rtrim with every non-slash character strips the filename, leaving "a/b/"
+        let mut stmt = conn.prepare(
+            "SELECT rtrim(rtrim(file_path, replace(file_path, '/', '')), '/') AS folder, COUNT(*)
+             FROM notes
+             GROUP BY folder
+             ORDER BY folder ASC"
+        )?;
+        let counts = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+    
     /// Update note position
     pub fn update_position(&self, id: &str, new_position: Option<i32>) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -289,6 +750,75 @@ impl NotesDatabase {
         Ok(max_position.unwrap_or(0) + 1)
     }
     
+    /// IDs of the most recently updated notes, newest first
+    pub fn get_recent_note_ids(&self, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id FROM notes ORDER BY updated_at DESC LIMIT ?1"
+        )?;
+        let ids = stmt.query_map(params![limit as i64], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+    
+    /// IDs of notes matching a saved filter, most recently updated first.
+    /// `now` anchors `updatedWithinDays`.
+    pub fn filter_note_ids(&self, filter: &SavedFilter, now: DateTime<Utc>, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+
+        for tag in &filter.tags {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM json_each(notes.tags) AS tag
+                         WHERE lower(ltrim(trim(tag.value), '#')) = ?)"
+            );
+            values.push(tag.trim().trim_start_matches('#').to_lowercase());
+        }
+        if filter.untagged {
+            conditions.push("NOT EXISTS (SELECT 1 FROM json_each(notes.tags) AS tag WHERE trim(tag.value) != '')");
+        }
+        if let Some(folder) = filter.folder.as_deref().map(|f| f.trim_matches('/')).filter(|f| !f.is_empty()) {
+            conditions.push("substr(file_path, 1, length(?) + 1) = ? || '/'");
+            values.push(folder.to_string());
+            values.push(folder.to_string());
+        }
+        if let Some(days) = filter.updated_within_days {
+            conditions.push("updated_at >= ?");
+            values.push((now - chrono::Duration::days(days as i64)).to_rfc3339());
+        }
+        if let Some(query) = filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let has_fts = conn
+                .query_row("SELECT 1 FROM sqlite_master WHERE name = 'notes_fts'", [], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if has_fts {
+                // Quote each word so user input can't be read as FTS syntax
+                conditions.push("id IN (SELECT id FROM notes_fts WHERE notes_fts MATCH ?)");
+                values.push(
+                    query.split_whitespace()
+                        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+            } else {
+                conditions.push("title LIKE ?");
+                values.push(format!("%{}%", query));
+            }
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let sql = format!("SELECT id FROM notes {} ORDER BY updated_at DESC LIMIT {}", where_clause, limit);
+        let mut stmt = conn.prepare(&sql)?;
+        let ids = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+    
     /// Check if a note with the given ID exists
     pub fn note_exists(&self, id: &str) -> Result<bool> {
         let conn = self.conn.lock().unwrap();
@@ -396,4 +926,80 @@ pub fn initialize_database(data_dir: &Path) -> Result<NotesDatabase> {
     }
     
     Ok(db)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, title: &str, hash: &str, position: Option<i32>) -> NoteRecord {
+        let date = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        NoteRecord {
+            id: id.to_string(),
+            title: title.to_string(),
+            file_path: format!("{}.md", id),
+            created_at: date,
+            updated_at: date,
+            tags: vec![],
+            position,
+            file_hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_index_only_touches_changed_rows() {
+        let existing = vec![
+            record("same", "Same", "h1", Some(0)),
+            record("retitled", "Old", "h2", Some(1)),
+            record("moved", "Moved", "h3", Some(2)),
+            record("gone", "Gone", "h4", Some(3)),
+            record("unloaded", "Unloaded", "h5", Some(4)),
+        ];
+        let desired = vec![
+            record("same", "Same", "h1", Some(0)),
+            record("retitled", "New", "h2", Some(1)),
+            record("moved", "Moved", "h3", Some(5)),
+            record("new", "New note", "h6", None),
+        ];
+        let on_disk: HashSet<String> = ["same", "retitled", "moved", "new", "unloaded"].iter().map(|s| s.to_string()).collect();
+
+        let diff = diff_index(existing, desired, &on_disk);
+        let mut upserts: Vec<&str> = diff.upserts.iter().map(|r| r.id.as_str()).collect();
+        upserts.sort();
+        assert_eq!(upserts, vec!["moved", "new", "retitled"]);
+        assert_eq!(diff.deletes, vec!["gone".to_string()]);
+    }
+
+    #[test]
+    fn test_diff_index_unchanged_vault_is_empty() {
+        let records = vec![record("a", "A", "h1", Some(0)), record("b", "B", "h2", Some(1))];
+        let on_disk: HashSet<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert!(diff_index(records.clone(), records, &on_disk).is_empty());
+    }
+
+    #[test]
+    fn test_filter_note_ids() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = initialize_database(temp_dir.path()).unwrap();
+        let now = DateTime::parse_from_rfc3339("2024-01-10T00:00:00Z").unwrap().with_timezone(&Utc);
+        let note = |id: &str, path: &str, tags: &[&str], days_ago: i64| {
+            let mut r = record(id, id, "h", None);
+            r.file_path = path.to_string();
+            r.tags = tags.iter().map(|t| t.to_string()).collect();
+            r.updated_at = now - chrono::Duration::days(days_ago);
+            db.upsert_note(&r).unwrap();
+        };
+        note("standup", "work/standup.md", &["#Work"], 1);
+        note("roadmap", "work/plans/roadmap.md", &["work", "plans"], 20);
+        note("groceries", "groceries.md", &[], 2);
+        note("workshop", "workshop/ideas.md", &[" "], 3);
+
+        let ids = |filter: SavedFilter| db.filter_note_ids(&filter, now, 10).unwrap();
+        let work = SavedFilter { name: "Work".to_string(), tags: vec!["work".to_string()], ..Default::default() };
+        assert_eq!(ids(work.clone()), ["standup", "roadmap"]);
+        assert_eq!(ids(SavedFilter { updated_within_days: Some(7), ..work }), ["standup"]);
+        assert_eq!(ids(SavedFilter { untagged: true, ..Default::default() }), ["groceries", "workshop"]);
+        assert_eq!(ids(SavedFilter { folder: Some("work/".to_string()), ..Default::default() }), ["standup", "roadmap"]);
+        assert_eq!(db.filter_note_ids(&SavedFilter::default(), now, 2).unwrap(), ["standup", "groceries"]);
+    }
+}
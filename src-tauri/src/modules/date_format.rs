@@ -0,0 +1,145 @@
+use std::sync::RwLock;
+use chrono::{DateTime, Local, Locale, TimeZone, Utc};
+
+use crate::types::config::{DateFormatConfig, DateStyle};
+
+/// The config's date format, kept here so menus can format without the config lock
+static DATE_FORMAT: RwLock<DateFormatConfig> = RwLock::new(DateFormatConfig {
+    style: DateStyle::Relative,
+    locale: None,
+});
+
+/// Called when the config is loaded or updated
+pub fn set_date_format(config: &DateFormatConfig) {
+    *DATE_FORMAT.write().unwrap() = config.clone();
+}
+
+/// Resolve a locale name such as `de_DE` or `de-DE`, trying the bare language
+/// before falling back to POSIX
+pub fn resolve_locale(name: Option<&str>) -> Locale {
+    let Some(name) = name else { return Locale::POSIX };
+    // Drop an encoding or modifier, as in `de_DE.UTF-8@euro`
+    let name = name.split(['.', '@']).next().unwrap_or(name).replace('-', "_");
+    let language = name.split('_').next().unwrap_or(&name).to_string();
+    Locale::try_from(name.as_str())
+        .or_else(|_| Locale::try_from(language.as_str()))
+        .unwrap_or(Locale::POSIX)
+}
+
+fn configured_locale(config: &DateFormatConfig) -> Locale {
+    match &config.locale {
+        Some(locale) => resolve_locale(Some(locale)),
+        None => resolve_locale(sys_locale::get_locale().as_deref()),
+    }
+}
+
+fn plural(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", n, unit)
+    }
+}
+
+/// Format `time` as of `now`. Relative phrases are English; dates and times
+/// follow `locale`.
+pub fn format_datetime_at<Tz: TimeZone>(
+    time: &DateTime<Utc>,
+    style: DateStyle,
+    locale: Locale,
+    now: &DateTime<Tz>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let local = time.with_timezone(&now.timezone());
+    match style {
+        DateStyle::Absolute => local.format_localized("%x %X", locale).to_string(),
+        DateStyle::Date => local.format_localized("%x", locale).to_string(),
+        DateStyle::Relative => {
+            let elapsed = now.clone().with_timezone(&Utc) - *time;
+            let days = (now.date_naive() - local.date_naive()).num_days();
+            if elapsed.num_minutes() < 1 {
+                "just now".to_string()
+            } else if elapsed.num_hours() < 1 {
+                plural(elapsed.num_minutes(), "minute")
+            } else if days == 0 {
+                plural(elapsed.num_hours(), "hour")
+            } else if days == 1 {
+                "yesterday".to_string()
+            } else if days < 7 {
+                plural(days, "day")
+            } else {
+                local.format_localized("%x", locale).to_string()
+            }
+        }
+    }
+}
+
+/// Format a timestamp with the configured locale, in `style` or the configured one
+pub fn format_datetime(time: &DateTime<Utc>, style: Option<DateStyle>) -> String {
+    let config = DATE_FORMAT.read().unwrap().clone();
+    let style = style.unwrap_or(config.style);
+    format_datetime_at(time, style, configured_locale(&config), &Local::now())
+}
+
+/// Format an RFC 3339 timestamp; anything that doesn't parse is returned as is
+pub fn format_rfc3339(ts: &str, style: Option<DateStyle>) -> String {
+    match DateTime::parse_from_rfc3339(ts) {
+        Ok(time) => format_datetime(&time.with_timezone(&Utc), style),
+        Err(_) => ts.to_string(),
+    }
+}
+
+/// Format a timestamp the way menus and exports do, so the frontend matches them
+#[tauri::command]
+pub async fn format_timestamp(ts: String, style: Option<DateStyle>) -> Result<String, String> {
+    let time = DateTime::parse_from_rfc3339(&ts).map_err(|e| format!("Invalid timestamp {}: {}", ts, e))?;
+    Ok(format_datetime(&time.with_timezone(&Utc), style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset};
+
+    fn at(now: &DateTime<FixedOffset>, ago: Duration) -> DateTime<Utc> {
+        (now.clone() - ago).with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_relative() {
+        let tz = FixedOffset::east_opt(3600).unwrap();
+        let now = tz.with_ymd_and_hms(2024, 3, 15, 18, 0, 0).unwrap();
+        let relative = |ago| format_datetime_at(&at(&now, ago), DateStyle::Relative, Locale::en_US, &now);
+
+        assert_eq!(relative(Duration::seconds(20)), "just now");
+        assert_eq!(relative(Duration::minutes(1)), "1 minute ago");
+        assert_eq!(relative(Duration::minutes(42)), "42 minutes ago");
+        assert_eq!(relative(Duration::hours(5)), "5 hours ago");
+        assert_eq!(relative(Duration::hours(20)), "yesterday");
+        assert_eq!(relative(Duration::days(3)), "3 days ago");
+        assert_eq!(relative(Duration::days(30)), "02/14/2024");
+        // Clock skew between machines shouldn't produce "-3 minutes ago"
+        assert_eq!(relative(Duration::minutes(-3)), "just now");
+    }
+
+    #[test]
+    fn test_absolute_follows_locale() {
+        let tz = FixedOffset::east_opt(0).unwrap();
+        let now = tz.with_ymd_and_hms(2024, 3, 15, 18, 0, 0).unwrap();
+        let time = tz.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap().with_timezone(&Utc);
+
+        assert_eq!(format_datetime_at(&time, DateStyle::Date, Locale::de_DE, &now), "09.03.2024");
+        assert_eq!(format_datetime_at(&time, DateStyle::Date, Locale::en_US, &now), "03/09/2024");
+        assert_eq!(format_datetime_at(&time, DateStyle::Absolute, Locale::de_DE, &now), "09.03.2024 14:05:00");
+    }
+
+    #[test]
+    fn test_resolve_locale() {
+        assert_eq!(resolve_locale(Some("de-DE")), Locale::de_DE);
+        assert_eq!(resolve_locale(Some("fr_FR.UTF-8")), Locale::fr_FR);
+        assert_eq!(resolve_locale(Some("not a locale")), Locale::POSIX);
+        assert_eq!(resolve_locale(None), Locale::POSIX);
+    }
+}
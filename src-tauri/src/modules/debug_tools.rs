@@ -0,0 +1,38 @@
+use std::env;
+use serde::Serialize;
+
+/// Set to `1` at launch to enable the read-only diagnostics in a release build
+pub const DIAGNOSTICS_ENV: &str = "BLINK_DIAGNOSTICS";
+
+/// Whether diagnostic commands may run: always with `debug-tools`, otherwise
+/// only when support has asked the user to relaunch with `BLINK_DIAGNOSTICS=1`
+pub fn diagnostics_unlocked() -> bool {
+    cfg!(feature = "debug-tools") || env::var(DIAGNOSTICS_ENV).is_ok_and(|v| v == "1")
+}
+
+/// Payload of `get_debug_capabilities`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugCapabilities {
+    /// Test and debug commands are registered
+    pub debug_tools: bool,
+    /// Read-only diagnostics may run
+    pub diagnostics: bool,
+}
+
+pub fn ensure_diagnostics_unlocked() -> Result<(), String> {
+    if diagnostics_unlocked() {
+        Ok(())
+    } else {
+        Err(format!("Diagnostics are disabled. Relaunch with {}=1 to enable them.", DIAGNOSTICS_ENV))
+    }
+}
+
+/// Which debug surfaces this process exposes, so the UI can hide the rest
+#[tauri::command]
+pub async fn get_debug_capabilities() -> Result<DebugCapabilities, String> {
+    Ok(DebugCapabilities {
+        debug_tools: cfg!(feature = "debug-tools"),
+        diagnostics: diagnostics_unlocked(),
+    })
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use sha2::{Sha256, Digest};
+use tauri::{AppHandle, Manager};
+
+use crate::types::window::DetachedWindow;
+use crate::modules::storage::get_default_notes_directory;
+use crate::utils::window_geometry::{nudge_onscreen, Rect};
+use crate::{log_debug, log_info};
+
+/// Geometry of a single monitor, used to fingerprint the display configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorGeometry {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorGeometry {
+    /// The monitor's bounds in logical units, which window positions and sizes are kept in
+    pub fn logical_bounds(&self) -> Rect {
+        Rect::new(
+            self.x as f64 / self.scale_factor,
+            self.y as f64 / self.scale_factor,
+            self.width as f64 / self.scale_factor,
+            self.height as f64 / self.scale_factor,
+        )
+    }
+}
+
+/// Saved window positions, keyed by display fingerprint and then by note ID
+pub type DisplayLayouts = HashMap<String, HashMap<String, DetachedWindow>>;
+
+/// Build a stable fingerprint for a set of monitors.
+/// Monitor order reported by the OS is not guaranteed, so geometries are sorted first.
+pub fn fingerprint_from_geometries(monitors: &[MonitorGeometry]) -> String {
+    let mut entries: Vec<String> = monitors
+        .iter()
+        .map(|m| format!("{}@{},{}:{}x{}*{:.2}", m.name, m.x, m.y, m.width, m.height, m.scale_factor))
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(entries.join("|").as_bytes());
+    let hash = format!("{:x}", hasher.finalize());
+    hash[..16].to_string()
+}
+
+/// Read the current monitor geometries from the OS
+pub fn current_monitor_geometries(app: &AppHandle) -> Vec<MonitorGeometry> {
+    app.available_monitors()
+        .map(|monitors| {
+            monitors
+                .iter()
+                .map(|m| MonitorGeometry {
+                    name: m.name().cloned().unwrap_or_default(),
+                    x: m.position().x,
+                    y: m.position().y,
+                    width: m.size().width,
+                    height: m.size().height,
+                    scale_factor: m.scale_factor(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fingerprint of the display configuration the app is currently running on
+pub fn current_display_fingerprint(app: &AppHandle) -> String {
+    fingerprint_from_geometries(&current_monitor_geometries(app))
+}
+
+/// The monitor a physical point lies on
+pub fn monitor_at(monitors: &[MonitorGeometry], x: f64, y: f64) -> Option<&MonitorGeometry> {
+    monitors.iter().find(|m| {
+        x >= m.x as f64
+            && y >= m.y as f64
+            && x < m.x as f64 + m.width as f64
+            && y < m.y as f64 + m.height as f64
+    })
+}
+
+/// Check whether a point lies on one of the given monitors
+pub fn is_position_on_monitors(monitors: &[MonitorGeometry], x: f64, y: f64) -> bool {
+    monitor_at(monitors, x, y).is_some()
+}
+
+/// Bring a window back into view if its stored coordinates put it off every
+/// connected display, keeping its size. Returns whether the window was moved.
+pub fn ensure_window_onscreen(app: &AppHandle, label: &str) -> Result<bool, String> {
+    let window = app.get_webview_window(label).ok_or_else(|| format!("Window {} not found", label))?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let screens: Vec<Rect> = current_monitor_geometries(app)
+        .iter()
+        .map(|m| Rect::new(m.x as f64, m.y as f64, m.width as f64, m.height as f64))
+        .collect();
+
+    let current = Rect::new(position.x as f64, position.y as f64, size.width as f64, size.height as f64);
+    let Some((x, y)) = nudge_onscreen(current, &screens) else {
+        return Ok(false);
+    };
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: x as i32, y: y as i32 }))
+        .map_err(|e| format!("Failed to move window onscreen: {}", e))?;
+    log_info!("WINDOW", "Moved offscreen window {} from ({}, {}) to ({}, {})", label, position.x, position.y, x, y);
+    Ok(true)
+}
+
+/// Load all per-display window layouts from disk
+pub async fn load_display_layouts() -> Result<DisplayLayouts, String> {
+    let notes_dir = get_default_notes_directory()?;
+    let layouts_file = notes_dir.join("display_layouts.json");
+
+    if !layouts_file.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let layouts_json = fs::read_to_string(layouts_file)
+        .map_err(|e| format!("Failed to read display layouts from disk: {}", e))?;
+
+    serde_json::from_str(&layouts_json)
+        .map_err(|e| format!("Failed to parse display layouts JSON: {}", e))
+}
+
+/// Save all per-display window layouts to disk
+pub async fn save_display_layouts(layouts: &DisplayLayouts) -> Result<(), String> {
+    let notes_dir = get_default_notes_directory()?;
+    fs::create_dir_all(&notes_dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let layouts_file = notes_dir.join("display_layouts.json");
+    let layouts_json = serde_json::to_string_pretty(layouts)
+        .map_err(|e| format!("Failed to serialize display layouts: {}", e))?;
+
+    fs::write(layouts_file, layouts_json)
+        .map_err(|e| format!("Failed to write display layouts to disk: {}", e))?;
+
+    Ok(())
+}
+
+/// Look up the saved geometry of a note window for the current display configuration
+pub async fn load_display_position(app: &AppHandle, note_id: &str) -> Option<DetachedWindow> {
+    let fingerprint = current_display_fingerprint(app);
+    let layouts = load_display_layouts().await.ok()?;
+    let saved = layouts.get(&fingerprint)?.get(note_id).cloned();
+
+    if saved.is_some() {
+        log_debug!("DISPLAY", "Found saved geometry for note {} on display {}", note_id, fingerprint);
+    }
+    saved
+}
+
+/// Record the geometry of a note window under the current display configuration
+pub async fn save_display_position(app: &AppHandle, window: &DetachedWindow) -> Result<(), String> {
+    let fingerprint = current_display_fingerprint(app);
+    let mut layouts = load_display_layouts().await?;
+
+    layouts
+        .entry(fingerprint)
+        .or_default()
+        .insert(window.note_id.clone(), window.clone());
+
+    save_display_layouts(&layouts).await
+}
+
+/// Get the fingerprint of the current display configuration
+#[tauri::command]
+pub async fn get_display_fingerprint(app: AppHandle) -> Result<String, String> {
+    let monitors = current_monitor_geometries(&app);
+    let fingerprint = fingerprint_from_geometries(&monitors);
+    log_info!("DISPLAY", "Current display fingerprint: {} ({} monitors)", fingerprint, monitors.len());
+    Ok(fingerprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorGeometry {
+        MonitorGeometry {
+            name: name.to_string(),
+            x,
+            y: 0,
+            width,
+            height: 1080,
+            scale_factor: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_monitor_order() {
+        let laptop = monitor("Built-in Retina Display", 0, 1512);
+        let external = monitor("DELL U2720Q", 1512, 2560);
+
+        let a = fingerprint_from_geometries(&[laptop.clone(), external.clone()]);
+        let b = fingerprint_from_geometries(&[external, laptop]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_fingerprint_differs_per_layout() {
+        let laptop = monitor("Built-in Retina Display", 0, 1512);
+        let external = monitor("DELL U2720Q", 1512, 2560);
+
+        let undocked = fingerprint_from_geometries(&[laptop.clone()]);
+        let docked = fingerprint_from_geometries(&[laptop, external]);
+        assert_ne!(undocked, docked);
+    }
+
+    #[test]
+    fn test_position_on_monitors() {
+        let monitors = vec![monitor("A", 0, 1512), monitor("B", 1512, 2560)];
+        assert!(is_position_on_monitors(&monitors, 100.0, 100.0));
+        assert!(is_position_on_monitors(&monitors, 2000.0, 500.0));
+        assert!(!is_position_on_monitors(&monitors, 5000.0, 100.0));
+        assert!(!is_position_on_monitors(&monitors, 100.0, -50.0));
+    }
+
+    #[test]
+    fn test_logical_bounds() {
+        assert_eq!(monitor("B", 3024, 5120).logical_bounds(), Rect::new(1512.0, 0.0, 2560.0, 540.0));
+    }
+}
@@ -0,0 +1,209 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::state::DragPreviewCacheState;
+use crate::types::note::Note;
+use crate::types::window::NotesState;
+use crate::log_debug;
+
+/// Lines of note content rendered into a preview
+const PREVIEW_MAX_LINES: usize = 8;
+/// Characters of note content rendered into a preview
+const PREVIEW_MAX_CHARS: usize = 280;
+
+/// Lightweight preview shown by drag ghosts instead of loading the full editor
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DragPreview {
+    pub note_id: String,
+    pub title: String,
+    /// Self-contained HTML snippet, safe to inject into the ghost window
+    pub html: String,
+    pub excerpt: String,
+    pub word_count: usize,
+    /// `updated_at` of the note the preview was rendered from; a mismatch means it's stale
+    pub updated_at: String,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Strip block-level markdown syntax from a line, returning the text and whether it was a heading
+fn strip_markdown_line(line: &str) -> (String, bool) {
+    let trimmed = line.trim();
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    if hashes > 0 && hashes <= 6 && trimmed[hashes..].starts_with(' ') {
+        return (trimmed[hashes..].trim().to_string(), true);
+    }
+
+    let text = trimmed
+        .strip_prefix("- [ ] ")
+        .or_else(|| trimmed.strip_prefix("- [x] "))
+        .or_else(|| trimmed.strip_prefix("- "))
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("> "))
+        .unwrap_or(trimmed);
+    (text.replace("**", "").replace('`', ""), false)
+}
+
+/// Render the preview for a note. Code blocks are skipped and the excerpt is
+/// capped so ghosts stay small regardless of note size.
+pub fn render_drag_preview(note: &Note) -> DragPreview {
+    let mut in_code = false;
+    let mut lines: Vec<(String, bool)> = Vec::new();
+    let mut chars = 0;
+
+    for line in note.content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || trimmed.is_empty() {
+            continue;
+        }
+        if lines.len() >= PREVIEW_MAX_LINES || chars >= PREVIEW_MAX_CHARS {
+            break;
+        }
+
+        let (mut text, is_heading) = strip_markdown_line(line);
+        // Skip a leading heading that just repeats the title
+        if lines.is_empty() && is_heading && text == note.title {
+            continue;
+        }
+        let remaining = PREVIEW_MAX_CHARS - chars;
+        if text.chars().count() > remaining {
+            text = text.chars().take(remaining).collect::<String>() + "…";
+        }
+        chars += text.chars().count();
+        lines.push((text, is_heading));
+    }
+
+    let body: String = lines
+        .iter()
+        .map(|(text, is_heading)| {
+            if *is_heading {
+                format!("<p><strong>{}</strong></p>", escape_html(text))
+            } else {
+                format!("<p>{}</p>", escape_html(text))
+            }
+        })
+        .collect();
+
+    let html = format!(
+        "<div class=\"blink-drag-preview\"><h1>{}</h1>{}</div>",
+        escape_html(&note.title),
+        body
+    );
+
+    DragPreview {
+        note_id: note.id.clone(),
+        title: note.title.clone(),
+        html,
+        excerpt: lines.into_iter().map(|(text, _)| text).collect::<Vec<_>>().join(" "),
+        word_count: note.content.split_whitespace().count(),
+        updated_at: note.updated_at.clone(),
+    }
+}
+
+/// Pre-render previews for every note in the background and drop entries for deleted notes
+pub fn warm_drag_previews(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let notes: Vec<Note> = {
+            let notes_state = app.state::<NotesState>();
+            let notes_lock = notes_state.lock().await;
+            notes_lock.values().cloned().collect()
+        };
+
+        let cache_state = app.state::<DragPreviewCacheState>();
+        let mut rendered = 0;
+        for note in &notes {
+            let fresh = {
+                let cache = cache_state.lock().await;
+                cache.get(&note.id).map_or(false, |p| p.updated_at == note.updated_at)
+            };
+            if !fresh {
+                let preview = render_drag_preview(note);
+                cache_state.lock().await.insert(note.id.clone(), preview);
+                rendered += 1;
+            }
+        }
+
+        let mut cache = cache_state.lock().await;
+        cache.retain(|id, _| notes.iter().any(|n| &n.id == id));
+        log_debug!("DRAG", "Pre-rendered {} drag previews ({} cached)", rendered, cache.len());
+    });
+}
+
+/// Get the drag ghost preview for a note, rendering it if the cached one is missing or stale
+#[tauri::command]
+pub async fn get_note_drag_preview(
+    note_id: String,
+    notes: State<'_, NotesState>,
+    previews: State<'_, DragPreviewCacheState>,
+) -> Result<DragPreview, String> {
+    let notes_lock = notes.lock().await;
+    let note = notes_lock.get(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let mut cache = previews.lock().await;
+    if let Some(preview) = cache.get(&note_id) {
+        if preview.updated_at == note.updated_at {
+            return Ok(preview.clone());
+        }
+    }
+
+    let preview = render_drag_preview(note);
+    cache.insert(note_id, preview.clone());
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(title: &str, content: &str) -> Note {
+        Note {
+            id: "n1".to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+            tags: vec![],
+            position: Some(0),
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_preview_strips_markdown_and_code() {
+        let preview = render_drag_preview(&note(
+            "Plan",
+            "# Plan\n\n- **ship** it\n```\nlet x = 1;\n```\n## Later\n",
+        ));
+        assert_eq!(preview.excerpt, "ship it Later");
+        assert!(preview.html.contains("<h1>Plan</h1>"));
+        assert!(preview.html.contains("<p><strong>Later</strong></p>"));
+        assert!(!preview.html.contains("let x"));
+    }
+
+    #[test]
+    fn test_preview_escapes_html() {
+        let preview = render_drag_preview(&note("<b>", "a < b & c"));
+        assert!(preview.html.contains("<h1>&lt;b&gt;</h1>"));
+        assert!(preview.html.contains("<p>a &lt; b &amp; c</p>"));
+    }
+
+    #[test]
+    fn test_preview_is_capped() {
+        let long = "word ".repeat(200);
+        let preview = render_drag_preview(&note("Long", &long));
+        assert!(preview.excerpt.chars().count() <= PREVIEW_MAX_CHARS + 1);
+        assert!(preview.excerpt.ends_with('…'));
+        assert_eq!(preview.word_count, 200);
+    }
+}
@@ -0,0 +1,219 @@
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::state::DragSessionState;
+use crate::{log_debug, log_info};
+
+/// How often a drag session moves its window (~60fps)
+const DRAG_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Backend-driven movement of a hybrid drag window.
+///
+/// Positions pushed from the frontend are coalesced and only the latest one is
+/// applied each frame; with `track_cursor` the session follows the OS cursor
+/// itself and the frontend doesn't need to send positions at all.
+#[derive(Debug, Clone, Default)]
+pub struct DragSession {
+    /// Distinguishes a session from a newer one for the same window
+    pub generation: u64,
+    pub track_cursor: bool,
+    /// Cursor offset from the window's top-left corner, in physical pixels
+    pub offset: (f64, f64),
+    /// Latest position received from the frontend, not yet applied
+    pub pending: Option<(f64, f64)>,
+    /// Position last applied to the window
+    pub applied: Option<(f64, f64)>,
+}
+
+impl DragSession {
+    /// Position the window should move to this frame, if it changed since the last one
+    pub fn next_position(&mut self, cursor: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        let target = if self.track_cursor {
+            cursor.map(|(x, y)| (x - self.offset.0, y - self.offset.1))
+        } else {
+            self.pending.take()
+        }?;
+
+        let target = (target.0.round(), target.1.round());
+        if self.applied == Some(target) {
+            return None;
+        }
+        self.applied = Some(target);
+        Some(target)
+    }
+}
+
+fn set_physical_position(app: &AppHandle, window_label: &str, (x, y): (f64, f64)) -> bool {
+    match app.get_webview_window(window_label) {
+        Some(window) => window
+            .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: x as i32, y: y as i32 }))
+            .is_ok(),
+        None => false,
+    }
+}
+
+fn spawn_drag_loop(app: AppHandle, window_label: String, generation: u64) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(DRAG_FRAME_INTERVAL);
+        let mut frames = 0u64;
+
+        loop {
+            interval.tick().await;
+
+            let sessions = app.state::<DragSessionState>();
+            let mut sessions_lock = sessions.lock().await;
+            let Some(session) = sessions_lock.get_mut(&window_label) else { break };
+            if session.generation != generation {
+                break;
+            }
+            // The window can also go away between moves
+            if app.get_webview_window(&window_label).is_none() {
+                sessions_lock.remove(&window_label);
+                break;
+            }
+
+            let cursor = if session.track_cursor {
+                app.cursor_position().ok().map(|p| (p.x, p.y))
+            } else {
+                None
+            };
+
+            if let Some(position) = session.next_position(cursor) {
+                if !set_physical_position(&app, &window_label, position) {
+                    // Window went away mid-drag
+                    sessions_lock.remove(&window_label);
+                    break;
+                }
+                frames += 1;
+            }
+        }
+
+        log_debug!("DRAG", "Drag session for '{}' ended after {} moves", window_label, frames);
+    });
+}
+
+/// Start a drag session for a hybrid drag window
+#[tauri::command]
+pub async fn begin_hybrid_drag_session(
+    app: AppHandle,
+    window_label: String,
+    offset_x: Option<f64>,
+    offset_y: Option<f64>,
+    track_cursor: Option<bool>,
+    sessions: State<'_, DragSessionState>,
+) -> Result<(), String> {
+    if app.get_webview_window(&window_label).is_none() {
+        return Err(format!("Window '{}' not found", window_label));
+    }
+
+    let generation = {
+        let mut sessions_lock = sessions.lock().await;
+        let generation = sessions_lock.get(&window_label).map_or(0, |s| s.generation + 1);
+        sessions_lock.insert(window_label.clone(), DragSession {
+            generation,
+            track_cursor: track_cursor.unwrap_or(false),
+            offset: (offset_x.unwrap_or(0.0), offset_y.unwrap_or(0.0)),
+            ..Default::default()
+        });
+        generation
+    };
+
+    log_info!("DRAG", "Started drag session for '{}' (track_cursor={:?})", window_label, track_cursor);
+    spawn_drag_loop(app, window_label, generation);
+    Ok(())
+}
+
+/// Queue a batch of positions; only the most recent one is applied on the next frame
+#[tauri::command]
+pub async fn queue_hybrid_drag_positions(
+    window_label: String,
+    positions: Vec<(f64, f64)>,
+    sessions: State<'_, DragSessionState>,
+) -> Result<(), String> {
+    let mut sessions_lock = sessions.lock().await;
+    let session = sessions_lock
+        .get_mut(&window_label)
+        .ok_or_else(|| format!("No drag session for '{}'", window_label))?;
+    if let Some(last) = positions.last() {
+        session.pending = Some(*last);
+    }
+    Ok(())
+}
+
+/// Queue a single position if a session is running. Returns false when there is none.
+pub async fn queue_drag_position(app: &AppHandle, window_label: &str, position: (f64, f64)) -> bool {
+    let sessions = app.state::<DragSessionState>();
+    let mut sessions_lock = sessions.lock().await;
+    match sessions_lock.get_mut(window_label) {
+        Some(session) => {
+            session.pending = Some(position);
+            true
+        }
+        None => false,
+    }
+}
+
+/// End a drag session, applying any position still pending. Returns the final position.
+#[tauri::command]
+pub async fn end_hybrid_drag_session(
+    app: AppHandle,
+    window_label: String,
+    sessions: State<'_, DragSessionState>,
+) -> Result<Option<(f64, f64)>, String> {
+    let Some(mut session) = sessions.lock().await.remove(&window_label) else {
+        return Ok(None);
+    };
+
+    let cursor = if session.track_cursor {
+        app.cursor_position().ok().map(|p| (p.x, p.y))
+    } else {
+        None
+    };
+    if let Some(position) = session.next_position(cursor) {
+        set_physical_position(&app, &window_label, position);
+    }
+
+    log_info!("DRAG", "Ended drag session for '{}' at {:?}", window_label, session.applied);
+    Ok(session.applied)
+}
+
+/// Forget the drag session of a destroyed window, which also stops its loop
+pub async fn drop_drag_session(app: &AppHandle, window_label: &str) {
+    if app.state::<DragSessionState>().lock().await.remove(window_label).is_some() {
+        log_debug!("DRAG", "Dropped drag session for destroyed window '{}'", window_label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_positions_coalesce() {
+        let mut session = DragSession::default();
+        session.pending = Some((10.0, 10.0));
+        session.pending = Some((12.4, 20.6));
+        assert_eq!(session.next_position(None), Some((12.0, 21.0)));
+        assert_eq!(session.next_position(None), None);
+    }
+
+    #[test]
+    fn test_unchanged_position_is_not_reapplied() {
+        let mut session = DragSession::default();
+        session.pending = Some((5.0, 5.0));
+        session.next_position(None);
+        session.pending = Some((5.2, 4.9));
+        assert_eq!(session.next_position(None), None);
+    }
+
+    #[test]
+    fn test_cursor_tracking_applies_offset() {
+        let mut session = DragSession {
+            track_cursor: true,
+            offset: (100.0, 20.0),
+            ..Default::default()
+        };
+        assert_eq!(session.next_position(Some((300.0, 220.0))), Some((200.0, 200.0)));
+        assert_eq!(session.next_position(None), None);
+    }
+}
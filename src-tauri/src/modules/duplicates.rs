@@ -0,0 +1,217 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::types::note::Note;
+use crate::types::window::NotesState;
+use crate::log_info;
+
+/// Words per shingle
+const SHINGLE_SIZE: usize = 3;
+/// Hash functions per MinHash signature
+const SIGNATURE_SIZE: usize = 64;
+/// Similarity used when the caller doesn't pass a threshold
+const DEFAULT_THRESHOLD: f64 = 0.8;
+
+/// Notes whose content is identical or nearly so
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub note_ids: Vec<String>,
+    pub titles: Vec<String>,
+    /// Lowest pairwise similarity within the group, 1.0 for exact duplicates
+    pub similarity: f64,
+    pub exact: bool,
+}
+
+/// Lowercased words, ignoring punctuation and whitespace differences
+fn normalized_words(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn seeded_hash<T: Hash>(seed: u64, value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// MinHash signature over word shingles; `None` for empty content
+pub fn minhash_signature(content: &str) -> Option<Vec<u64>> {
+    let words = normalized_words(content);
+    if words.is_empty() {
+        return None;
+    }
+    let shingles: Vec<&[String]> = if words.len() < SHINGLE_SIZE {
+        vec![&words[..]]
+    } else {
+        words.windows(SHINGLE_SIZE).collect()
+    };
+
+    Some(
+        (0..SIGNATURE_SIZE as u64)
+            .map(|seed| shingles.iter().map(|s| seeded_hash(seed, s)).min().unwrap_or(u64::MAX))
+            .collect(),
+    )
+}
+
+/// Estimated Jaccard similarity of two signatures
+pub fn signature_similarity(a: &[u64], b: &[u64]) -> f64 {
+    let matching = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    matching as f64 / a.len().max(1) as f64
+}
+
+fn find_root(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+/// Group notes whose content is at least `threshold` similar. Exact duplicates
+/// are found by hash; near-duplicates by comparing MinHash signatures.
+pub fn find_duplicate_groups(notes: &[&Note], threshold: f64) -> Vec<DuplicateGroup> {
+    let hashes: Vec<String> = notes
+        .iter()
+        .map(|n| ModifiedStateTracker::compute_content_hash(n.content.trim()))
+        .collect();
+    let signatures: Vec<Option<Vec<u64>>> = notes.iter().map(|n| minhash_signature(&n.content)).collect();
+
+    let mut parents: Vec<usize> = (0..notes.len()).collect();
+    let mut pair_scores: HashMap<(usize, usize), f64> = HashMap::new();
+
+    for i in 0..notes.len() {
+        for j in (i + 1)..notes.len() {
+            let score = if hashes[i] == hashes[j] && !notes[i].content.trim().is_empty() {
+                1.0
+            } else {
+                match (&signatures[i], &signatures[j]) {
+                    (Some(a), Some(b)) => signature_similarity(a, b),
+                    _ => continue,
+                }
+            };
+            if score >= threshold {
+                pair_scores.insert((i, j), score);
+                let (ri, rj) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[rj] = ri;
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..notes.len() {
+        let root = find_root(&mut parents, i);
+        members.entry(root).or_default().push(i);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = members
+        .into_values()
+        .filter(|m| m.len() > 1)
+        .map(|m| {
+            let similarity = pair_scores
+                .iter()
+                .filter(|((i, j), _)| m.contains(i) && m.contains(j))
+                .map(|(_, s)| *s)
+                .fold(1.0, f64::min);
+            DuplicateGroup {
+                note_ids: m.iter().map(|&i| notes[i].id.clone()).collect(),
+                titles: m.iter().map(|&i| notes[i].title.clone()).collect(),
+                similarity,
+                exact: m.iter().all(|&i| hashes[i] == hashes[m[0]]),
+            }
+        })
+        .collect();
+
+    // Most similar first, then by first ID for stable output
+    groups.sort_by(|a, b| {
+        b.similarity
+            .partial_cmp(&a.similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.note_ids.cmp(&b.note_ids))
+    });
+    groups
+}
+
+/// Find groups of duplicate or near-duplicate notes. `threshold` is the minimum
+/// similarity between 0 and 1 (default 0.8).
+#[tauri::command]
+pub async fn find_duplicate_notes(
+    threshold: Option<f64>,
+    notes: State<'_, NotesState>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!("Threshold must be between 0 and 1, got {}", threshold));
+    }
+
+    let notes_lock = notes.lock().await;
+    let mut all: Vec<&Note> = notes_lock.values().collect();
+    all.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let groups = find_duplicate_groups(&all, threshold);
+    log_info!("DUPLICATES", "Found {} duplicate groups among {} notes", groups.len(), all.len());
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, content: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: content.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: vec![],
+            position: None,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_exact_duplicates() {
+        let notes = [note("a", "Buy milk and eggs\n"), note("b", "Buy milk and eggs"), note("c", "Something else")];
+        let refs: Vec<&Note> = notes.iter().collect();
+        let groups = find_duplicate_groups(&refs, 0.9);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].note_ids, vec!["a", "b"]);
+        assert!(groups[0].exact);
+        assert_eq!(groups[0].similarity, 1.0);
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        let base = "the quick brown fox jumps over the lazy dog while the cat sleeps in the warm afternoon sun";
+        let notes = [
+            note("a", base),
+            note("b", &format!("{} today", base)),
+            note("c", "completely unrelated meeting notes about the quarterly budget review"),
+        ];
+        let refs: Vec<&Note> = notes.iter().collect();
+        let groups = find_duplicate_groups(&refs, 0.7);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].note_ids, vec!["a", "b"]);
+        assert!(!groups[0].exact);
+        assert!(groups[0].similarity >= 0.7);
+    }
+
+    #[test]
+    fn test_empty_notes_are_not_duplicates() {
+        let notes = [note("a", ""), note("b", "  ")];
+        let refs: Vec<&Note> = notes.iter().collect();
+        assert!(find_duplicate_groups(&refs, 0.5).is_empty());
+    }
+}
@@ -40,8 +40,13 @@ impl FileNotesStorage {
     
     /// Save a single note to disk and update cache
     pub async fn save_note(&self, note: &Note) -> Result<(), String> {
+        self.save_note_in(note, None).await
+    }
+    
+    /// Save a note into `folder` of the vault; see `FileStorageManager::save_note_in`
+    pub async fn save_note_in(&self, note: &Note, folder: Option<&str>) -> Result<(), String> {
         // Save to disk
-        self.storage.save_note(note).await?;
+        self.storage.save_note_in(note, folder).await?;
         
         // Update cache
         let mut cache = self.cache.lock().await;
@@ -95,6 +100,10 @@ impl FileNotesStorage {
     
     /// Run migration from old JSON format if needed
     pub async fn migrate_if_needed(&self, json_path: PathBuf) -> Result<(), String> {
+        if self.storage.is_read_only() {
+            return Ok(());
+        }
+        
         if json_path.exists() && !json_path.with_extension("json.backup").exists() {
             log_info!("FILE_NOTES_STORAGE", "Detected old notes.json, running migration...");
             self.storage.migrate_from_json(&json_path).await?;
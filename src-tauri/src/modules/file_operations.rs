@@ -1,30 +1,46 @@
 use crate::error::{BlinkError, BlinkResult};
 use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::file_storage::has_note_extension;
+use crate::modules::formatting::hard_wrap;
 use crate::ModifiedStateTrackerState;
 use crate::modules::storage::{get_configured_notes_directory, save_config_to_disk};
+use crate::modules::vault_lock::{ensure_vault_writable, switch_vault_lock};
+use crate::modules::vault_picker::remember_vault;
+use crate::modules::audit_log::{record_audit, AuditAction, AuditEntry, AuditSource};
+use crate::VaultLockState;
 use crate::ConfigState;
 use crate::types::note::Note;
 use crate::types::window::NotesState;
+use crate::utils::{generate_slug, generate_unique_slug, uuid_from_slug};
+use crate::utils::paths::{ensure_within, user_roots};
 use crate::{log_debug, log_error, log_info};
+use crate::modules::window_capabilities::{authorize, Capability};
 use std::fs;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Import notes from a directory
 #[tauri::command]
 pub async fn import_notes_from_directory(
+    window: tauri::Window,
     directory_path: String,
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
     modified_tracker: State<'_, ModifiedStateTrackerState>,
+    vault_lock: State<'_, VaultLockState>,
 ) -> Result<Vec<Note>, String> {
+    authorize(&window, Capability::ManageVault)?;
     log_info!("FILE_IMPORT", "Importing notes from directory: {}", directory_path);
+    ensure_vault_writable(&config, &vault_lock).await?;
     
     let mut imported_notes = Vec::new();
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
     
-    let dir_path = Path::new(&directory_path);
+    let dir_path = ensure_within(Path::new(&directory_path), &allowed_roots(&config_lock))?;
+    let dir_path = dir_path.as_path();
     if !dir_path.exists() {
         return Err("Directory does not exist".to_string());
     }
@@ -32,7 +48,7 @@ pub async fn import_notes_from_directory(
     // Create FileNotesStorage instance
     let file_storage = FileNotesStorage::new(&config_lock)?;
     
-    // Read all markdown files in the directory
+    // Read every file with a note extension in the directory
     let entries = fs::read_dir(dir_path)
         .map_err(|e| format!("Failed to read directory: {}", e))?;
     
@@ -40,7 +56,7 @@ pub async fn import_notes_from_directory(
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
         
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
+        if has_note_extension(&path, &config_lock.storage.note_extensions) {
             match parse_markdown_file(&path).await {
                 Ok(note) => {
                     log_info!("FILE_IMPORT", "Imported note: {} from {}", note.title, path.display());
@@ -66,18 +82,25 @@ pub async fn import_notes_from_directory(
 /// Import a single markdown file as a note
 #[tauri::command]
 pub async fn import_single_file(
+    window: tauri::Window,
     file_path: String,
     notes: State<'_, NotesState>,
     config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
 ) -> Result<Note, String> {
+    authorize(&window, Capability::ManageVault)?;
     log_info!("FILE_IMPORT", "Importing single file: {}", file_path);
+    ensure_vault_writable(&config, &vault_lock).await?;
     
-    let path = Path::new(&file_path);
+    let path = {
+        let config_lock = config.lock().await;
+        ensure_within(Path::new(&file_path), &allowed_roots(&config_lock))?
+    };
     if !path.exists() {
         return Err("File does not exist".to_string());
     }
     
-    let note = parse_markdown_file(path).await?;
+    let note = parse_markdown_file(&path).await?;
     
     let mut notes_lock = notes.lock().await;
     let config_lock = config.lock().await;
@@ -94,34 +117,44 @@ pub async fn import_single_file(
     Ok(note)
 }
 
-/// Export a note to a markdown file
+/// Export a note to a markdown file, hard-wrapped at `wrap_column` if given
 #[tauri::command]
 pub async fn export_note_to_file(
+    window: tauri::Window,
     note_id: String,
     file_path: String,
+    wrap_column: Option<usize>,
     notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
 ) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
     log_info!("FILE_EXPORT", "Exporting note {} to {}", note_id, file_path);
+    ensure_within(Path::new(&file_path), &allowed_roots(&*config.lock().await))?;
     
     let notes_lock = notes.lock().await;
     let note = notes_lock.get(&note_id)
         .ok_or("Note not found")?;
     
-    write_note_to_file(note, &file_path).await?;
+    write_note_to_file(note, &file_path, wrap_column).await?;
     
     log_info!("FILE_EXPORT", "Successfully exported note to {}", file_path);
     Ok(())
 }
 
-/// Export all notes to a directory
+/// Export all notes to a directory, hard-wrapped at `wrap_column` if given
 #[tauri::command]
 pub async fn export_all_notes_to_directory(
+    window: tauri::Window,
     directory_path: String,
+    wrap_column: Option<usize>,
     notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
 ) -> Result<Vec<String>, String> {
+    authorize(&window, Capability::ManageVault)?;
     log_info!("FILE_EXPORT", "Exporting all notes to directory: {}", directory_path);
     
-    let dir_path = Path::new(&directory_path);
+    let dir_path = ensure_within(Path::new(&directory_path), &allowed_roots(&*config.lock().await))?;
+    let dir_path = dir_path.as_path();
     fs::create_dir_all(dir_path)
         .map_err(|e| format!("Failed to create directory: {}", e))?;
     
@@ -133,7 +166,7 @@ pub async fn export_all_notes_to_directory(
         let file_name = format!("{}.md", note.id);
         let file_path = dir_path.join(&file_name);
         
-        match write_note_to_file(note, file_path.to_str().unwrap()).await {
+        match write_note_to_file(note, file_path.to_str().unwrap(), wrap_column).await {
             Ok(_) => {
                 exported_files.push(file_name);
                 log_info!("FILE_EXPORT", "Exported note: {}", note.title);
@@ -148,12 +181,136 @@ pub async fn export_all_notes_to_directory(
     Ok(exported_files)
 }
 
+/// Folders imports may read from and exports may write to
+pub fn allowed_roots(config: &crate::types::config::AppConfig) -> Vec<PathBuf> {
+    user_roots(get_configured_notes_directory(config).ok().as_deref())
+}
+
+/// File types accepted when dropped onto a window
+const DROP_IMPORT_EXTENSIONS: &[&str] = &["md", "markdown", "txt"];
+
+/// Payload of the `drop-import-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropImportProgress {
+    pub window_label: String,
+    pub current: usize,
+    pub total: usize,
+    pub file: String,
+}
+
+/// Payload of the `drop-import-finished` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropImportResult {
+    pub window_label: String,
+    /// IDs of the imported notes, for the frontend to select
+    pub note_ids: Vec<String>,
+    pub skipped: Vec<String>,
+    pub error: Option<String>,
+}
+
+pub(crate) fn is_droppable_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map_or(false, |ext| DROP_IMPORT_EXTENSIONS.iter().any(|x| ext.eq_ignore_ascii_case(x)))
+}
+
+/// Import files dropped onto a window, reporting progress through events
+pub async fn import_dropped_files(app: &AppHandle, window_label: &str, paths: Vec<PathBuf>) {
+    let (accepted, mut skipped): (Vec<PathBuf>, Vec<PathBuf>) =
+        paths.into_iter().partition(|p| is_droppable_file(p));
+    let mut result = DropImportResult {
+        window_label: window_label.to_string(),
+        note_ids: Vec::new(),
+        skipped: Vec::new(),
+        error: None,
+    };
+
+    if !accepted.is_empty() {
+        log_info!("FILE_IMPORT", "Importing {} dropped file(s) into {}", accepted.len(), window_label);
+        if let Err(e) = import_dropped_into_vault(app, window_label, &accepted, &mut result.note_ids, &mut skipped).await {
+            log_error!("FILE_IMPORT", "Drop import failed: {}", e);
+            result.error = Some(e);
+        }
+    }
+
+    result.skipped = skipped.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    if let Err(e) = app.emit("drop-import-finished", &result) {
+        log_error!("FILE_IMPORT", "Failed to emit drop-import-finished event: {}", e);
+    }
+}
+
+async fn import_dropped_into_vault(
+    app: &AppHandle,
+    window_label: &str,
+    files: &[PathBuf],
+    imported_ids: &mut Vec<String>,
+    skipped: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let config = app.state::<ConfigState>();
+    let vault_lock = app.state::<VaultLockState>();
+    let notes = app.state::<NotesState>();
+    let modified_tracker = app.state::<ModifiedStateTrackerState>();
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+    let file_storage = FileNotesStorage::new(&config_lock)?;
+
+    let mut existing_slugs: HashSet<String> = notes_lock.values().map(|n| generate_slug(&n.title)).collect();
+    let mut next_position = notes_lock.values().filter_map(|n| n.position).max().unwrap_or(-1) + 1;
+
+    for (index, path) in files.iter().enumerate() {
+        let _ = app.emit("drop-import-progress", &DropImportProgress {
+            window_label: window_label.to_string(),
+            current: index + 1,
+            total: files.len(),
+            file: path.to_string_lossy().to_string(),
+        });
+
+        let mut note = match parse_markdown_file(path).await {
+            Ok(note) => note,
+            Err(e) => {
+                log_error!("FILE_IMPORT", "Failed to import {}: {}", path.display(), e);
+                skipped.push(path.clone());
+                continue;
+            }
+        };
+
+        // Dropped files get app-style IDs so they can't replace an existing note
+        let slug = generate_unique_slug(&note.title, &existing_slugs);
+        note.id = uuid_from_slug(&slug);
+        note.position = Some(next_position);
+        existing_slugs.insert(slug);
+        next_position += 1;
+
+        file_storage.save_note(&note).await?;
+        modified_tracker.initialize_note(&note).await;
+        log_info!("FILE_IMPORT", "Imported dropped file {} as {}", path.display(), note.id);
+        record_audit(&config_lock, AuditEntry::new(
+            AuditAction::Create, AuditSource::Import, Some(&note.id), format!("Imported {}", path.display()),
+        ).with_actor(window_label));
+
+        let _ = app.emit("note-created", &note);
+        imported_ids.push(note.id.clone());
+        notes_lock.insert(note.id.clone(), note);
+    }
+
+    Ok(())
+}
+
 /// Set the notes directory
 #[tauri::command]
 pub async fn set_notes_directory(
+    window: tauri::Window,
+    app: AppHandle,
     directory_path: String,
     config: State<'_, ConfigState>,
 ) -> Result<(), String> {
+    authorize(&window, Capability::ManageVault)?;
     log_info!("STORAGE", "Setting notes directory to: {}", directory_path);
     
     let path = PathBuf::from(&directory_path);
@@ -166,6 +323,7 @@ pub async fn set_notes_directory(
     }
     
     let mut config_lock = config.lock().await;
+    remember_vault(&mut config_lock.storage.recent_vaults, &directory_path, &chrono::Utc::now().to_rfc3339());
     config_lock.storage.notes_directory = Some(directory_path);
     config_lock.storage.use_custom_directory = true;
     
@@ -174,6 +332,11 @@ pub async fn set_notes_directory(
     
     save_config_to_disk(&config_clone).await?;
     
+    // Move the vault lock along with the notes directory
+    if !config_clone.read_only {
+        switch_vault_lock(&app, &path).await?;
+    }
+    
     log_info!("STORAGE", "Notes directory updated successfully");
     Ok(())
 }
@@ -181,10 +344,12 @@ pub async fn set_notes_directory(
 /// Reload notes from the configured directory
 #[tauri::command]
 pub async fn reload_notes_from_directory(
+    window: tauri::Window,
     config: State<'_, ConfigState>,
     notes: State<'_, NotesState>,
     modified_tracker: State<'_, ModifiedStateTrackerState>,
 ) -> Result<Vec<Note>, String> {
+    authorize(&window, Capability::ManageVault)?;
     log_info!("STORAGE", "Reloading notes from configured directory");
     
     let config_lock = config.lock().await;
@@ -269,14 +434,19 @@ async fn parse_markdown_file(path: &Path) -> Result<Note, String> {
         updated_at: now,
         tags: vec![],
         position: None,
+        icon: None,
     })
 }
 
 
 /// Write a note to a markdown file
-async fn write_note_to_file(note: &Note, file_path: &str) -> Result<(), String> {
+async fn write_note_to_file(note: &Note, file_path: &str, wrap_column: Option<usize>) -> Result<(), String> {
     // Write pure markdown content - no frontmatter
-    fs::write(file_path, &note.content)
+    let content = match wrap_column {
+        Some(column) => hard_wrap(&note.content, column),
+        None => note.content.clone(),
+    };
+    fs::write(file_path, content)
         .map_err(|e| format!("Failed to write file: {}", e))?;
     
     Ok(())
@@ -1,21 +1,198 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::io::Write;
+use std::sync::{OnceLock, RwLock};
 use sha2::{Sha256, Digest};
 
 use crate::types::{
     note::{Note, NoteFrontmatter},
-    workspace::{WorkspaceState, WindowState, NotesIndex, NoteIndexEntry},
-    config::AppConfig,
+    workspace::{WorkspaceState, WindowState, NotesIndex, NoteIndexEntry, TagRoutingRule},
+    config::{AppConfig, StorageConfig},
 };
+use crate::error::BlinkError;
+use crate::modules::database;
+use crate::modules::ocr::searchable_content;
 use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::tag_routing::target_note_dir;
+use crate::modules::workspace_settings::load_workspace_settings;
 use crate::{log_debug, log_info, log_error};
 
 /// File-based storage manager for notes and workspace state
 pub struct FileStorageManager {
     notes_dir: PathBuf,
     blink_dir: PathBuf,
+    read_only: bool,
+    follow_symlinks: bool,
+    content_cache: bool,
+    extensions: Vec<String>,
+    default_extension: String,
+    tag_routing: Vec<TagRoutingRule>,
+    /// The vault's index, opened on first use and kept after
+    index: OnceLock<database::NotesDatabase>,
+}
+
+/// The config's note extensions, for scans that don't have the config at hand
+static NOTE_EXTENSIONS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Called when the config is loaded or updated
+pub fn set_note_extensions(storage: &StorageConfig) {
+    *NOTE_EXTENSIONS.write().unwrap() = storage.note_extensions.clone();
+}
+
+fn configured_extensions() -> Vec<String> {
+    let extensions = NOTE_EXTENSIONS.read().unwrap();
+    if extensions.is_empty() {
+        vec!["md".to_string()]
+    } else {
+        extensions.clone()
+    }
+}
+
+/// Modification time in milliseconds since the epoch, as the content cache stores it
+fn file_mtime_millis(metadata: &fs::Metadata) -> i64 {
+    metadata.modified().ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Whether `path` ends in one of `extensions`, which may be written with or
+/// without the dot, in any case
+pub fn has_note_extension(path: &Path, extensions: &[String]) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else { return false };
+    extensions.iter().any(|x| x.trim_start_matches('.').eq_ignore_ascii_case(ext))
+}
+
+/// A note file found by `scan_note_files`, with the other paths that lead to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteFile {
+    pub path: PathBuf,
+    /// Symlinks or hard links to the same file, found elsewhere in the vault
+    pub aliases: Vec<PathBuf>,
+}
+
+/// What makes two paths the same file
+#[cfg(unix)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FileIdentity(u64, u64);
+
+#[cfg(not(unix))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FileIdentity(PathBuf);
+
+#[cfg(unix)]
+fn file_identity(_path: &Path, metadata: &fs::Metadata) -> Option<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    Some(FileIdentity(metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(path: &Path, _metadata: &fs::Metadata) -> Option<FileIdentity> {
+    fs::canonicalize(path).ok().map(FileIdentity)
+}
+
+/// Walk the vault for note files with one of `extensions`, skipping hidden directories (like `.blink`).
+///
+/// With `follow_symlinks`, symlinked folders and files are followed; without it
+/// they are ignored. Folders are walked once: a link back to a folder already
+/// walked, whether a cycle or a second route to it, is not descended into, and
+/// the files under it are recorded as aliases at the link's path instead. Paths
+/// that lead to the same file are returned as one `NoteFile`, with the path
+/// reached without a symlink as the primary.
+pub fn scan_note_files(notes_dir: &Path, follow_symlinks: bool, extensions: &[String]) -> Result<Vec<NoteFile>, String> {
+    let root_metadata = fs::metadata(notes_dir)
+        .map_err(|e| format!("Failed to read notes directory: {}", e))?;
+    let mut visited_dirs: HashMap<FileIdentity, PathBuf> = HashMap::new();
+    if let Some(identity) = file_identity(notes_dir, &root_metadata) {
+        visited_dirs.insert(identity, notes_dir.to_path_buf());
+    }
+    // Linked folders wait until every real folder is walked, so a file is first
+    // reached by its real path
+    let mut pending = vec![notes_dir.to_path_buf()];
+    let mut linked: Vec<(PathBuf, FileIdentity)> = Vec::new();
+    // (link, folder it points to) for links to folders already walked
+    let mut folder_aliases: Vec<(PathBuf, PathBuf)> = Vec::new();
+    // (path, identity, reached through a symlink)
+    let mut found: Vec<(PathBuf, FileIdentity, bool)> = Vec::new();
+
+    loop {
+        let (dir, via_link) = if let Some(dir) = pending.pop() {
+            (dir, false)
+        } else if let Some((dir, identity)) = linked.pop() {
+            if let Some(target) = visited_dirs.get(&identity) {
+                log_debug!("FILE_STORAGE", "Not descending into {:?}, already scanned as {:?}", dir, target);
+                folder_aliases.push((dir, target.clone()));
+                continue;
+            }
+            visited_dirs.insert(identity, dir.clone());
+            (dir, true)
+        } else {
+            break;
+        };
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read notes directory: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else { continue };
+            let is_link = file_type.is_symlink();
+            if is_link && !follow_symlinks {
+                continue;
+            }
+            // Follows the link; a broken one has no metadata and is skipped
+            let Ok(metadata) = fs::metadata(&path) else { continue };
+            let Some(identity) = file_identity(&path, &metadata) else { continue };
+
+            if metadata.is_dir() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                if via_link || is_link {
+                    linked.push((path, identity));
+                } else if !visited_dirs.contains_key(&identity) {
+                    visited_dirs.insert(identity, path.clone());
+                    pending.push(path);
+                }
+            } else if has_note_extension(&path, extensions) {
+                found.push((path, identity, via_link || is_link));
+            }
+        }
+    }
+
+    for (link, target) in &folder_aliases {
+        let under_link: Vec<_> = found
+            .iter()
+            .filter_map(|(path, identity, _)| {
+                let relative = path.strip_prefix(target).ok()?;
+                Some((link.join(relative), identity.clone(), true))
+            })
+            .collect();
+        found.extend(under_link);
+    }
+
+    // Direct paths sort before linked ones, then by path
+    found.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+    let mut files: Vec<NoteFile> = Vec::new();
+    let mut by_identity: HashMap<FileIdentity, usize> = HashMap::new();
+    for (path, identity, _) in found {
+        match by_identity.get(&identity) {
+            Some(&i) => files[i].aliases.push(path),
+            None => {
+                by_identity.insert(identity, files.len());
+                files.push(NoteFile { path, aliases: Vec::new() });
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Every note file in the vault, including subfolders and, with
+/// `follow_symlinks`, symlinked ones, each file once. See `scan_note_files`.
+pub fn list_note_files(notes_dir: &Path, follow_symlinks: bool) -> Result<Vec<PathBuf>, String> {
+    Ok(scan_note_files(notes_dir, follow_symlinks, &configured_extensions())?.into_iter().map(|f| f.path).collect())
 }
 
 impl FileStorageManager {
@@ -23,56 +200,81 @@ impl FileStorageManager {
         let notes_dir = get_configured_notes_directory(config)?;
         let blink_dir = notes_dir.join(".blink");
         
-        // Create directories if they don't exist
-        fs::create_dir_all(&notes_dir)
-            .map_err(|e| format!("Failed to create notes directory: {}", e))?;
-        fs::create_dir_all(&blink_dir)
-            .map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+        // Create directories if they don't exist (never touch a read-only vault)
+        if !config.read_only {
+            fs::create_dir_all(&notes_dir)
+                .map_err(|e| format!("Failed to create notes directory: {}", e))?;
+            fs::create_dir_all(&blink_dir)
+                .map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+        }
+        
+        log_info!("FILE_STORAGE", "Initialized file storage at: {:?} (read_only={})", notes_dir, config.read_only);
         
-        log_info!("FILE_STORAGE", "Initialized file storage at: {:?}", notes_dir);
+        let tag_routing = load_workspace_settings(&notes_dir).tag_routing;
         
         Ok(Self {
             notes_dir,
             blink_dir,
+            read_only: config.read_only,
+            follow_symlinks: config.storage.follow_symlinks,
+            content_cache: config.storage.content_cache,
+            extensions: config.storage.note_extensions.clone(),
+            default_extension: config.storage.default_extension.trim_start_matches('.').to_string(),
+            tag_routing,
+            index: OnceLock::new(),
         })
     }
     
+    /// Whether this storage refuses all writes
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    
+    /// The vault's note files, following symlinks if the config allows
+    fn note_files(&self) -> Result<Vec<NoteFile>, String> {
+        scan_note_files(&self.notes_dir, self.follow_symlinks, &self.extensions)
+    }
+    
+    fn ensure_writable(&self) -> Result<(), String> {
+        if self.read_only {
+            Err(BlinkError::ReadOnly.to_string())
+        } else {
+            Ok(())
+        }
+    }
+    
     /// Load all notes from markdown files
     pub async fn load_notes(&self) -> Result<HashMap<String, Note>, String> {
         log_info!("FILE_STORAGE", "Loading notes from file system...");
         
         let mut notes = HashMap::new();
+        let content_cache = self.load_content_cache();
+        let mut cache_hits = HashSet::new();
         
-        // Read all .md files in the notes directory
-        let entries = fs::read_dir(&self.notes_dir)
-            .map_err(|e| format!("Failed to read notes directory: {}", e))?;
-        
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-            let path = entry.path();
-            
-            // Only process .md files
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "md") {
-                match self.load_note_from_file(&path).await {
-                    Ok(note) => {
-                        // Since ID comes from filename, duplicates shouldn't occur
-                        // The filesystem ensures unique filenames
-                        if notes.contains_key(&note.id) {
-                            log_error!("FILE_STORAGE", "🚨 Unexpected duplicate ID: {} in file {:?}. Skipping file.", 
-                                note.id, path);
-                            continue;
-                        }
-                        
-                        log_debug!("FILE_STORAGE", "Loaded note: {} from {:?}", note.id, path);
-                        notes.insert(note.id.clone(), note);
-                    }
-                    Err(e) => {
-                        log_error!("FILE_STORAGE", "Failed to load note from {:?}: {}", path, e);
+        // Read all note files in the notes directory and its folders
+        for NoteFile { path, .. } in self.note_files()? {
+            match self.load_note_cached(&path, &content_cache, &mut cache_hits) {
+                Ok(note) => {
+                    // Since ID comes from filename, duplicates shouldn't occur
+                    // The filesystem ensures unique filenames
+                    if notes.contains_key(&note.id) {
+                        log_error!("FILE_STORAGE", "🚨 Unexpected duplicate ID: {} in file {:?}. Skipping file.", 
+                            note.id, path);
+                        continue;
                     }
+                    
+                    log_debug!("FILE_STORAGE", "Loaded note: {} from {:?}", note.id, path);
+                    notes.insert(note.id.clone(), note);
+                }
+                Err(e) => {
+                    log_error!("FILE_STORAGE", "Failed to load note from {:?}: {}", path, e);
                 }
             }
         }
         
+        // Icons live in the index, not in the markdown
+        self.apply_note_icons(&mut notes);
+        
         // Fix position conflicts
         let mut position_fixes = Vec::new();
         let mut position_counts = std::collections::HashMap::new();
@@ -137,28 +339,119 @@ impl FileStorageManager {
             }
         }
         
+        log_info!("FILE_STORAGE", "Loaded {} notes from file system", notes.len());
+        
+        // In read-only mode position fixes stay in memory and the index is left as-is
+        if self.read_only {
+            return Ok(notes);
+        }
+        
+        self.prune_content_cache(&cache_hits);
+        
         // Save notes with fixed positions back to disk
         for note in position_fixes {
             self.save_note(&note).await?;
             log_info!("FILE_STORAGE", "✅ Saved position fix for note: {}", note.id);
         }
         
-        log_info!("FILE_STORAGE", "Loaded {} notes from file system", notes.len());
-        
         // Update the index
         self.update_notes_index(&notes).await?;
         
         Ok(notes)
     }
     
-    /// Load a single note from a markdown file
-    async fn load_note_from_file(&self, path: &Path) -> Result<Note, String> {
+    /// Cached note text from the index, when `storage.contentCache` is on. A
+    /// missing index is not created here.
+    fn load_content_cache(&self) -> HashMap<String, database::CachedContent> {
+        if !self.content_cache || !database::get_database_path(&self.notes_dir).exists() {
+            return HashMap::new();
+        }
+        database::initialize_database(&self.notes_dir)
+            .and_then(|db| db.get_cached_contents())
+            .unwrap_or_else(|e| {
+                log_error!("FILE_STORAGE", "Failed to read content cache: {}", e);
+                HashMap::new()
+            })
+    }
+    
+    /// Drop cached text that wasn't used: files that changed or are gone, or
+    /// everything once the cache is turned off
+    fn prune_content_cache(&self, hits: &HashSet<String>) {
+        let result = database::initialize_database(&self.notes_dir)
+            .and_then(|db| db.update_cached_contents(&[], hits));
+        match result {
+            Ok(()) => log_debug!("FILE_STORAGE", "Content cache served {} files", hits.len()),
+            Err(e) => log_error!("FILE_STORAGE", "Failed to prune content cache: {}", e),
+        }
+    }
+    
+    /// Cache the text of a note file Blink just wrote. The cache fills as notes
+    /// are saved rather than by copying the whole vault in on one load.
+    fn cache_written_content(&self, path: &Path, content: &str) {
+        if !self.content_cache {
+            return;
+        }
+        let (Some(relative), Ok(metadata)) = (self.relative_path(path), fs::metadata(path)) else { return };
+        let cached = database::CachedContent {
+            file_hash: Self::compute_file_hash(content),
+            mtime: file_mtime_millis(&metadata),
+            size: metadata.len() as i64,
+            content: content.to_string(),
+        };
+        let Some(db) = self.index(true) else { return };
+        if let Err(e) = db.cache_content(&relative, &cached) {
+            log_error!("FILE_STORAGE", "Failed to cache content of {}: {}", relative, e);
+        }
+    }
+    
+    /// Load a note, taking its text from the content cache when the file's
+    /// modification time and size match and the cached text still hashes to
+    /// what was stored. Paths served from the cache are added to `hits`.
+    fn load_note_cached(
+        &self,
+        path: &Path,
+        cache: &HashMap<String, database::CachedContent>,
+        hits: &mut HashSet<String>,
+    ) -> Result<Note, String> {
+        if let Some(relative) = self.relative_path(path).filter(|r| cache.contains_key(r)) {
+            let metadata = fs::metadata(path)
+                .map_err(|e| format!("Failed to read note file: {}", e))?;
+            let cached = &cache[&relative];
+            if cached.mtime == file_mtime_millis(&metadata)
+                && cached.size == metadata.len() as i64
+                && Self::compute_file_hash(&cached.content) == cached.file_hash
+            {
+                let note = self.parse_markdown_note(&cached.content, path);
+                hits.insert(relative);
+                return note;
+            }
+        }
+        
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read note file: {}", e))?;
-        
         self.parse_markdown_note(&content, path)
     }
     
+    /// Fill in icons saved with `set_note_icon`. A missing index is not created
+    /// here, so read-only vaults stay untouched.
+    fn apply_note_icons(&self, notes: &mut HashMap<String, Note>) {
+        use crate::modules::database;
+        
+        if !database::get_database_path(&self.notes_dir).exists() {
+            return;
+        }
+        match database::initialize_database(&self.notes_dir).and_then(|db| db.get_note_icons()) {
+            Ok(icons) => {
+                for (id, icon) in icons {
+                    if let Some(note) = notes.get_mut(&id) {
+                        note.icon = Some(icon);
+                    }
+                }
+            }
+            Err(e) => log_error!("FILE_STORAGE", "Failed to load note icons: {}", e),
+        }
+    }
+    
     /// Parse pure markdown content
     fn parse_markdown_note(&self, content: &str, path: &Path) -> Result<Note, String> {
         // For migration: check if this is an old file with frontmatter
@@ -211,9 +504,9 @@ impl FileStorageManager {
         };
         
         // Get timestamps and metadata
-        let (created_at, updated_at, tags, position) = if let Some(fm) = frontmatter_data {
+        let (created_at, updated_at, tags, position, icon) = if let Some(fm) = frontmatter_data {
             // Use frontmatter data for migration
-            (fm.created_at, fm.updated_at, fm.tags, fm.position)
+            (fm.created_at, fm.updated_at, fm.tags, fm.position, fm.icon)
         } else {
             // For new files without frontmatter, use file metadata
             let metadata = fs::metadata(path).ok();
@@ -224,7 +517,7 @@ impl FileStorageManager {
                 .flatten()
                 .unwrap_or_else(chrono::Utc::now)
                 .to_rfc3339();
-            (modified.clone(), modified, vec![], None)
+            (modified.clone(), modified, vec![], None, None)
         };
         
         Ok(Note {
@@ -235,13 +528,102 @@ impl FileStorageManager {
             updated_at,
             tags,
             position,
+            icon,
         })
     }
     
+    /// Find a note's file: where the index says it is, at the vault root, or in a folder
+    pub fn find_note_file(&self, note_id: &str) -> Option<PathBuf> {
+        if let Some(path) = self.known_note_file(note_id) {
+            return Some(path);
+        }
+        self.note_files()
+            .ok()?
+            .into_iter()
+            .map(|f| f.path)
+            .find(|p| p.file_stem().map_or(false, |s| s == note_id))
+    }
+    
+    /// Where the index says the note is, or the vault root, without walking
+    /// the vault
+    fn known_note_file(&self, note_id: &str) -> Option<PathBuf> {
+        if let Some(path) = self.indexed_note_file(note_id) {
+            return Some(path);
+        }
+        self.extensions
+            .iter()
+            .map(|ext| self.notes_dir.join(format!("{}.{}", note_id, ext.trim_start_matches('.'))))
+            .find(|path| path.exists())
+    }
+    
+    /// The note's file as recorded in the index, if it is still there
+    fn indexed_note_file(&self, note_id: &str) -> Option<PathBuf> {
+        // Never create an index just to look something up
+        let record = self.index(false)?.get_note(note_id).ok()??;
+        let path = self.notes_dir.join(&record.file_path);
+        (path.is_file() && has_note_extension(&path, &self.extensions)).then_some(path)
+    }
+    
+    /// The vault's index, reusing the handle once it is open. Unless `create`,
+    /// a missing index is left missing.
+    fn index(&self, create: bool) -> Option<&database::NotesDatabase> {
+        if let Some(db) = self.index.get() {
+            return Some(db);
+        }
+        if !create && !database::get_database_path(&self.notes_dir).exists() {
+            return None;
+        }
+        match database::initialize_database(&self.notes_dir) {
+            Ok(db) => Some(self.index.get_or_init(|| db)),
+            Err(e) => {
+                log_error!("FILE_STORAGE", "Failed to open index: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// Path of a file relative to the vault root, with `/` separators
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.notes_dir)
+            .ok()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+    }
+    
     /// Save a note to a markdown file
     pub async fn save_note(&self, note: &Note) -> Result<(), String> {
-        // Use slug ID as filename
-        let file_path = self.notes_dir.join(format!("{}.md", note.id));
+        self.save_note_in(note, None).await
+    }
+    
+    /// Save a note, putting it in `folder` (relative to the vault root) rather
+    /// than where its file already is. Tag routing rules still come first.
+    pub async fn save_note_in(&self, note: &Note, folder: Option<&str>) -> Result<(), String> {
+        self.ensure_writable()?;
+        
+        // Tag routing rules decide the folder; otherwise the note stays where it is.
+        // Every saved note is in the index, so one that isn't there is new and
+        // the vault isn't walked looking for it.
+        let current_path = self.known_note_file(&note.id);
+        let current_dir = current_path.as_ref()
+            .and_then(|p| p.parent())
+            .and_then(|d| self.relative_path(d))
+            .filter(|d| !d.is_empty());
+        let target = match folder {
+            Some(folder) => target_note_dir(&self.tag_routing, &note.tags, None).or_else(|| Some(folder.to_string())),
+            None => target_note_dir(&self.tag_routing, &note.tags, current_dir.as_deref()),
+        };
+        let target_dir = match target {
+            Some(folder) => self.notes_dir.join(folder),
+            None => self.notes_dir.clone(),
+        };
+        fs::create_dir_all(&target_dir)
+            .map_err(|e| format!("Failed to create note folder: {}", e))?;
+        
+        // Use slug ID as filename, keeping the extension of an existing file
+        let extension = current_path.as_ref()
+            .and_then(|p| p.extension())
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.default_extension.clone());
+        let file_path = target_dir.join(format!("{}.{}", note.id, extension));
         
         // Write pure markdown content - no frontmatter
         let file_content = &note.content;
@@ -251,21 +633,31 @@ impl FileStorageManager {
         
         fs::write(&file_path, file_content)
             .map_err(|e| format!("Failed to write note file: {}", e))?;
+        self.cache_written_content(&file_path, file_content);
         
         log_info!("FILE_STORAGE", "💾 Wrote note {} to disk: {:?} ({} bytes, content_hash={})", 
             note.id, file_path, note.content.len(), &content_hash[..8]);
         
+        // Remove the old copy only after the new one is written
+        if let Some(old_path) = current_path.filter(|p| p != &file_path) {
+            fs::remove_file(&old_path)
+                .map_err(|e| format!("Failed to move note file: {}", e))?;
+            log_info!("FILE_STORAGE", "📁 Routed note {} from {:?} to {:?}", note.id, old_path, file_path);
+        }
+        
         Ok(())
     }
     
     /// Delete a note file
     pub async fn delete_note(&self, note_id: &str) -> Result<(), String> {
-        // Find the note file by ID
+        self.ensure_writable()?;
+        
+        // Find the note file by ID, falling back to the path in the index
         let index = self.load_notes_index().await?;
+        let file_path = self.find_note_file(note_id)
+            .or_else(|| index.notes.get(note_id).map(|entry| self.notes_dir.join(&entry.file_path)));
         
-        if let Some(entry) = index.notes.get(note_id) {
-            let file_path = self.notes_dir.join(&entry.file_path);
-            
+        if let Some(file_path) = file_path {
             if file_path.exists() {
                 fs::remove_file(&file_path)
                     .map_err(|e| format!("Failed to delete note file: {}", e))?;
@@ -274,6 +666,22 @@ impl FileStorageManager {
             }
         }
         
+        // Symlinks and hard links to the note would otherwise bring it back
+        if let Ok(db) = database::initialize_database(&self.notes_dir) {
+            // The icon is kept in the index, not the file
+            if let Err(e) = db.set_note_icon(note_id, None) {
+                log_error!("FILE_STORAGE", "Failed to remove icon of deleted note {}: {}", note_id, e);
+            }
+            for alias in db.get_note_aliases(note_id).unwrap_or_default() {
+                let alias_path = self.notes_dir.join(&alias);
+                if fs::symlink_metadata(&alias_path).is_ok() {
+                    fs::remove_file(&alias_path)
+                        .map_err(|e| format!("Failed to delete note alias {}: {}", alias, e))?;
+                    log_info!("FILE_STORAGE", "Deleted note alias: {:?}", alias_path);
+                }
+            }
+        }
+        
         Ok(())
     }
     
@@ -298,6 +706,8 @@ impl FileStorageManager {
     
     /// Save workspace state
     pub async fn save_workspace_state(&self, state: &WorkspaceState) -> Result<(), String> {
+        self.ensure_writable()?;
+        
         let workspace_file = self.blink_dir.join("workspace.json");
         
         let content = serde_json::to_string_pretty(state)
@@ -335,39 +745,47 @@ impl FileStorageManager {
         format!("{:x}", hasher.finalize())
     }
     
-    /// Update notes index in database
+    /// Bring the notes index in line with `notes`. Only rows whose hash, title,
+    /// position, path, tags or timestamp changed are rewritten, and rows are
+    /// deleted for notes whose files are gone.
     pub async fn update_notes_index(&self, notes: &HashMap<String, Note>) -> Result<(), String> {
         use crate::modules::database;
         
+        self.ensure_writable()?;
+        
         // Initialize database
         let db = database::initialize_database(&self.notes_dir)
             .map_err(|e| format!("Failed to initialize database: {}", e))?;
         
-        // Update each note in the database
-        for (_, note) in notes {
-            let filename = self.sanitize_filename(&note.title);
-            let file_path = format!("{}.md", filename);
-            
-            // Compute hash of the full file content
-            let frontmatter = NoteFrontmatter {
-                id: note.id.clone(),
-                title: note.title.clone(),
-                created_at: note.created_at.clone(),
-                updated_at: note.updated_at.clone(),
-                tags: note.tags.clone(),
-                position: note.position,
-            };
-            
-            let frontmatter_yaml = serde_yaml::to_string(&frontmatter)
-                .unwrap_or_default();
-            let file_content = format!("---\n{}---\n{}", frontmatter_yaml, note.content);
-            let file_hash = Self::compute_file_hash(&file_content);
-            
-            // Create database record
-            let note_record = database::NoteRecord {
+        // Notes may live in folders, so record where each file actually is
+        let files = self.note_files()?;
+        let locations: HashMap<String, String> = files
+            .iter()
+            .filter_map(|file| {
+                let stem = file.path.file_stem()?.to_string_lossy().to_string();
+                Some((stem, self.relative_path(&file.path)?))
+            })
+            .collect();
+        
+        // Other paths to the same file are kept so deletes can remove them too
+        let aliases: Vec<(String, String)> = files
+            .iter()
+            .filter_map(|file| Some((file.path.file_stem()?.to_string_lossy().to_string(), &file.aliases)))
+            .flat_map(|(id, aliases)| {
+                aliases.iter().filter_map(move |alias| Some((self.relative_path(alias)?, id.clone())))
+            })
+            .collect();
+        db.set_note_aliases(&aliases)
+            .map_err(|e| format!("Failed to record note aliases: {}", e))?;
+        
+        let desired: Vec<database::NoteRecord> = notes
+            .values()
+            .map(|note| database::NoteRecord {
                 id: note.id.clone(),
                 title: note.title.clone(),
-                file_path,
+                file_path: locations.get(&note.id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}.{}", note.id, self.default_extension)),
                 created_at: chrono::DateTime::parse_from_rfc3339(&note.created_at)
                     .unwrap_or_else(|_| chrono::Utc::now().into())
                     .with_timezone(&chrono::Utc),
@@ -376,13 +794,29 @@ impl FileStorageManager {
                     .with_timezone(&chrono::Utc),
                 tags: note.tags.clone(),
                 position: note.position, // Keep Option<i32> as is
-                file_hash,
-            };
-            
-            // Upsert to database
-            db.upsert_note(&note_record)
-                .map_err(|e| format!("Failed to update database: {}", e))?;
+                // Hash exactly what save_note writes (pure markdown) so it can be
+                // compared against the file on disk later
+                file_hash: Self::compute_file_hash(&note.content),
+            })
+            .collect();
+        
+        let existing = db.get_all_notes()
+            .map_err(|e| format!("Failed to read notes index: {}", e))?;
+        let on_disk: HashSet<String> = locations.into_keys().collect();
+        let diff = database::diff_index(existing, desired, &on_disk);
+        if diff.is_empty() {
+            return Ok(());
+        }
+        
+        db.apply_index_diff(&diff)
+            .map_err(|e| format!("Failed to update database: {}", e))?;
+        for record in &diff.upserts {
+            let Some(note) = notes.get(&record.id) else { continue };
+            if let Err(e) = db.upsert_fts(&note.id, &note.title, &searchable_content(&db, &note.content)) {
+                log_debug!("FILE_STORAGE", "Skipped full-text index for {}: {}", note.id, e);
+            }
         }
+        log_debug!("FILE_STORAGE", "Index updated: {} upserted, {} deleted", diff.upserts.len(), diff.deletes.len());
         
         Ok(())
     }
@@ -419,16 +853,7 @@ impl FileStorageManager {
     
     /// Sanitize filename for safe file system usage
     fn sanitize_filename(&self, title: &str) -> String {
-        title
-            .chars()
-            .map(|c| match c {
-                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
-                c if c.is_control() => '-',
-                c => c,
-            })
-            .collect::<String>()
-            .trim()
-            .to_string()
+        crate::utils::sanitize_filename(title)
     }
     
     /// Migrate from legacy notes.json to file-based system
@@ -459,4 +884,150 @@ impl FileStorageManager {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    fn md() -> Vec<String> {
+        vec!["md".to_string()]
+    }
+
+    fn vault() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("work")).unwrap();
+        fs::write(dir.path().join("inbox.md"), "# Inbox").unwrap();
+        fs::write(dir.path().join("work").join("plan.md"), "# Plan").unwrap();
+        dir
+    }
+
+    fn relative(root: &Path, paths: impl IntoIterator<Item = PathBuf>) -> Vec<String> {
+        paths
+            .into_iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_symlinked_folder_is_an_alias() {
+        let dir = vault();
+        symlink(dir.path().join("work"), dir.path().join("projects")).unwrap();
+
+        let files = scan_note_files(dir.path(), true, &md()).unwrap();
+        assert_eq!(relative(dir.path(), files.iter().map(|f| f.path.clone())), ["inbox.md", "work/plan.md"]);
+        assert_eq!(relative(dir.path(), files[1].aliases.clone()), ["projects/plan.md"]);
+    }
+
+    #[test]
+    fn test_folder_cycles_terminate() {
+        let dir = vault();
+        symlink(dir.path(), dir.path().join("work").join("loop")).unwrap();
+
+        let files = scan_note_files(dir.path(), true, &md()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(relative(dir.path(), files[0].aliases.clone()), ["work/loop/inbox.md"]);
+    }
+
+    #[test]
+    fn test_linked_files_are_aliases() {
+        let dir = vault();
+        fs::hard_link(dir.path().join("inbox.md"), dir.path().join("work").join("inbox-copy.md")).unwrap();
+        symlink(dir.path().join("work").join("plan.md"), dir.path().join("plan-link.md")).unwrap();
+        symlink(dir.path().join("missing.md"), dir.path().join("broken.md")).unwrap();
+
+        let files = scan_note_files(dir.path(), true, &md()).unwrap();
+        assert_eq!(relative(dir.path(), files.iter().map(|f| f.path.clone())), ["inbox.md", "work/plan.md"]);
+        assert_eq!(relative(dir.path(), files[0].aliases.clone()), ["work/inbox-copy.md"]);
+        assert_eq!(relative(dir.path(), files[1].aliases.clone()), ["plan-link.md"]);
+    }
+
+    #[test]
+    fn test_symlinks_can_be_ignored() {
+        let dir = vault();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("elsewhere.md"), "# Elsewhere").unwrap();
+        symlink(outside.path(), dir.path().join("linked")).unwrap();
+
+        let followed = scan_note_files(dir.path(), true, &md()).unwrap();
+        assert_eq!(relative(dir.path(), followed.into_iter().map(|f| f.path)), ["inbox.md", "linked/elsewhere.md", "work/plan.md"]);
+
+        let ignored = scan_note_files(dir.path(), false, &md()).unwrap();
+        assert_eq!(relative(dir.path(), ignored.into_iter().map(|f| f.path)), ["inbox.md", "work/plan.md"]);
+    }
+
+    #[test]
+    fn test_note_extensions() {
+        let dir = vault();
+        fs::write(dir.path().join("todo.TXT"), "- milk").unwrap();
+        fs::write(dir.path().join("essay.markdown"), "# Essay").unwrap();
+        fs::write(dir.path().join("photo.png"), "").unwrap();
+
+        let extensions = vec!["md".to_string(), ".txt".to_string()];
+        let files = scan_note_files(dir.path(), true, &extensions).unwrap();
+        assert_eq!(relative(dir.path(), files.into_iter().map(|f| f.path)), ["inbox.md", "todo.TXT", "work/plan.md"]);
+        assert!(!has_note_extension(Path::new("notes"), &extensions));
+    }
+
+    fn storage(dir: &TempDir, content_cache: bool) -> FileStorageManager {
+        let mut config = AppConfig::default();
+        config.storage.notes_directory = Some(dir.path().to_string_lossy().to_string());
+        config.storage.use_custom_directory = true;
+        config.storage.content_cache = content_cache;
+        FileStorageManager::new(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_saving_an_indexed_note_keeps_its_folder() {
+        let dir = vault();
+        let storage = storage(&dir, false);
+
+        let mut plan = storage.load_notes().await.unwrap().remove("plan").unwrap();
+        plan.content = "# Plan\n\nShip it".to_string();
+        storage.save_note(&plan).await.unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("work").join("plan.md")).unwrap(), plan.content);
+        assert!(!dir.path().join("plan.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_saving_a_new_note_into_a_folder() {
+        let dir = vault();
+        let storage = storage(&dir, false);
+
+        let mut note = storage.load_notes().await.unwrap().remove("inbox").unwrap();
+        note.id = "standup".to_string();
+        note.content = "# Standup".to_string();
+        storage.save_note_in(&note, Some("meetings")).await.unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("meetings").join("standup.md")).unwrap(), "# Standup");
+        let files = storage.note_files().unwrap().into_iter().map(|f| f.path);
+        assert_eq!(relative(dir.path(), files), ["inbox.md", "meetings/standup.md", "work/plan.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_content_cache_fills_on_save() {
+        let dir = vault();
+        let cached = |dir: &TempDir| database::initialize_database(dir.path()).unwrap().get_cached_contents().unwrap();
+
+        let storage = storage(&dir, true);
+        let mut plan = storage.load_notes().await.unwrap().remove("plan").unwrap();
+        assert!(cached(&dir).is_empty());
+        plan.content = "# Plan\n\nShip it".to_string();
+        storage.save_note(&plan).await.unwrap();
+        assert_eq!(cached(&dir)["work/plan.md"].content, plan.content);
+
+        // An edit made outside Blink is read from the file and drops the stale copy
+        fs::write(dir.path().join("work").join("plan.md"), "# Plan\n\nShipped today").unwrap();
+        let notes = storage.load_notes().await.unwrap();
+        assert_eq!(notes["plan"].content, "# Plan\n\nShipped today");
+        assert!(cached(&dir).is_empty());
+
+        // Turning the cache off empties it
+        storage.save_note(&notes["plan"]).await.unwrap();
+        assert_eq!(cached(&dir).len(), 1);
+        self::storage(&dir, false).load_notes().await.unwrap();
+        assert!(cached(&dir).is_empty());
+    }
+}
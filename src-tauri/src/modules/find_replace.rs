@@ -0,0 +1,268 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::audit_log::{record_audit, AuditAction, AuditEntry, AuditSource};
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::undo::{record_undo, UndoKind};
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::{log_error, log_info, log_warn, AutosaveState, UndoHistoryState, VaultLockState};
+
+/// Lines shown per note in a preview; the counts still cover every match
+const PREVIEW_LINES_PER_NOTE: usize = 20;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindReplaceOptions {
+    /// Treat the query as a regular expression; the replacement may use `$1`
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Write the changes. Without it only the preview is returned.
+    pub apply: bool,
+    /// Limit the replacement to these notes, e.g. the ones kept from the preview
+    pub note_ids: Option<Vec<String>>,
+}
+
+/// One changed line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineChange {
+    /// 1-based
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// Matches in one note
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMatches {
+    pub note_id: String,
+    pub title: String,
+    pub matches: usize,
+    pub lines: Vec<LineChange>,
+}
+
+/// What `find_and_replace` found, and changed when applied
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceReport {
+    pub applied: bool,
+    pub notes: Vec<NoteMatches>,
+    pub total_matches: usize,
+    /// Notes left alone because an edit to them is waiting for autosave
+    pub skipped: Vec<String>,
+    /// Copies of the changed notes as they were, under `.blink/history`
+    pub snapshots: Vec<String>,
+}
+
+/// Compile the query. Patterns that match empty text are refused, since they
+/// would insert the replacement between every character.
+pub fn build_matcher(query: &str, options: &FindReplaceOptions) -> Result<Regex, String> {
+    if query.is_empty() {
+        return Err("Search text is empty".to_string());
+    }
+    let pattern = if options.regex { query.to_string() } else { regex::escape(query) };
+    let matcher = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+    if matcher.is_match("") {
+        return Err(format!("Pattern {:?} matches empty text", query));
+    }
+    Ok(matcher)
+}
+
+/// Replace every match line by line, so matches never span lines. Returns the
+/// new content, the number of matches and the changed lines.
+pub fn replace_in_content(content: &str, matcher: &Regex, replacement: &str, expand: bool) -> (String, usize, Vec<LineChange>) {
+    let mut output = String::with_capacity(content.len());
+    let mut matches = 0;
+    let mut lines = Vec::new();
+
+    for (index, raw) in content.split_inclusive('\n').enumerate() {
+        let text = raw.strip_suffix('\n').unwrap_or(raw);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let ending = &raw[text.len()..];
+        let count = matcher.find_iter(text).count();
+        if count == 0 {
+            output.push_str(raw);
+            continue;
+        }
+
+        let replaced = if expand {
+            matcher.replace_all(text, replacement)
+        } else {
+            matcher.replace_all(text, NoExpand(replacement))
+        };
+        matches += count;
+        output.push_str(&replaced);
+        output.push_str(ending);
+        lines.push(LineChange { line: index + 1, before: text.to_string(), after: replaced.to_string() });
+    }
+    (output, matches, lines)
+}
+
+fn history_dir(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(".blink").join("history")
+}
+
+/// Copy a note's content into `.blink/history` before it is rewritten.
+/// `compact_vault` prunes old copies.
+fn write_history_snapshot(notes_dir: &Path, note: &Note, stamp: &str) -> Result<PathBuf, String> {
+    let dir = history_dir(notes_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create history folder: {}", e))?;
+    let path = dir.join(format!("{}-{}.md", note.id, stamp));
+    fs::write(&path, &note.content).map_err(|e| format!("Failed to snapshot {}: {}", note.id, e))?;
+    Ok(path)
+}
+
+/// Find `query` in every note and preview the replacement per note. With
+/// `options.apply` the changes are written: each changed note is snapshotted
+/// to `.blink/history` first, and if any note fails to save the ones already
+/// written are put back, so the vault ends up either fully replaced or as it
+/// was. The whole replacement is a single step for `undo_last_operation`.
+#[tauri::command]
+pub async fn find_and_replace(
+    window: tauri::Window,
+    app: AppHandle,
+    query: String,
+    replacement: String,
+    options: Option<FindReplaceOptions>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
+    autosave: State<'_, AutosaveState>,
+) -> Result<FindReplaceReport, String> {
+    let options = options.unwrap_or_default();
+    let matcher = build_matcher(&query, &options)?;
+    if options.apply {
+        authorize(&window, Capability::WriteNotes)?;
+        ensure_vault_writable(&config, &vault_lock).await?;
+    }
+
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+
+    let mut report = FindReplaceReport { applied: options.apply, ..Default::default() };
+    let mut changes: Vec<(Note, String)> = Vec::new();
+    let mut candidates: Vec<&Note> = notes_lock
+        .values()
+        .filter(|note| options.note_ids.as_ref().map_or(true, |ids| ids.contains(&note.id)))
+        .collect();
+    candidates.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.id.cmp(&b.id)));
+
+    for note in candidates {
+        let (content, matches, mut lines) = replace_in_content(&note.content, &matcher, &replacement, options.regex);
+        if matches == 0 {
+            continue;
+        }
+        lines.truncate(PREVIEW_LINES_PER_NOTE);
+        report.total_matches += matches;
+        report.notes.push(NoteMatches { note_id: note.id.clone(), title: note.display_title(), matches, lines });
+        changes.push((note.clone(), content));
+    }
+    if !options.apply || changes.is_empty() {
+        return Ok(report);
+    }
+
+    // Unsaved edits would overwrite the replacement when autosave catches up
+    let pending: Vec<String> = autosave.lock().unwrap().keys().cloned().collect();
+    changes.retain(|(note, _)| {
+        let waiting = pending.contains(&note.id);
+        if waiting {
+            report.skipped.push(note.id.clone());
+        }
+        !waiting
+    });
+
+    let notes_dir = get_configured_notes_directory(&config_lock)?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S").to_string();
+    for (note, _) in &changes {
+        let path = write_history_snapshot(&notes_dir, note, &stamp)?;
+        report.snapshots.push(path.to_string_lossy().to_string());
+    }
+
+    let file_storage = FileNotesStorage::new(&config_lock)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut written: Vec<&Note> = Vec::new();
+    let mut updated: Vec<Note> = Vec::new();
+    for (note, content) in &changes {
+        let mut changed = note.clone();
+        changed.content = content.clone();
+        changed.updated_at = now.clone();
+        if let Err(e) = file_storage.save_note(&changed).await {
+            for original in written {
+                if let Err(restore_error) = file_storage.save_note(original).await {
+                    log_error!("NOTES", "Failed to restore {} after a failed replace: {}", original.id, restore_error);
+                }
+            }
+            log_warn!("NOTES", "Replace stopped at {}, {} notes restored", note.id, updated.len());
+            return Err(format!("Failed to save {}: {}. No notes were changed.", note.title, e));
+        }
+        written.push(note);
+        updated.push(changed);
+    }
+
+    for note in &updated {
+        notes_lock.insert(note.id.clone(), note.clone());
+        modified_tracker.update_content_hash(&note.id, &note.content).await;
+        app.emit("note-updated", note).unwrap_or_else(|e| {
+            log_error!("NOTES", "Failed to emit note-updated event: {}", e);
+        });
+    }
+    report.notes.retain(|n| !report.skipped.contains(&n.note_id));
+    report.total_matches = report.notes.iter().map(|n| n.matches).sum();
+
+    let description = format!("Replace \"{}\" with \"{}\" in {} notes", query, replacement, updated.len());
+    record_audit(&config_lock, AuditEntry::new(AuditAction::Update, AuditSource::Command, None, description.clone()));
+    record_undo(&history, UndoKind::Replace, description.clone(), changes.into_iter().map(|(note, _)| note).collect());
+    log_info!("NOTES", "{} ({} matches)", description, report.total_matches);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(regex: bool, case_sensitive: bool) -> FindReplaceOptions {
+        FindReplaceOptions { regex, case_sensitive, ..Default::default() }
+    }
+
+    #[test]
+    fn test_literal_replace_previews_changed_lines() {
+        let matcher = build_matcher("Falcon", &options(false, false)).unwrap();
+        let (content, matches, lines) =
+            replace_in_content("Project falcon\r\nnothing here\nFALCON and falcon.", &matcher, "Osprey $1", false);
+        assert_eq!(content, "Project Osprey $1\r\nnothing here\nOsprey $1 and Osprey $1.");
+        assert_eq!(matches, 3);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], LineChange { line: 1, before: "Project falcon".into(), after: "Project Osprey $1".into() });
+        assert_eq!(lines[1].line, 3);
+    }
+
+    #[test]
+    fn test_regex_replace_expands_groups() {
+        let matcher = build_matcher(r"v(\d+)\.(\d+)", &options(true, true)).unwrap();
+        let (content, matches, _) = replace_in_content("ship v1.2, then V3.4", &matcher, "v$1.$2.0", true);
+        assert_eq!(content, "ship v1.2.0, then V3.4");
+        assert_eq!(matches, 1);
+    }
+
+    #[test]
+    fn test_bad_patterns_are_refused() {
+        assert!(build_matcher("", &options(false, false)).is_err());
+        assert!(build_matcher("(unclosed", &options(true, false)).is_err());
+        assert!(build_matcher("x*", &options(true, false)).is_err());
+        assert!(build_matcher("(unclosed", &options(false, false)).is_ok());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::types::config::FormattingConfig;
+
+/// Narrowest column `hard_wrap` accepts; anything narrower would leave list
+/// markers and quote prefixes on lines of their own
+pub const MIN_WRAP_COLUMN: usize = 20;
+
+/// Apply the enabled format-on-save transforms to note content.
+///
+/// Transforms run in a fixed order so the result is stable regardless of which
+/// ones are enabled. Fenced code blocks are left untouched by the line-level
+/// transforms.
+pub fn apply_save_transforms(content: &str, config: &FormattingConfig) -> String {
+    let mut result = content.to_string();
+
+    if config.convert_smart_quotes {
+        result = convert_smart_quotes(&result);
+    }
+    if config.trim_trailing_whitespace {
+        result = trim_trailing_whitespace(&result);
+    }
+    if config.normalize_heading_spacing {
+        result = normalize_heading_spacing(&result);
+    }
+    if let Some(column) = config.wrap_column {
+        result = hard_wrap(&result, column);
+    }
+    if config.ensure_final_newline {
+        result = ensure_final_newline(&result);
+    }
+
+    result
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Map each line through `f`, skipping lines inside fenced code blocks
+fn map_prose_lines(content: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut in_code = false;
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| {
+            if is_fence(line) {
+                in_code = !in_code;
+                return line.to_string();
+            }
+            if in_code {
+                line.to_string()
+            } else {
+                f(line)
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Replace typographic quotes with their ASCII equivalents
+pub fn convert_smart_quotes(content: &str) -> String {
+    map_prose_lines(content, |line| {
+        line.replace(['\u{201C}', '\u{201D}', '\u{201E}'], "\"")
+            .replace(['\u{2018}', '\u{2019}', '\u{201A}'], "'")
+    })
+}
+
+/// Strip trailing spaces and tabs from every line
+pub fn trim_trailing_whitespace(content: &str) -> String {
+    map_prose_lines(content, |line| line.trim_end_matches([' ', '\t']).to_string())
+}
+
+/// Collapse the space after ATX heading hashes to one and ensure a blank line before headings.
+/// Lines like `#tag` are not headings in CommonMark and are left alone.
+pub fn normalize_heading_spacing(content: &str) -> String {
+    let mut in_code = false;
+    let mut output: Vec<String> = Vec::new();
+
+    for line in content.split('\n') {
+        if is_fence(line) {
+            in_code = !in_code;
+            output.push(line.to_string());
+            continue;
+        }
+
+        if in_code {
+            output.push(line.to_string());
+            continue;
+        }
+
+        let hashes = line.chars().take_while(|c| *c == '#').count();
+        let rest = &line[hashes..];
+        if hashes == 0 || hashes > 6 || rest.trim().is_empty() || !rest.starts_with([' ', '\t']) {
+            output.push(line.to_string());
+            continue;
+        }
+
+        let heading = format!("{} {}", "#".repeat(hashes), rest.trim_start());
+        if output.last().map_or(false, |prev| !prev.trim().is_empty()) {
+            output.push(String::new());
+        }
+        output.push(heading);
+    }
+
+    output.join("\n")
+}
+
+/// Length of a list marker at the start of `text`, with its space and any
+/// task box, or 0 when `text` isn't a list item
+fn list_marker_len(text: &str) -> usize {
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    let marker = if text.starts_with(['-', '*', '+']) {
+        1
+    } else if (1..=9).contains(&digits) && text[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        return 0;
+    };
+    if !text[marker..].starts_with(' ') {
+        return 0;
+    }
+    let task = &text[marker + 1..];
+    if task.starts_with("[ ] ") || task.starts_with("[x] ") || task.starts_with("[X] ") {
+        marker + 5
+    } else {
+        marker + 1
+    }
+}
+
+/// A table's delimiter row, like `| --- | :-: |`
+fn is_table_delimiter(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.contains('|') && trimmed.contains('-') && trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+/// Whether a word at the start of a line would begin a new block: a list
+/// item, heading, quote, fence, rule, setext underline or HTML
+fn starts_block(word: &str) -> bool {
+    let digits = word.chars().take_while(|c| c.is_ascii_digit()).count();
+    word == "+"
+        || word.chars().all(|c| matches!(c, '-' | '*' | '_' | '='))
+        || word.starts_with(['#', '>', '<', '|'])
+        || word.starts_with("```")
+        || word.starts_with("~~~")
+        || ((1..=9).contains(&digits) && matches!(&word[digits..], "." | ")"))
+}
+
+/// Lines that can't be split without changing what they render as
+fn is_wrappable(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let indent: usize = line[..line.len() - trimmed.len()].chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+    let indented_code = indent >= 4 && list_marker_len(trimmed) == 0 && !trimmed.starts_with('>');
+    !(indented_code || trimmed.starts_with(['#', '|', '<']) || (trimmed.starts_with('[') && trimmed.contains("]:")))
+}
+
+/// Split a line at spaces so each piece fits in `column` where it can. The
+/// first piece keeps the line's indent, quote markers and list marker; the
+/// rest repeat the quote markers and indent under the list marker. Words that
+/// would start a block stay on the line before, and a trailing hard break
+/// stays at the end.
+fn wrap_line(line: &str, column: usize) -> Vec<String> {
+    let mut quote_end = line.len() - line.trim_start().len();
+    while let Some(rest) = line[quote_end..].strip_prefix('>') {
+        quote_end += if rest.starts_with(' ') { 2 } else { 1 };
+    }
+    let marker_len = list_marker_len(&line[quote_end..]);
+    let continuation = format!("{}{}", &line[..quote_end], " ".repeat(marker_len));
+    let text = &line[quote_end + marker_len..];
+    let body = text.trim_end();
+
+    let mut lines = Vec::new();
+    let mut current = line[..quote_end + marker_len].to_string();
+    let mut width = current.chars().count();
+    let mut has_words = false;
+    for word in body.split(' ') {
+        let word_width = word.chars().count();
+        if has_words && width + 1 + word_width > column && !starts_block(word) {
+            lines.push(std::mem::replace(&mut current, continuation.clone()));
+            width = current.chars().count();
+            has_words = false;
+        }
+        if has_words {
+            current.push(' ');
+            width += 1;
+        }
+        current.push_str(word);
+        width += word_width;
+        has_words = true;
+    }
+    current.push_str(&text[body.len()..]);
+    lines.push(current);
+    lines
+}
+
+/// Hard-wrap prose lines longer than `column` characters at spaces, for
+/// tools that want wrapped plain text. Lines are only ever split, never
+/// joined, so existing breaks are kept. Fenced and indented code, tables,
+/// headings, HTML and link definitions are left alone, and words longer than
+/// `column` (like URLs) get a line to themselves rather than being broken.
+pub fn hard_wrap(content: &str, column: usize) -> String {
+    if column < MIN_WRAP_COLUMN {
+        return content.to_string();
+    }
+
+    let lines: Vec<&str> = content.split('\n').collect();
+    let mut in_code = false;
+    let mut in_table = false;
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        if is_fence(line) {
+            in_code = !in_code;
+            output.push(line.to_string());
+            continue;
+        }
+        // A table runs from the row above its delimiter row to the next blank line
+        if line.trim().is_empty() {
+            in_table = false;
+        } else if !in_table && line.contains('|') && lines.get(i + 1).map_or(false, |next| is_table_delimiter(next)) {
+            in_table = true;
+        }
+
+        if in_code || in_table || line.chars().count() <= column || !is_wrappable(line) {
+            output.push(line.to_string());
+        } else {
+            output.extend(wrap_line(line, column));
+        }
+    }
+
+    output.join("\n")
+}
+
+/// Make sure the content ends with exactly one newline
+pub fn ensure_final_newline(content: &str) -> String {
+    if content.is_empty() {
+        return String::new();
+    }
+    format!("{}\n", content.trim_end_matches('\n'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_enabled() -> FormattingConfig {
+        FormattingConfig {
+            trim_trailing_whitespace: true,
+            normalize_heading_spacing: true,
+            convert_smart_quotes: true,
+            ensure_final_newline: true,
+            wrap_column: None,
+        }
+    }
+
+    #[test]
+    fn test_disabled_pipeline_is_identity() {
+        let content = "#Title  \nIt\u{2019}s fine";
+        let config = crate::types::config::default_formatting();
+        assert_eq!(apply_save_transforms(content, &config), content);
+    }
+
+    #[test]
+    fn test_full_pipeline() {
+        let content = "#   Title  \nSome \u{201C}quoted\u{201D} text\t\n## Next\n\n\n";
+        let expected = "# Title\nSome \"quoted\" text\n\n## Next\n";
+        assert_eq!(apply_save_transforms(content, &all_enabled()), expected);
+    }
+
+    #[test]
+    fn test_hashtags_are_not_headings() {
+        let content = "intro\n#project notes";
+        assert_eq!(normalize_heading_spacing(content), content);
+    }
+
+    #[test]
+    fn test_code_blocks_are_untouched() {
+        let content = "```\n#not a heading  \n\u{201C}x\u{201D}\n```";
+        assert_eq!(convert_smart_quotes(content), content);
+        assert_eq!(trim_trailing_whitespace(content), content);
+        assert_eq!(normalize_heading_spacing(content), content);
+    }
+
+    #[test]
+    fn test_empty_content_stays_empty() {
+        assert_eq!(ensure_final_newline(""), "");
+        assert_eq!(ensure_final_newline("a"), "a\n");
+    }
+
+    #[test]
+    fn test_hard_wrap_keeps_markers_and_prefixes() {
+        let content = "A plain paragraph line that runs well past the wrapping column.  \n\
+                       - [ ] a task item long enough that it needs wrapping - twice\n\
+                       > quoted text that goes on and on beyond the column limit";
+        let expected = "A plain paragraph line that runs\nwell past the wrapping column.  \n\
+                        - [ ] a task item long enough that\n      it needs wrapping - twice\n\
+                        > quoted text that goes on and on\n> beyond the column limit";
+        assert_eq!(hard_wrap(content, 34), expected);
+        assert_eq!(hard_wrap(expected, 34), expected);
+
+        // Continuation lines must not start a list item
+        assert_eq!(hard_wrap("one two three four fives - six 12. seven", 25), "one two three four fives -\nsix 12. seven");
+        assert_eq!(hard_wrap("one two three four fives 12. six", 25), "one two three four fives 12.\nsix");
+    }
+
+    #[test]
+    fn test_hard_wrap_skips_code_tables_and_headings() {
+        let content = "# A heading that is much longer than the wrap column\n\
+                       ```\nlet code = \"a long line of code that must never be wrapped\";\n```\n\
+                       | a long table header cell | another long header cell |\n|---|---|\n\
+                       | a row that is far wider than the column | x |\n\n\
+                       see https://example.com/a/very/long/url/that/cannot/be/broken ok";
+        let expected = content.replace(
+            "see https://example.com/a/very/long/url/that/cannot/be/broken ok",
+            "see\nhttps://example.com/a/very/long/url/that/cannot/be/broken\nok",
+        );
+        assert_eq!(hard_wrap(content, 30), expected);
+        assert_eq!(hard_wrap(content, 5), content);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::State;
+
+use crate::modules::network_policy::NetworkPolicy;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::{ConfigState, NotesState};
+use crate::VaultLockState;
+use crate::{log_info, log_warn};
+
+const GITHUB_API: &str = "https://api.github.com";
+const GIST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Where a note is published. Notes are plain markdown, so this lives in
+/// `.blink/publications.json` rather than in the note file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GistPublication {
+    pub gist_id: String,
+    pub url: String,
+    pub public: bool,
+    pub published_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GistResponse {
+    id: String,
+    html_url: String,
+}
+
+fn publications_path(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(".blink").join("publications.json")
+}
+
+pub fn load_publications(notes_dir: &Path) -> HashMap<String, GistPublication> {
+    fs::read_to_string(publications_path(notes_dir))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_publications(notes_dir: &Path, publications: &HashMap<String, GistPublication>) -> Result<(), String> {
+    let path = publications_path(notes_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(publications)
+        .map_err(|e| format!("Failed to serialize publications: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save publications: {}", e))
+}
+
+/// Turn a GitHub error response into something the user can act on
+fn gist_error(status: StatusCode, body: &str) -> String {
+    match status {
+        StatusCode::UNAUTHORIZED => "GitHub rejected the token; check that it is valid and not expired".to_string(),
+        StatusCode::FORBIDDEN if body.contains("rate limit") => "GitHub rate limit reached; try again later".to_string(),
+        StatusCode::FORBIDDEN | StatusCode::NOT_FOUND => {
+            "The token can't manage this gist; it needs the 'gist' scope".to_string()
+        }
+        StatusCode::UNPROCESSABLE_ENTITY => format!("GitHub refused the gist: {}", body),
+        _ => format!("GitHub request failed ({}): {}", status, body),
+    }
+}
+
+async fn gist_request(
+    client: &reqwest::Client,
+    method: Method,
+    path: &str,
+    token: &str,
+    body: Option<serde_json::Value>,
+) -> Result<(StatusCode, String), String> {
+    let mut request = client
+        .request(method, format!("{}{}", GITHUB_API, path))
+        .bearer_auth(token)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    if let Some(body) = body {
+        request = request
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+    }
+
+    let response = request.send().await.map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| format!("Failed to read GitHub response: {}", e))?;
+    Ok((status, text))
+}
+
+/// Create a gist for a note, or update the one it was published to before
+#[tauri::command]
+pub async fn publish_note_to_gist(
+    window: tauri::Window,
+    note_id: String,
+    public: bool,
+    token: String,
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<GistPublication, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    if token.trim().is_empty() {
+        return Err("A GitHub token is required to publish".to_string());
+    }
+    // The publication is recorded in the vault, so check it can be written first
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let policy = NetworkPolicy::current(&config).await;
+    let client = policy.http_client("Gist publishing", GIST_TIMEOUT)?;
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let note = notes
+        .lock()
+        .await
+        .get(&note_id)
+        .cloned()
+        .ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let mut publications = load_publications(&notes_dir);
+    let existing = publications.get(&note_id).cloned();
+    if let Some(existing) = &existing {
+        if existing.public != public {
+            return Err("GitHub can't change a gist's visibility; unpublish the note first".to_string());
+        }
+    }
+
+    let files = json!({ format!("{}.md", note.id): { "content": note.content } });
+    let mut response = None;
+    if let Some(existing) = &existing {
+        let body = json!({ "description": note.title, "files": files });
+        let (status, text) = gist_request(&client, Method::PATCH, &format!("/gists/{}", existing.gist_id), &token, Some(body)).await?;
+        // A gist deleted on GitHub is recreated rather than reported as an error
+        if status != StatusCode::NOT_FOUND {
+            response = Some((status, text));
+        } else {
+            log_warn!("GIST", "Gist {} for {} is gone; creating a new one", existing.gist_id, note_id);
+        }
+    }
+    let (status, text) = match response {
+        Some(response) => response,
+        None => {
+            let body = json!({ "description": note.title, "public": public, "files": files });
+            gist_request(&client, Method::POST, "/gists", &token, Some(body)).await?
+        }
+    };
+    if !status.is_success() {
+        return Err(gist_error(status, &text));
+    }
+    let gist: GistResponse = serde_json::from_str(&text)
+        .map_err(|e| format!("Unexpected response from GitHub: {}", e))?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let publication = GistPublication {
+        published_at: existing
+            .filter(|e| e.gist_id == gist.id)
+            .map(|e| e.published_at)
+            .unwrap_or_else(|| now.clone()),
+        gist_id: gist.id,
+        url: gist.html_url,
+        public,
+        updated_at: now,
+    };
+    publications.insert(note_id.clone(), publication.clone());
+    save_publications(&notes_dir, &publications)?;
+
+    log_info!("GIST", "Published {} to {}", note_id, publication.url);
+    Ok(publication)
+}
+
+/// Delete a note's gist and forget the publication
+#[tauri::command]
+pub async fn unpublish_note_gist(
+    window: tauri::Window,
+    note_id: String,
+    token: String,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let policy = NetworkPolicy::current(&config).await;
+    let client = policy.http_client("Gist publishing", GIST_TIMEOUT)?;
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+
+    let mut publications = load_publications(&notes_dir);
+    let publication = publications
+        .get(&note_id)
+        .cloned()
+        .ok_or_else(|| format!("Note {} is not published", note_id))?;
+
+    let (status, text) = gist_request(&client, Method::DELETE, &format!("/gists/{}", publication.gist_id), &token, None).await?;
+    // 404 means it was already deleted on GitHub
+    if !status.is_success() && status != StatusCode::NOT_FOUND {
+        return Err(gist_error(status, &text));
+    }
+
+    publications.remove(&note_id);
+    save_publications(&notes_dir, &publications)?;
+    log_info!("GIST", "Unpublished {} (gist {})", note_id, publication.gist_id);
+    Ok(())
+}
+
+/// The gist a note is published to, if any
+#[tauri::command]
+pub async fn get_gist_publication(
+    note_id: String,
+    config: State<'_, ConfigState>,
+) -> Result<Option<GistPublication>, String> {
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    Ok(load_publications(&notes_dir).remove(&note_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn publication(gist_id: &str) -> GistPublication {
+        GistPublication {
+            gist_id: gist_id.to_string(),
+            url: format!("https://gist.github.com/{}", gist_id),
+            public: false,
+            published_at: "2024-05-01T12:00:00Z".to_string(),
+            updated_at: "2024-05-02T12:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_publications_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_publications(dir.path()).is_empty());
+
+        let publications = HashMap::from([("plan".to_string(), publication("abc123"))]);
+        save_publications(dir.path(), &publications).unwrap();
+        let loaded = load_publications(dir.path());
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded["plan"].gist_id, "abc123");
+        assert_eq!(loaded["plan"].published_at, "2024-05-01T12:00:00Z");
+    }
+
+    #[test]
+    fn test_unreadable_publications_are_empty() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".blink")).unwrap();
+        fs::write(publications_path(dir.path()), "not json").unwrap();
+        assert!(load_publications(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_gist_errors() {
+        assert!(gist_error(StatusCode::UNAUTHORIZED, "").contains("token"));
+        assert!(gist_error(StatusCode::FORBIDDEN, "API rate limit exceeded").contains("rate limit"));
+        assert!(gist_error(StatusCode::FORBIDDEN, "").contains("'gist' scope"));
+        assert!(gist_error(StatusCode::NOT_FOUND, "").contains("'gist' scope"));
+        assert_eq!(gist_error(StatusCode::UNPROCESSABLE_ENTITY, "bad files"), "GitHub refused the gist: bad files");
+        assert!(gist_error(StatusCode::BAD_GATEWAY, "").contains("502"));
+    }
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use tauri::{AppHandle, State};
+
+use crate::modules::commands::insert_generated_note;
+use crate::modules::file_operations::allowed_roots;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::{CreateNoteRequest, Note};
+use crate::types::window::{ConfigState, NotesState};
+use crate::utils::paths::ensure_within;
+use crate::VaultLockState;
+use crate::log_info;
+
+/// Folder meeting notes are created in
+pub const MEETINGS_FOLDER: &str = "meetings";
+
+/// When an event happens, as written in the calendar file
+#[derive(Debug, Clone, PartialEq)]
+pub enum IcsTime {
+    Utc(DateTime<Utc>),
+    /// Wall-clock time, optionally in a named zone (`TZID`)
+    Local(NaiveDateTime, Option<String>),
+    AllDay(NaiveDate),
+}
+
+/// The fields of a `VEVENT` a meeting note needs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IcsEvent {
+    pub summary: String,
+    pub start: Option<IcsTime>,
+    pub end: Option<IcsTime>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub organizer: Option<String>,
+    pub attendees: Vec<String>,
+}
+
+/// Long lines are folded onto continuation lines that start with a space or tab
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+/// Split `NAME;PARAM=x:value` into the name, its parameters and the value
+fn split_property(line: &str) -> Option<(String, Vec<(String, String)>, &str)> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value))
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<IcsTime> {
+    if param(params, "VALUE") == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(IcsTime::AllDay);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(IcsTime::Utc(Utc.from_utc_datetime(&naive)));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some(IcsTime::Local(naive, param(params, "TZID").map(str::to_string)))
+}
+
+/// A person from `ORGANIZER` or `ATTENDEE`: their name when given, else the address
+fn person(value: &str, params: &[(String, String)]) -> String {
+    param(params, "CN")
+        .map(str::to_string)
+        .unwrap_or_else(|| value.trim_start_matches("mailto:").trim_start_matches("MAILTO:").to_string())
+}
+
+/// Parse the first event in an iCalendar file
+pub fn parse_ics_event(text: &str) -> Result<IcsEvent, String> {
+    let mut event: Option<IcsEvent> = None;
+    // Nested components such as VALARM have their own DESCRIPTION
+    let mut nested_depth = 0;
+
+    for line in unfold_lines(text) {
+        let Some((name, params, value)) = split_property(&line) else { continue };
+        if event.is_none() {
+            if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+                event = Some(IcsEvent::default());
+            }
+            continue;
+        }
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", Some(_)) => nested_depth += 1,
+            ("END", Some(_)) if nested_depth > 0 => nested_depth -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => break,
+            (_, Some(_)) if nested_depth > 0 => {}
+            ("SUMMARY", Some(e)) => e.summary = unescape(value),
+            ("DTSTART", Some(e)) => e.start = parse_time(value, &params),
+            ("DTEND", Some(e)) => e.end = parse_time(value, &params),
+            ("LOCATION", Some(e)) => e.location = Some(unescape(value)).filter(|v| !v.is_empty()),
+            ("DESCRIPTION", Some(e)) => e.description = Some(unescape(value)).filter(|v| !v.trim().is_empty()),
+            ("ORGANIZER", Some(e)) => e.organizer = Some(person(value, &params)),
+            ("ATTENDEE", Some(e)) => e.attendees.push(person(value, &params)),
+            _ => {}
+        }
+    }
+
+    let mut event = event.ok_or("No event found in calendar data")?;
+    if event.summary.trim().is_empty() {
+        event.summary = "Meeting".to_string();
+    }
+    Ok(event)
+}
+
+fn format_time(time: &IcsTime) -> String {
+    match time {
+        IcsTime::Utc(utc) => utc.with_timezone(&Local).format("%a %-d %b %Y, %H:%M").to_string(),
+        IcsTime::Local(naive, Some(zone)) => format!("{} ({})", naive.format("%a %-d %b %Y, %H:%M"), zone),
+        IcsTime::Local(naive, None) => naive.format("%a %-d %b %Y, %H:%M").to_string(),
+        IcsTime::AllDay(date) => format!("{} (all day)", date.format("%a %-d %b %Y")),
+    }
+}
+
+fn event_date(time: &IcsTime) -> NaiveDate {
+    match time {
+        IcsTime::Utc(utc) => utc.with_timezone(&Local).date_naive(),
+        IcsTime::Local(naive, _) => naive.date(),
+        IcsTime::AllDay(date) => *date,
+    }
+}
+
+/// Title and markdown for a meeting note
+pub fn meeting_note(event: &IcsEvent) -> (String, String) {
+    let title = match &event.start {
+        Some(start) => format!("{} {}", event_date(start).format("%Y-%m-%d"), event.summary),
+        None => event.summary.clone(),
+    };
+
+    let mut content = format!("# {}\n\n", event.summary);
+    if let Some(start) = &event.start {
+        match &event.end {
+            Some(end) if event_date(start) == event_date(end) && !matches!(end, IcsTime::AllDay(_)) => {
+                let end_time = match end {
+                    IcsTime::Utc(utc) => utc.with_timezone(&Local).format("%H:%M").to_string(),
+                    IcsTime::Local(naive, _) => naive.format("%H:%M").to_string(),
+                    IcsTime::AllDay(_) => unreachable!(),
+                };
+                content.push_str(&format!("**When:** {} – {}\n", format_time(start), end_time));
+            }
+            Some(end) if !matches!((start, end), (IcsTime::AllDay(_), IcsTime::AllDay(_))) => {
+                content.push_str(&format!("**When:** {} – {}\n", format_time(start), format_time(end)));
+            }
+            _ => content.push_str(&format!("**When:** {}\n", format_time(start))),
+        }
+    }
+    if let Some(location) = &event.location {
+        content.push_str(&format!("**Where:** {}\n", location));
+    }
+    if let Some(organizer) = &event.organizer {
+        content.push_str(&format!("**Organizer:** {}\n", organizer));
+    }
+
+    if !event.attendees.is_empty() {
+        content.push_str("\n## Attendees\n\n");
+        for attendee in &event.attendees {
+            content.push_str(&format!("- {}\n", attendee));
+        }
+    }
+    if let Some(description) = &event.description {
+        content.push_str(&format!("\n## Details\n\n{}\n", description.trim()));
+    }
+    content.push_str("\n## Agenda\n\n- \n\n## Notes\n\n\n## Action items\n\n- [ ] \n");
+
+    (title, content)
+}
+
+/// Create a meeting note in `meetings/` from an .ics file path or raw iCalendar text
+#[tauri::command]
+pub async fn create_note_from_ics(
+    window: tauri::Window,
+    app: AppHandle,
+    path_or_text: String,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Note, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let text = if path_or_text.contains("BEGIN:VCALENDAR") || path_or_text.contains("BEGIN:VEVENT") {
+        path_or_text
+    } else {
+        let path = ensure_within(Path::new(path_or_text.trim()), &allowed_roots(&*config.lock().await))?;
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read calendar file: {}", e))?
+    };
+    let event = parse_ics_event(&text)?;
+    let (title, content) = meeting_note(&event);
+
+    let note = insert_generated_note(
+        &app,
+        CreateNoteRequest { title, content, tags: vec!["meeting".to_string()] },
+        Some(MEETINGS_FOLDER),
+        &notes,
+        &config,
+        &modified_tracker,
+    )
+    .await?;
+    log_info!("ICS", "Created meeting note from calendar event: {}", note.title);
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Design review\\, Q3\r
+DTSTART;TZID=Europe/Paris:20240115T100000\r
+DTEND;TZID=Europe/Paris:20240115T110000\r
+LOCATION:Room 4\r
+ORGANIZER;CN=Ada Lovelace:mailto:ada@example.com\r
+ATTENDEE;CN=\"Grace Hopper\";ROLE=REQ-PARTICIPANT:mailto:grace@example.com\r
+ATTENDEE:mailto:alan@example.com\r
+DESCRIPTION:Walk through the new\\nsync design and decide on rol\r
+ lout.\r
+BEGIN:VALARM\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_ics_event() {
+        let event = parse_ics_event(EVENT).unwrap();
+        assert_eq!(event.summary, "Design review, Q3");
+        assert_eq!(event.organizer.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(event.attendees, vec!["Grace Hopper", "alan@example.com"]);
+        assert_eq!(event.description.as_deref(), Some("Walk through the new\nsync design and decide on rollout."));
+        assert_eq!(event.start, Some(IcsTime::Local(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap().and_hms_opt(10, 0, 0).unwrap(),
+            Some("Europe/Paris".to_string()),
+        )));
+
+        assert!(parse_ics_event("BEGIN:VCALENDAR\nEND:VCALENDAR").is_err());
+    }
+
+    #[test]
+    fn test_meeting_note() {
+        let (title, content) = meeting_note(&parse_ics_event(EVENT).unwrap());
+        assert_eq!(title, "2024-01-15 Design review, Q3");
+        assert!(content.contains("**When:** Mon 15 Jan 2024, 10:00 (Europe/Paris) – 11:00"));
+        assert!(content.contains("## Attendees\n\n- Grace Hopper\n- alan@example.com\n"));
+        assert!(content.contains("## Agenda"));
+    }
+
+    #[tokio::test]
+    async fn test_meeting_notes_are_saved_in_meetings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = crate::types::config::AppConfig::default();
+        config.storage.notes_directory = Some(dir.path().to_string_lossy().to_string());
+        config.storage.use_custom_directory = true;
+
+        let (title, content) = meeting_note(&parse_ics_event(EVENT).unwrap());
+        let note = Note {
+            id: "design-review".to_string(),
+            title,
+            content: content.clone(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: vec!["meeting".to_string()],
+            position: None,
+            icon: None,
+        };
+        let storage = crate::modules::file_notes_storage::FileNotesStorage::new(&config).unwrap();
+        storage.save_note_in(&note, Some(MEETINGS_FOLDER)).await.unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join(MEETINGS_FOLDER).join("design-review.md")).unwrap(), content);
+        assert!(!dir.path().join("design-review.md").exists());
+    }
+}
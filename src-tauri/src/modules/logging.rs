@@ -1,35 +1,205 @@
 use chrono::Local;
-use std::fs::OpenOptions;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
 use dirs;
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::types::config::{default_logging, LogLevel, LoggingConfig};
+
+/// Categories that also get a file of their own, next to the combined `blink.log`
+pub const LOG_CATEGORIES: [&str; 4] = ["WINDOW", "STORAGE", "SHORTCUT", "DRAG"];
+
+/// A log file is rotated once it grows past this
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated copies kept, as `blink.log.1` (newest) to `blink.log.3`
+const KEEP_ROTATED: usize = 3;
+
+const COMBINED_LOG_FILE: &str = "blink.log";
+
+/// Levels from the config, kept here so logging never waits on the config lock
+static LOG_LEVELS: RwLock<Option<LoggingConfig>> = RwLock::new(None);
+
+/// Called when the config is loaded or updated
+pub fn set_log_levels(config: &LoggingConfig) {
+    *LOG_LEVELS.write().unwrap() = Some(config.clone());
+}
+
+/// The file category a message belongs to: `WINDOW_POOL` and `FILE_STORAGE` go
+/// to the WINDOW and STORAGE files, `SHORTCUT-HANDLER` to SHORTCUT
+pub fn log_category(target: &str) -> Option<&'static str> {
+    let target = target.to_ascii_uppercase();
+    let mut words = target.split(['_', '-']);
+    words.find_map(|word| LOG_CATEGORIES.iter().copied().find(|category| *category == word))
+}
+
+fn level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Off => LevelFilter::Off,
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+    }
+}
+
+/// The level for a target: an override for the exact target, then one for its
+/// category, then the default
+pub fn level_for(config: &LoggingConfig, target: &str) -> LevelFilter {
+    let target = target.to_ascii_uppercase();
+    let level = config
+        .categories
+        .iter()
+        .find(|(name, _)| name.to_ascii_uppercase() == target)
+        .or_else(|| {
+            let category = log_category(&target)?;
+            config.categories.iter().find(|(name, _)| name.eq_ignore_ascii_case(category))
+        })
+        .map(|(_, level)| *level)
+        .unwrap_or(config.level);
+    level_filter(level)
+}
+
+/// A log file that is moved aside to `<name>.1` when it gets too big
+struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(path: PathBuf) -> Self {
+        let written = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self { path, file: None, written }
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}", index));
+        self.path.with_file_name(name)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        for index in (1..KEEP_ROTATED).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.path.exists() {
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            self.file = Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        }
+        let file = self.file.as_mut().expect("log file was just opened");
+        file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+struct LogFiles {
+    combined: RotatingFile,
+    categories: HashMap<&'static str, RotatingFile>,
+}
+
+/// Writes every message to the combined file and, for the `LOG_CATEGORIES`,
+/// to that category's file too
+struct BlinkLogger {
+    files: Mutex<Option<LogFiles>>,
+}
+
+static LOGGER: BlinkLogger = BlinkLogger { files: Mutex::new(None) };
+
+impl Log for BlinkLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let levels = LOG_LEVELS.read().unwrap();
+        let level = match levels.as_ref() {
+            Some(config) => level_for(config, metadata.target()),
+            None => level_for(&default_logging(), metadata.target()),
+        };
+        metadata.level() <= level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "[BLINK] [{}] [{}] [{}] {}\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let mut files = self.files.lock().unwrap();
+        let Some(files) = files.as_mut() else { return };
+        let _ = files.combined.write_line(&line);
+        if let Some(file) = log_category(record.target()).and_then(|c| files.categories.get_mut(c)) {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(files) = self.files.lock().unwrap().as_mut() {
+            for file in std::iter::once(&mut files.combined).chain(files.categories.values_mut()) {
+                if let Some(file) = file.file.as_mut() {
+                    let _ = file.flush();
+                }
+            }
+        }
+    }
+}
+
+fn logs_directory() -> Result<PathBuf, String> {
+    Ok(dirs::data_dir()
+        .ok_or("Could not find data directory")?
+        .join("com.blink.dev")
+        .join("logs"))
+}
+
+/// `blink.log`, or `blink-window.log` and so on for a category
+fn log_file_name(category: Option<&str>) -> String {
+    match category {
+        Some(category) => format!("blink-{}.log", category.to_ascii_lowercase()),
+        None => COMBINED_LOG_FILE.to_string(),
+    }
+}
+
+fn open_log_files(logs_dir: &Path) -> LogFiles {
+    LogFiles {
+        combined: RotatingFile::new(logs_dir.join(log_file_name(None))),
+        categories: LOG_CATEGORIES
+            .iter()
+            .map(|category| (*category, RotatingFile::new(logs_dir.join(log_file_name(Some(category))))))
+            .collect(),
+    }
+}
 
 // Initialize file logging
 pub fn init_file_logging() -> Result<PathBuf, String> {
     // Create logs directory in app data folder
-    let app_data_dir = dirs::data_dir()
-        .ok_or("Could not find data directory")?
-        .join("com.blink.dev");
-    
-    let logs_dir = app_data_dir.join("logs");
+    let logs_dir = logs_directory()?;
     std::fs::create_dir_all(&logs_dir)
         .map_err(|e| format!("Failed to create logs directory: {}", e))?;
     
-    let log_file = logs_dir.join("blink.log");
-    
-    // Initialize env_logger to write to file
-    let log_file_clone = log_file.clone();
-    env_logger::Builder::from_default_env()
-        .target(env_logger::Target::Pipe(Box::new(std::fs::File::create(&log_file_clone)
-            .map_err(|e| format!("Failed to create log file: {}", e))?)))
-        .format(|buf, record| {
-            writeln!(buf, "[BLINK] [{}] [{}] [{}] {}",
-                Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-                record.level(),
-                record.target(),
-                record.args())
-        })
-        .init();
+    let log_file = logs_dir.join(COMBINED_LOG_FILE);
+    *LOGGER.files.lock().unwrap() = Some(open_log_files(&logs_dir));
+    log::set_logger(&LOGGER).map_err(|e| format!("Failed to install logger: {}", e))?;
+    // Levels are filtered per category in `enabled`
+    log::set_max_level(LevelFilter::Debug);
     
     println!("[BLINK] [{}] [LOGGING] Log file initialized at: {}", 
         Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
@@ -87,26 +257,29 @@ macro_rules! log_warn {
     }};
 }
 
-// Command to get log file path
+// Command to get log file path: the combined log, or one category's log
 #[tauri::command]
-pub async fn get_log_file_path() -> Result<String, String> {
-    let app_data_dir = dirs::data_dir()
-        .ok_or("Could not find data directory")?
-        .join("com.blink.dev")
-        .join("logs")
-        .join("blink.log");
+pub async fn get_log_file_path(category: Option<String>) -> Result<String, String> {
+    let category = match category {
+        Some(category) => Some(
+            LOG_CATEGORIES
+                .iter()
+                .copied()
+                .find(|c| c.eq_ignore_ascii_case(category.trim()))
+                .ok_or_else(|| format!("Unknown log category '{}', expected one of {}", category, LOG_CATEGORIES.join(", ")))?,
+        ),
+        None => None,
+    };
+    let log_file = logs_directory()?.join(log_file_name(category));
     
-    Ok(app_data_dir.to_string_lossy().to_string())
+    Ok(log_file.to_string_lossy().to_string())
 }
 
 // Command to get recent log entries
 #[tauri::command]
 pub async fn get_recent_logs(lines: Option<usize>) -> Result<String, String> {
-    let app_data_dir = dirs::data_dir()
-        .ok_or("Could not find data directory")?
-        .join("com.blink.dev")
-        .join("logs")
-        .join("blink.log");
+    crate::modules::debug_tools::ensure_diagnostics_unlocked()?;
+    let app_data_dir = logs_directory()?.join(COMBINED_LOG_FILE);
     
     if !app_data_dir.exists() {
         return Ok("Log file not found".to_string());
@@ -126,4 +299,43 @@ pub async fn get_recent_logs(lines: Option<usize>) -> Result<String, String> {
         .collect();
     
     Ok(recent_lines.join("\n"))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_category() {
+        assert_eq!(log_category("WINDOW"), Some("WINDOW"));
+        assert_eq!(log_category("WINDOW_POOL"), Some("WINDOW"));
+        assert_eq!(log_category("FILE_STORAGE"), Some("STORAGE"));
+        assert_eq!(log_category("SHORTCUT-HANDLER"), Some("SHORTCUT"));
+        assert_eq!(log_category("drag"), Some("DRAG"));
+        assert_eq!(log_category("GET_DETACHED_WINDOWS"), None);
+        assert_eq!(log_category("STARTUP"), None);
+    }
+
+    #[test]
+    fn test_level_for() {
+        let mut config = default_logging();
+        config.categories.insert("window".to_string(), LogLevel::Debug);
+        config.categories.insert("WINDOW_POOL".to_string(), LogLevel::Error);
+        assert_eq!(level_for(&config, "STARTUP"), LevelFilter::Info);
+        assert_eq!(level_for(&config, "WINDOW_SERVICE"), LevelFilter::Debug);
+        assert_eq!(level_for(&config, "WINDOW_POOL"), LevelFilter::Error);
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut file = RotatingFile::new(dir.path().join("blink.log"));
+        let line = format!("{}\n", "x".repeat(1023));
+        for _ in 0..(MAX_LOG_BYTES / 1024) * 2 + 1 {
+            file.write_line(&line).unwrap();
+        }
+        assert!(dir.path().join("blink.log.1").exists());
+        assert!(dir.path().join("blink.log.2").exists());
+        assert_eq!(std::fs::metadata(dir.path().join("blink.log")).unwrap().len(), 1024);
+        assert_eq!(std::fs::metadata(dir.path().join("blink.log.1")).unwrap().len(), MAX_LOG_BYTES);
+    }
+}
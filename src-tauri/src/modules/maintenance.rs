@@ -0,0 +1,960 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::database::{self, NoteRecord};
+use crate::modules::display_layouts::{load_display_layouts, save_display_layouts};
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::file_storage::list_note_files;
+use crate::modules::ocr::searchable_content;
+use crate::modules::storage::{
+    get_configured_notes_directory, get_default_notes_directory,
+    load_detached_windows_from_disk, save_detached_windows_to_disk,
+};
+use crate::modules::confirmation::require_confirmation;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::state::ConfirmationTokenState;
+use crate::types::config::AppConfig;
+use crate::types::note::NoteFrontmatter;
+use crate::types::window::{ConfigState, DetachedWindowsState, NotesState};
+use crate::utils::generate_slug;
+use crate::{ModifiedStateTrackerState, VaultLockState};
+use crate::{log_debug, log_info, log_warn};
+use crate::modules::window_capabilities::{authorize, Capability};
+
+/// Metadata key holding the name of the last maintenance command that ran
+pub const LAST_MAINTENANCE_KEY: &str = "last_maintenance";
+
+/// Metadata key set when `rebuild_index` reconciles the index with the files
+/// on disk. The value is the number of notes indexed.
+pub const LAST_SYNC_KEY: &str = "last_sync";
+
+/// Note in the index that `command` ran, for the health panel
+pub(crate) fn record_maintenance_run(notes_dir: &Path, command: &str) {
+    if let Err(e) = database::initialize_database(notes_dir).and_then(|db| db.set_metadata(LAST_MAINTENANCE_KEY, command)) {
+        log_warn!("MAINTENANCE", "Failed to record {} run: {}", command, e);
+    }
+}
+
+/// A single change made (or proposed) by a maintenance command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceChange {
+    /// What was touched: "database", "frontmatter", "spatial", "window", "layout"
+    pub kind: String,
+    pub from: String,
+    pub to: Option<String>,
+    pub detail: String,
+}
+
+/// Result of `repair_note_ids`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdRepairReport {
+    pub dry_run: bool,
+    pub changes: Vec<MaintenanceChange>,
+}
+
+impl IdRepairReport {
+    fn record(&mut self, kind: &str, from: &str, to: Option<&str>, detail: String) {
+        log_info!("MAINTENANCE", "[{}] {} -> {:?}: {}", kind, from, to, detail);
+        self.changes.push(MaintenanceChange {
+            kind: kind.to_string(),
+            from: from.to_string(),
+            to: to.map(|s| s.to_string()),
+            detail,
+        });
+    }
+}
+
+/// A markdown file found in the vault
+#[derive(Debug, Clone)]
+pub struct VaultFile {
+    /// Filename without `.md`; this is the canonical note ID
+    pub stem: String,
+    /// Path relative to the vault root, e.g. `work/meeting.md`
+    pub relative_path: String,
+    pub title: String,
+    pub frontmatter: Option<NoteFrontmatter>,
+    pub body: String,
+}
+
+/// Split legacy YAML frontmatter from a note file
+fn split_frontmatter(content: &str) -> (Option<NoteFrontmatter>, String) {
+    if content.starts_with("---\n") {
+        let parts: Vec<&str> = content.splitn(3, "---\n").collect();
+        if parts.len() >= 3 {
+            if let Ok(frontmatter) = serde_yaml::from_str::<NoteFrontmatter>(parts[1]) {
+                return (Some(frontmatter), parts[2].to_string());
+            }
+        }
+    }
+    (None, content.to_string())
+}
+
+fn title_from_body(body: &str, stem: &str) -> String {
+    body.lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .unwrap_or_else(|| stem.replace('-', " "))
+}
+
+/// Read one note file, `None` when it can't be read
+fn read_vault_file(notes_dir: &Path, path: &Path) -> Option<VaultFile> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string())?;
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            log_warn!("MAINTENANCE", "Skipping unreadable file {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let (frontmatter, body) = split_frontmatter(&content);
+    let title = frontmatter
+        .as_ref()
+        .map(|fm| fm.title.clone())
+        .unwrap_or_else(|| title_from_body(&body, &stem));
+
+    let relative_path = path
+        .strip_prefix(notes_dir)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| format!("{}.md", stem));
+
+    Some(VaultFile { stem, relative_path, title, frontmatter, body })
+}
+
+/// Read every markdown file in the vault
+pub fn scan_vault_files(notes_dir: &Path, follow_symlinks: bool) -> Result<Vec<VaultFile>, String> {
+    Ok(list_note_files(notes_dir, follow_symlinks)?.iter().filter_map(|path| read_vault_file(notes_dir, path)).collect())
+}
+
+/// Work out which stale IDs map to which canonical (filename) IDs.
+///
+/// Returns `old_id -> Some(canonical_id)` for IDs that can be resolved and
+/// `old_id -> None` for database records with no backing file.
+pub fn build_id_mapping(files: &[VaultFile], records: &[NoteRecord]) -> HashMap<String, Option<String>> {
+    let canonical: HashSet<&str> = files.iter().map(|f| f.stem.as_str()).collect();
+    let mut mapping = HashMap::new();
+
+    // Frontmatter IDs left over from the UUID era
+    for file in files {
+        if let Some(fm) = &file.frontmatter {
+            if fm.id != file.stem && !canonical.contains(fm.id.as_str()) {
+                mapping.insert(fm.id.clone(), Some(file.stem.clone()));
+            }
+        }
+    }
+
+    // Titles only identify a file when they are unambiguous
+    let mut by_title: HashMap<&str, Vec<&str>> = HashMap::new();
+    for file in files {
+        by_title.entry(file.title.as_str()).or_default().push(file.stem.as_str());
+    }
+
+    for record in records {
+        if canonical.contains(record.id.as_str()) || mapping.contains_key(&record.id) {
+            continue;
+        }
+
+        let path_stem = Path::new(&record.file_path).with_extension("");
+        let path_stem = path_stem.to_string_lossy();
+        let slug = generate_slug(&record.title);
+        let resolved = if canonical.contains(path_stem.as_ref()) {
+            Some(path_stem.to_string())
+        } else if let Some([only]) = by_title.get(record.title.as_str()).map(|v| v.as_slice()) {
+            Some(only.to_string())
+        } else if canonical.contains(slug.as_str()) {
+            Some(slug)
+        } else {
+            None
+        };
+
+        mapping.insert(record.id.clone(), resolved);
+    }
+
+    mapping
+}
+
+fn parse_timestamp(value: &str) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|_| chrono::Utc::now())
+}
+
+fn rename_spatial_file(dir: &Path, from: &str, to: &str, dry_run: bool, report: &mut IdRepairReport) {
+    let source: PathBuf = dir.join(format!("spatial_{}.json", from));
+    let target: PathBuf = dir.join(format!("spatial_{}.json", to));
+    if !source.exists() {
+        return;
+    }
+    if target.exists() {
+        report.record("spatial", from, Some(to), "Target spatial file already exists, left old file in place".to_string());
+        return;
+    }
+    if !dry_run {
+        if let Err(e) = fs::rename(&source, &target) {
+            log_warn!("MAINTENANCE", "Failed to rename {:?}: {}", source, e);
+            return;
+        }
+    }
+    report.record("spatial", from, Some(to), "Renamed spatial file".to_string());
+}
+
+/// Rebuild a canonical ID mapping (filename stem = note ID) and rewrite the
+/// database, legacy frontmatter, spatial files and saved window state to match
+#[tauri::command]
+pub async fn repair_note_ids(
+    window: tauri::Window,
+    dry_run: Option<bool>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    detached_windows: State<'_, DetachedWindowsState>,
+    modified_tracker: State<'_, ModifiedStateTrackerState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<IdRepairReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        ensure_vault_writable(&config, &vault_lock).await?;
+    }
+
+    let config_snapshot = config.lock().await.clone();
+    let notes_dir = get_configured_notes_directory(&config_snapshot)?;
+    let mut report = IdRepairReport { dry_run, ..Default::default() };
+
+    log_info!("MAINTENANCE", "Repairing note IDs in {:?} (dry_run={})", notes_dir, dry_run);
+
+    let files = scan_vault_files(&notes_dir, config_snapshot.storage.follow_symlinks)?;
+    let db = database::initialize_database(&notes_dir)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+    let records = db.get_all_notes()
+        .map_err(|e| format!("Failed to load notes from database: {}", e))?;
+    let mapping = build_id_mapping(&files, &records);
+    let records_by_id: HashMap<&str, &NoteRecord> = records.iter().map(|r| (r.id.as_str(), r)).collect();
+    let paths_by_stem: HashMap<&str, &str> = files.iter().map(|f| (f.stem.as_str(), f.relative_path.as_str())).collect();
+
+    // Database: move stale records to their canonical ID, drop orphans
+    for record in &records {
+        match mapping.get(&record.id) {
+            Some(Some(new_id)) => {
+                if !dry_run {
+                    db.delete_note(&record.id).map_err(|e| format!("Failed to update database: {}", e))?;
+                    if !records_by_id.contains_key(new_id.as_str()) {
+                        let mut moved = record.clone();
+                        moved.id = new_id.clone();
+                        moved.file_path = paths_by_stem
+                            .get(new_id.as_str())
+                            .map(|p| p.to_string())
+                            .unwrap_or_else(|| format!("{}.md", new_id));
+                        db.upsert_note(&moved).map_err(|e| format!("Failed to update database: {}", e))?;
+                    }
+                }
+                report.record("database", &record.id, Some(new_id.as_str()), format!("Re-keyed record for \"{}\"", record.title));
+            }
+            Some(None) => {
+                if !dry_run {
+                    db.delete_note(&record.id).map_err(|e| format!("Failed to update database: {}", e))?;
+                }
+                report.record("database", &record.id, None, format!("Removed record for \"{}\" with no file", record.title));
+            }
+            None => {
+                let expected_path = paths_by_stem
+                    .get(record.id.as_str())
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| format!("{}.md", record.id));
+                if record.file_path != expected_path {
+                    if !dry_run {
+                        let mut fixed = record.clone();
+                        fixed.file_path = expected_path.clone();
+                        db.upsert_note(&fixed).map_err(|e| format!("Failed to update database: {}", e))?;
+                    }
+                    report.record("database", &record.id, Some(record.id.as_str()), format!("Fixed file path {} -> {}", record.file_path, expected_path));
+                }
+            }
+        }
+    }
+
+    // Frontmatter: move metadata into the database and strip it from the file
+    for file in &files {
+        let Some(fm) = &file.frontmatter else { continue };
+        if !dry_run {
+            let record = NoteRecord {
+                id: file.stem.clone(),
+                title: fm.title.clone(),
+                file_path: file.relative_path.clone(),
+                created_at: parse_timestamp(&fm.created_at),
+                updated_at: parse_timestamp(&fm.updated_at),
+                tags: fm.tags.clone(),
+                position: fm.position,
+                file_hash: crate::modules::file_storage::FileStorageManager::compute_file_hash(&file.body),
+            };
+            db.upsert_note(&record).map_err(|e| format!("Failed to update database: {}", e))?;
+            fs::write(notes_dir.join(&file.relative_path), &file.body)
+                .map_err(|e| format!("Failed to rewrite {}: {}", file.relative_path, e))?;
+        }
+        report.record("frontmatter", &fm.id, Some(file.stem.as_str()), "Moved frontmatter metadata into the database".to_string());
+    }
+
+    let renames: Vec<(&String, &String)> = mapping
+        .iter()
+        .filter_map(|(old, new)| new.as_ref().map(|new| (old, new)))
+        .collect();
+
+    // Spatial files live in the app data directory
+    if let Ok(data_dir) = get_default_notes_directory() {
+        for (old_id, new_id) in &renames {
+            rename_spatial_file(&data_dir, old_id, new_id, dry_run, &mut report);
+        }
+    }
+
+    // Saved detached windows
+    let mut saved_windows = load_detached_windows_from_disk().await.unwrap_or_default();
+    let mut windows_changed = false;
+    for window in saved_windows.values_mut() {
+        if let Some(Some(new_id)) = mapping.get(&window.note_id) {
+            report.record("window", &window.note_id, Some(new_id.as_str()), format!("Updated saved window {}", window.window_label));
+            window.note_id = new_id.clone();
+            window.window_label = format!("note-{}", new_id);
+            windows_changed = true;
+        }
+    }
+    if windows_changed && !dry_run {
+        let rekeyed: HashMap<_, _> = saved_windows
+            .into_values()
+            .map(|w| (w.window_label.clone(), w))
+            .collect();
+        save_detached_windows_to_disk(&rekeyed).await?;
+        *detached_windows.lock().await = rekeyed;
+    }
+
+    // Per-display layouts
+    let mut layouts = load_display_layouts().await.unwrap_or_default();
+    let mut layouts_changed = false;
+    for (fingerprint, positions) in layouts.iter_mut() {
+        for (old_id, new_id) in &renames {
+            if let Some(mut window) = positions.remove(*old_id) {
+                window.note_id = (*new_id).clone();
+                window.window_label = format!("note-{}", new_id);
+                positions.entry((*new_id).clone()).or_insert(window);
+                report.record("layout", old_id, Some(new_id.as_str()), format!("Updated layout for display {}", fingerprint));
+                layouts_changed = true;
+            }
+        }
+    }
+    if layouts_changed && !dry_run {
+        save_display_layouts(&layouts).await?;
+    }
+
+    // Reload notes so every window sees the canonical IDs
+    if !dry_run && !report.changes.is_empty() {
+        let file_storage = FileNotesStorage::new(&config_snapshot)?;
+        let reloaded = file_storage.load_notes().await?;
+        let mut notes_lock = notes.lock().await;
+        *notes_lock = reloaded;
+        modified_tracker.clear_all().await;
+        for note in notes_lock.values() {
+            modified_tracker.initialize_note(note).await;
+        }
+    }
+
+    if !dry_run {
+        record_maintenance_run(&notes_dir, "repair_note_ids");
+    }
+    log_info!("MAINTENANCE", "Note ID repair finished with {} change(s)", report.changes.len());
+    Ok(report)
+}
+
+/// Progress of `rebuild_index`, emitted as `index-rebuild-progress`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexRebuildProgress {
+    pub current: usize,
+    pub total: usize,
+    pub note_id: Option<String>,
+    pub done: bool,
+}
+
+/// Summary returned by `rebuild_index`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexRebuildReport {
+    pub indexed: usize,
+    pub skipped: Vec<String>,
+}
+
+/// Wipe the SQLite index (including full-text search) and rebuild it from the `.md` files.
+/// Positions, tags and creation dates are carried over from the old index where possible.
+#[tauri::command]
+pub async fn rebuild_index(
+    window: tauri::Window,
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<IndexRebuildReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let (notes_dir, follow_symlinks) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.storage.follow_symlinks)
+    };
+    log_info!("MAINTENANCE", "Rebuilding note index for {:?}", notes_dir);
+
+    let files = scan_vault_files(&notes_dir, follow_symlinks)?;
+    let total = files.len();
+    let db = database::initialize_database(&notes_dir)
+        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+
+    // Keep manual ordering and metadata that only live in the index
+    let previous: HashMap<String, NoteRecord> = db.get_all_notes()
+        .map_err(|e| format!("Failed to load notes from database: {}", e))?
+        .into_iter()
+        .map(|r| (r.id.clone(), r))
+        .collect();
+
+    db.clear_index().map_err(|e| format!("Failed to clear index: {}", e))?;
+
+    let mut used_positions = HashSet::new();
+    let mut report = IndexRebuildReport { indexed: 0, skipped: Vec::new() };
+
+    for (i, file) in files.iter().enumerate() {
+        let old = previous.get(&file.stem);
+        let fm = file.frontmatter.as_ref();
+        let now = chrono::Utc::now();
+
+        // Positions are unique in the index; later duplicates become unordered
+        let position = old.and_then(|r| r.position)
+            .or_else(|| fm.and_then(|f| f.position))
+            .filter(|p| used_positions.insert(*p));
+
+        let record = NoteRecord {
+            id: file.stem.clone(),
+            title: file.title.clone(),
+            file_path: file.relative_path.clone(),
+            created_at: old.map(|r| r.created_at)
+                .or_else(|| fm.map(|f| parse_timestamp(&f.created_at)))
+                .unwrap_or(now),
+            updated_at: old.map(|r| r.updated_at)
+                .or_else(|| fm.map(|f| parse_timestamp(&f.updated_at)))
+                .unwrap_or(now),
+            tags: old.map(|r| r.tags.clone())
+                .or_else(|| fm.map(|f| f.tags.clone()))
+                .unwrap_or_default(),
+            position,
+            file_hash: crate::modules::file_storage::FileStorageManager::compute_file_hash(&file.body),
+        };
+
+        match db.upsert_note(&record) {
+            Ok(()) => {
+                let _ = db.upsert_fts(&record.id, &record.title, &searchable_content(&db, &file.body));
+                report.indexed += 1;
+            }
+            Err(e) => {
+                log_warn!("MAINTENANCE", "Failed to index {}: {}", file.stem, e);
+                report.skipped.push(file.stem.clone());
+            }
+        }
+
+        let _ = app.emit("index-rebuild-progress", IndexRebuildProgress {
+            current: i + 1,
+            total,
+            note_id: Some(file.stem.clone()),
+            done: false,
+        });
+    }
+
+    let _ = app.emit("index-rebuild-progress", IndexRebuildProgress {
+        current: total,
+        total,
+        note_id: None,
+        done: true,
+    });
+
+    if let Err(e) = db.set_metadata(LAST_SYNC_KEY, &report.indexed.to_string()) {
+        log_warn!("MAINTENANCE", "Failed to record index sync: {}", e);
+    }
+    record_maintenance_run(&notes_dir, "rebuild_index");
+    log_info!("MAINTENANCE", "Index rebuilt: {} indexed, {} skipped", report.indexed, report.skipped.len());
+    Ok(report)
+}
+
+/// Result of comparing the vault's files with the index
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultVerifyReport {
+    pub checked: usize,
+    /// Files whose content no longer matches the stored hash (edited outside Blink)
+    pub modified: Vec<String>,
+    /// Indexed notes whose file is gone
+    pub missing_files: Vec<String>,
+    /// Files the index doesn't know about
+    pub unindexed: Vec<String>,
+}
+
+impl VaultVerifyReport {
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.missing_files.is_empty() && self.unindexed.is_empty()
+    }
+}
+
+/// Compare `(note_id, hash)` pairs from disk against those stored in the index
+pub fn compare_file_hashes(on_disk: &[(String, String)], indexed: &[(String, String)]) -> VaultVerifyReport {
+    let stored: HashMap<&str, &str> = indexed.iter().map(|(id, h)| (id.as_str(), h.as_str())).collect();
+    let present: HashSet<&str> = on_disk.iter().map(|(id, _)| id.as_str()).collect();
+
+    let mut report = VaultVerifyReport { checked: on_disk.len(), ..Default::default() };
+    for (id, hash) in on_disk {
+        match stored.get(id.as_str()) {
+            Some(stored_hash) if *stored_hash != hash => report.modified.push(id.clone()),
+            Some(_) => {}
+            None => report.unindexed.push(id.clone()),
+        }
+    }
+    for (id, _) in indexed {
+        if !present.contains(id.as_str()) {
+            report.missing_files.push(id.clone());
+        }
+    }
+
+    report.modified.sort();
+    report.missing_files.sort();
+    report.unindexed.sort();
+    report
+}
+
+/// A file written this soon before it was hashed may change again without its
+/// modification time moving (coarse timestamps), so its stat isn't trusted
+const RACY_WINDOW_MS: i64 = 2000;
+
+/// Whether a file with this modification time and size can be taken to still
+/// hash to `stat.body_hash` without reading it
+pub fn stat_unchanged(stat: &database::FileStat, mtime: i64, size: i64) -> bool {
+    stat.mtime == mtime && stat.size == size && stat.checked_at - stat.mtime > RACY_WINDOW_MS
+}
+
+fn millis_since_epoch(time: std::time::SystemTime) -> i64 {
+    time.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or_default()
+}
+
+/// `(note_id, body hash)` of every note file
+struct HashedVault {
+    hashes: Vec<(String, String)>,
+    /// Files read and hashed this time, for the stat cache
+    fresh: Vec<(String, database::FileStat)>,
+    present: HashSet<String>,
+}
+
+/// Hash the body of every note file, reusing the cached hash of files whose
+/// modification time and size haven't changed. At 10k notes that turns a
+/// full read of the vault into a directory walk and a few reads.
+fn hash_vault_files(
+    notes_dir: &Path,
+    follow_symlinks: bool,
+    cache: &HashMap<String, database::FileStat>,
+) -> Result<HashedVault, String> {
+    let mut vault = HashedVault { hashes: Vec::new(), fresh: Vec::new(), present: HashSet::new() };
+    for path in list_note_files(notes_dir, follow_symlinks)? {
+        let Ok(metadata) = fs::metadata(&path) else { continue };
+        let mtime = metadata.modified().map(millis_since_epoch).unwrap_or_default();
+        let size = metadata.len() as i64;
+        let relative = path.strip_prefix(notes_dir).map(|p| p.to_string_lossy().replace('\\', "/")).ok();
+
+        if let Some(stat) = relative.as_ref().and_then(|r| cache.get(r)).filter(|s| stat_unchanged(s, mtime, size)) {
+            vault.present.insert(relative.unwrap_or_default());
+            vault.hashes.push((stat.stem.clone(), stat.body_hash.clone()));
+            continue;
+        }
+
+        let checked_at = millis_since_epoch(std::time::SystemTime::now());
+        let Some(file) = read_vault_file(notes_dir, &path) else { continue };
+        let body_hash = crate::modules::file_storage::FileStorageManager::compute_file_hash(&file.body);
+        if let Some(relative) = relative {
+            vault.present.insert(relative.clone());
+            vault.fresh.push((relative, database::FileStat {
+                stem: file.stem.clone(),
+                mtime,
+                size,
+                body_hash: body_hash.clone(),
+                checked_at,
+            }));
+        }
+        vault.hashes.push((file.stem, body_hash));
+    }
+    Ok(vault)
+}
+
+/// Hash every note file and compare with the index. Unless `full`, files
+/// whose modification time and size match the stat cache aren't read again.
+/// Only the stat cache is written.
+pub fn verify_vault_with(notes_dir: &Path, follow_symlinks: bool, full: bool) -> Result<VaultVerifyReport, String> {
+    // Don't create an index just to verify against it
+    let db = if database::get_database_path(notes_dir).exists() {
+        Some(database::initialize_database(notes_dir).map_err(|e| format!("Failed to initialize database: {}", e))?)
+    } else {
+        None
+    };
+    let cache = match (&db, full) {
+        (Some(db), false) => db.get_file_stats().unwrap_or_else(|e| {
+            log_warn!("MAINTENANCE", "Failed to read file stat cache: {}", e);
+            HashMap::new()
+        }),
+        _ => HashMap::new(),
+    };
+
+    let vault = hash_vault_files(notes_dir, follow_symlinks, &cache)?;
+    log_debug!("MAINTENANCE", "Hashed {} of {} files", vault.fresh.len(), vault.hashes.len());
+
+    let indexed: Vec<(String, String)> = match &db {
+        Some(db) => {
+            if !vault.fresh.is_empty() || vault.present.len() != cache.len() {
+                if let Err(e) = db.update_file_stats(&vault.fresh, &vault.present) {
+                    log_warn!("MAINTENANCE", "Failed to update file stat cache: {}", e);
+                }
+            }
+            db.get_all_notes()
+                .map_err(|e| format!("Failed to load notes from database: {}", e))?
+                .into_iter()
+                .map(|r| (r.id, r.file_hash))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(compare_file_hashes(&vault.hashes, &indexed))
+}
+
+/// Check the vault against the index, hashing only files that look changed
+pub fn verify_vault_at(notes_dir: &Path, follow_symlinks: bool) -> Result<VaultVerifyReport, String> {
+    verify_vault_with(notes_dir, follow_symlinks, false)
+}
+
+/// Run the integrity check on the configured vault and tell the frontend
+/// about anything changed outside the app
+pub fn run_vault_verification(app: &AppHandle, config: &AppConfig, full: bool) -> Result<VaultVerifyReport, String> {
+    let notes_dir = get_configured_notes_directory(config)?;
+    let report = verify_vault_with(&notes_dir, config.storage.follow_symlinks, full)?;
+
+    if report.is_clean() {
+        log_info!("MAINTENANCE", "Vault verified: {} files match the index", report.checked);
+    } else {
+        log_warn!(
+            "MAINTENANCE",
+            "Vault drift: {} modified, {} missing, {} unindexed",
+            report.modified.len(), report.missing_files.len(), report.unindexed.len()
+        );
+        let _ = app.emit("external-changes-detected", &report);
+    }
+
+    Ok(report)
+}
+
+/// Compare each note file's hash with the one stored in the index. `full`
+/// rereads every file instead of trusting unchanged modification times.
+#[tauri::command]
+pub async fn verify_vault(
+    app: AppHandle,
+    full: Option<bool>,
+    config: State<'_, ConfigState>,
+) -> Result<VaultVerifyReport, String> {
+    let config_snapshot = config.lock().await.clone();
+    run_vault_verification(&app, &config_snapshot, full.unwrap_or(false))
+}
+
+/// Directories under `.blink` whose files are pruned by `compact_vault`
+const RETAINED_DIRS: &[&str] = &["backups", "history", "trash"];
+/// Default age after which backups and snapshots are pruned
+const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+/// Result of `compact_vault`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultCompactReport {
+    pub dry_run: bool,
+    pub database_bytes_before: u64,
+    pub database_bytes_after: u64,
+    pub pruned_files: Vec<String>,
+    pub pruned_bytes: u64,
+    /// Total space freed by vacuuming and pruning
+    pub reclaimed_bytes: u64,
+}
+
+/// A file eligible for retention pruning
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetainedFile {
+    pub path: PathBuf,
+    pub modified: std::time::SystemTime,
+    pub size: u64,
+}
+
+/// Files older than `max_age`, always keeping the newest `keep_latest` of the group
+pub fn select_expired(
+    mut files: Vec<RetainedFile>,
+    now: std::time::SystemTime,
+    max_age: std::time::Duration,
+    keep_latest: usize,
+) -> Vec<RetainedFile> {
+    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    files
+        .into_iter()
+        .skip(keep_latest)
+        .filter(|f| now.duration_since(f.modified).map_or(false, |age| age > max_age))
+        .collect()
+}
+
+pub(crate) fn list_retained_files(dir: &Path, filter: impl Fn(&Path) -> bool) -> Vec<RetainedFile> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter(|e| filter(&e.path()))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            metadata.is_file().then(|| RetainedFile {
+                path: e.path(),
+                modified: metadata.modified().ok()?,
+                size: metadata.len(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn database_size(db_path: &Path) -> u64 {
+    ["", "-wal", "-shm"]
+        .iter()
+        .filter_map(|suffix| fs::metadata(format!("{}{}", db_path.display(), suffix)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// VACUUM the index database and prune old backups and snapshots from `.blink`
+#[tauri::command]
+pub async fn compact_vault(
+    window: tauri::Window,
+    dry_run: Option<bool>,
+    retention_days: Option<u64>,
+    confirmation_token: Option<String>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+    confirmations: State<'_, ConfirmationTokenState>,
+) -> Result<VaultCompactReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        ensure_vault_writable(&config, &vault_lock).await?;
+        require_confirmation(&confirmations, confirmation_token.as_deref(), "compact_vault").await?;
+    }
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let blink_dir = notes_dir.join(".blink");
+    let max_age = std::time::Duration::from_secs(retention_days.unwrap_or(DEFAULT_RETENTION_DAYS) * 24 * 60 * 60);
+    let now = std::time::SystemTime::now();
+
+    let mut report = VaultCompactReport { dry_run, ..Default::default() };
+
+    let db_path = database::get_database_path(&notes_dir);
+    report.database_bytes_before = database_size(&db_path);
+    report.database_bytes_after = report.database_bytes_before;
+    if !dry_run && db_path.exists() {
+        let db = database::initialize_database(&notes_dir)
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        db.vacuum().map_err(|e| format!("Failed to vacuum database: {}", e))?;
+        drop(db);
+        report.database_bytes_after = database_size(&db_path);
+    }
+
+    // Each snapshot directory and the loose migration backups are pruned separately
+    let mut groups: Vec<Vec<RetainedFile>> = RETAINED_DIRS
+        .iter()
+        .map(|name| list_retained_files(&blink_dir.join(name), |_| true))
+        .collect();
+    let is_backup = |p: &Path| p.extension().map_or(false, |e| e == "backup");
+    groups.push(list_retained_files(&blink_dir, is_backup));
+    groups.push(list_retained_files(&notes_dir, is_backup));
+
+    for group in groups {
+        for file in select_expired(group, now, max_age, 1) {
+            if !dry_run {
+                if let Err(e) = fs::remove_file(&file.path) {
+                    log_warn!("MAINTENANCE", "Failed to prune {:?}: {}", file.path, e);
+                    continue;
+                }
+            }
+            report.pruned_bytes += file.size;
+            report.pruned_files.push(file.path.to_string_lossy().to_string());
+        }
+    }
+
+    report.reclaimed_bytes = report.database_bytes_before.saturating_sub(report.database_bytes_after) + report.pruned_bytes;
+    if !dry_run {
+        record_maintenance_run(&notes_dir, "compact_vault");
+    }
+    log_info!(
+        "MAINTENANCE",
+        "Compacted vault{}: {} bytes reclaimed, {} files pruned",
+        if dry_run { " (dry run)" } else { "" },
+        report.reclaimed_bytes,
+        report.pruned_files.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(stem: &str, title: &str, fm_id: Option<&str>) -> VaultFile {
+        VaultFile {
+            stem: stem.to_string(),
+            relative_path: format!("{}.md", stem),
+            title: title.to_string(),
+            frontmatter: fm_id.map(|id| NoteFrontmatter {
+                id: id.to_string(),
+                title: title.to_string(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                tags: vec![],
+                position: None,
+                icon: None,
+            }),
+            body: String::new(),
+        }
+    }
+
+    fn record(id: &str, title: &str, file_path: &str) -> NoteRecord {
+        NoteRecord {
+            id: id.to_string(),
+            title: title.to_string(),
+            file_path: file_path.to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            tags: vec![],
+            position: None,
+            file_hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_canonical_records_are_untouched() {
+        let files = vec![file("groceries", "Groceries", None)];
+        let records = vec![record("groceries", "Groceries", "groceries.md")];
+        assert!(build_id_mapping(&files, &records).is_empty());
+    }
+
+    #[test]
+    fn test_uuid_records_map_to_filenames() {
+        let files = vec![
+            file("groceries", "Groceries", None),
+            file("meeting-notes", "Meeting Notes", Some("1b4e28ba-2fa1-11d2-883f-0016d3cca427")),
+        ];
+        let records = vec![
+            record("6fa459ea-ee8a-3ca4-894e-db77e160355e", "Groceries", "Groceries.md"),
+            record("c56a4180-65aa-42ec-a945-5fd21dec0538", "Deleted long ago", "Deleted long ago.md"),
+        ];
+
+        let mapping = build_id_mapping(&files, &records);
+        assert_eq!(mapping["6fa459ea-ee8a-3ca4-894e-db77e160355e"], Some("groceries".to_string()));
+        assert_eq!(mapping["1b4e28ba-2fa1-11d2-883f-0016d3cca427"], Some("meeting-notes".to_string()));
+        assert_eq!(mapping["c56a4180-65aa-42ec-a945-5fd21dec0538"], None);
+    }
+
+    #[test]
+    fn test_ambiguous_titles_are_not_guessed() {
+        let files = vec![file("todo-home", "Todo", None), file("todo-work", "Todo", None)];
+        let records = vec![record("old-id", "Todo", "Todo.md")];
+        assert_eq!(build_id_mapping(&files, &records)["old-id"], None);
+    }
+
+    #[test]
+    fn test_ambiguous_titles_fall_back_to_slug() {
+        let files = vec![file("todo", "Todo", None), file("todo-2", "Todo", None)];
+        let records = vec![record("old-id", "Todo", "Todo.md")];
+        // "todo" is the slug of the title, so it matches without guessing
+        assert_eq!(build_id_mapping(&files, &records)["old-id"], Some("todo".to_string()));
+    }
+
+    #[test]
+    fn test_compare_file_hashes() {
+        let pair = |id: &str, hash: &str| (id.to_string(), hash.to_string());
+        let on_disk = vec![pair("same", "h1"), pair("edited", "h2-new"), pair("new-file", "h3")];
+        let indexed = vec![pair("same", "h1"), pair("edited", "h2"), pair("deleted", "h4")];
+
+        let report = compare_file_hashes(&on_disk, &indexed);
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.modified, vec!["edited".to_string()]);
+        assert_eq!(report.missing_files, vec!["deleted".to_string()]);
+        assert_eq!(report.unindexed, vec!["new-file".to_string()]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_unchanged_files_are_not_rehashed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("a.md"), "# A\nfirst").unwrap();
+        fs::write(dir.path().join("b.md"), "# B").unwrap();
+
+        let first = hash_vault_files(dir.path(), true, &HashMap::new()).unwrap();
+        assert_eq!(first.fresh.len(), 2);
+        // Pretend both were hashed well after they were written
+        let mut cache: HashMap<String, database::FileStat> = first.fresh.into_iter().collect();
+        for stat in cache.values_mut() {
+            stat.checked_at = stat.mtime + RACY_WINDOW_MS + 1;
+        }
+
+        let second = hash_vault_files(dir.path(), true, &cache).unwrap();
+        assert!(second.fresh.is_empty());
+        assert_eq!(second.present.len(), 2);
+
+        fs::write(dir.path().join("a.md"), "# A\nsecond, longer").unwrap();
+        let third = hash_vault_files(dir.path(), true, &cache).unwrap();
+        assert_eq!(third.fresh.len(), 1);
+        assert_eq!(third.fresh[0].0, "a.md");
+        assert_ne!(third.fresh[0].1.body_hash, cache["a.md"].body_hash);
+    }
+
+    #[test]
+    fn test_recently_written_stats_are_not_trusted() {
+        let stat = database::FileStat {
+            stem: "a".to_string(),
+            mtime: 10_000,
+            size: 5,
+            body_hash: "h".to_string(),
+            checked_at: 10_500,
+        };
+        assert!(!stat_unchanged(&stat, 10_000, 5));
+        let settled = database::FileStat { checked_at: 20_000, ..stat };
+        assert!(stat_unchanged(&settled, 10_000, 5));
+        assert!(!stat_unchanged(&settled, 10_001, 5));
+        assert!(!stat_unchanged(&settled, 10_000, 6));
+    }
+
+    #[test]
+    fn test_select_expired_keeps_latest_and_recent() {
+        use std::time::{Duration, SystemTime};
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let file = |name: &str, age_days: u32| RetainedFile {
+            path: PathBuf::from(name),
+            modified: now - day * age_days,
+            size: 10,
+        };
+
+        let expired = select_expired(
+            vec![file("old", 90), file("recent", 2), file("older", 120)],
+            now,
+            day * 30,
+            1,
+        );
+        let names: Vec<_> = expired.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+        assert_eq!(names, vec!["old", "older"]);
+
+        // The newest file survives even when everything is past retention
+        let expired = select_expired(vec![file("a", 60), file("b", 90)], now, day * 30, 1);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].path, PathBuf::from("b"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::Path;
+use regex::Regex;
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::file_operations::allowed_roots;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::config::AppConfig;
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::utils::paths::{ensure_within, normalize_relative};
+use crate::log_info;
+
+/// Structured metadata for a single note, for external scripts and dashboards
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMetadata {
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub position: Option<i32>,
+    /// Targets of markdown links and `[[wiki links]]`, excluding attachments
+    pub links: Vec<String>,
+    /// Local files referenced by the note (images and linked files)
+    pub attachments: Vec<String>,
+    pub word_count: usize,
+}
+
+/// Metadata for every note in the vault
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMetadata {
+    pub exported_at: String,
+    pub notes_directory: String,
+    pub notes: Vec<NoteMetadata>,
+}
+
+pub(crate) fn is_remote(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:") || target.starts_with('#')
+}
+
+/// A link to something with a file extension that isn't another note
+pub(crate) fn is_attachment(target: &str) -> bool {
+    let path = target.split(['?', '#']).next().unwrap_or(target);
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some(ext) => !ext.eq_ignore_ascii_case("md"),
+        None => false,
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+/// Extract links and local attachments from markdown content
+pub fn extract_links(content: &str) -> (Vec<String>, Vec<String>) {
+    // ![alt](target) and [text](target), optionally with a "title"
+    let markdown_link = Regex::new(r#"(!?)\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#).unwrap();
+    let wiki_link = Regex::new(r"(!?)\[\[([^\]|#]+)(?:[#|][^\]]*)?\]\]").unwrap();
+
+    let mut links = Vec::new();
+    let mut attachments = Vec::new();
+
+    for caps in markdown_link.captures_iter(content) {
+        let is_embed = &caps[1] == "!";
+        let target = caps[2].to_string();
+        if !is_remote(&target) && (is_embed || is_attachment(&target)) {
+            // Files outside the vault are not the note's attachments
+            if normalize_relative(&target).is_ok() {
+                push_unique(&mut attachments, target);
+            }
+        } else {
+            push_unique(&mut links, target);
+        }
+    }
+
+    for caps in wiki_link.captures_iter(content) {
+        let is_embed = &caps[1] == "!";
+        let target = caps[2].trim().to_string();
+        if is_embed || is_attachment(&target) {
+            if normalize_relative(&target).is_ok() {
+                push_unique(&mut attachments, target);
+            }
+        } else {
+            push_unique(&mut links, target);
+        }
+    }
+
+    (links, attachments)
+}
+
+/// Build the exported metadata for a note
+pub fn note_metadata(note: &Note) -> NoteMetadata {
+    let (links, attachments) = extract_links(&note.content);
+    NoteMetadata {
+        id: note.id.clone(),
+        title: note.title.clone(),
+        tags: note.tags.clone(),
+        created_at: note.created_at.clone(),
+        updated_at: note.updated_at.clone(),
+        position: note.position,
+        links,
+        attachments,
+        word_count: note.content.split_whitespace().count(),
+    }
+}
+
+fn write_json<T: Serialize>(value: &T, output_path: &str, config: &AppConfig) -> Result<(), String> {
+    ensure_within(Path::new(output_path), &allowed_roots(config))?;
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+    fs::write(output_path, json).map_err(|e| format!("Failed to write metadata to {}: {}", output_path, e))
+}
+
+/// Export one note's metadata, optionally writing it to `output_path` as JSON
+#[tauri::command]
+pub async fn export_note_metadata(
+    window: tauri::Window,
+    note_id: String,
+    output_path: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<NoteMetadata, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let notes_lock = notes.lock().await;
+    let note = notes_lock.get(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let metadata = note_metadata(note);
+
+    if let Some(path) = output_path {
+        write_json(&metadata, &path, &*config.lock().await)?;
+        log_info!("FILE_EXPORT", "Exported metadata for note {} to {}", note_id, path);
+    }
+    Ok(metadata)
+}
+
+/// Export metadata for every note, optionally writing it to `output_path` as JSON
+#[tauri::command]
+pub async fn export_vault_metadata(
+    window: tauri::Window,
+    output_path: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<VaultMetadata, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let (notes_directory, config_snapshot) = {
+        let config_lock = config.lock().await;
+        let dir = get_configured_notes_directory(&config_lock)?.to_string_lossy().to_string();
+        (dir, config_lock.clone())
+    };
+
+    let notes_lock = notes.lock().await;
+    let mut all: Vec<NoteMetadata> = notes_lock.values().map(note_metadata).collect();
+    all.sort_by(|a, b| match (a.position, b.position) {
+        (Some(pa), Some(pb)) => pa.cmp(&pb),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.id.cmp(&b.id),
+    });
+
+    let metadata = VaultMetadata {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        notes_directory,
+        notes: all,
+    };
+
+    if let Some(path) = output_path {
+        write_json(&metadata, &path, &config_snapshot)?;
+        log_info!("FILE_EXPORT", "Exported metadata for {} notes to {}", metadata.notes.len(), path);
+    }
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links_and_attachments() {
+        let content = "See [docs](https://example.com) and [[Meeting Notes|the meeting]].\n\
+                       ![diagram](attachments/flow.png)\n\
+                       [spec](files/spec.pdf) and [other](other-note.md)\n\
+                       ![[scan.jpg]]";
+        let (links, attachments) = extract_links(content);
+
+        assert_eq!(links, vec!["https://example.com", "other-note.md", "Meeting Notes"]);
+        assert_eq!(attachments, vec!["attachments/flow.png", "files/spec.pdf", "scan.jpg"]);
+    }
+
+    #[test]
+    fn test_duplicate_links_are_listed_once() {
+        let (links, _) = extract_links("[a](x.md) [b](x.md) [[Y]] [[Y]]");
+        assert_eq!(links, vec!["x.md", "Y"]);
+    }
+
+    #[test]
+    fn test_attachments_outside_the_vault_are_dropped() {
+        let (_, attachments) = extract_links("![a](../../etc/secret.png) ![b](/tmp/b.png) ![c](img/../c.png)");
+        assert_eq!(attachments, vec!["img/../c.png"]);
+    }
+}
@@ -11,6 +11,69 @@ pub mod system_commands;
 pub mod test_commands;
 // pub mod config;  // TODO: Extract config functions
 pub mod windows;
+pub mod display_layouts;
+pub mod formatting;
+pub mod maintenance;
+pub mod workspace_settings;
+pub mod note_sync;
+pub mod note_locks;
+pub mod metadata_export;
+pub mod drag_preview;
+pub mod drag_session;
+pub mod native_drag;
+pub mod window_pool;
+pub mod audit_log;
+pub mod confirmation;
+pub mod duplicates;
+pub mod tag_routing;
+pub mod url_metadata;
+pub mod network_policy;
+pub mod note_palette;
+pub mod about;
+pub mod settings_window;
+pub mod note_counts;
+pub mod attachments;
+pub mod pasted_images;
+pub mod ocr;
+pub mod gist;
+pub mod ics;
+pub mod weekly_review;
+pub mod assistant;
+pub mod audio_capture;
+pub mod note_icons;
+pub mod window_dock;
+pub mod window_theme;
+pub mod window_reveal;
+pub mod note_bootstrap;
+pub mod debug_tools;
+pub mod window_capabilities;
+pub mod note_command_guard;
+pub mod vault_health;
+pub mod date_format;
+pub mod config_reload;
+pub mod window_capture;
+pub mod archive_policy;
+pub mod note_styles;
+pub mod window_events;
+pub mod sticky_notes;
+pub mod autosave;
+pub mod note_limits;
+pub mod safe_mode;
+pub mod undo;
+pub mod vault_format;
+pub mod reading_mode;
+pub mod related_notes;
+pub mod power_mode;
+pub mod recent_notes;
+pub mod screen_capture;
+pub mod tag_export;
+pub mod find_replace;
+pub mod annotations;
+pub mod window_arrangements;
+pub mod vault_picker;
+pub mod normalized_export;
+pub mod command_manifest;
 // pub mod shortcuts;  // TODO: Extract shortcuts functions
 pub mod logging;
-pub mod modified_state_tracker;
\ No newline at end of file
+pub mod modified_state_tracker;
+pub mod vault_lock;
\ No newline at end of file
@@ -16,6 +16,8 @@ pub struct ModifiedStateTracker {
     dirty_flags: Arc<Mutex<HashMap<String, bool>>>,
     /// Maps note IDs to their last saved content hash (for drift detection)
     content_hashes: Arc<Mutex<HashMap<String, String>>>,
+    /// Maps note IDs to the label of the window that last saved them
+    last_writers: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl ModifiedStateTracker {
@@ -23,6 +25,7 @@ impl ModifiedStateTracker {
         Self {
             dirty_flags: Arc::new(Mutex::new(HashMap::new())),
             content_hashes: Arc::new(Mutex::new(HashMap::new())),
+            last_writers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
     
@@ -76,6 +79,36 @@ impl ModifiedStateTracker {
         }
     }
     
+    /// Get the last saved content hash for a note
+    pub async fn get_content_hash(&self, note_id: &str) -> Option<String> {
+        let hashes = self.content_hashes.lock().await;
+        hashes.get(note_id).cloned()
+    }
+    
+    /// Whether an edit based on `base_hash` would overwrite a newer save.
+    /// Edits without a base hash are never considered stale.
+    pub async fn is_stale_base(&self, note_id: &str, base_hash: Option<&str>) -> bool {
+        match base_hash {
+            Some(base) => {
+                let hashes = self.content_hashes.lock().await;
+                hashes.get(note_id).map_or(false, |current| current != base)
+            }
+            None => false,
+        }
+    }
+    
+    /// Remember which window last saved a note
+    pub async fn record_writer(&self, note_id: &str, window_label: &str) {
+        let mut writers = self.last_writers.lock().await;
+        writers.insert(note_id.to_string(), window_label.to_string());
+    }
+    
+    /// Label of the window that last saved a note
+    pub async fn last_writer(&self, note_id: &str) -> Option<String> {
+        let writers = self.last_writers.lock().await;
+        writers.get(note_id).cloned()
+    }
+    
     /// Mark a note as modified (has unsaved changes)
     pub async fn mark_modified(&self, note_id: &str) {
         let mut flags = self.dirty_flags.lock().await;
@@ -121,6 +154,9 @@ impl ModifiedStateTracker {
         let mut flags = self.dirty_flags.lock().await;
         flags.remove(note_id);
         
+        let mut writers = self.last_writers.lock().await;
+        writers.remove(note_id);
+        
         log_debug!("MODIFIED_STATE", "Removed tracking for note {}", note_id);
     }
     
@@ -140,6 +176,9 @@ impl ModifiedStateTracker {
         let mut hashes = self.content_hashes.lock().await;
         hashes.clear();
         
+        let mut writers = self.last_writers.lock().await;
+        writers.clear();
+        
         log_debug!("MODIFIED_STATE", "Cleared all tracking data");
     }
 }
@@ -198,4 +237,19 @@ mod tests {
         // Different content should be changed
         assert!(tracker.has_content_changed(note_id, content2).await);
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_stale_base_detection() {
+        let tracker = ModifiedStateTracker::new();
+        let note_id = "test-note-1";
+        let base = ModifiedStateTracker::compute_content_hash("v1");
+        
+        tracker.update_content_hash(note_id, "v1").await;
+        assert!(!tracker.is_stale_base(note_id, Some(&base)).await);
+        assert!(!tracker.is_stale_base(note_id, None).await);
+        
+        // Another window saved in the meantime
+        tracker.update_content_hash(note_id, "v2").await;
+        assert!(tracker.is_stale_base(note_id, Some(&base)).await);
+    }
+}
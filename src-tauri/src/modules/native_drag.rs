@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::NotesState;
+use crate::utils::sanitize_filename;
+use crate::log_info;
+
+#[cfg(target_os = "macos")]
+use cocoa::base::{id, nil, YES};
+#[cfg(target_os = "macos")]
+use objc::{msg_send, sel, sel_impl};
+
+/// Exports older than this are removed; drop targets copy the file well before then
+const DRAG_EXPORT_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Result of `begin_native_file_drag`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeFileDrag {
+    pub note_id: String,
+    /// Temporary markdown file handed to the OS
+    pub path: String,
+    /// Whether an OS drag session was started. When false the frontend should
+    /// fall back to an HTML drag using `path`.
+    pub started: bool,
+}
+
+fn drag_export_dir() -> PathBuf {
+    std::env::temp_dir().join("blink-drag")
+}
+
+/// Remove exports left over from earlier drags
+fn prune_drag_exports(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map_or(false, |age| age > DRAG_EXPORT_MAX_AGE);
+        if expired {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Write the note to a fresh temp directory as `<title>.md`. Each drag gets its
+/// own directory so files keep their readable names without clashing.
+pub fn materialize_drag_export(note: &Note) -> Result<PathBuf, String> {
+    let base = drag_export_dir();
+    prune_drag_exports(&base);
+
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let dir = base.join(format!("{}-{}", stamp, note.id));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drag export directory: {}", e))?;
+
+    let path = dir.join(format!("{}.md", sanitize_filename(&note.title)));
+    fs::write(&path, &note.content).map_err(|e| format!("Failed to write drag export: {}", e))?;
+    Ok(path)
+}
+
+/// Start an AppKit file drag from the window under the current mouse event.
+/// `dragFile:fromRect:slideBack:event:` runs its own NSDraggingSession and
+/// blocks until the drop, so it is dispatched to the main thread without waiting.
+#[cfg(target_os = "macos")]
+fn start_os_file_drag(app: &AppHandle, window_label: &str, path: &Path) -> Result<(), String> {
+    use cocoa::appkit::NSApp;
+    use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window(window_label)
+        .ok_or_else(|| format!("Window '{}' not found", window_label))?;
+    let path = path.to_string_lossy().to_string();
+    let label = window_label.to_string();
+
+    app.run_on_main_thread(move || {
+        let ns_window = match window.ns_window() {
+            Ok(ns_window) => ns_window as id,
+            Err(e) => {
+                crate::log_error!("DRAG", "No native window for '{}': {}", label, e);
+                return;
+            }
+        };
+        unsafe {
+            let event: id = msg_send![NSApp(), currentEvent];
+            if event == nil {
+                crate::log_error!("DRAG", "No current mouse event to start a file drag from");
+                return;
+            }
+            let view: id = msg_send![ns_window, contentView];
+            let location: NSPoint = msg_send![event, locationInWindow];
+            let rect = NSRect::new(NSPoint::new(location.x - 16.0, location.y - 16.0), NSSize::new(32.0, 32.0));
+            let ns_path = NSString::alloc(nil).init_str(&path);
+            let _: () = msg_send![view, dragFile: ns_path fromRect: rect slideBack: YES event: event];
+        }
+        crate::log_debug!("DRAG", "Native file drag finished for {}", path);
+    })
+    .map_err(|e| format!("Failed to start native drag: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn start_os_file_drag(_app: &AppHandle, _window_label: &str, _path: &Path) -> Result<(), String> {
+    Err("Native file drag is not supported on this platform yet".to_string())
+}
+
+/// Drag a note out of Blink as a real .md file
+#[tauri::command]
+pub async fn begin_native_file_drag(
+    app: AppHandle,
+    window: tauri::Window,
+    note_id: String,
+    notes: State<'_, NotesState>,
+) -> Result<NativeFileDrag, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let path = {
+        let notes_lock = notes.lock().await;
+        let note = notes_lock.get(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+        materialize_drag_export(note)?
+    };
+
+    let started = match start_os_file_drag(&app, window.label(), &path) {
+        Ok(()) => true,
+        Err(e) => {
+            log_info!("DRAG", "Falling back to web drag for note {}: {}", note_id, e);
+            false
+        }
+    };
+
+    log_info!("DRAG", "Native file drag for note {} from {}", note_id, path.display());
+    Ok(NativeFileDrag {
+        note_id,
+        path: path.to_string_lossy().to_string(),
+        started,
+    })
+}
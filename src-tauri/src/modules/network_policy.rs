@@ -0,0 +1,74 @@
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::{BlinkError, BlinkResult};
+use crate::modules::storage::save_config_to_disk;
+use crate::types::config::AppConfig;
+use crate::types::window::ConfigState;
+use crate::log_info;
+use crate::modules::window_capabilities::{authorize, Capability};
+
+/// Decides whether the app may touch the network. Every module that makes
+/// requests gets its HTTP client from here, so offline mode can't be bypassed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkPolicy {
+    pub offline: bool,
+}
+
+impl NetworkPolicy {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self { offline: config.offline }
+    }
+
+    /// Current policy from the managed config
+    pub async fn current(config: &ConfigState) -> Self {
+        Self::from_config(&config.lock().await)
+    }
+
+    /// Fail when network access for `purpose` is not allowed
+    pub fn ensure_allowed(&self, purpose: &str) -> BlinkResult<()> {
+        if self.offline {
+            return Err(BlinkError::Offline(purpose.to_string()));
+        }
+        Ok(())
+    }
+
+    /// HTTP client for `purpose`; the only way modules should build one
+    pub fn http_client(&self, purpose: &str, timeout: Duration) -> BlinkResult<reqwest::Client> {
+        self.ensure_allowed(purpose)?;
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!("Blink/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| BlinkError::InvalidOperation(format!("Failed to create HTTP client: {}", e)))
+    }
+}
+
+#[tauri::command]
+pub async fn get_network_policy(config: State<'_, ConfigState>) -> Result<NetworkPolicy, String> {
+    Ok(NetworkPolicy::current(&config).await)
+}
+
+/// Turn offline mode on or off and persist it
+#[tauri::command]
+pub async fn set_offline_mode(
+    window: tauri::Window,
+    app: AppHandle,
+    offline: bool,
+    config: State<'_, ConfigState>,
+) -> Result<NetworkPolicy, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let policy = {
+        let mut config_lock = config.lock().await;
+        config_lock.offline = offline;
+        save_config_to_disk(&config_lock).await?;
+        NetworkPolicy::from_config(&config_lock)
+    };
+    log_info!("NETWORK", "Offline mode {}", if offline { "enabled" } else { "disabled" });
+
+    let _ = app.emit("network-policy-changed", &policy);
+    Ok(policy)
+}
+
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use tauri::State;
+
+use crate::modules::file_operations::allowed_roots;
+use crate::modules::file_storage::list_note_files;
+use crate::modules::metadata_export::{is_attachment, is_remote};
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::utils::paths::{ensure_within, lexical_normalize};
+use crate::log_info;
+
+/// Frontmatter keys Blink writes itself; a note's own values for these are replaced
+const STANDARD_KEYS: [&str; 5] = ["title", "date", "updated", "tags", "id"];
+
+/// How relative attachment links are rewritten
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentPaths {
+    /// Absolute paths into the vault
+    #[default]
+    Absolute,
+    /// Copied into a `<name>_files` folder next to the output file
+    Bundle,
+}
+
+/// What `export_note_normalized` produced
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedExport {
+    pub markdown: String,
+    /// Where the markdown was written, if an output path was given
+    pub output_path: Option<String>,
+    /// Attachment links that were rewritten, as written in the note
+    pub attachments: Vec<String>,
+    /// Attachment links whose file isn't in the vault; left as they were
+    pub missing_attachments: Vec<String>,
+    pub references_resolved: usize,
+}
+
+/// A note body with its links made standard
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NormalizedBody {
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub missing_attachments: Vec<String>,
+    pub references_resolved: usize,
+}
+
+/// A reference definition's destination and optional title
+type Reference = (String, Option<String>);
+
+fn reference_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Lines outside fenced code, with whether each one is outside
+fn outside_code(content: &str) -> Vec<(&str, bool)> {
+    let mut in_code = false;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if is_fence(line) {
+                in_code = !in_code;
+                return (line, false);
+            }
+            (line, !in_code)
+        })
+        .collect()
+}
+
+fn inline_link(bang: &str, text: &str, destination: &str, title: Option<&str>) -> String {
+    let destination = if destination.contains(' ') { format!("<{}>", destination) } else { destination.to_string() };
+    match title {
+        Some(title) => format!("{}[{}]({} \"{}\")", bang, text, destination, title.replace('"', "\\\"")),
+        None => format!("{}[{}]({})", bang, text, destination),
+    }
+}
+
+/// Turn `[text][ref]`, `[text][]` and `[ref]` into inline links, outside code
+/// spans, and count how many were resolved. Undefined references are kept.
+fn resolve_references(line: &str, references: &HashMap<String, Reference>, resolved: &mut usize) -> String {
+    let reference = Regex::new(r"(!?)\[([^\[\]]*)\](?:\[([^\[\]]*)\])?").unwrap();
+
+    // Odd segments between backticks are code spans and stay literal
+    line.split('`')
+        .enumerate()
+        .map(|(i, segment)| {
+            if i % 2 == 1 {
+                return segment.to_string();
+            }
+            reference
+                .replace_all(segment, |caps: &Captures| {
+                    let whole = caps.get(0).unwrap();
+                    let text = &caps[2];
+                    let label = match caps.get(3) {
+                        Some(label) if !label.as_str().is_empty() => label.as_str(),
+                        Some(_) => text,
+                        None => {
+                            // A shortcut reference can't be an inline link, a
+                            // definition or part of a wiki link
+                            let before = segment[..whole.start()].chars().last();
+                            let after = segment[whole.end()..].chars().next();
+                            if before == Some('[') || matches!(after, Some('(') | Some(':') | Some(']')) {
+                                return whole.as_str().to_string();
+                            }
+                            text
+                        }
+                    };
+                    if label.starts_with('^') {
+                        return whole.as_str().to_string();
+                    }
+                    match references.get(&reference_label(label)) {
+                        Some((destination, title)) => {
+                            *resolved += 1;
+                            inline_link(&caps[1], text, destination, title.as_deref())
+                        }
+                        None => whole.as_str().to_string(),
+                    }
+                })
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+/// Make a note's markdown standard for external tools: wiki links become
+/// their text and wiki embeds plain images, reference-style links are inlined
+/// and their definitions dropped, and relative attachment links are passed to
+/// `rewrite_attachment`, which returns the new destination or `None` when the
+/// file can't be found. Fenced code is left alone.
+pub fn normalize_markdown(content: &str, mut rewrite_attachment: impl FnMut(&str) -> Option<String>) -> NormalizedBody {
+    let definition =
+        Regex::new(r#"^ {0,3}\[([^\]]+)\]:\s*(?:<([^>]*)>|(\S+))(?:\s+(?:"([^"]*)"|'([^']*)'|\(([^)]*)\)))?\s*$"#).unwrap();
+    let wiki_link = Regex::new(r"(!?)\[\[([^\]|#]+)(#[^\]|]*)?(?:\|([^\]]*))?\]\]").unwrap();
+    let markdown_link = Regex::new(r#"(!?)\[([^\]]*)\]\(\s*(<[^>]+>|[^)\s]+)(\s+"[^"]*")?\s*\)"#).unwrap();
+
+    let content = content.replace("\r\n", "\n");
+    let lines = outside_code(&content);
+
+    // The first definition of a label wins
+    let mut references: HashMap<String, Reference> = HashMap::new();
+    for (line, outside) in &lines {
+        if let Some(caps) = definition.captures(line.trim_end_matches('\n')).filter(|_| *outside) {
+            if caps[1].starts_with('^') {
+                continue;
+            }
+            let destination = caps.get(2).or(caps.get(3)).map_or("", |m| m.as_str()).to_string();
+            let title = caps.get(4).or(caps.get(5)).or(caps.get(6)).map(|m| m.as_str().to_string());
+            references.entry(reference_label(&caps[1])).or_insert((destination, title));
+        }
+    }
+
+    let mut result = NormalizedBody::default();
+    for (line, outside) in lines {
+        if !outside {
+            result.content.push_str(line);
+            continue;
+        }
+        if definition.captures(line.trim_end_matches('\n')).map_or(false, |caps| !caps[1].starts_with('^')) {
+            continue;
+        }
+
+        let line = wiki_link.replace_all(line, |caps: &Captures| {
+            let name = caps[2].trim();
+            if &caps[1] == "!" {
+                return inline_link("!", "", name, None);
+            }
+            caps.get(4).map_or(name, |m| m.as_str().trim()).to_string()
+        });
+        let line = resolve_references(&line, &references, &mut result.references_resolved);
+        let line = markdown_link.replace_all(&line, |caps: &Captures| {
+            let (bang, text) = (&caps[1], &caps[2]);
+            let target = caps[3].trim_start_matches('<').trim_end_matches('>');
+            let title = caps.get(4).map_or("", |m| m.as_str());
+            if is_remote(target) || Path::new(target).is_absolute() || !(bang == "!" || is_attachment(target)) {
+                return caps[0].to_string();
+            }
+            match rewrite_attachment(target) {
+                Some(destination) => {
+                    if !result.attachments.iter().any(|a| a == target) {
+                        result.attachments.push(target.to_string());
+                    }
+                    let destination = if destination.contains(' ') { format!("<{}>", destination) } else { destination };
+                    format!("{}[{}]({}{})", bang, text, destination, title)
+                }
+                None => {
+                    if !result.missing_attachments.iter().any(|a| a == target) {
+                        result.missing_attachments.push(target.to_string());
+                    }
+                    caps[0].to_string()
+                }
+            }
+        });
+        result.content.push_str(&line);
+    }
+
+    // Definitions usually sit at the end; don't leave the blank lines behind
+    let trimmed = result.content.trim_end().len();
+    result.content.truncate(trimmed);
+    result.content.push('\n');
+    result
+}
+
+/// Split a leading YAML block off a note body, if it parses as a mapping
+fn split_frontmatter(body: &str) -> (Option<Mapping>, &str) {
+    let Some(rest) = body.strip_prefix("---\n") else {
+        return (None, body);
+    };
+    let Some(end) = rest.find("\n---\n").map(|i| i + 1).or_else(|| rest.strip_suffix("\n---").map(|r| r.len() + 1)) else {
+        return (None, body);
+    };
+    match serde_yaml::from_str::<Mapping>(&rest[..end]) {
+        Ok(mapping) => (Some(mapping), rest[end..].strip_prefix("---").unwrap_or("").trim_start_matches('\n')),
+        Err(_) => (None, body),
+    }
+}
+
+/// The frontmatter every export starts with: title, dates, tags and id in
+/// that order, then any other keys the note's own frontmatter had
+pub fn standard_frontmatter(note: &Note, own: Option<Mapping>) -> Result<String, String> {
+    let title = if note.title.trim().is_empty() { "Untitled Note" } else { note.title.trim() };
+    let tags: Vec<Value> =
+        note.tags.iter().map(|t| t.trim_start_matches('#')).filter(|t| !t.is_empty()).map(Value::from).collect();
+
+    let mut mapping = Mapping::new();
+    mapping.insert("title".into(), title.into());
+    mapping.insert("date".into(), note.created_at.as_str().into());
+    mapping.insert("updated".into(), note.updated_at.as_str().into());
+    mapping.insert("tags".into(), Value::Sequence(tags));
+    mapping.insert("id".into(), note.id.as_str().into());
+    for (key, value) in own.unwrap_or_default() {
+        if !key.as_str().map_or(false, |k| STANDARD_KEYS.contains(&k)) {
+            mapping.insert(key, value);
+        }
+    }
+    let yaml = serde_yaml::to_string(&mapping).map_err(|e| format!("Failed to write frontmatter: {}", e))?;
+    Ok(format!("---\n{}---\n\n", yaml))
+}
+
+/// Folder holding a note's file, which its relative links start from
+fn note_folder(notes_dir: &Path, note_id: &str, follow_symlinks: bool) -> PathBuf {
+    list_note_files(notes_dir, follow_symlinks)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|p| p.file_stem().map_or(false, |s| s == note_id))
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| notes_dir.to_path_buf())
+}
+
+/// The vault file a relative attachment link points at, if it exists and
+/// stays inside the vault
+fn resolve_attachment(notes_dir: &Path, folder: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or(target);
+    let path = urlencoding::decode(path).ok()?;
+    let resolved = lexical_normalize(&folder.join(path.as_ref()));
+    (resolved.starts_with(notes_dir) && resolved.is_file()).then_some(resolved)
+}
+
+/// Export a note as markdown that pandoc and similar tools read without
+/// Blink's extensions: standard frontmatter, inline links only, and
+/// attachments linked by absolute path or bundled next to `output_path`.
+/// The markdown is returned, and also written when `output_path` is given.
+#[tauri::command]
+pub async fn export_note_normalized(
+    window: tauri::Window,
+    note_id: String,
+    output_path: Option<String>,
+    attachments: Option<AttachmentPaths>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<NormalizedExport, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let mode = attachments.unwrap_or_default();
+    let (notes_dir, follow_symlinks, output_path) = {
+        let config_lock = config.lock().await;
+        let output_path = match output_path {
+            Some(path) => Some(ensure_within(Path::new(&path), &allowed_roots(&config_lock))?),
+            None => None,
+        };
+        (get_configured_notes_directory(&config_lock)?, config_lock.storage.follow_symlinks, output_path)
+    };
+    let bundle_dir = match (mode, &output_path) {
+        (AttachmentPaths::Absolute, _) => None,
+        (AttachmentPaths::Bundle, Some(path)) => {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| note_id.clone());
+            Some(format!("{}_files", stem))
+        }
+        (AttachmentPaths::Bundle, None) => return Err("Bundling attachments needs an output path".to_string()),
+    };
+
+    let note = notes.lock().await.get(&note_id).cloned().ok_or_else(|| format!("Note not found: {}", note_id))?;
+    let notes_dir = notes_dir.canonicalize().unwrap_or(notes_dir);
+    let folder = note_folder(&notes_dir, &note_id, follow_symlinks);
+
+    let mut copies: Vec<(PathBuf, String)> = Vec::new();
+    let (own, body) = split_frontmatter(&note.content);
+    let normalized = normalize_markdown(body, |target| {
+        let source = resolve_attachment(&notes_dir, &folder, target)?;
+        match &bundle_dir {
+            None => Some(source.to_string_lossy().to_string()),
+            Some(bundle_dir) => {
+                let relative = source.strip_prefix(&notes_dir).ok()?.to_string_lossy().replace('\\', "/");
+                let destination = format!("{}/{}", bundle_dir, relative);
+                copies.push((source, destination.clone()));
+                Some(destination)
+            }
+        }
+    });
+    let markdown = format!("{}{}", standard_frontmatter(&note, own)?, normalized.content);
+
+    if let Some(path) = &output_path {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        for (source, destination) in &copies {
+            let target = parent.join(destination);
+            if let Some(dir) = target.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::copy(source, &target).map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+        fs::write(path, &markdown).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        log_info!(
+            "FILE_EXPORT",
+            "Exported normalized note {} to {} ({} references resolved, {} attachments)",
+            note_id,
+            path.display(),
+            normalized.references_resolved,
+            normalized.attachments.len()
+        );
+    }
+
+    Ok(NormalizedExport {
+        markdown,
+        output_path: output_path.map(|p| p.to_string_lossy().to_string()),
+        attachments: normalized.attachments,
+        missing_attachments: normalized.missing_attachments,
+        references_resolved: normalized.references_resolved,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(content: &str) -> NormalizedBody {
+        normalize_markdown(content, |target| (target != "gone.png").then(|| format!("/vault/{}", target)))
+    }
+
+    #[test]
+    fn test_reference_links_are_inlined() {
+        let body = normalize(
+            "See [the docs][docs], [Docs][] and [docs].\n![logo][img] but [^1] and `[docs]`.\n\n\
+             [docs]: https://example.com/docs \"Docs\"\n[img]: <https://example.com/a b.png>\n",
+        );
+        assert_eq!(
+            body.content,
+            "See [the docs](https://example.com/docs \"Docs\"), [Docs](https://example.com/docs \"Docs\") \
+             and [docs](https://example.com/docs \"Docs\").\n![logo](<https://example.com/a b.png>) but [^1] and `[docs]`.\n"
+        );
+        assert_eq!(body.references_resolved, 4);
+    }
+
+    #[test]
+    fn test_attachments_and_wiki_links_are_rewritten() {
+        let body = normalize("[[Plan|the plan]] ![[photo.png]] [spec](files/spec.pdf) ![x](gone.png) [next](next.md)\n");
+        assert_eq!(
+            body.content,
+            "the plan ![](/vault/photo.png) [spec](/vault/files/spec.pdf) ![x](gone.png) [next](next.md)\n"
+        );
+        assert_eq!(body.attachments, ["photo.png", "files/spec.pdf"]);
+        assert_eq!(body.missing_attachments, ["gone.png"]);
+    }
+
+    #[test]
+    fn test_fenced_code_and_frontmatter() {
+        let body = normalize("```\n[a]: https://example.com\n[[Plan]]\n```\n");
+        assert_eq!(body.content, "```\n[a]: https://example.com\n[[Plan]]\n```\n");
+
+        let note = Note {
+            id: "plan".to_string(),
+            title: "Plan".to_string(),
+            content: "---\ntitle: Old\nauthor: Ada\n---\nBody\n".to_string(),
+            created_at: "2026-01-02T00:00:00Z".to_string(),
+            updated_at: "2026-01-03T00:00:00Z".to_string(),
+            tags: vec!["#work".to_string()],
+            position: None,
+            icon: None,
+        };
+        let (own, rest) = split_frontmatter(&note.content);
+        assert_eq!(rest, "Body\n");
+        let frontmatter = standard_frontmatter(&note, own).unwrap();
+        assert!(frontmatter.starts_with("---\ntitle: Plan\n"));
+        assert!(frontmatter.contains("tags:\n- work\nid: plan\nauthor: Ada\n---\n\n"));
+        assert!(!frontmatter.contains("Old"));
+    }
+}
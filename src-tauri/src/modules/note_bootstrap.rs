@@ -0,0 +1,66 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::types::config::AppearanceConfig;
+use crate::types::note::Note;
+use crate::types::window::ConfigState;
+use crate::log_error;
+
+/// Global the bootstrap is assigned to, read by `src/lib/note-bootstrap.ts`
+const BOOTSTRAP_GLOBAL: &str = "__BLINK_BOOTSTRAP__";
+
+/// Data a note window needs for its first paint. The frontend still fetches the
+/// note once mounted and replaces the snapshot when `updated_at` has moved on,
+/// which covers edits made between window creation and load.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteBootstrap<'a> {
+    note_id: &'a str,
+    note: &'a Note,
+    appearance: &'a AppearanceConfig,
+}
+
+/// Initialization script that embeds `note` and the appearance settings
+pub fn bootstrap_script(note: &Note, appearance: &AppearanceConfig) -> Result<String, String> {
+    let bootstrap = NoteBootstrap { note_id: &note.id, note, appearance };
+    let json = serde_json::to_string(&bootstrap)
+        .map_err(|e| format!("Failed to serialize note bootstrap: {}", e))?;
+    Ok(format!("window.{} = {};", BOOTSTRAP_GLOBAL, json))
+}
+
+/// Bootstrap script for a new note window, or `None` to let it fetch everything
+pub async fn note_bootstrap_script(app: &AppHandle, note: &Note) -> Option<String> {
+    let appearance = app.state::<ConfigState>().lock().await.appearance.clone();
+    bootstrap_script(note, &appearance)
+        .map_err(|e| log_error!("WINDOW", "{}", e))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::config::default_appearance;
+
+    #[test]
+    fn test_bootstrap_script_embeds_note() {
+        let note = Note {
+            id: "abc".to_string(),
+            title: "Quote \"test\"".to_string(),
+            content: "line one\n</script>\u{2028}".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+            tags: vec![],
+            position: None,
+            icon: None,
+        };
+        let script = bootstrap_script(&note, &default_appearance()).unwrap();
+        let json = script
+            .strip_prefix("window.__BLINK_BOOTSTRAP__ = ")
+            .and_then(|s| s.strip_suffix(';'))
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value["noteId"], "abc");
+        assert_eq!(value["note"]["content"], note.content);
+        assert_eq!(value["appearance"]["theme"], "dark");
+    }
+}
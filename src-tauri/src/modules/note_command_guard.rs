@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+/// Window operations on a note that must not overlap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteCommand {
+    CreateWindow,
+    CloseWindow,
+}
+
+#[derive(Default)]
+struct Guards {
+    /// One lock per note, so operations on the same note run one at a time
+    locks: HashMap<String, Arc<tokio::sync::Mutex<()>>>,
+    in_flight: HashSet<(String, NoteCommand)>,
+}
+
+/// Per-note reentrancy guard for window commands. A generalized `ToggleState`:
+/// repeats of an operation already in flight for a note are dropped, and
+/// different operations on the same note are serialized.
+#[derive(Clone, Default)]
+pub struct NoteCommandGuards {
+    inner: Arc<Mutex<Guards>>,
+}
+
+/// Held for the length of a guarded operation
+pub struct NoteCommandGuard {
+    guards: NoteCommandGuards,
+    key: (String, NoteCommand),
+    _lock: OwnedMutexGuard<()>,
+}
+
+impl NoteCommandGuards {
+    /// Start `command` on `note_id`, waiting for other operations on the note to
+    /// finish. Returns `None` when the same command is already in flight.
+    pub async fn begin(&self, note_id: &str, command: NoteCommand) -> Option<NoteCommandGuard> {
+        let key = (note_id.to_string(), command);
+        let lock = {
+            let mut guards = self.inner.lock().unwrap();
+            if !guards.in_flight.insert(key.clone()) {
+                return None;
+            }
+            guards.locks.entry(note_id.to_string()).or_default().clone()
+        };
+        let lock = lock.lock_owned().await;
+        Some(NoteCommandGuard { guards: self.clone(), key, _lock: lock })
+    }
+}
+
+impl Drop for NoteCommandGuard {
+    fn drop(&mut self) {
+        let mut guards = self.guards.inner.lock().unwrap();
+        guards.in_flight.remove(&self.key);
+        let note_id = &self.key.0;
+        if !guards.in_flight.iter().any(|(id, _)| id == note_id) {
+            guards.locks.remove(note_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_duplicate_requests_are_dropped() {
+        let guards = NoteCommandGuards::default();
+        let first = guards.begin("a", NoteCommand::CreateWindow).await;
+        assert!(first.is_some());
+        assert!(guards.begin("a", NoteCommand::CreateWindow).await.is_none());
+        assert!(guards.begin("b", NoteCommand::CreateWindow).await.is_some());
+
+        drop(first);
+        assert!(guards.begin("a", NoteCommand::CreateWindow).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_operations_on_a_note_are_serialized() {
+        let guards = NoteCommandGuards::default();
+        let create = guards.begin("a", NoteCommand::CreateWindow).await.unwrap();
+
+        let waiting = guards.clone();
+        let close = tokio::spawn(async move { waiting.begin("a", NoteCommand::CloseWindow).await.is_some() });
+        tokio::task::yield_now().await;
+        assert!(!close.is_finished());
+
+        drop(create);
+        assert!(close.await.unwrap());
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::database::{self, NotesDatabase};
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::window::ConfigState;
+
+/// A sidebar badge: a tag or folder and how many notes it has
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NoteCount {
+    pub name: String,
+    pub count: i64,
+}
+
+async fn open_index(config: &ConfigState) -> Result<NotesDatabase, String> {
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    database::initialize_database(&notes_dir).map_err(|e| format!("Failed to open database: {}", e))
+}
+
+/// A tag offered while typing, ranked by how often and how lately it's used
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestion {
+    pub name: String,
+    pub count: i64,
+    pub last_used: Option<String>,
+}
+
+/// Suggestions returned when the caller doesn't pass a limit
+const DEFAULT_SUGGESTION_LIMIT: usize = 10;
+/// Extra weight for a tag used just now, halved every `RECENCY_HALF_LIFE_DAYS`
+const RECENCY_WEIGHT: f64 = 5.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+fn tag_score(count: i64, last_used: Option<&str>, now: DateTime<Utc>) -> f64 {
+    let recency = last_used
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| {
+            let days = (now - at.with_timezone(&Utc)).num_seconds().max(0) as f64 / 86_400.0;
+            RECENCY_WEIGHT * 0.5f64.powf(days / RECENCY_HALF_LIFE_DAYS)
+        })
+        .unwrap_or(0.0);
+    count as f64 + recency
+}
+
+/// Order tags by usage plus a bonus for recent use, best first
+pub fn rank_tag_suggestions(rows: Vec<(String, i64, String)>, now: DateTime<Utc>, limit: usize) -> Vec<TagSuggestion> {
+    let mut scored: Vec<(f64, TagSuggestion)> = rows
+        .into_iter()
+        .map(|(name, count, last_used)| {
+            let last_used = Some(last_used).filter(|at| !at.is_empty());
+            (tag_score(count, last_used.as_deref(), now), TagSuggestion { name, count, last_used })
+        })
+        .collect();
+    scored.sort_by(|(a, x), (b, y)| b.total_cmp(a).then_with(|| x.name.cmp(&y.name)));
+    scored.into_iter().take(limit).map(|(_, suggestion)| suggestion).collect()
+}
+
+fn to_counts(rows: Vec<(String, i64)>) -> Vec<NoteCount> {
+    rows.into_iter().map(|(name, count)| NoteCount { name, count }).collect()
+}
+
+/// Notes per tag, most used first
+#[tauri::command]
+pub async fn get_tag_counts(config: State<'_, ConfigState>) -> Result<Vec<NoteCount>, String> {
+    let db = open_index(&config).await?;
+    let rows = db.tag_counts().map_err(|e| format!("Failed to count tags: {}", e))?;
+    Ok(to_counts(rows))
+}
+
+/// Tags starting with `prefix` for autocompletion, most used and most recent first
+#[tauri::command]
+pub async fn suggest_tags(
+    prefix: String,
+    limit: Option<usize>,
+    config: State<'_, ConfigState>,
+) -> Result<Vec<TagSuggestion>, String> {
+    let db = open_index(&config).await?;
+    let rows = db.tags_with_prefix(&prefix).map_err(|e| format!("Failed to look up tags: {}", e))?;
+    Ok(rank_tag_suggestions(rows, Utc::now(), limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT)))
+}
+
+/// Notes directly inside each folder; the vault root is `""`
+#[tauri::command]
+pub async fn get_folder_counts(config: State<'_, ConfigState>) -> Result<Vec<NoteCount>, String> {
+    let db = open_index(&config).await?;
+    let rows = db.folder_counts().map_err(|e| format!("Failed to count folders: {}", e))?;
+    Ok(to_counts(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::database::NoteRecord;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn record(id: &str, file_path: &str, tags: &[&str]) -> NoteRecord {
+        NoteRecord {
+            id: id.to_string(),
+            title: id.to_string(),
+            file_path: file_path.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            position: None,
+            file_hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_tag_and_folder_counts() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = database::initialize_database(temp_dir.path()).unwrap();
+        db.upsert_note(&record("a", "a.md", &["work", "Ideas"])).unwrap();
+        db.upsert_note(&record("b", "work/b.md", &["work"])).unwrap();
+        db.upsert_note(&record("c", "work/clients/c.md", &["ideas", " "])).unwrap();
+
+        assert_eq!(to_counts(db.tag_counts().unwrap()), vec![
+            NoteCount { name: "ideas".to_string(), count: 2 },
+            NoteCount { name: "work".to_string(), count: 2 },
+        ]);
+        assert_eq!(db.folder_counts().unwrap(), vec![
+            ("".to_string(), 1),
+            ("work".to_string(), 1),
+            ("work/clients".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_suggest_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = database::initialize_database(temp_dir.path()).unwrap();
+        let now = Utc::now();
+        let old = |id: &str, tags: &[&str], days_ago: i64| {
+            let mut r = record(id, &format!("{}.md", id), tags);
+            r.updated_at = now - chrono::Duration::days(days_ago);
+            db.upsert_note(&r).unwrap();
+        };
+        old("a", &["project", "Proposal"], 90);
+        old("b", &["project"], 90);
+        old("c", &["proposal"], 0);
+        old("d", &["personal"], 0);
+        old("e", &["#work"], 0);
+        old("f", &["Work"], 90);
+
+        let names = |prefix: &str, limit| {
+            let rows = db.tags_with_prefix(prefix).unwrap();
+            rank_tag_suggestions(rows, now, limit).into_iter().map(|s| s.name).collect::<Vec<_>>()
+        };
+        // "proposal" is as common as "project" but was used today
+        assert_eq!(names("PRO", 10), ["proposal", "project"]);
+        assert_eq!(names("p", 2), ["proposal", "personal"]);
+        assert!(names("x", 10).is_empty());
+        // Stored `#work` and `Work` are one tag, whether or not the prefix has a `#`
+        assert_eq!(names("wo", 10), ["work"]);
+        assert_eq!(names("#wo", 10), ["work"]);
+    }
+}
@@ -0,0 +1,79 @@
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::database;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::windows::refresh_note_window_titles;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, DetachedWindowsState, NotesState};
+use crate::VaultLockState;
+use crate::{log_error, log_info};
+
+/// Longest icon accepted, in characters. Enough for ZWJ and flag sequences.
+const MAX_ICON_CHARS: usize = 16;
+
+/// Normalize an icon. Blank means no icon.
+pub fn validate_icon(icon: Option<&str>) -> Result<Option<String>, String> {
+    let Some(icon) = icon.map(str::trim).filter(|i| !i.is_empty()) else {
+        return Ok(None);
+    };
+    if icon.chars().count() > MAX_ICON_CHARS || icon.chars().any(char::is_whitespace) {
+        return Err("An icon must be a single emoji".to_string());
+    }
+    Ok(Some(icon.to_string()))
+}
+
+/// Set or clear the emoji shown before a note's title
+#[tauri::command]
+pub async fn set_note_icon(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    emoji: Option<String>,
+    notes: State<'_, NotesState>,
+    detached_windows: State<'_, DetachedWindowsState>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Note, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let icon = validate_icon(emoji.as_deref())?;
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let updated_note = {
+        let mut notes_lock = notes.lock().await;
+        let note = notes_lock.get_mut(&note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+        database::initialize_database(&notes_dir)
+            .and_then(|db| db.set_note_icon(&note_id, icon.as_deref()))
+            .map_err(|e| format!("Failed to save note icon: {}", e))?;
+        note.icon = icon;
+        note.clone()
+    };
+
+    app.emit("note-updated", &updated_note).unwrap_or_else(|e| {
+        log_error!("NOTES", "Failed to emit note-updated event: {}", e);
+    });
+    refresh_note_window_titles(&app, &updated_note).await;
+    crate::handlers::menu_handler::update_app_menu(app.clone(), detached_windows, notes).await?;
+
+    log_info!("NOTES", "Set icon of {} to {:?}", note_id, updated_note.icon);
+    Ok(updated_note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_icon() {
+        assert_eq!(validate_icon(Some(" 🚀 ")).unwrap().as_deref(), Some("🚀"));
+        assert_eq!(validate_icon(Some("👩‍💻")).unwrap().as_deref(), Some("👩‍💻"));
+        assert_eq!(validate_icon(Some("  ")).unwrap(), None);
+        assert_eq!(validate_icon(None).unwrap(), None);
+        assert!(validate_icon(Some("🚀 launch")).is_err());
+    }
+}
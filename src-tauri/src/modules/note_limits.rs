@@ -0,0 +1,53 @@
+use crate::error::BlinkError;
+
+/// Largest note `update_note` accepts without `allow_large`. Content this big is
+/// almost always a bad paste, and hashing, indexing and writing it stalls the app.
+pub const MAX_NOTE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Most lines `update_note` accepts without `allow_large`
+pub const MAX_NOTE_LINES: usize = 200_000;
+
+/// Refuse content over the size or line limits. The byte check runs first so a
+/// huge paste is turned away without scanning it.
+pub fn check_note_size(content: &str) -> Result<(), BlinkError> {
+    if content.len() > MAX_NOTE_BYTES {
+        return Err(BlinkError::NoteTooLarge(format!(
+            "{:.1} MB, the limit is {} MB",
+            content.len() as f64 / (1024.0 * 1024.0),
+            MAX_NOTE_BYTES / (1024 * 1024),
+        )));
+    }
+    let lines = content.bytes().filter(|&b| b == b'\n').count() + 1;
+    if lines > MAX_NOTE_LINES {
+        return Err(BlinkError::NoteTooLarge(format!("{} lines, the limit is {}", lines, MAX_NOTE_LINES)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordinary_notes_pass() {
+        assert!(check_note_size("").is_ok());
+        assert!(check_note_size(&"word ".repeat(100_000)).is_ok());
+        assert!(check_note_size(&"line\n".repeat(MAX_NOTE_LINES - 1)).is_ok());
+    }
+
+    #[test]
+    fn test_huge_paste_is_refused() {
+        let paste = "x".repeat(MAX_NOTE_BYTES + 1);
+        let err = check_note_size(&paste).unwrap_err();
+        assert!(matches!(err, BlinkError::NoteTooLarge(_)));
+        assert!(err.to_string().contains("16.0 MB"));
+    }
+
+    #[test]
+    fn test_too_many_lines_is_refused() {
+        let lines = "\n".repeat(MAX_NOTE_LINES);
+        assert!(lines.len() < MAX_NOTE_BYTES);
+        let err = check_note_size(&lines).unwrap_err();
+        assert!(err.to_string().contains(&format!("{} lines", MAX_NOTE_LINES + 1)));
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::types::window::NoteEditLock;
+use crate::NoteEditLockState;
+use crate::{log_debug, log_info};
+
+/// A lock not renewed for this long is released automatically
+const EDIT_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Lock state for one note, sent to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteEditLockStatus {
+    pub note_id: String,
+    /// Label of the window holding the lock, if any
+    pub holder: Option<String>,
+    /// Whether the requesting window holds the lock
+    pub acquired: bool,
+}
+
+/// Try to take (or renew) the lock for `window_label`. Returns the holder after the attempt.
+pub fn try_acquire(
+    locks: &mut HashMap<String, NoteEditLock>,
+    note_id: &str,
+    window_label: &str,
+    now: Instant,
+) -> String {
+    let expired = locks
+        .get(note_id)
+        .map_or(false, |lock| now.duration_since(lock.last_active) > EDIT_LOCK_TIMEOUT);
+
+    match locks.get_mut(note_id) {
+        Some(lock) if lock.holder != window_label && !expired => lock.holder.clone(),
+        Some(lock) => {
+            lock.holder = window_label.to_string();
+            lock.last_active = now;
+            lock.holder.clone()
+        }
+        None => {
+            locks.insert(note_id.to_string(), NoteEditLock {
+                holder: window_label.to_string(),
+                last_active: now,
+            });
+            window_label.to_string()
+        }
+    }
+}
+
+/// Drop locks not renewed within the timeout, returning the affected note IDs
+pub fn expire_idle_locks(locks: &mut HashMap<String, NoteEditLock>, now: Instant) -> Vec<String> {
+    let expired: Vec<String> = locks
+        .iter()
+        .filter(|(_, lock)| now.duration_since(lock.last_active) > EDIT_LOCK_TIMEOUT)
+        .map(|(note_id, _)| note_id.clone())
+        .collect();
+    for note_id in &expired {
+        locks.remove(note_id);
+    }
+    expired
+}
+
+fn emit_lock_changed(app: &AppHandle, note_id: &str, holder: Option<&str>) {
+    let status = NoteEditLockStatus {
+        note_id: note_id.to_string(),
+        holder: holder.map(|h| h.to_string()),
+        acquired: false,
+    };
+    let _ = app.emit("note-edit-lock-changed", &status);
+}
+
+/// Release every lock held by a window; called when the window goes away
+pub async fn release_window_edit_locks(app: &AppHandle, window_label: &str) {
+    let lock_state = app.state::<NoteEditLockState>();
+    let mut locks = lock_state.lock().await;
+
+    let released: Vec<String> = locks
+        .iter()
+        .filter(|(_, lock)| lock.holder == window_label)
+        .map(|(note_id, _)| note_id.clone())
+        .collect();
+    for note_id in &released {
+        locks.remove(note_id);
+        emit_lock_changed(app, note_id, None);
+    }
+
+    if !released.is_empty() {
+        log_info!("EDIT_LOCK", "Released {} edit lock(s) held by closed window {}", released.len(), window_label);
+    }
+}
+
+/// Take or renew the editing lock for a note. Windows should call this when editing
+/// starts and periodically while typing; other windows are told to go read-only.
+#[tauri::command]
+pub async fn acquire_note_edit_lock(
+    note_id: String,
+    app: AppHandle,
+    window: tauri::Window,
+    edit_locks: State<'_, NoteEditLockState>,
+) -> Result<NoteEditLockStatus, String> {
+    let mut locks = edit_locks.lock().await;
+    let now = Instant::now();
+
+    for expired in expire_idle_locks(&mut locks, now) {
+        log_debug!("EDIT_LOCK", "Edit lock on {} expired", expired);
+        emit_lock_changed(&app, &expired, None);
+    }
+
+    let previous = locks.get(&note_id).map(|lock| lock.holder.clone());
+    let holder = try_acquire(&mut locks, &note_id, window.label(), now);
+    let acquired = holder == window.label();
+
+    if acquired && previous.as_deref() != Some(window.label()) {
+        log_info!("EDIT_LOCK", "Window {} acquired edit lock on {}", window.label(), note_id);
+        emit_lock_changed(&app, &note_id, Some(&holder));
+    }
+
+    Ok(NoteEditLockStatus { note_id, holder: Some(holder), acquired })
+}
+
+/// Give up the editing lock for a note if this window holds it
+#[tauri::command]
+pub async fn release_note_edit_lock(
+    note_id: String,
+    app: AppHandle,
+    window: tauri::Window,
+    edit_locks: State<'_, NoteEditLockState>,
+) -> Result<bool, String> {
+    let mut locks = edit_locks.lock().await;
+
+    let released = match locks.get(&note_id) {
+        Some(lock) if lock.holder == window.label() => {
+            locks.remove(&note_id);
+            true
+        }
+        _ => false,
+    };
+
+    if released {
+        log_info!("EDIT_LOCK", "Window {} released edit lock on {}", window.label(), note_id);
+        emit_lock_changed(&app, &note_id, None);
+    }
+    Ok(released)
+}
+
+/// Get who currently holds the editing lock for a note
+#[tauri::command]
+pub async fn get_note_edit_lock(
+    note_id: String,
+    window: tauri::Window,
+    edit_locks: State<'_, NoteEditLockState>,
+) -> Result<NoteEditLockStatus, String> {
+    let mut locks = edit_locks.lock().await;
+    expire_idle_locks(&mut locks, Instant::now());
+
+    let holder = locks.get(&note_id).map(|lock| lock.holder.clone());
+    let acquired = holder.as_deref() == Some(window.label());
+    Ok(NoteEditLockStatus { note_id, holder, acquired })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_window_wins() {
+        let mut locks = HashMap::new();
+        let now = Instant::now();
+
+        assert_eq!(try_acquire(&mut locks, "note", "main", now), "main");
+        assert_eq!(try_acquire(&mut locks, "note", "note-abc", now), "main");
+        // Renewing keeps the lock
+        assert_eq!(try_acquire(&mut locks, "note", "main", now + Duration::from_secs(5)), "main");
+    }
+
+    #[test]
+    fn test_idle_lock_can_be_taken_over() {
+        let mut locks = HashMap::new();
+        let now = Instant::now();
+
+        try_acquire(&mut locks, "note", "main", now);
+        let later = now + EDIT_LOCK_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(try_acquire(&mut locks, "note", "note-abc", later), "note-abc");
+    }
+
+    #[test]
+    fn test_expire_idle_locks() {
+        let mut locks = HashMap::new();
+        let now = Instant::now();
+
+        try_acquire(&mut locks, "old", "main", now);
+        try_acquire(&mut locks, "fresh", "main", now + EDIT_LOCK_TIMEOUT);
+
+        let expired = expire_idle_locks(&mut locks, now + EDIT_LOCK_TIMEOUT + Duration::from_secs(1));
+        assert_eq!(expired, vec!["old".to_string()]);
+        assert!(locks.contains_key("fresh"));
+    }
+}
@@ -0,0 +1,242 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+use crate::modules::recent_notes::note_opened;
+use crate::services::window_service::WindowService;
+use crate::types::note::Note;
+use crate::types::window::{CreateDetachedWindowRequest, DetachedWindowsState, NotesState};
+use crate::{log_error, log_info};
+
+/// Label of the "Open Note…" palette window
+pub const PALETTE_WINDOW_LABEL: &str = "palette";
+/// Results returned when the caller doesn't pass a limit
+const DEFAULT_RESULT_LIMIT: usize = 50;
+
+/// A note matching a palette query
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMatch {
+    pub note_id: String,
+    pub title: String,
+    pub icon: Option<String>,
+    pub score: i64,
+    /// Character indices of the title that matched, for highlighting
+    pub matched_indices: Vec<usize>,
+    /// Whether the note already has a detached window
+    pub is_open: bool,
+}
+
+/// Score `text` against `query` as a case-insensitive subsequence. Consecutive
+/// matches and matches at word starts score higher; `None` means no match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let is_word_start = |i: usize| i == 0 || !chars[i - 1].is_alphanumeric();
+    let rest_matches = |q: &[char], from: usize| {
+        let mut remaining = q.iter().peekable();
+        for c in &lower[from.min(lower.len())..] {
+            if remaining.peek() == Some(&c) {
+                remaining.next();
+            }
+        }
+        remaining.peek().is_none()
+    };
+
+    let mut indices: Vec<usize> = Vec::with_capacity(query.len());
+    let mut score = 0i64;
+    let mut from = 0;
+
+    for (q, wanted) in query.iter().enumerate() {
+        let nearest = (from..lower.len()).find(|&i| lower[i] == *wanted)?;
+        // Jump ahead to a word start when the rest of the query still fits after it
+        let chosen = if indices.last().map_or(false, |&last| last + 1 == nearest) || is_word_start(nearest) {
+            nearest
+        } else {
+            (nearest..lower.len())
+                .find(|&i| lower[i] == *wanted && is_word_start(i) && rest_matches(&query[q + 1..], i + 1))
+                .unwrap_or(nearest)
+        };
+
+        score += 1;
+        if indices.last().map_or(false, |&last| last + 1 == chosen) {
+            score += 5;
+        }
+        if is_word_start(chosen) {
+            score += 8;
+        }
+        indices.push(chosen);
+        from = chosen + 1;
+    }
+
+    // Prefer matches that start early and titles that are short
+    score -= indices[0] as i64;
+    score -= (chars.len() as i64 - indices.len() as i64) / 8;
+    Some((score, indices))
+}
+
+/// Rank notes by title against `query`, best first. An empty query lists the
+/// most recently updated notes.
+pub fn rank_notes(notes: &[&Note], query: &str, limit: usize) -> Vec<(String, i64, Vec<usize>)> {
+    let mut ranked: Vec<(&Note, i64, Vec<usize>)> = notes
+        .iter()
+        .filter_map(|note| fuzzy_score(query, &note.title).map(|(score, indices)| (*note, score, indices)))
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| b.0.updated_at.cmp(&a.0.updated_at))
+            .then_with(|| a.0.title.cmp(&b.0.title))
+    });
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(note, score, indices)| (note.id.clone(), score, indices))
+        .collect()
+}
+
+/// Fuzzy-search note titles for the palette and other quick pickers
+#[tauri::command]
+pub async fn fuzzy_find_notes(
+    query: String,
+    limit: Option<usize>,
+    notes: State<'_, NotesState>,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<Vec<NoteMatch>, String> {
+    let notes_lock = notes.lock().await;
+    let all: Vec<&Note> = notes_lock.values().collect();
+    let ranked = rank_notes(&all, &query, limit.unwrap_or(DEFAULT_RESULT_LIMIT));
+
+    let windows_lock = detached_windows.lock().await;
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(note_id, score, matched_indices)| {
+            let note = notes_lock.get(&note_id)?;
+            Some(NoteMatch {
+                title: note.title.clone(),
+                icon: note.icon.clone(),
+                is_open: windows_lock.values().any(|w| w.note_id == note_id),
+                note_id,
+                score,
+                matched_indices,
+            })
+        })
+        .collect())
+}
+
+/// Focus the note's detached window, or detach it into a new one
+pub async fn focus_or_detach_note(app: &AppHandle, note_id: String) -> Result<(), String> {
+    let detached_windows = app.state::<DetachedWindowsState>();
+    let existing_label = detached_windows
+        .lock()
+        .await
+        .iter()
+        .find(|(_, w)| w.note_id == note_id)
+        .map(|(label, _)| label.clone());
+
+    if let Some(window) = existing_label.and_then(|label| app.get_webview_window(&label)) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        note_opened(app, &note_id).await;
+        return Ok(());
+    }
+
+    let request = CreateDetachedWindowRequest {
+        note_id,
+        x: None,
+        y: None,
+        width: None,
+        height: None,
+    };
+    app.state::<WindowService>().create_detached_window(request).await?;
+    Ok(())
+}
+
+/// Show the "Open Note…" palette, creating its window on first use
+#[tauri::command]
+pub async fn open_note_palette(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PALETTE_WINDOW_LABEL) {
+        window.show().map_err(|e| format!("Failed to show note palette: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus note palette: {}", e))?;
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(&app, PALETTE_WINDOW_LABEL, WebviewUrl::App("index.html?palette=true".into()))
+        .title("Open Note")
+        .inner_size(560.0, 380.0)
+        .center()
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .shadow(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(true)
+        .build()
+        .map_err(|e| format!("Failed to create note palette: {}", e))?;
+
+    // Like Spotlight, the palette goes away when it loses focus
+    let palette = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(false) = event {
+            let _ = palette.hide();
+        }
+    });
+
+    log_info!("PALETTE", "Opened note palette");
+    Ok(())
+}
+
+/// Open the note picked in the palette and dismiss the palette
+#[tauri::command]
+pub async fn select_palette_note(app: AppHandle, note_id: String) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PALETTE_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    focus_or_detach_note(&app, note_id.clone()).await.map_err(|e| {
+        log_error!("PALETTE", "Failed to open note {}: {}", note_id, e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, title: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: vec![],
+            position: None,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score_matches_subsequence() {
+        let (_, indices) = fuzzy_score("mtg", "Meeting notes").unwrap();
+        assert_eq!(indices, vec![0, 3, 6]);
+        assert!(fuzzy_score("xyz", "Meeting notes").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some((0, Vec::new())));
+    }
+
+    #[test]
+    fn test_word_starts_and_runs_rank_higher() {
+        let notes = [note("a", "Random thoughts"), note("b", "Reading notes"), note("c", "Groceries")];
+        let refs: Vec<&Note> = notes.iter().collect();
+        let ranked = rank_notes(&refs, "rn", 10);
+
+        // "Reading notes" matches both word starts
+        assert_eq!(ranked[0].0, "b");
+        assert!(ranked.iter().all(|(id, _, _)| id != "c"));
+        assert_eq!(rank_notes(&refs, "", 2).len(), 2);
+    }
+}
@@ -0,0 +1,217 @@
+use tauri::{AppHandle, Manager, State};
+
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::modules::workspace_settings::{load_workspace_settings, save_workspace_settings};
+use crate::types::window::{ConfigState, DetachedWindowsState};
+use crate::{log_info, log_warn, VaultLockState};
+
+/// Largest snippet a note may carry
+pub const MAX_NOTE_CSS_BYTES: usize = 16 * 1024;
+
+/// Id of the `<style>` element holding a note's CSS
+const STYLE_ELEMENT_ID: &str = "blink-note-css";
+
+/// Constructs that load or run something rather than style the page
+const FORBIDDEN: &[&str] = &["@import", "expression(", "javascript:", "-moz-binding", "behavior:", "</"];
+
+/// CSS functions a snippet may call. Anything else is refused, since newer
+/// functions like `image-set()` can fetch resources without `url()`. `url()`
+/// itself is checked separately and may only hold `data:` URLs.
+const ALLOWED_FUNCTIONS: &[&str] = &[
+    // Colors and math
+    "rgb", "rgba", "hsl", "hsla", "hwb", "lab", "lch", "oklab", "oklch", "color", "color-mix",
+    "calc", "min", "max", "clamp", "var", "env", "attr", "counter", "counters",
+    // Gradients
+    "linear-gradient", "radial-gradient", "conic-gradient",
+    "repeating-linear-gradient", "repeating-radial-gradient", "repeating-conic-gradient",
+    // Transforms, filters and timing
+    "translate", "translatex", "translatey", "translatez", "translate3d",
+    "rotate", "rotatex", "rotatey", "rotatez", "rotate3d",
+    "scale", "scalex", "scaley", "scalez", "scale3d",
+    "skew", "skewx", "skewy", "matrix", "matrix3d", "perspective",
+    "blur", "brightness", "contrast", "drop-shadow", "grayscale", "hue-rotate", "invert",
+    "opacity", "saturate", "sepia", "cubic-bezier", "steps",
+    // Layout and shapes
+    "repeat", "minmax", "fit-content", "inset", "circle", "ellipse", "polygon",
+    // Fonts, for `@font-face` with a `data:` source
+    "url", "local", "format",
+    // Selectors
+    "not", "is", "where", "has", "nth-child", "nth-last-child", "nth-of-type", "nth-last-of-type", "lang", "dir",
+];
+
+/// The name of every function called in `css`, lowercase
+fn function_names(css: &str) -> Vec<&str> {
+    css.match_indices('(')
+        .filter_map(|(i, _)| {
+            let start = css[..i]
+                .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .map_or(0, |p| p + 1);
+            // Plain parentheses, as in media queries
+            (start < i).then(|| &css[start..i])
+        })
+        .collect()
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Check a note's CSS and return it trimmed and without comments. Snippets may
+/// not import stylesheets, run script, call functions outside
+/// `ALLOWED_FUNCTIONS`, or load anything but `data:` URLs.
+pub fn sanitize_note_css(css: &str) -> Result<String, String> {
+    if css.len() > MAX_NOTE_CSS_BYTES {
+        return Err(format!("Note CSS is {} bytes, the limit is {}", css.len(), MAX_NOTE_CSS_BYTES));
+    }
+    let css = strip_comments(css).trim().to_string();
+    // Escapes could spell any of the checks below in disguise
+    if css.contains('\\') {
+        return Err("Note CSS may not contain escape sequences".to_string());
+    }
+
+    let lower = css.to_ascii_lowercase();
+    if let Some(token) = FORBIDDEN.iter().find(|token| lower.contains(*token)) {
+        return Err(format!("Note CSS may not use {}", token));
+    }
+    if let Some(name) = function_names(&lower).into_iter().find(|name| !ALLOWED_FUNCTIONS.contains(name)) {
+        return Err(format!("Note CSS may not use {}()", name));
+    }
+    for (i, _) in lower.match_indices("url(") {
+        let target = lower[i + 4..].trim_start_matches([' ', '\t', '\n', '"', '\'']);
+        if !target.starts_with("data:") {
+            return Err("Note CSS may only use data: URLs".to_string());
+        }
+    }
+    Ok(css)
+}
+
+/// Script that puts `css` in the page, or removes it when `None`. Works both as
+/// an initialization script, before the document exists, and through `eval`.
+pub fn note_css_script(css: Option<&str>) -> String {
+    let css = serde_json::to_string(&css).unwrap_or_else(|_| "null".to_string());
+    format!(
+        "(function () {{\
+            var css = {css};\
+            function apply() {{\
+                var style = document.getElementById('{id}');\
+                if (css === null) {{ if (style) style.remove(); return; }}\
+                if (!style) {{ style = document.createElement('style'); style.id = '{id}'; }}\
+                style.textContent = css;\
+                (document.head || document.documentElement).appendChild(style);\
+            }}\
+            if (document.readyState === 'loading') document.addEventListener('DOMContentLoaded', apply); else apply();\
+        }})();",
+        css = css,
+        id = STYLE_ELEMENT_ID,
+    )
+}
+
+/// The note's saved CSS, sanitized again in case `.blink/settings.json` was edited by hand
+pub async fn note_css(app: &AppHandle, note_id: &str) -> Option<String> {
+    let notes_dir = get_configured_notes_directory(&*app.state::<ConfigState>().lock().await).ok()?;
+    let css = load_workspace_settings(&notes_dir).note_styles.remove(note_id)?;
+    match sanitize_note_css(&css) {
+        Ok(css) if !css.is_empty() => Some(css),
+        Ok(_) => None,
+        Err(e) => {
+            log_warn!("WINDOW", "Ignoring CSS for note {}: {}", note_id, e);
+            None
+        }
+    }
+}
+
+/// Get the CSS stored for a note
+#[tauri::command]
+pub async fn get_note_css(app: AppHandle, note_id: String) -> Result<Option<String>, String> {
+    Ok(note_css(&app, &note_id).await)
+}
+
+/// Store CSS for a note's window and apply it to the window if open. `None`
+/// or an empty snippet removes it.
+#[tauri::command]
+pub async fn set_note_css(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    css: Option<String>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let css = css.as_deref().map(sanitize_note_css).transpose()?.filter(|css| !css.is_empty());
+    let notes_dir = get_configured_notes_directory(&*config.lock().await)?;
+    let mut settings = load_workspace_settings(&notes_dir);
+    match &css {
+        Some(css) => settings.note_styles.insert(note_id.clone(), css.clone()),
+        None => settings.note_styles.remove(&note_id),
+    };
+    save_workspace_settings(&notes_dir, &settings)?;
+
+    let script = note_css_script(css.as_deref());
+    for (label, _) in detached_windows.lock().await.iter().filter(|(_, w)| w.note_id == note_id) {
+        if let Some(note_window) = app.get_webview_window(label) {
+            let _ = note_window.eval(&script);
+        }
+    }
+    log_info!("WINDOW", "{} custom CSS for note {}", if css.is_some() { "Set" } else { "Cleared" }, note_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_note_css() {
+        let clock = "/* big clock */ body { font-size: 96px; background: url(\"data:image/png;base64,AAAA\"); }";
+        assert_eq!(
+            sanitize_note_css(clock).unwrap(),
+            "body { font-size: 96px; background: url(\"data:image/png;base64,AAAA\"); }"
+        );
+
+        assert!(sanitize_note_css("@IMPORT url(data:text/css,x);").is_err());
+        assert!(sanitize_note_css("body { background: url( 'https://example.com/track.png') }").is_err());
+        assert!(sanitize_note_css("body { width: expression(alert(1)) }").is_err());
+        assert!(sanitize_note_css("</style><script>alert(1)</script>").is_err());
+        assert!(sanitize_note_css("body { background: u\\72l(https://x) }").is_err());
+        assert!(sanitize_note_css(&"a".repeat(MAX_NOTE_CSS_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_only_allowed_functions() {
+        let dashboard = "@media (min-width: 600px) and (hover) { .clock:not(.small) { \
+            width: calc(100% - 2rem); transform: translateX(-50%) rotate(2deg); \
+            background: linear-gradient(rgb(0 0 0), hsl(200 50% 50%)); } }";
+        assert!(sanitize_note_css(dashboard).is_ok());
+
+        assert_eq!(
+            sanitize_note_css("body { background: image-set('https://example.com/a.png' 1x) }").unwrap_err(),
+            "Note CSS may not use image-set()"
+        );
+        assert!(sanitize_note_css("body { background: -WEBKIT-image-set('https://x/a.png' 1x) }").is_err());
+        assert!(sanitize_note_css("body { background: cross-fade(src('https://x/a.png'), red) }").is_err());
+        assert!(sanitize_note_css("body { background: element(#tracker) }").is_err());
+    }
+
+    #[test]
+    fn test_note_css_script_escapes_css() {
+        let script = note_css_script(Some("h1::after { content: \"'\" }"));
+        assert!(script.contains(r#"var css = "h1::after { content: \"'\" }";"#));
+        assert!(note_css_script(None).contains("var css = null;"));
+    }
+}
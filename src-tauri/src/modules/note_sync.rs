@@ -0,0 +1,139 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::types::window::DetachedWindowsState;
+use crate::{log_debug, log_error};
+
+/// Minimal single-range edit turning the old content into the new content.
+/// Offsets are in UTF-16 code units so the frontend can apply them to JS strings directly.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDelta {
+    pub start: usize,
+    pub delete_count: usize,
+    pub insert: String,
+}
+
+/// Payload of the `note-content-sync` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSyncPayload {
+    pub note_id: String,
+    /// Window that made the edit; receivers ignore their own saves
+    pub source_window: Option<String>,
+    pub previous_hash: Option<String>,
+    pub content_hash: String,
+    pub delta: TextDelta,
+    /// The edit was based on an older version and overwrote a newer save (last writer wins)
+    pub conflict: bool,
+    pub updated_at: String,
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+}
+
+/// Compute the delta between two versions by trimming their common prefix and suffix
+pub fn compute_text_delta(old: &str, new: &str) -> TextDelta {
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+
+    let prefix = old_chars
+        .iter()
+        .zip(new_chars.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let max_suffix = old_chars.len().min(new_chars.len()) - prefix;
+    let suffix = old_chars
+        .iter()
+        .rev()
+        .zip(new_chars.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let prefix_str: String = old_chars[..prefix].iter().collect();
+    let deleted: String = old_chars[prefix..old_chars.len() - suffix].iter().collect();
+    let inserted: String = new_chars[prefix..new_chars.len() - suffix].iter().collect();
+
+    TextDelta {
+        start: utf16_len(&prefix_str),
+        delete_count: utf16_len(&deleted),
+        insert: inserted,
+    }
+}
+
+/// Labels of every window currently showing a note: the main window plus any detached windows
+pub async fn windows_showing_note(app: &AppHandle, note_id: &str) -> Vec<String> {
+    let mut labels = vec!["main".to_string()];
+
+    let detached_windows = app.state::<DetachedWindowsState>();
+    let windows_lock = detached_windows.lock().await;
+    labels.extend(
+        windows_lock
+            .iter()
+            .filter(|(label, w)| label.starts_with("note-") && w.note_id == note_id)
+            .map(|(label, _)| label.clone()),
+    );
+
+    labels
+}
+
+/// Send a content update to every other window showing the note
+pub async fn publish_note_sync(app: &AppHandle, payload: &NoteSyncPayload) {
+    for label in windows_showing_note(app, &payload.note_id).await {
+        if payload.source_window.as_deref() == Some(label.as_str()) {
+            continue;
+        }
+        if app.get_webview_window(&label).is_none() {
+            continue;
+        }
+        if let Err(e) = app.emit_to(label.as_str(), "note-content-sync", payload) {
+            log_error!("NOTE_SYNC", "Failed to sync note {} to {}: {}", payload.note_id, label, e);
+        } else {
+            log_debug!("NOTE_SYNC", "Synced note {} to {}", payload.note_id, label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(old: &str, delta: &TextDelta) -> String {
+        let units: Vec<u16> = old.encode_utf16().collect();
+        let mut result: Vec<u16> = units[..delta.start].to_vec();
+        result.extend(delta.insert.encode_utf16());
+        result.extend_from_slice(&units[delta.start + delta.delete_count..]);
+        String::from_utf16(&result).unwrap()
+    }
+
+    #[test]
+    fn test_insert_in_middle() {
+        let delta = compute_text_delta("hello world", "hello brave world");
+        assert_eq!(delta, TextDelta { start: 6, delete_count: 0, insert: "brave ".to_string() });
+    }
+
+    #[test]
+    fn test_replace_and_delete() {
+        let delta = compute_text_delta("abcdef", "abXf");
+        assert_eq!(delta, TextDelta { start: 2, delete_count: 3, insert: "X".to_string() });
+        assert_eq!(compute_text_delta("same", "same").delete_count, 0);
+    }
+
+    #[test]
+    fn test_repeated_characters_do_not_overlap() {
+        let delta = compute_text_delta("aaa", "aaaa");
+        assert_eq!(apply("aaa", &delta), "aaaa");
+    }
+
+    #[test]
+    fn test_offsets_are_utf16() {
+        let old = "🚀 launch";
+        let new = "🚀 launch now";
+        let delta = compute_text_delta(old, new);
+        assert_eq!(delta.start, 9);
+        assert_eq!(apply(old, &delta), new);
+    }
+}
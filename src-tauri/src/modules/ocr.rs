@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::modules::attachments::linked_attachments;
+use crate::modules::database::{self, NotesDatabase};
+use crate::types::window::NotesState;
+use crate::{log_debug, log_info, log_warn};
+
+/// Text to put in the full-text index for a note: its content plus the text
+/// recognized in the images it links to. The extra text is only searched, never shown.
+pub fn searchable_content(db: &NotesDatabase, content: &str) -> String {
+    let texts = db.get_attachment_text(&linked_attachments(content)).unwrap_or_default();
+    if texts.is_empty() {
+        return content.to_string();
+    }
+    format!("{}\n{}", content, texts.join("\n"))
+}
+
+/// Run the `tesseract` binary; used instead of Vision when built with `ocr-tesseract`
+#[cfg(feature = "ocr-tesseract")]
+fn recognize_text(path: &Path) -> Result<String, String> {
+    let output = std::process::Command::new("tesseract")
+        .arg(path)
+        .arg("stdout")
+        .output()
+        .map_err(|e| format!("Failed to run tesseract: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(all(target_os = "macos", not(feature = "ocr-tesseract")))]
+#[link(name = "Vision", kind = "framework")]
+extern "C" {}
+
+/// Recognize text with the Vision framework. `performRequests:error:` is synchronous,
+/// so this must run off the main thread.
+#[cfg(all(target_os = "macos", not(feature = "ocr-tesseract")))]
+fn recognize_text(path: &Path) -> Result<String, String> {
+    use cocoa::base::{id, nil, BOOL, NO};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    let path = path.to_string_lossy().to_string();
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+        let ns_path = NSString::alloc(nil).init_str(&path);
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let options: id = msg_send![class!(NSDictionary), dictionary];
+
+        let handler: id = msg_send![class!(VNImageRequestHandler), alloc];
+        let handler: id = msg_send![handler, initWithURL: url options: options];
+        let request: id = msg_send![class!(VNRecognizeTextRequest), new];
+        let requests: id = msg_send![class!(NSArray), arrayWithObject: request];
+
+        let mut error: id = nil;
+        let ok: BOOL = msg_send![handler, performRequests: requests error: &mut error];
+
+        let mut lines = Vec::new();
+        if ok != NO {
+            let results: id = msg_send![request, results];
+            let count: usize = if results == nil { 0 } else { msg_send![results, count] };
+            for i in 0..count {
+                let observation: id = msg_send![results, objectAtIndex: i];
+                let candidates: id = msg_send![observation, topCandidates: 1usize];
+                let candidate: id = msg_send![candidates, firstObject];
+                if candidate == nil {
+                    continue;
+                }
+                let text: id = msg_send![candidate, string];
+                let utf8: *const c_char = msg_send![text, UTF8String];
+                if !utf8.is_null() {
+                    lines.push(CStr::from_ptr(utf8).to_string_lossy().to_string());
+                }
+            }
+        }
+
+        let _: () = msg_send![request, release];
+        let _: () = msg_send![handler, release];
+        let _: () = msg_send![ns_path, release];
+        let _: () = msg_send![pool, drain];
+
+        if ok == NO {
+            return Err("Vision could not read the image".to_string());
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(all(not(target_os = "macos"), not(feature = "ocr-tesseract")))]
+fn recognize_text(_path: &Path) -> Result<String, String> {
+    Err("OCR needs macOS or a build with the ocr-tesseract feature".to_string())
+}
+
+/// Recognize text in a stored attachment in the background, save it, and refresh the
+/// note's full-text entry so the screenshot can be found by search
+pub fn spawn_attachment_ocr(app: AppHandle, notes_dir: PathBuf, note_id: String, relative_path: String) {
+    tauri::async_runtime::spawn(async move {
+        let file_name = relative_path.rsplit('/').next().unwrap_or(&relative_path).to_string();
+        let path = notes_dir.join(&relative_path);
+        let text = match tauri::async_runtime::spawn_blocking(move || recognize_text(&path)).await {
+            Ok(Ok(text)) => text.trim().to_string(),
+            Ok(Err(e)) => {
+                log_warn!("OCR", "Skipped {}: {}", file_name, e);
+                return;
+            }
+            Err(e) => {
+                log_warn!("OCR", "OCR task for {} failed: {}", file_name, e);
+                return;
+            }
+        };
+        if text.is_empty() {
+            log_debug!("OCR", "No text found in {}", file_name);
+            return;
+        }
+
+        let db = match database::initialize_database(&notes_dir) {
+            Ok(db) => db,
+            Err(e) => {
+                log_warn!("OCR", "Failed to open database: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = db.set_attachment_text(&file_name, &text) {
+            log_warn!("OCR", "Failed to store text for {}: {}", file_name, e);
+            return;
+        }
+
+        // The paste may already be saved; later saves pick the text up anyway
+        let note = app.state::<NotesState>().lock().await.get(&note_id).cloned();
+        if let Some(note) = note {
+            let _ = db.upsert_fts(&note.id, &note.title, &searchable_content(&db, &note.content));
+        }
+        log_info!("OCR", "Indexed {} characters of text from {}", text.len(), file_name);
+    });
+}
@@ -0,0 +1,143 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::modules::attachments::{store_attachment, StoredAttachment};
+use crate::modules::ocr::spawn_attachment_ocr;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::ConfigState;
+use crate::VaultLockState;
+use crate::log_info;
+
+/// A pasted image after it has been stored
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PastedImage {
+    /// Ready to insert at the cursor
+    pub markdown: String,
+    pub attachment: StoredAttachment,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn image_format(mime: &str) -> Result<ImageFormat, String> {
+    match mime.to_lowercase().as_str() {
+        "image/png" => Ok(ImageFormat::Png),
+        "image/jpeg" | "image/jpg" => Ok(ImageFormat::Jpeg),
+        other => Err(format!("Unsupported image type: {}", other)),
+    }
+}
+
+/// Scale `image` down so its longest side is at most `max_dimension`
+pub fn fit_within(image: DynamicImage, max_dimension: u32) -> DynamicImage {
+    if max_dimension == 0 || (image.width() <= max_dimension && image.height() <= max_dimension) {
+        return image;
+    }
+    image.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, jpeg_quality: u8) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut buffer, jpeg_quality.clamp(1, 100));
+            // JPEG has no alpha channel
+            DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)
+        }
+        _ => {
+            let encoder = PngEncoder::new_with_quality(&mut buffer, CompressionType::Best, PngFilterType::Adaptive);
+            image.write_with_encoder(encoder)
+        }
+    };
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer)
+}
+
+/// Decode, downscale and re-encode a pasted image. The original bytes are kept
+/// when nothing was resized and re-encoding would not make the file smaller.
+pub fn optimize_image(bytes: &[u8], mime: &str, max_dimension: u32, jpeg_quality: u8) -> Result<(Vec<u8>, &'static str, u32, u32), String> {
+    let format = image_format(mime)?;
+    let extension = if format == ImageFormat::Jpeg { "jpg" } else { "png" };
+    let decoded = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let original_size = (decoded.width(), decoded.height());
+    let image = fit_within(decoded, max_dimension);
+    let (width, height) = (image.width(), image.height());
+    let encoded = encode(&image, format, jpeg_quality)?;
+
+    if (width, height) == original_size && encoded.len() >= bytes.len() {
+        return Ok((bytes.to_vec(), extension, width, height));
+    }
+    Ok((encoded, extension, width, height))
+}
+
+/// Store image data pasted into a note and return the markdown that embeds it.
+/// With OCR enabled, text in new images is indexed in the background.
+#[tauri::command]
+pub async fn save_pasted_image(
+    window: tauri::Window,
+    app: AppHandle,
+    note_id: String,
+    bytes: Vec<u8>,
+    mime: String,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<PastedImage, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let (notes_dir, settings) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.attachments.clone())
+    };
+
+    let (data, extension, width, height) =
+        optimize_image(&bytes, &mime, settings.max_image_dimension, settings.jpeg_quality)?;
+    let attachment = store_attachment(&notes_dir, &data, extension)?;
+    // Linked from the vault root, so tag routing can move the note without breaking it
+    let markdown = format!("![]({})", attachment.relative_path);
+    if settings.ocr && !attachment.deduplicated {
+        spawn_attachment_ocr(app, notes_dir.clone(), note_id.clone(), attachment.relative_path.clone());
+    }
+
+    log_info!(
+        "ATTACHMENTS",
+        "Pasted {}x{} image into {} ({} -> {} bytes{})",
+        width,
+        height,
+        note_id,
+        bytes.len(),
+        attachment.size,
+        if attachment.deduplicated { ", already stored" } else { "" }
+    );
+    Ok(PastedImage { markdown, attachment, width, height })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        encode(&image, ImageFormat::Png, 85).unwrap()
+    }
+
+    #[test]
+    fn test_large_images_are_scaled_down() {
+        let (data, extension, width, height) = optimize_image(&png(400, 100), "image/png", 200, 85).unwrap();
+        assert_eq!((extension, width, height), ("png", 200, 50));
+        let decoded = image::load_from_memory(&data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (200, 50));
+
+        let (_, _, width, height) = optimize_image(&png(40, 10), "image/png", 200, 85).unwrap();
+        assert_eq!((width, height), (40, 10));
+
+        assert!(optimize_image(&png(4, 4), "image/gif", 200, 85).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::modules::archive_policy::run_archive_policies_at_startup;
+use crate::modules::config_reload::set_config_watcher_paused;
+use crate::modules::maintenance::run_vault_verification;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::ConfigState;
+use crate::{log_debug, log_error, log_info};
+
+/// How often the power source is checked on macOS
+const POWER_SOURCE_POLL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerMode {
+    Normal,
+    /// Fewer wake-ups: the config watcher is paused, autosave and the vault
+    /// lock heartbeat wait longer and maintenance jobs wait for normal mode
+    Low,
+}
+
+static LOW_POWER: AtomicBool = AtomicBool::new(false);
+
+/// Background jobs held back in low power mode, run once it ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeferredJob {
+    ArchivePolicies,
+    /// The startup integrity check, which hashes every changed file
+    VaultVerification,
+}
+
+static DEFERRED: Mutex<Vec<DeferredJob>> = Mutex::new(Vec::new());
+
+pub fn is_low_power() -> bool {
+    LOW_POWER.load(Ordering::SeqCst)
+}
+
+pub fn power_mode() -> PowerMode {
+    if is_low_power() { PowerMode::Low } else { PowerMode::Normal }
+}
+
+/// `normal` in normal mode, `low` in low power mode
+pub fn power_scaled(normal: Duration, low: Duration) -> Duration {
+    if is_low_power() { low } else { normal }
+}
+
+/// Hold `job` back until normal mode. Returns false when it should run now.
+pub fn defer_in_low_power(job: DeferredJob) -> bool {
+    if !is_low_power() {
+        return false;
+    }
+    let mut deferred = DEFERRED.lock().unwrap();
+    if !deferred.contains(&job) {
+        deferred.push(job);
+    }
+    log_info!("POWER", "Low power mode, deferring {:?}", job);
+    true
+}
+
+async fn run_deferred(app: &AppHandle) {
+    let jobs = std::mem::take(&mut *DEFERRED.lock().unwrap());
+    for job in jobs {
+        log_info!("POWER", "Running deferred {:?}", job);
+        match job {
+            DeferredJob::ArchivePolicies => run_archive_policies_at_startup(app).await,
+            DeferredJob::VaultVerification => {
+                let config = app.state::<ConfigState>().lock().await.clone();
+                if let Err(e) = run_vault_verification(app, &config, false) {
+                    log_error!("POWER", "Deferred vault integrity check failed: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Switch modes, pausing or resuming what low power mode holds back
+pub async fn apply_power_mode(app: &AppHandle, mode: PowerMode, reason: &str) {
+    let low = mode == PowerMode::Low;
+    if LOW_POWER.swap(low, Ordering::SeqCst) == low {
+        return;
+    }
+    log_info!("POWER", "Power mode {:?} ({})", mode, reason);
+
+    set_config_watcher_paused(app, low);
+    let _ = app.emit("power-mode-changed", mode);
+    if !low {
+        run_deferred(app).await;
+    }
+}
+
+/// Whether `pmset -g batt` says the Mac runs on battery, `None` when the
+/// output doesn't say
+pub fn parse_on_battery(pmset_output: &str) -> Option<bool> {
+    let first_line = pmset_output.lines().next()?;
+    if first_line.contains("'Battery Power'") {
+        Some(true)
+    } else if first_line.contains("'AC Power'") || first_line.contains("'UPS Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    parse_on_battery(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Pick the mode from the power source at launch and follow it afterwards.
+/// Only changes of power source switch modes, so a mode set by hand stays
+/// until the laptop is plugged in or unplugged.
+pub fn start_power_monitor(app: &AppHandle) {
+    let Some(mut last) = on_battery() else {
+        log_debug!("POWER", "Power source unknown, not watching it");
+        return;
+    };
+    // Nothing that low power mode pauses has started yet
+    LOW_POWER.store(last, Ordering::SeqCst);
+    if last {
+        log_info!("POWER", "Starting on battery, low power mode");
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POWER_SOURCE_POLL).await;
+            let Some(now) = tauri::async_runtime::spawn_blocking(on_battery).await.ok().flatten() else { continue };
+            if now != last {
+                last = now;
+                let mode = if now { PowerMode::Low } else { PowerMode::Normal };
+                apply_power_mode(&app, mode, if now { "on battery" } else { "on AC power" }).await;
+            }
+        }
+    });
+}
+
+/// Switch between normal and low power mode. Manual saves are never delayed.
+#[tauri::command]
+pub async fn set_power_mode(window: tauri::Window, app: AppHandle, mode: PowerMode) -> Result<PowerMode, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    apply_power_mode(&app, mode, "set by user").await;
+    Ok(power_mode())
+}
+
+#[tauri::command]
+pub async fn get_power_mode() -> Result<PowerMode, String> {
+    Ok(power_mode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_on_battery() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t84%; discharging; 5:12 remaining present: true";
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged; 0:00 remaining present: true";
+        assert_eq!(parse_on_battery(battery), Some(true));
+        assert_eq!(parse_on_battery(ac), Some(false));
+        assert_eq!(parse_on_battery(""), None);
+        assert_eq!(parse_on_battery("No batteries"), None);
+    }
+
+    #[test]
+    fn test_low_power_defers_jobs_once() {
+        let (normal, low) = (Duration::from_secs(2), Duration::from_secs(10));
+        assert_eq!(power_mode(), PowerMode::Normal);
+        assert!(!defer_in_low_power(DeferredJob::VaultVerification));
+        assert_eq!(power_scaled(normal, low), normal);
+
+        LOW_POWER.store(true, Ordering::SeqCst);
+        assert_eq!(power_mode(), PowerMode::Low);
+        assert_eq!(power_scaled(normal, low), low);
+        assert!(defer_in_low_power(DeferredJob::VaultVerification));
+        assert!(defer_in_low_power(DeferredJob::ArchivePolicies));
+        assert!(defer_in_low_power(DeferredJob::VaultVerification));
+        LOW_POWER.store(false, Ordering::SeqCst);
+
+        let deferred = std::mem::take(&mut *DEFERRED.lock().unwrap());
+        assert_eq!(deferred, [DeferredJob::VaultVerification, DeferredJob::ArchivePolicies]);
+    }
+
+    #[test]
+    fn test_power_mode_names() {
+        assert_eq!(serde_json::to_string(&PowerMode::Low).unwrap(), "\"low\"");
+        assert_eq!(serde_json::from_str::<PowerMode>("\"normal\"").unwrap(), PowerMode::Normal);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
+
+use crate::types::window::{ConfigState, NotesState};
+use crate::{log_debug, log_error, log_info, ReadingModeState};
+
+/// Reading windows are labelled with this prefix and the note ID
+pub const READING_WINDOW_PREFIX: &str = "reading-";
+
+/// Title bar height of a shaded window, as in `toggle_window_shade`
+const SHADED_HEIGHT: f64 = 48.0;
+
+pub fn reading_window_label(note_id: &str) -> String {
+    format!("{}{}", READING_WINDOW_PREFIX, note_id)
+}
+
+pub fn is_reading_window(label: &str) -> bool {
+    label.starts_with(READING_WINDOW_PREFIX)
+}
+
+/// Add a paragraph or line to the page, starting a new page when it won't fit
+fn push_block(block: &str, separator: &str, page_chars: usize, page: &mut String, pages: &mut Vec<String>) {
+    if !page.is_empty() && page.chars().count() + separator.len() + block.chars().count() > page_chars {
+        pages.push(std::mem::take(page));
+    }
+    if !page.is_empty() {
+        page.push_str(separator);
+    }
+    page.push_str(block);
+}
+
+/// Split note content into pages of about `page_chars` characters. Pages break
+/// between paragraphs, or between lines when a paragraph is longer than a page;
+/// a single line longer than a page gets a page to itself.
+pub fn paginate(content: &str, page_chars: usize) -> Vec<String> {
+    let page_chars = page_chars.max(1);
+    let mut pages = Vec::new();
+    let mut page = String::new();
+
+    for paragraph in content.split("\n\n").map(str::trim_end).filter(|p| !p.trim().is_empty()) {
+        if paragraph.chars().count() <= page_chars {
+            push_block(paragraph, "\n\n", page_chars, &mut page, &mut pages);
+            continue;
+        }
+        let mut first = true;
+        for line in paragraph.lines() {
+            push_block(line, if first { "\n\n" } else { "\n" }, page_chars, &mut page, &mut pages);
+            first = false;
+        }
+    }
+    if !page.is_empty() || pages.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+/// One open reading window
+#[derive(Debug, Clone)]
+pub struct ReadingSession {
+    pub note_id: String,
+    pub pages: Vec<String>,
+    pub page: usize,
+}
+
+impl ReadingSession {
+    /// Move by `delta` pages, stopping at the first and last. Returns false
+    /// when already there.
+    pub fn turn(&mut self, delta: isize) -> bool {
+        let last = self.pages.len().saturating_sub(1) as isize;
+        let target = (self.page as isize + delta).clamp(0, last) as usize;
+        if target == self.page {
+            return false;
+        }
+        self.page = target;
+        true
+    }
+
+    pub fn current(&self) -> ReadingPage {
+        ReadingPage {
+            note_id: self.note_id.clone(),
+            page: self.page,
+            total: self.pages.len(),
+            content: self.pages.get(self.page).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// Open reading windows by label, and the one holding the page keys
+#[derive(Debug, Default)]
+pub struct ReadingMode {
+    sessions: HashMap<String, ReadingSession>,
+    focused: Option<String>,
+}
+
+/// Payload of `reading-page` and what `get_reading_page` returns
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingPage {
+    pub note_id: String,
+    pub page: usize,
+    pub total: usize,
+    pub content: String,
+}
+
+/// Keys that turn pages, and by how much
+fn page_keys() -> Vec<(Shortcut, isize)> {
+    vec![
+        (Shortcut::new(None, Code::ArrowRight), 1),
+        (Shortcut::new(None, Code::PageDown), 1),
+        (Shortcut::new(None, Code::Space), 1),
+        (Shortcut::new(None, Code::ArrowLeft), -1),
+        (Shortcut::new(None, Code::PageUp), -1),
+        (Shortcut::new(Some(Modifiers::SHIFT), Code::Space), -1),
+    ]
+}
+
+fn register_page_keys(app: &AppHandle) {
+    let manager = app.global_shortcut();
+    for (shortcut, _) in page_keys() {
+        if let Err(e) = manager.register(shortcut) {
+            log_debug!("SHORTCUT", "Could not capture {:?} for reading mode: {}", shortcut, e);
+        }
+    }
+}
+
+fn unregister_page_keys(app: &AppHandle) {
+    let manager = app.global_shortcut();
+    for (shortcut, _) in page_keys() {
+        let _ = manager.unregister(shortcut);
+    }
+}
+
+/// The page keys are only registered while a reading window has focus, so
+/// they never reach other apps or Blink's own editors
+fn on_reading_focus(app: &AppHandle, label: &str, focused: bool) {
+    let state = app.state::<ReadingModeState>();
+    let mut reading = state.lock().unwrap();
+    if focused {
+        if reading.focused.replace(label.to_string()).is_none() {
+            register_page_keys(app);
+        }
+    } else if reading.focused.as_deref() == Some(label) {
+        reading.focused = None;
+        unregister_page_keys(app);
+    }
+}
+
+fn on_reading_closed(app: &AppHandle, label: &str) {
+    on_reading_focus(app, label, false);
+    app.state::<ReadingModeState>().lock().unwrap().sessions.remove(label);
+}
+
+/// Turn the page of the focused reading window. Returns false for any other shortcut.
+pub fn handle_reading_key(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    let Some((_, delta)) = page_keys().into_iter().find(|(key, _)| key == shortcut) else { return false };
+
+    let (label, page) = {
+        let state = app.state::<ReadingModeState>();
+        let mut reading = state.lock().unwrap();
+        let Some(label) = reading.focused.clone() else { return false };
+        let Some(session) = reading.sessions.get_mut(&label) else { return false };
+        if !session.turn(delta) {
+            return true;
+        }
+        (label, session.current())
+    };
+
+    if let Err(e) = app.emit_to(label.as_str(), "reading-page", &page) {
+        log_error!("READING", "Failed to emit reading-page to {}: {}", label, e);
+    }
+    true
+}
+
+/// Open a note in a distraction-free reading window, paginated by the
+/// backend. The window can't edit: its label gives it the `Reader` role.
+#[tauri::command]
+pub async fn open_reading_mode(
+    note_id: String,
+    app: AppHandle,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    reading: State<'_, ReadingModeState>,
+) -> Result<(), String> {
+    let content = notes
+        .lock()
+        .await
+        .get(&note_id)
+        .map(|note| note.content.clone())
+        .ok_or_else(|| format!("Note {} not found", note_id))?;
+    let reading_config = config.lock().await.reading.clone();
+
+    let label = reading_window_label(&note_id);
+    let session = ReadingSession {
+        note_id: note_id.clone(),
+        pages: paginate(&content, reading_config.page_chars),
+        page: 0,
+    };
+    let page = session.current();
+    reading.lock().unwrap().sessions.insert(label.clone(), session);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        // Reopening starts over with the note as it is now
+        let _ = app.emit_to(label.as_str(), "reading-page", &page);
+        let _ = window.unminimize();
+        window.show().map_err(|e| format!("Failed to show reading window: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus reading window: {}", e))?;
+        return Ok(());
+    }
+
+    let url = format!(
+        "index.html?reading={}&opacity={}",
+        urlencoding::encode(&note_id),
+        reading_config.opacity
+    );
+    let height = if reading_config.shaded { SHADED_HEIGHT } else { reading_config.height };
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title("Reading")
+        .inner_size(reading_config.width, height)
+        .min_inner_size(320.0, SHADED_HEIGHT)
+        .center()
+        .decorations(false)
+        .transparent(true)
+        .resizable(true)
+        .focused(true)
+        .build()
+        .map_err(|e| format!("Failed to create reading window: {}", e))?;
+
+    let app_handle = app.clone();
+    let window_label = label.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::Focused(focused) => on_reading_focus(&app_handle, &window_label, *focused),
+        tauri::WindowEvent::Destroyed => on_reading_closed(&app_handle, &window_label),
+        _ => {}
+    });
+
+    log_info!("READING", "Opened reading mode for note {}", note_id);
+    Ok(())
+}
+
+/// The page the calling reading window is on, for its first render
+#[tauri::command]
+pub async fn get_reading_page(
+    window: tauri::Window,
+    reading: State<'_, ReadingModeState>,
+) -> Result<ReadingPage, String> {
+    reading
+        .lock()
+        .unwrap()
+        .sessions
+        .get(window.label())
+        .map(ReadingSession::current)
+        .ok_or_else(|| format!("{} is not a reading window", window.label()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_breaks_between_paragraphs() {
+        let content = "First paragraph.\n\nSecond paragraph.\n\nThird.";
+        assert_eq!(paginate(content, 1000), vec![content.to_string()]);
+        assert_eq!(
+            paginate(content, 36),
+            vec!["First paragraph.\n\nSecond paragraph.".to_string(), "Third.".to_string()]
+        );
+        assert_eq!(paginate("", 100), vec![String::new()]);
+    }
+
+    #[test]
+    fn test_paginate_splits_long_paragraphs_by_line() {
+        let content = "- one\n- two\n- three";
+        assert_eq!(paginate(content, 12), vec!["- one\n- two".to_string(), "- three".to_string()]);
+    }
+
+    #[test]
+    fn test_turn_stops_at_the_ends() {
+        let mut session = ReadingSession {
+            note_id: "n".to_string(),
+            pages: vec!["a".to_string(), "b".to_string()],
+            page: 0,
+        };
+        assert!(!session.turn(-1));
+        assert!(session.turn(1));
+        assert_eq!(session.current().content, "b");
+        assert!(!session.turn(1));
+        assert_eq!(session.current().total, 2);
+    }
+}
@@ -0,0 +1,112 @@
+use tauri::{AppHandle, Manager};
+
+use crate::handlers::menu_handler::refresh_open_recent_menu;
+use crate::RecentNotesState;
+
+/// Notes listed under Notes > Open Recent
+pub const OPEN_RECENT_LIMIT: usize = 10;
+
+/// Recently opened notes, most recent first, backing the Open Recent menu.
+/// Until notes are opened it is filled from the index's most recently
+/// updated notes, the way a document app remembers its last documents.
+#[derive(Debug, Default)]
+pub struct RecentNotes {
+    ids: Vec<String>,
+    /// Seeded from the index or cleared by the user; either way an empty list stays empty
+    seeded: bool,
+}
+
+impl RecentNotes {
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Move `note_id` to the top. Returns false when it already was there.
+    pub fn record(&mut self, note_id: &str) -> bool {
+        if self.ids.first().map(String::as_str) == Some(note_id) {
+            return false;
+        }
+        self.ids.retain(|id| id != note_id);
+        self.ids.insert(0, note_id.to_string());
+        self.ids.truncate(OPEN_RECENT_LIMIT);
+        true
+    }
+
+    /// Fill in below the notes opened so far, once. An empty list doesn't
+    /// count, since the index may not be readable yet.
+    pub fn seed(&mut self, recent_ids: &[String]) {
+        if self.seeded || recent_ids.is_empty() {
+            return;
+        }
+        self.seeded = true;
+        for id in recent_ids {
+            if self.ids.len() >= OPEN_RECENT_LIMIT {
+                break;
+            }
+            if !self.ids.contains(id) {
+                self.ids.push(id.clone());
+            }
+        }
+    }
+
+    /// "Clear Menu": forget everything, including what seeding would add back
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.seeded = true;
+    }
+}
+
+/// Record that a note was opened and move it to the top of Open Recent
+pub async fn note_opened(app: &AppHandle, note_id: &str) {
+    let changed = app.state::<RecentNotesState>().lock().unwrap().record(note_id);
+    if changed {
+        refresh_open_recent_menu(app).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(recent: &RecentNotes) -> Vec<&str> {
+        recent.ids().iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_record_moves_note_to_top() {
+        let mut recent = RecentNotes::default();
+        assert!(recent.record("a"));
+        assert!(recent.record("b"));
+        assert!(!recent.record("b"));
+        assert!(recent.record("a"));
+        assert_eq!(ids(&recent), ["a", "b"]);
+
+        for i in 0..OPEN_RECENT_LIMIT {
+            recent.record(&format!("n{}", i));
+        }
+        assert_eq!(recent.ids().len(), OPEN_RECENT_LIMIT);
+        assert!(!recent.ids().contains(&"b".to_string()));
+    }
+
+    #[test]
+    fn test_seed_goes_below_opened_notes_once() {
+        let mut recent = RecentNotes::default();
+        recent.seed(&[]);
+        recent.record("b");
+        recent.seed(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        assert_eq!(ids(&recent), ["b", "a", "c"]);
+
+        recent.seed(&["d".to_string()]);
+        assert_eq!(ids(&recent), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_cleared_list_is_not_seeded_again() {
+        let mut recent = RecentNotes::default();
+        recent.clear();
+        recent.seed(&["a".to_string()]);
+        assert!(recent.ids().is_empty());
+        recent.record("b");
+        assert_eq!(ids(&recent), ["b"]);
+    }
+}
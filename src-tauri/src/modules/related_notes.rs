@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::database;
+use crate::modules::metadata_export::extract_links;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::log_warn;
+
+/// Related notes returned when the caller doesn't ask for a number
+pub const DEFAULT_RELATED_LIMIT: usize = 5;
+
+/// Only a note's most frequent terms are indexed
+const MAX_TERMS_PER_NOTE: usize = 200;
+const MIN_TERM_LENGTH: usize = 3;
+
+/// Words too common to say anything about what a note is about
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was", "one", "our",
+    "out", "has", "him", "his", "how", "its", "let", "may", "new", "now", "old", "see", "two", "who", "did",
+    "get", "got", "use", "she", "too", "this", "that", "with", "have", "from", "they", "will", "would", "there",
+    "their", "what", "about", "which", "when", "were", "been", "than", "then", "them", "these", "some", "into",
+    "more", "also", "just", "only", "very", "over", "such", "should", "could", "your", "here", "where", "each",
+    "other", "after", "before", "because", "while", "http", "https", "www", "com",
+];
+
+/// How much each signal adds to a score. Term similarity and tag overlap are
+/// both between 0 and 1; a link either way counts as much as identical tags.
+const TAG_WEIGHT: f64 = 1.0;
+const LINK_WEIGHT: f64 = 1.0;
+const TERM_WEIGHT: f64 = 1.0;
+
+/// A note that shares something with the one asked about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedNote {
+    pub note_id: String,
+    pub title: String,
+    pub score: f64,
+    pub shared_tags: Vec<String>,
+    /// One of the two notes links to the other
+    pub linked: bool,
+    /// The terms that contributed most, for showing why
+    pub shared_terms: Vec<String>,
+}
+
+/// Counts of the distinctive words in a text, most frequent first. This is
+/// what the index keeps per note.
+pub fn term_counts(text: &str) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.chars().count() < MIN_TERM_LENGTH || word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let word = word.to_lowercase();
+        if STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(MAX_TERMS_PER_NOTE);
+    counts
+}
+
+/// Notes a note links to, by ID. Links name a note by its file (`other.md`)
+/// or, as wiki links, by its title.
+fn linked_note_ids(note: &Note, by_name: &HashMap<String, String>) -> HashSet<String> {
+    let (links, _) = extract_links(&note.content);
+    links
+        .iter()
+        .filter(|target| !target.contains("://"))
+        .filter_map(|target| {
+            let path = target.split(['?', '#']).next().unwrap_or(target);
+            let name = Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string())?;
+            by_name.get(&name.to_lowercase()).or_else(|| by_name.get(&target.to_lowercase())).cloned()
+        })
+        .filter(|id| *id != note.id)
+        .collect()
+}
+
+/// TF-IDF weights of one note's terms
+fn tf_idf(terms: &HashMap<String, usize>, document_frequency: &HashMap<&str, usize>, documents: usize) -> HashMap<String, f64> {
+    terms
+        .iter()
+        .map(|(term, count)| {
+            let df = document_frequency.get(term.as_str()).copied().unwrap_or(0);
+            let idf = ((documents as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0;
+            (term.clone(), *count as f64 * idf)
+        })
+        .collect()
+}
+
+fn norm(weights: &HashMap<String, f64>) -> f64 {
+    weights.values().map(|w| w * w).sum::<f64>().sqrt()
+}
+
+/// Score every other note against `note_id` and keep the best `limit`. Term
+/// counts come from the index; notes it doesn't cover yet are counted here.
+pub fn related_notes(
+    note_id: &str,
+    notes: &HashMap<String, Note>,
+    mut indexed_terms: HashMap<String, HashMap<String, usize>>,
+    limit: usize,
+) -> Vec<RelatedNote> {
+    let Some(target) = notes.get(note_id) else { return Vec::new() };
+
+    let terms: HashMap<&str, HashMap<String, usize>> = notes
+        .values()
+        .map(|note| {
+            let counts = indexed_terms.remove(&note.id).unwrap_or_else(|| {
+                term_counts(&format!("{}\n{}", note.title, note.content)).into_iter().collect()
+            });
+            (note.id.as_str(), counts)
+        })
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for counts in terms.values() {
+        for term in counts.keys() {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut by_name: HashMap<String, String> = HashMap::new();
+    for note in notes.values() {
+        by_name.insert(note.title.to_lowercase(), note.id.clone());
+    }
+    // IDs win over titles, since a file name is unambiguous
+    for note in notes.values() {
+        by_name.insert(note.id.to_lowercase(), note.id.clone());
+    }
+
+    let target_weights = tf_idf(&terms[note_id], &document_frequency, terms.len());
+    let target_norm = norm(&target_weights);
+    let target_links = linked_note_ids(target, &by_name);
+    let target_tags: HashSet<&String> = target.tags.iter().collect();
+
+    let mut related: Vec<RelatedNote> = notes
+        .values()
+        .filter(|note| note.id != note_id)
+        .filter_map(|note| {
+            let mut shared_tags: Vec<String> = note.tags.iter().filter(|t| target_tags.contains(t)).cloned().collect();
+            shared_tags.sort();
+            shared_tags.dedup();
+            let note_tags: HashSet<&String> = note.tags.iter().collect();
+            let tag_union = target_tags.union(&note_tags).count();
+            let tag_score = if tag_union == 0 { 0.0 } else { shared_tags.len() as f64 / tag_union as f64 };
+
+            let linked = target_links.contains(&note.id) || linked_note_ids(note, &by_name).contains(note_id);
+
+            let weights = tf_idf(&terms[note.id.as_str()], &document_frequency, terms.len());
+            let mut contributions: Vec<(&String, f64)> = target_weights
+                .iter()
+                .filter_map(|(term, w)| weights.get(term).map(|other| (term, w * other)))
+                .collect();
+            let dot: f64 = contributions.iter().map(|(_, c)| c).sum();
+            let denominator = target_norm * norm(&weights);
+            let term_score = if denominator == 0.0 { 0.0 } else { dot / denominator };
+            contributions.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+            let score = TAG_WEIGHT * tag_score + if linked { LINK_WEIGHT } else { 0.0 } + TERM_WEIGHT * term_score;
+            (score > 0.0).then(|| RelatedNote {
+                note_id: note.id.clone(),
+                title: note.title.clone(),
+                score,
+                shared_tags,
+                linked,
+                shared_terms: contributions.into_iter().take(5).map(|(term, _)| term.clone()).collect(),
+            })
+        })
+        .collect();
+
+    related.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    related.truncate(limit);
+    related
+}
+
+/// Notes related to `note_id` by shared tags, links between them and the words
+/// they have in common, best first. Everything is computed locally.
+#[tauri::command]
+pub async fn get_related_notes(
+    note_id: String,
+    limit: Option<usize>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<Vec<RelatedNote>, String> {
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    // The index only saves counting; without it every note is counted here
+    let indexed_terms = database::initialize_database(&notes_dir)
+        .and_then(|db| db.get_all_note_terms())
+        .unwrap_or_else(|e| {
+            log_warn!("RELATED", "Counting terms without the index: {}", e);
+            HashMap::new()
+        });
+
+    let notes_lock = notes.lock().await;
+    if !notes_lock.contains_key(&note_id) {
+        return Err(format!("Note {} not found", note_id));
+    }
+    Ok(related_notes(&note_id, &notes_lock, indexed_terms, limit.unwrap_or(DEFAULT_RELATED_LIMIT)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, title: &str, content: &str, tags: &[&str]) -> Note {
+        Note {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            position: None,
+            icon: None,
+        }
+    }
+
+    fn vault(notes: Vec<Note>) -> HashMap<String, Note> {
+        notes.into_iter().map(|n| (n.id.clone(), n)).collect()
+    }
+
+    #[test]
+    fn test_term_counts_skip_common_words() {
+        let counts = term_counts("The Rust borrow checker and the rust compiler, 2024 in it");
+        assert_eq!(counts[0], ("rust".to_string(), 2));
+        let terms: Vec<&str> = counts.iter().map(|(t, _)| t.as_str()).collect();
+        assert!(terms.contains(&"borrow"));
+        assert!(!terms.contains(&"the"));
+        assert!(!terms.contains(&"2024"));
+        assert!(!terms.contains(&"in"));
+    }
+
+    #[test]
+    fn test_shared_terms_rank_notes() {
+        let notes = vault(vec![
+            note("sourdough", "Sourdough", "Feed the starter, fold the dough, proof overnight.", &[]),
+            note("bagels", "Bagels", "Boil the dough after an overnight proof in the fridge.", &[]),
+            note("taxes", "Taxes", "Receipts and invoices for the accountant.", &[]),
+        ]);
+        let related = related_notes("sourdough", &notes, HashMap::new(), 5);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].note_id, "bagels");
+        assert!(related[0].shared_terms.contains(&"dough".to_string()));
+    }
+
+    #[test]
+    fn test_tags_and_links_count() {
+        let notes = vault(vec![
+            note("plan", "Plan", "Nothing in common here.", &["work", "q3"]),
+            note("review", "Review", "Unrelated words entirely.", &["work"]),
+            note("notes", "Meeting Notes", "See [[Plan]] for details.", &[]),
+            note("other", "Other", "Something else.", &["home"]),
+        ]);
+        let related = related_notes("plan", &notes, HashMap::new(), 5);
+        let ids: Vec<&str> = related.iter().map(|r| r.note_id.as_str()).collect();
+        assert_eq!(ids, ["notes", "review"]);
+        assert!(related[0].linked);
+        assert_eq!(related[1].shared_tags, ["work"]);
+        assert!((related[1].score - 0.5).abs() < 1e-9);
+    }
+}
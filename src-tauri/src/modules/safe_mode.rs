@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::log_warn;
+
+/// Command-line flag that starts Blink in safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+/// Environment variable that does the same, for launchers that can't pass arguments
+pub const SAFE_MODE_ENV: &str = "BLINK_SAFE_MODE";
+
+/// Set once in `run()`, before anything it turns off has started
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the flag or a truthy environment value asks for safe mode
+pub fn wants_safe_mode<I, S>(args: I, env: Option<&str>) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let from_env = env
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    from_env || args.into_iter().any(|arg| arg.as_ref() == SAFE_MODE_FLAG)
+}
+
+/// Read the launch arguments and environment. In safe mode global shortcuts
+/// aren't registered, detached windows aren't restored and the config watcher
+/// doesn't start, so a crash in one of them can't keep the notes out of reach.
+pub fn init_safe_mode() -> bool {
+    let enabled = wants_safe_mode(std::env::args().skip(1), std::env::var(SAFE_MODE_ENV).ok().as_deref());
+    SAFE_MODE.store(enabled, Ordering::SeqCst);
+    if enabled {
+        log_warn!("STARTUP", "Safe mode: shortcuts, window restore and the config watcher are off");
+    }
+    enabled
+}
+
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// Whether this launch is in safe mode, so the frontend can say so
+#[tauri::command]
+pub async fn get_safe_mode() -> Result<bool, String> {
+    Ok(is_safe_mode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wants_safe_mode() {
+        assert!(!wants_safe_mode(Vec::<String>::new(), None));
+        assert!(wants_safe_mode(["--safe-mode"], None));
+        assert!(!wants_safe_mode(["--safe-mode-later", "safe-mode"], None));
+        assert!(wants_safe_mode(Vec::<String>::new(), Some("1")));
+        assert!(wants_safe_mode(Vec::<String>::new(), Some(" TRUE ")));
+        assert!(!wants_safe_mode(Vec::<String>::new(), Some("0")));
+        assert!(!wants_safe_mode(Vec::<String>::new(), Some("")));
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::modules::attachments::{store_attachment, StoredAttachment};
+use crate::modules::commands::{append_to_note, insert_generated_note};
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::ocr::spawn_attachment_ocr;
+use crate::modules::pasted_images::optimize_image;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::CreateNoteRequest;
+use crate::types::window::{ConfigState, NotesState};
+use crate::VaultLockState;
+use crate::{log_error, log_info};
+
+/// What to capture. Region and window are picked interactively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureMode {
+    Region,
+    Window,
+    Full,
+}
+
+/// A screenshot stored and linked from a note
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenCapture {
+    pub note_id: String,
+    /// True when a note was created for it
+    pub created_note: bool,
+    pub markdown: String,
+    pub attachment: StoredAttachment,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Arguments for macOS `screencapture` writing a PNG to `path`, without the shutter sound
+pub fn screencapture_args(mode: CaptureMode, path: &Path) -> Vec<String> {
+    let mut args: Vec<String> = match mode {
+        CaptureMode::Region => vec!["-i".into(), "-s".into()],
+        CaptureMode::Window => vec!["-i".into(), "-w".into()],
+        CaptureMode::Full => Vec::new(),
+    };
+    args.extend(["-x".into(), "-t".into(), "png".into()]);
+    args.push(path.to_string_lossy().to_string());
+    args
+}
+
+/// Run `screencapture` and return the PNG, or `None` when the user pressed
+/// Escape before picking anything
+#[cfg(target_os = "macos")]
+fn run_screencapture(mode: CaptureMode, path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let status = std::process::Command::new("/usr/sbin/screencapture")
+        .args(screencapture_args(mode, path))
+        .status()
+        .map_err(|e| format!("Failed to run screencapture: {}", e))?;
+    // A cancelled selection exits without writing the file, sometimes with a failure status
+    if !path.exists() {
+        return match mode {
+            CaptureMode::Full => Err(format!(
+                "screencapture wrote nothing ({}); check Screen Recording permission for Blink",
+                status
+            )),
+            _ => Ok(None),
+        };
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read screenshot: {}", e));
+    let _ = std::fs::remove_file(path);
+    bytes.map(Some)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run_screencapture(_mode: CaptureMode, _path: &Path) -> Result<Option<Vec<u8>>, String> {
+    Err("Screen capture is only available on macOS".to_string())
+}
+
+fn capture_path() -> PathBuf {
+    std::env::temp_dir().join(format!("blink-capture-{}.png", uuid::Uuid::new_v4()))
+}
+
+/// Capture the screen and put the image in `note_id`, or in a new note when
+/// there is none. Returns `None` when the capture was cancelled.
+pub(crate) async fn capture_to_note(
+    app: &AppHandle,
+    mode: CaptureMode,
+    note_id: Option<String>,
+    notes: &NotesState,
+    config: &ConfigState,
+    modified_tracker: &ModifiedStateTracker,
+) -> Result<Option<ScreenCapture>, String> {
+    if let Some(note_id) = &note_id {
+        if !notes.lock().await.contains_key(note_id) {
+            return Err(format!("Note not found: {}", note_id));
+        }
+    }
+    let (notes_dir, settings) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.attachments.clone())
+    };
+
+    let path = capture_path();
+    let Some(png) = tauri::async_runtime::spawn_blocking(move || run_screencapture(mode, &path))
+        .await
+        .map_err(|e| format!("Screen capture stopped unexpectedly: {}", e))??
+    else {
+        log_info!("CAPTURE", "Screen capture cancelled");
+        return Ok(None);
+    };
+
+    let (data, extension, width, height) =
+        optimize_image(&png, "image/png", settings.max_image_dimension, settings.jpeg_quality)?;
+    let attachment = store_attachment(&notes_dir, &data, extension)?;
+
+    let (note_id, created_note, markdown) = match note_id {
+        Some(note_id) => {
+            let markdown = format!("![Screenshot]({})", attachment.relative_path);
+            append_to_note(app, &note_id, &markdown, notes, config, modified_tracker).await?;
+            (note_id, false, markdown)
+        }
+        None => {
+            let markdown = format!("![Screenshot]({})", attachment.relative_path);
+            let request = CreateNoteRequest {
+                title: format!("Screenshot {}", chrono::Local::now().format("%Y-%m-%d at %H.%M.%S")),
+                content: format!("{}\n", markdown),
+                tags: Vec::new(),
+            };
+            let note = insert_generated_note(app, request, None, notes, config, modified_tracker).await?;
+            (note.id, true, markdown)
+        }
+    };
+    if settings.ocr && !attachment.deduplicated {
+        spawn_attachment_ocr(app.clone(), notes_dir, note_id.clone(), attachment.relative_path.clone());
+    }
+
+    log_info!("CAPTURE", "Captured {}x{} {:?} screenshot into {}", width, height, mode, note_id);
+    Ok(Some(ScreenCapture { note_id, created_note, markdown, attachment, width, height }))
+}
+
+/// Take a screenshot with macOS `screencapture`, store it as an attachment and
+/// append it to `note_id`, or to a new note when none is given. Resolves to
+/// `null` when the user cancels the selection.
+#[tauri::command]
+pub async fn capture_screen_to_note(
+    window: tauri::Window,
+    app: AppHandle,
+    mode: CaptureMode,
+    note_id: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Option<ScreenCapture>, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    capture_to_note(&app, mode, note_id, &notes, &config, &modified_tracker).await
+}
+
+/// Hyperkey+S: capture a region into a new note and show it
+pub fn capture_region_from_shortcut(app: &AppHandle) {
+    use crate::modules::windows::reveal_note_in_app;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let config = app.state::<ConfigState>();
+        if let Err(e) = ensure_vault_writable(&config, &app.state::<VaultLockState>()).await {
+            log_error!("CAPTURE", "Not capturing: {}", e);
+            return;
+        }
+        let result = capture_to_note(
+            &app,
+            CaptureMode::Region,
+            None,
+            &app.state::<NotesState>(),
+            &config,
+            &app.state::<ModifiedStateTracker>(),
+        )
+        .await;
+        match result {
+            Ok(Some(capture)) => {
+                if let Err(e) = reveal_note_in_app(&app, &capture.note_id).await {
+                    log_error!("CAPTURE", "Failed to show screenshot note: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log_error!("CAPTURE", "Screen capture failed: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screencapture_args() {
+        let path = Path::new("/tmp/shot.png");
+        assert_eq!(screencapture_args(CaptureMode::Region, path), ["-i", "-s", "-x", "-t", "png", "/tmp/shot.png"]);
+        assert_eq!(screencapture_args(CaptureMode::Window, path), ["-i", "-w", "-x", "-t", "png", "/tmp/shot.png"]);
+        assert_eq!(screencapture_args(CaptureMode::Full, path), ["-x", "-t", "png", "/tmp/shot.png"]);
+    }
+}
@@ -0,0 +1,45 @@
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::modules::about::ABOUT_WINDOW_LABEL;
+use crate::modules::note_palette::PALETTE_WINDOW_LABEL;
+use crate::modules::reading_mode::is_reading_window;
+use crate::modules::vault_picker::VAULT_PICKER_WINDOW_LABEL;
+use crate::log_info;
+
+/// Label of the settings window
+pub const SETTINGS_WINDOW_LABEL: &str = "settings";
+
+/// App-level windows that are not notes. They never enter detached window
+/// bookkeeping and are left alone by the hover toggle.
+pub fn is_utility_window(label: &str) -> bool {
+    [SETTINGS_WINDOW_LABEL, ABOUT_WINDOW_LABEL, PALETTE_WINDOW_LABEL, VAULT_PICKER_WINDOW_LABEL].contains(&label) || is_reading_window(label)
+}
+
+/// Show the settings window. There is only ever one; later calls focus it.
+#[tauri::command]
+pub async fn open_settings_window(app: AppHandle, section: Option<String>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(SETTINGS_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        window.show().map_err(|e| format!("Failed to show settings window: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus settings window: {}", e))?;
+        return Ok(());
+    }
+
+    let mut url = "index.html?settings=true".to_string();
+    if let Some(section) = section {
+        url.push_str(&format!("&section={}", urlencoding::encode(&section)));
+    }
+
+    WebviewWindowBuilder::new(&app, SETTINGS_WINDOW_LABEL, WebviewUrl::App(url.into()))
+        .title("Settings")
+        .inner_size(860.0, 620.0)
+        .min_inner_size(640.0, 420.0)
+        .center()
+        .decorations(true)
+        .resizable(true)
+        .build()
+        .map_err(|e| format!("Failed to create settings window: {}", e))?;
+
+    log_info!("SETTINGS", "Opened settings window");
+    Ok(())
+}
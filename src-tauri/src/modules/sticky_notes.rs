@@ -0,0 +1,132 @@
+use tauri::{AppHandle, State};
+
+use crate::modules::commands::insert_generated_note;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::modules::workspace_settings::{load_workspace_settings, save_workspace_settings};
+use crate::services::window_service::WindowService;
+use crate::types::note::{CreateNoteRequest, Note};
+use crate::types::window::{ConfigState, CreateDetachedWindowRequest, DetachedWindow, NotesState};
+use crate::{log_info, VaultLockState};
+
+/// Tag that marks a note as a sticky; such notes stay out of the notes list
+pub const STICKY_TAG: &str = "sticky";
+
+/// Sticky windows keep this size and can't be resized
+pub const STICKY_SIZE: (f64, f64) = (240.0, 240.0);
+
+/// Paper colors, in the order new stickies cycle through them
+pub const PAPER_COLORS: &[(&str, &str)] = &[
+    ("yellow", "#fff59d"),
+    ("pink", "#f8bbd0"),
+    ("blue", "#b3e5fc"),
+    ("green", "#c8e6c9"),
+    ("orange", "#ffe0b2"),
+    ("purple", "#e1bee7"),
+];
+
+const STICKY_TITLE_MAX_CHARS: usize = 40;
+
+pub fn is_sticky(note: &Note) -> bool {
+    note.tags.iter().any(|tag| tag.eq_ignore_ascii_case(STICKY_TAG))
+}
+
+/// The hex value of a named paper color
+pub fn paper_color(name: &str) -> Option<&'static str> {
+    PAPER_COLORS
+        .iter()
+        .find(|(color, _)| color.eq_ignore_ascii_case(name.trim()))
+        .map(|(_, hex)| *hex)
+}
+
+/// The color for the next sticky when `existing` stickies are already out
+pub fn next_paper_color(existing: usize) -> &'static str {
+    PAPER_COLORS[existing % PAPER_COLORS.len()].0
+}
+
+/// Window CSS that turns a note window into colored paper
+pub fn sticky_css(hex: &str) -> String {
+    format!("html, body, #root {{ background: {} !important; color: #3a3a3a !important; }}", hex)
+}
+
+/// A sticky is titled by its first line of text
+pub fn sticky_title(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("Sticky");
+    let mut title: String = line.trim_start_matches('#').trim().chars().take(STICKY_TITLE_MAX_CHARS).collect();
+    if line.chars().count() > STICKY_TITLE_MAX_CHARS {
+        title.push('…');
+    }
+    title
+}
+
+/// Create a note tagged `sticky` and open it as a small always-on-top window on
+/// colored paper. Without `color` the next color in the cycle is used.
+#[tauri::command]
+pub async fn create_sticky(
+    window: tauri::Window,
+    app: AppHandle,
+    color: Option<String>,
+    text: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+    window_service: State<'_, WindowService>,
+) -> Result<DetachedWindow, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let color = match color {
+        Some(color) => color.trim().to_lowercase(),
+        None => next_paper_color(notes.lock().await.values().filter(|n| is_sticky(n)).count()).to_string(),
+    };
+    let hex = paper_color(&color).ok_or_else(|| {
+        let names: Vec<&str> = PAPER_COLORS.iter().map(|(name, _)| *name).collect();
+        format!("Unknown paper color {}; use one of {}", color, names.join(", "))
+    })?;
+
+    let text = text.unwrap_or_default();
+    let note = insert_generated_note(
+        &app,
+        CreateNoteRequest { title: sticky_title(&text), content: text, tags: vec![STICKY_TAG.to_string()] },
+        None,
+        &notes,
+        &config,
+        &modified_tracker,
+    )
+    .await?;
+
+    // The paper color is the note's window CSS, so it survives reopening
+    let notes_dir = get_configured_notes_directory(&*config.lock().await)?;
+    let mut settings = load_workspace_settings(&notes_dir);
+    settings.note_styles.insert(note.id.clone(), sticky_css(hex));
+    save_workspace_settings(&notes_dir, &settings)?;
+
+    let request = CreateDetachedWindowRequest { note_id: note.id.clone(), x: None, y: None, width: None, height: None };
+    let detached = window_service.create_detached_window(request).await?;
+    log_info!("STICKY", "Created {} sticky {}", color, note.id);
+    Ok(detached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paper_colors_cycle() {
+        assert_eq!(next_paper_color(0), "yellow");
+        assert_eq!(next_paper_color(1), "pink");
+        assert_eq!(next_paper_color(PAPER_COLORS.len()), "yellow");
+        assert_eq!(paper_color(" Blue "), Some("#b3e5fc"));
+        assert_eq!(paper_color("chartreuse"), None);
+    }
+
+    #[test]
+    fn test_sticky_title() {
+        assert_eq!(sticky_title(""), "Sticky");
+        assert_eq!(sticky_title("\n  # Call Sam back  \nabout the lease"), "Call Sam back");
+        assert_eq!(sticky_title(&"x".repeat(50)), format!("{}…", "x".repeat(40)));
+    }
+}
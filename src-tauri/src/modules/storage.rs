@@ -8,7 +8,12 @@ use crate::types::{
     config::AppConfig,
     window::{DetachedWindow, ConfigState, DetachedWindowsState},
 };
+use crate::modules::config_reload::{apply_config, validate_config};
+use crate::modules::settings_window::is_utility_window;
+use crate::modules::window_events::{record_window_state, replay_window_state};
+use crate::modules::safe_mode::is_safe_mode;
 use crate::{log_debug, log_info};
+use crate::modules::window_capabilities::{authorize, Capability};
 
 /// Save notes to disk as JSON
 pub async fn save_notes_to_disk(notes: &HashMap<String, Note>) -> Result<(), String> {
@@ -79,37 +84,52 @@ pub async fn load_config_from_disk() -> Result<AppConfig, String> {
     Ok(config)
 }
 
-/// Save detached windows state to disk
+/// Record detached window changes on disk. Each save appends what changed to
+/// the window event log rather than rewriting the whole state.
 pub async fn save_detached_windows_to_disk(windows: &HashMap<String, DetachedWindow>) -> Result<(), String> {
+    // Windows from a safe-mode session would replace the layout it didn't restore
+    if is_safe_mode() {
+        return Ok(());
+    }
     let notes_dir = get_notes_directory()?;
     fs::create_dir_all(&notes_dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
     
-    let windows_file = notes_dir.join("detached_windows.json");
-    let windows_json = serde_json::to_string_pretty(windows)
-        .map_err(|e| format!("Failed to serialize detached windows: {}", e))?;
-    
-    fs::write(windows_file, windows_json)
-        .map_err(|e| format!("Failed to write detached windows to disk: {}", e))?;
-    
-    Ok(())
+    record_window_state(&notes_dir, windows)
 }
 
-/// Load detached windows state from disk
+/// Load detached windows state from disk by replaying the event log onto the last snapshot
 pub async fn load_detached_windows_from_disk() -> Result<HashMap<String, DetachedWindow>, String> {
     let notes_dir = get_notes_directory()?;
-    let windows_file = notes_dir.join("detached_windows.json");
+    let mut windows = replay_window_state(&notes_dir)?;
     
-    if !windows_file.exists() {
-        return Ok(HashMap::new());
-    }
+    // Settings, About and the palette are never tracked as note windows
+    windows.retain(|label, _| !is_utility_window(label));
     
-    let windows_json = fs::read_to_string(windows_file)
-        .map_err(|e| format!("Failed to read detached windows from disk: {}", e))?;
-    
-    let windows: HashMap<String, DetachedWindow> = serde_json::from_str(&windows_json)
-        .map_err(|e| format!("Failed to parse detached windows JSON: {}", e))?;
-    
-    Ok(windows)
+    Ok(migrate_legacy_window_labels(windows))
+}
+
+/// Older builds kept dragged-out windows under their `hybrid-drag-<id>` label.
+/// Re-key those entries as `note-<id>` so every window is found by one label scheme.
+fn migrate_legacy_window_labels(windows: HashMap<String, DetachedWindow>) -> HashMap<String, DetachedWindow> {
+    let mut migrated = HashMap::with_capacity(windows.len());
+    let mut legacy = Vec::new();
+    for (label, window) in windows {
+        if label.starts_with("hybrid-drag-") {
+            legacy.push(window);
+        } else {
+            migrated.insert(label, window);
+        }
+    }
+    for mut window in legacy {
+        let label = format!("note-{}", window.note_id);
+        if migrated.contains_key(&label) {
+            continue;
+        }
+        log_info!("STORAGE", "Migrated window {} to {}", window.window_label, label);
+        window.window_label = label.clone();
+        migrated.insert(label, window);
+    }
+    migrated
 }
 
 /// Get the notes directory path
@@ -161,13 +181,14 @@ pub async fn get_config(config: State<'_, ConfigState>) -> Result<AppConfig, Str
 
 #[tauri::command]
 pub async fn update_config(
+    window: tauri::Window,
+    app: tauri::AppHandle,
     new_config: AppConfig,
-    config: State<'_, ConfigState>,
 ) -> Result<AppConfig, String> {
-    let mut config_lock = config.lock().await;
-    *config_lock = new_config.clone();
+    authorize(&window, Capability::WriteConfig)?;
+    validate_config(&new_config)?;
+    apply_config(&app, new_config.clone(), "from settings").await?;
     save_config_to_disk(&new_config).await?;
-    log_info!("CONFIG", "Configuration updated");
     Ok(new_config) // Return the updated config instead of ()
 }
 
@@ -178,7 +199,7 @@ pub async fn get_detached_windows(
     let windows_lock = windows.lock().await;
     log_debug!("GET_DETACHED_WINDOWS", "Returning {} windows to frontend", windows_lock.len());
     
-    // Filter out hybrid-drag windows - only return actual detached note windows
+    // Only note windows are tracked, but guard against anything else in the file
     let filtered_windows: HashMap<String, DetachedWindow> = windows_lock
         .iter()
         .filter(|(window_label, _)| window_label.starts_with("note-"))
@@ -186,4 +207,42 @@ pub async fn get_detached_windows(
         .collect();
     
     Ok(filtered_windows)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(label: &str, note_id: &str, x: f64) -> (String, DetachedWindow) {
+        (label.to_string(), DetachedWindow {
+            note_id: note_id.to_string(),
+            window_label: label.to_string(),
+            position: (x, 100.0),
+            size: (400.0, 300.0),
+            always_on_top: false,
+            opacity: 1.0,
+            is_shaded: false,
+            original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
+        })
+    }
+
+    #[test]
+    fn test_hybrid_drag_labels_are_rekeyed() {
+        let windows = HashMap::from([
+            window("hybrid-drag-groceries", "groceries", 10.0),
+            window("note-plan", "plan", 20.0),
+            // Both labels for one note: the canonical entry wins
+            window("hybrid-drag-plan", "plan", 30.0),
+        ]);
+
+        let migrated = migrate_legacy_window_labels(windows);
+        let mut labels: Vec<&String> = migrated.keys().collect();
+        labels.sort();
+        assert_eq!(labels, ["note-groceries", "note-plan"]);
+        assert_eq!(migrated["note-groceries"].window_label, "note-groceries");
+        assert_eq!(migrated["note-groceries"].position, (10.0, 100.0));
+        assert_eq!(migrated["note-plan"].position, (20.0, 100.0));
+    }
+}
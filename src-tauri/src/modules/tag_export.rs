@@ -0,0 +1,484 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::modules::archive_policy::has_tag;
+use crate::modules::file_operations::allowed_roots;
+use crate::modules::metadata_export::{is_attachment, is_remote};
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::utils::paths::{ensure_within, normalize_relative};
+use crate::log_info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Md,
+    Html,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Md => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+/// What `export_tagged_notes` wrote
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagExportReport {
+    /// File names of the exported notes, one per note
+    pub files: Vec<String>,
+    /// Attachments copied next to them, by vault-relative path
+    pub attachments: Vec<String>,
+    /// Links between exported notes, pointed at the exported files
+    pub rewritten_links: usize,
+    /// Links to notes left out of the export, replaced by their text
+    pub dropped_links: usize,
+}
+
+/// A note's content with its links fixed up for the exported set
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewrittenNote {
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub rewritten_links: usize,
+    pub dropped_links: usize,
+}
+
+/// Where links to other notes can point: lowercased titles and IDs to IDs
+pub fn note_names<'a>(notes: impl IntoIterator<Item = &'a Note>) -> HashMap<String, String> {
+    let notes: Vec<&Note> = notes.into_iter().collect();
+    let mut by_name = HashMap::new();
+    for note in &notes {
+        by_name.insert(note.title.to_lowercase(), note.id.clone());
+    }
+    // IDs win over titles, since a file name is unambiguous
+    for note in &notes {
+        by_name.insert(note.id.to_lowercase(), note.id.clone());
+    }
+    by_name
+}
+
+fn split_fragment(target: &str) -> (&str, &str) {
+    match target.find('#') {
+        Some(i) => target.split_at(i),
+        None => (target, ""),
+    }
+}
+
+/// Vault-relative path of an attachment link. Notes in folders link with
+/// `../` up to the vault root, which the flat export doesn't need.
+fn vault_relative(target: &str) -> Option<String> {
+    let path = target.split(['?', '#']).next().unwrap_or(target);
+    let path = urlencoding::decode(path).ok()?;
+    let mut rest: &str = &path;
+    while let Some(stripped) = rest.strip_prefix("../") {
+        rest = stripped;
+    }
+    let normalized = normalize_relative(rest).ok()?;
+    let parts: Vec<String> = normalized
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Point links between exported notes at their exported files and replace
+/// links to any other note with their text. Remote links are kept; attachment
+/// links are made vault-relative and listed for copying. Fenced code is left alone.
+pub fn rewrite_links(
+    content: &str,
+    by_name: &HashMap<String, String>,
+    exported: &BTreeSet<String>,
+    format: ExportFormat,
+) -> RewrittenNote {
+    let markdown_link = Regex::new(r#"(!?)\[([^\]]*)\]\(\s*<?([^)\s>]+)>?(\s+"[^"]*")?\s*\)"#).unwrap();
+    let wiki_link = Regex::new(r"(!?)\[\[([^\]|#]+)(#[^\]|]*)?(?:\|([^\]]*))?\]\]").unwrap();
+
+    let mut result = RewrittenNote {
+        content: String::with_capacity(content.len()),
+        attachments: Vec::new(),
+        rewritten_links: 0,
+        dropped_links: 0,
+    };
+    let resolve = |name: &str| -> Option<String> {
+        let stem = Path::new(name).file_stem().map(|s| s.to_string_lossy().to_lowercase());
+        stem.and_then(|s| by_name.get(&s))
+            .or_else(|| by_name.get(&name.to_lowercase()))
+            .filter(|id| exported.contains(*id))
+            .cloned()
+    };
+    let exported_file = |id: &str, fragment: &str| format!("{}.{}{}", id, format.extension(), fragment);
+
+    let mut in_code = false;
+    for line in content.split_inclusive('\n') {
+        let fence = line.trim_start();
+        if fence.starts_with("```") || fence.starts_with("~~~") {
+            in_code = !in_code;
+        }
+        if in_code || fence.starts_with("```") || fence.starts_with("~~~") {
+            result.content.push_str(line);
+            continue;
+        }
+
+        let line = markdown_link.replace_all(line, |caps: &Captures| {
+            let (bang, text, target) = (&caps[1], &caps[2], &caps[3]);
+            let title = caps.get(4).map_or("", |m| m.as_str());
+            if is_remote(target) {
+                return caps[0].to_string();
+            }
+            if bang == "!" || is_attachment(target) {
+                return match vault_relative(target) {
+                    Some(relative) => {
+                        if !result.attachments.contains(&relative) {
+                            result.attachments.push(relative.clone());
+                        }
+                        format!("{}[{}]({}{})", bang, text, relative.replace(' ', "%20"), title)
+                    }
+                    None => caps[0].to_string(),
+                };
+            }
+            let (name, fragment) = split_fragment(target);
+            let name = urlencoding::decode(name).map(|n| n.to_string()).unwrap_or_else(|_| name.to_string());
+            match resolve(&name) {
+                Some(id) => {
+                    result.rewritten_links += 1;
+                    format!("[{}]({}{})", text, exported_file(&id, fragment), title)
+                }
+                None => {
+                    result.dropped_links += 1;
+                    text.to_string()
+                }
+            }
+        });
+
+        let line = wiki_link.replace_all(&line, |caps: &Captures| {
+            // Embeds are resolved by file name anywhere in the vault; leave them be
+            if &caps[1] == "!" || is_attachment(&caps[2]) {
+                return caps[0].to_string();
+            }
+            let name = caps[2].trim();
+            let fragment = caps.get(3).map_or("", |m| m.as_str());
+            let text = caps.get(4).map_or(name, |m| m.as_str().trim());
+            match resolve(name) {
+                Some(id) => {
+                    result.rewritten_links += 1;
+                    format!("[{}]({})", text, exported_file(&id, fragment))
+                }
+                None => {
+                    result.dropped_links += 1;
+                    text.to_string()
+                }
+            }
+        });
+        result.content.push_str(&line);
+    }
+    result
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Inline markdown: code spans, images, links, bold, italics and strikethrough
+fn inline_html(text: &str) -> String {
+    let image = Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap();
+    let link = Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap();
+    let bold = Regex::new(r"\*\*([^*]+)\*\*|__([^_]+)__").unwrap();
+    let italic = Regex::new(r"\*([^*]+)\*|\b_([^_]+)_\b").unwrap();
+    let strike = Regex::new(r"~~([^~]+)~~").unwrap();
+
+    // Odd segments between backticks are code spans and stay literal
+    text.split('`')
+        .enumerate()
+        .map(|(i, segment)| {
+            let escaped = escape_html(segment);
+            if i % 2 == 1 {
+                return format!("<code>{}</code>", escaped);
+            }
+            let html = image.replace_all(&escaped, r#"<img src="$2" alt="$1">"#);
+            let html = link.replace_all(&html, r#"<a href="$2">$1</a>"#);
+            let html = bold.replace_all(&html, "<strong>$1$2</strong>");
+            let html = italic.replace_all(&html, "<em>$1$2</em>");
+            strike.replace_all(&html, "<del>$1</del>").to_string()
+        })
+        .collect()
+}
+
+/// The block being collected while rendering
+enum Block {
+    None,
+    Paragraph(Vec<String>),
+    Quote(Vec<String>),
+    List(&'static str, Vec<String>),
+}
+
+fn flush(block: &mut Block, html: &mut String) {
+    match std::mem::replace(block, Block::None) {
+        Block::None => {}
+        Block::Paragraph(lines) => html.push_str(&format!("<p>{}</p>\n", inline_html(&lines.join("\n")))),
+        Block::Quote(lines) => {
+            html.push_str(&format!("<blockquote><p>{}</p></blockquote>\n", inline_html(&lines.join("\n"))))
+        }
+        Block::List(tag, items) => {
+            html.push_str(&format!("<{}>\n", tag));
+            for item in items {
+                html.push_str(&format!("<li>{}</li>\n", item));
+            }
+            html.push_str(&format!("</{}>\n", tag));
+        }
+    }
+}
+
+/// A list item's tag and rendered content, if the line is one
+fn list_item(line: &str) -> Option<(&'static str, String)> {
+    let (tag, rest) = if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).or_else(|| line.strip_prefix("+ ")) {
+        ("ul", rest)
+    } else {
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        let rest = line[digits..].strip_prefix(". ").filter(|_| digits > 0)?;
+        ("ol", rest)
+    };
+    let item = if let Some(task) = rest.strip_prefix("[ ] ") {
+        format!(r#"<input type="checkbox" disabled> {}"#, inline_html(task))
+    } else if let Some(task) = rest.strip_prefix("[x] ").or_else(|| rest.strip_prefix("[X] ")) {
+        format!(r#"<input type="checkbox" disabled checked> {}"#, inline_html(task))
+    } else {
+        inline_html(rest)
+    };
+    Some((tag, item))
+}
+
+/// Render the markdown notes are written in: headings, paragraphs, lists,
+/// task lists, quotes, rules and fenced code, with inline formatting
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut block = Block::None;
+    let mut code: Option<String> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        let is_fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        if let Some(mut text) = code.take() {
+            if is_fence {
+                html.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&text)));
+            } else {
+                text.push_str(line);
+                text.push('\n');
+                code = Some(text);
+            }
+            continue;
+        }
+        if is_fence {
+            flush(&mut block, &mut html);
+            code = Some(String::new());
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut block, &mut html);
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            flush(&mut block, &mut html);
+            html.push_str(&format!("<h{0}>{1}</h{0}>\n", hashes, inline_html(trimmed[hashes..].trim())));
+        } else if matches!(trimmed, "---" | "***" | "___") {
+            flush(&mut block, &mut html);
+            html.push_str("<hr>\n");
+        } else if let Some((tag, item)) = list_item(trimmed) {
+            match &mut block {
+                Block::List(current, items) if *current == tag => items.push(item),
+                _ => {
+                    flush(&mut block, &mut html);
+                    block = Block::List(tag, vec![item]);
+                }
+            }
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let quote = quote.trim_start().to_string();
+            match &mut block {
+                Block::Quote(lines) => lines.push(quote),
+                _ => {
+                    flush(&mut block, &mut html);
+                    block = Block::Quote(vec![quote]);
+                }
+            }
+        } else {
+            match &mut block {
+                Block::Paragraph(lines) => lines.push(trimmed.to_string()),
+                _ => {
+                    flush(&mut block, &mut html);
+                    block = Block::Paragraph(vec![trimmed.to_string()]);
+                }
+            }
+        }
+    }
+    flush(&mut block, &mut html);
+    // An unclosed fence runs to the end of the note
+    if let Some(text) = code {
+        html.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&text)));
+    }
+    html
+}
+
+/// A standalone page for one exported note
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font:16px/1.6 -apple-system,sans-serif;max-width:42rem;margin:2rem auto;padding:0 1rem}}\
+         pre{{overflow:auto;background:#f5f5f5;padding:.75rem}}img{{max-width:100%}}</style>\n\
+         </head>\n<body>\n<article>\n{}</article>\n</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+/// Export exactly the notes tagged `tag` to `output_dir`, one `<id>.md` or
+/// `<id>.html` each. Links between them are pointed at the exported files and
+/// links to notes outside the set are reduced to their text, so the folder can
+/// be shared on its own. Attachments they embed are copied alongside.
+#[tauri::command]
+pub async fn export_tagged_notes(
+    window: tauri::Window,
+    tag: String,
+    output_dir: String,
+    format: ExportFormat,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+) -> Result<TagExportReport, String> {
+    authorize(&window, Capability::ManageVault)?;
+    if tag.trim().trim_start_matches('#').is_empty() {
+        return Err("Tag is empty".to_string());
+    }
+    let (notes_dir, output_dir) = {
+        let config_lock = config.lock().await;
+        let output_dir = ensure_within(Path::new(&output_dir), &allowed_roots(&config_lock))?;
+        (get_configured_notes_directory(&config_lock)?, output_dir)
+    };
+    fs::create_dir_all(&output_dir).map_err(|e| format!("Failed to create {}: {}", output_dir.display(), e))?;
+
+    let notes_lock = notes.lock().await;
+    let by_name = note_names(notes_lock.values());
+    let mut tagged: Vec<&Note> = notes_lock.values().filter(|note| has_tag(note, &tag)).collect();
+    tagged.sort_by(|a, b| a.id.cmp(&b.id));
+    let exported: BTreeSet<String> = tagged.iter().map(|note| note.id.clone()).collect();
+
+    let mut report = TagExportReport::default();
+    let mut attachments: BTreeSet<String> = BTreeSet::new();
+    for note in tagged {
+        let rewritten = rewrite_links(&note.content, &by_name, &exported, format);
+        let contents = match format {
+            ExportFormat::Md => rewritten.content,
+            ExportFormat::Html => html_page(&note.display_title(), &markdown_to_html(&rewritten.content)),
+        };
+        let file_name = format!("{}.{}", note.id, format.extension());
+        fs::write(output_dir.join(&file_name), contents).map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+
+        report.files.push(file_name);
+        report.rewritten_links += rewritten.rewritten_links;
+        report.dropped_links += rewritten.dropped_links;
+        attachments.extend(rewritten.attachments);
+    }
+    drop(notes_lock);
+
+    for relative in attachments {
+        let source: PathBuf = notes_dir.join(&relative);
+        if !source.is_file() {
+            continue;
+        }
+        let target = output_dir.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(&source, &target).map_err(|e| format!("Failed to copy {}: {}", relative, e))?;
+        report.attachments.push(relative);
+    }
+
+    log_info!(
+        "FILE_EXPORT",
+        "Exported {} notes tagged {} to {} ({} links rewritten, {} dropped, {} attachments)",
+        report.files.len(),
+        tag,
+        output_dir.display(),
+        report.rewritten_links,
+        report.dropped_links,
+        report.attachments.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, title: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: Vec::new(),
+            position: None,
+            icon: None,
+        }
+    }
+
+    fn rewrite(content: &str, format: ExportFormat) -> RewrittenNote {
+        let notes = [note("plan", "Launch Plan"), note("budget", "Budget"), note("private", "Diary")];
+        let exported: BTreeSet<String> = ["plan".to_string(), "budget".to_string()].into();
+        rewrite_links(content, &note_names(&notes), &exported, format)
+    }
+
+    #[test]
+    fn test_links_inside_the_set_are_rewritten() {
+        let rewritten = rewrite("See [the plan](plan.md#dates), [[Budget]] and [[Launch Plan|launch]].", ExportFormat::Html);
+        assert_eq!(
+            rewritten.content,
+            "See [the plan](plan.html#dates), [Budget](budget.html) and [launch](plan.html)."
+        );
+        assert_eq!((rewritten.rewritten_links, rewritten.dropped_links), (3, 0));
+    }
+
+    #[test]
+    fn test_links_outside_the_set_are_dropped() {
+        let rewritten = rewrite("[my diary](private.md), [[Diary]], [[Missing|gone]] and [site](https://example.com)", ExportFormat::Md);
+        assert_eq!(rewritten.content, "my diary, Diary, gone and [site](https://example.com)");
+        assert_eq!(rewritten.dropped_links, 3);
+    }
+
+    #[test]
+    fn test_attachments_become_vault_relative() {
+        let rewritten = rewrite("![chart](../attachments/chart.png)\n```\n[[Diary]]\n```\n", ExportFormat::Md);
+        assert_eq!(rewritten.content, "![chart](attachments/chart.png)\n```\n[[Diary]]\n```\n");
+        assert_eq!(rewritten.attachments, ["attachments/chart.png"]);
+        assert_eq!(rewritten.dropped_links, 0);
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html("# Title\n\nSome **bold** and `<code>`.\n\n- [x] done\n- [see](budget.html)\n\n```\nlet a = 1 < 2;\n```");
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n\
+             <p>Some <strong>bold</strong> and <code>&lt;code&gt;</code>.</p>\n\
+             <ul>\n<li><input type=\"checkbox\" disabled checked> done</li>\n<li><a href=\"budget.html\">see</a></li>\n</ul>\n\
+             <pre><code>let a = 1 &lt; 2;\n</code></pre>\n"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::workspace_settings::{load_workspace_settings, save_workspace_settings};
+use crate::types::window::ConfigState;
+use crate::types::workspace::TagRoutingRule;
+use crate::VaultLockState;
+use crate::log_info;
+use crate::modules::window_capabilities::{authorize, Capability};
+
+/// Clean up a rule's folder into a relative `a/b` path inside the vault
+pub fn normalize_folder(folder: &str) -> Result<String, String> {
+    let parts: Vec<&str> = folder
+        .split(['/', '\\'])
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if parts.is_empty() {
+        return Err("Routing folder cannot be empty".to_string());
+    }
+    if folder.trim_start().starts_with(['/', '\\']) || parts[0].contains(':') {
+        return Err(format!("Routing folder must be relative to the vault: {}", folder));
+    }
+    if parts.iter().any(|p| p.starts_with('.')) {
+        return Err(format!("Routing folder cannot be hidden or leave the vault: {}", folder));
+    }
+    Ok(parts.join("/"))
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Folder for a note with these tags: the first rule whose tag it has
+pub fn route_folder<'a>(rules: &'a [TagRoutingRule], tags: &[String]) -> Option<&'a str> {
+    let tags: Vec<String> = tags.iter().map(|t| normalize_tag(t)).collect();
+    rules
+        .iter()
+        .find(|rule| tags.contains(&normalize_tag(&rule.tag)))
+        .map(|rule| rule.folder.as_str())
+}
+
+/// Where a note should live, relative to the vault root (`None` is the root).
+///
+/// Matching notes go to their rule's folder. A note sitting in a routed folder
+/// whose tag it lost moves back to the root; notes the user filed elsewhere stay put.
+pub fn target_note_dir(rules: &[TagRoutingRule], tags: &[String], current_dir: Option<&str>) -> Option<String> {
+    if let Some(folder) = route_folder(rules, tags) {
+        return Some(folder.to_string());
+    }
+    match current_dir {
+        Some(dir) if rules.iter().any(|rule| rule.folder == dir) => None,
+        other => other.map(|d| d.to_string()),
+    }
+}
+
+/// Get the tag → folder rules of the current vault
+#[tauri::command]
+pub async fn get_tag_routing_rules(config: State<'_, ConfigState>) -> Result<Vec<TagRoutingRule>, String> {
+    let config_lock = config.lock().await;
+    let notes_dir = get_configured_notes_directory(&config_lock)?;
+    Ok(load_workspace_settings(&notes_dir).tag_routing)
+}
+
+/// Replace the tag → folder rules. They take effect the next time a note is saved.
+#[tauri::command]
+pub async fn set_tag_routing_rules(
+    window: tauri::Window,
+    app: AppHandle,
+    rules: Vec<TagRoutingRule>,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Vec<TagRoutingRule>, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let rules = rules
+        .into_iter()
+        .filter(|rule| !normalize_tag(&rule.tag).is_empty())
+        .map(|rule| Ok(TagRoutingRule { tag: rule.tag.trim().to_string(), folder: normalize_folder(&rule.folder)? }))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let mut settings = load_workspace_settings(&notes_dir);
+    settings.tag_routing = rules.clone();
+    save_workspace_settings(&notes_dir, &settings)?;
+    log_info!("WORKSPACE", "Saved {} tag routing rules", rules.len());
+
+    let _ = app.emit("workspace-settings-changed", &settings);
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tag: &str, folder: &str) -> TagRoutingRule {
+        TagRoutingRule { tag: tag.to_string(), folder: folder.to_string() }
+    }
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![rule("work", "work"), rule("#Clients", "work/clients")];
+        assert_eq!(route_folder(&rules, &tags(&["clients", "work"])), Some("work"));
+        assert_eq!(route_folder(&rules, &tags(&["#clients"])), Some("work/clients"));
+        assert_eq!(route_folder(&rules, &tags(&["home"])), None);
+    }
+
+    #[test]
+    fn test_target_dir_keeps_manual_folders() {
+        let rules = vec![rule("work", "work")];
+        assert_eq!(target_note_dir(&rules, &tags(&["work"]), None), Some("work".to_string()));
+        // Lost the tag: leave the routed folder
+        assert_eq!(target_note_dir(&rules, &tags(&[]), Some("work")), None);
+        // Filed by hand somewhere else: stay there
+        assert_eq!(target_note_dir(&rules, &tags(&[]), Some("journal")), Some("journal".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(normalize_folder(" work//clients/ ").unwrap(), "work/clients");
+        assert!(normalize_folder("/etc").is_err());
+        assert!(normalize_folder("../outside").is_err());
+        assert!(normalize_folder(".blink").is_err());
+        assert!(normalize_folder("C:\\notes").is_err());
+        assert!(normalize_folder("  ").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modules::database;
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::note::Note;
+use crate::types::window::{ConfigState, NotesState};
+use crate::{log_error, log_info, UndoHistoryState, VaultLockState};
+
+/// Operations kept for undo; older ones are dropped
+pub const UNDO_HISTORY_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UndoKind {
+    Delete,
+    Reorder,
+    Replace,
+}
+
+/// A destructive note operation and what it takes to put things back. The
+/// editor has its own undo for text; this covers everything done to whole notes.
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub kind: UndoKind,
+    pub description: String,
+    pub at: String,
+    /// Notes as they were before the operation, restored as they are
+    pub before: Vec<Note>,
+}
+
+impl UndoEntry {
+    pub fn new(kind: UndoKind, description: String, before: Vec<Note>) -> Self {
+        Self { kind, description, at: chrono::Utc::now().to_rfc3339(), before }
+    }
+
+    pub fn summary(&self) -> UndoSummary {
+        UndoSummary {
+            kind: self.kind,
+            description: self.description.clone(),
+            at: self.at.clone(),
+            note_ids: self.before.iter().map(|note| note.id.clone()).collect(),
+        }
+    }
+}
+
+/// What the frontend is told about an undoable operation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSummary {
+    pub kind: UndoKind,
+    pub description: String,
+    pub at: String,
+    pub note_ids: Vec<String>,
+}
+
+/// The most recent operations, newest last
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    entries: VecDeque<UndoEntry>,
+}
+
+impl UndoHistory {
+    /// Remember an operation, forgetting the oldest past `UNDO_HISTORY_LIMIT`.
+    /// Operations that didn't change any note aren't recorded.
+    pub fn push(&mut self, entry: UndoEntry) {
+        if entry.before.is_empty() {
+            return;
+        }
+        self.entries.push_back(entry);
+        while self.entries.len() > UNDO_HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<UndoEntry> {
+        self.entries.pop_back()
+    }
+
+    pub fn summaries(&self) -> Vec<UndoSummary> {
+        self.entries.iter().rev().map(UndoEntry::summary).collect()
+    }
+}
+
+/// Record an operation from a command
+pub fn record_undo(history: &UndoHistoryState, kind: UndoKind, description: String, before: Vec<Note>) {
+    history.lock().unwrap().push(UndoEntry::new(kind, description, before));
+}
+
+/// Put back the notes changed by the most recent delete, reorder or replace. Returns
+/// what was undone, or `None` when there is nothing left to undo.
+#[tauri::command]
+pub async fn undo_last_operation(
+    window: tauri::Window,
+    app: AppHandle,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+    history: State<'_, UndoHistoryState>,
+) -> Result<Option<UndoSummary>, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let entry = history.lock().unwrap().pop();
+    let Some(entry) = entry else { return Ok(None) };
+
+    let mut notes_lock = notes.lock().await;
+    let config_lock = config.lock().await;
+    let existed: Vec<bool> = entry
+        .before
+        .iter()
+        .map(|note| notes_lock.insert(note.id.clone(), note.clone()).is_some())
+        .collect();
+
+    let file_storage = FileNotesStorage::new(&config_lock)?;
+    let saved = if entry.before.len() == 1 {
+        file_storage.save_note(&entry.before[0]).await
+    } else {
+        file_storage.save_all_notes(&notes_lock).await
+    };
+    if let Err(e) = saved {
+        // Keep the entry so the undo can be tried again
+        log_error!("NOTES", "Failed to undo {}: {}", entry.description, e);
+        history.lock().unwrap().push(entry);
+        return Err(e);
+    }
+
+    // Deleting a note drops its icon from the index, so put it back
+    let restored_icons: Vec<(&str, &str)> = entry
+        .before
+        .iter()
+        .zip(&existed)
+        .filter(|(_, existed)| !**existed)
+        .filter_map(|(note, _)| note.icon.as_deref().map(|icon| (note.id.as_str(), icon)))
+        .collect();
+    if !restored_icons.is_empty() {
+        match get_configured_notes_directory(&config_lock).and_then(|dir| {
+            database::initialize_database(&dir).map_err(|e| format!("Failed to open database: {}", e))
+        }) {
+            Ok(db) => {
+                for (id, icon) in restored_icons {
+                    if let Err(e) = db.set_note_icon(id, Some(icon)) {
+                        log_error!("NOTES", "Failed to restore icon of {}: {}", id, e);
+                    }
+                }
+            }
+            Err(e) => log_error!("NOTES", "Failed to restore note icons: {}", e),
+        }
+    }
+
+    for (note, existed) in entry.before.iter().zip(existed) {
+        if existed {
+            let _ = app.emit("note-updated", note);
+        } else {
+            modified_tracker.initialize_note(note).await;
+            let _ = app.emit("note-created", note);
+        }
+    }
+    log_info!("NOTES", "Undid {}", entry.description);
+    Ok(Some(entry.summary()))
+}
+
+/// Operations that can be undone, most recent first
+#[tauri::command]
+pub async fn get_undo_history(history: State<'_, UndoHistoryState>) -> Result<Vec<UndoSummary>, String> {
+    Ok(history.lock().unwrap().summaries())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            tags: vec![],
+            position: None,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_newest_first() {
+        let mut history = UndoHistory::default();
+        history.push(UndoEntry::new(UndoKind::Reorder, "nothing moved".to_string(), vec![]));
+        assert!(history.summaries().is_empty());
+
+        for i in 0..UNDO_HISTORY_LIMIT + 5 {
+            history.push(UndoEntry::new(UndoKind::Delete, format!("Delete {}", i), vec![note(&i.to_string())]));
+        }
+        let summaries = history.summaries();
+        assert_eq!(summaries.len(), UNDO_HISTORY_LIMIT);
+        assert_eq!(summaries[0].description, format!("Delete {}", UNDO_HISTORY_LIMIT + 4));
+        assert_eq!(summaries.last().unwrap().description, "Delete 5");
+
+        assert_eq!(history.pop().unwrap().before[0].id, (UNDO_HISTORY_LIMIT + 4).to_string());
+        assert_eq!(history.summaries().len(), UNDO_HISTORY_LIMIT - 1);
+    }
+}
@@ -0,0 +1,201 @@
+use std::time::Duration;
+use regex::Regex;
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::network_policy::NetworkPolicy;
+use crate::state::UrlMetadataCacheState;
+use crate::types::window::ConfigState;
+use crate::{log_debug, log_info};
+
+/// Give up on pages that take longer than this to respond
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Stop reading after this many bytes; titles live in the `<head>`
+const MAX_BODY_BYTES: usize = 512 * 1024;
+/// Cached entries kept before the cache is cleared
+const MAX_CACHE_ENTRIES: usize = 500;
+
+/// Title and description of a web page, for turning a pasted URL into a link
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlMetadata {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    /// `[title](url)`, or `<url>` when the page has no title
+    pub markdown: String,
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Decode entities and collapse whitespace; `None` for empty text
+fn clean_text(text: &str) -> Option<String> {
+    let cleaned = decode_entities(text).split_whitespace().collect::<Vec<_>>().join(" ");
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Attributes of every `<meta>` tag, as (name-or-property, content) pairs
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    let tag_re = Regex::new(r"(?is)<meta\s[^>]*>").unwrap();
+    let attr_re = Regex::new(r#"(?is)([a-z:_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    tag_re
+        .find_iter(html)
+        .filter_map(|tag| {
+            let mut key = None;
+            let mut content = None;
+            for attr in attr_re.captures_iter(tag.as_str()) {
+                let value = attr.get(2).or_else(|| attr.get(3)).map_or("", |m| m.as_str()).to_string();
+                match attr[1].to_lowercase().as_str() {
+                    "name" | "property" => key = Some(value.to_lowercase()),
+                    "content" => content = Some(value),
+                    _ => {}
+                }
+            }
+            Some((key?, content?))
+        })
+        .collect()
+}
+
+/// Pull the title, description and site name out of an HTML page. Open Graph
+/// tags win over `<title>` and the plain description.
+pub fn parse_html_metadata(url: &str, html: &str) -> UrlMetadata {
+    let metas = meta_tags(html);
+    let meta = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| metas.iter().find(|(k, _)| k == key).and_then(|(_, v)| clean_text(v)))
+    };
+
+    let title_re = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+    let title = meta(&["og:title", "twitter:title"])
+        .or_else(|| title_re.captures(html).and_then(|c| clean_text(&c[1])));
+
+    UrlMetadata {
+        url: url.to_string(),
+        markdown: markdown_link(url, title.as_deref()),
+        title,
+        description: meta(&["og:description", "description", "twitter:description"]),
+        site_name: meta(&["og:site_name"]),
+    }
+}
+
+/// Markdown for a pasted URL: a titled link, or an autolink without a title
+pub fn markdown_link(url: &str, title: Option<&str>) -> String {
+    match title {
+        Some(title) => {
+            let escaped = title.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]");
+            format!("[{}]({})", escaped, url)
+        }
+        None => format!("<{}>", url),
+    }
+}
+
+/// Read at most `MAX_BODY_BYTES` of the page
+async fn fetch_page(policy: NetworkPolicy, url: &str) -> Result<String, String> {
+    let client = policy.http_client("URL metadata", FETCH_TIMEOUT)?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |v| v.contains("html"));
+    if !is_html {
+        return Ok(String::new());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_BODY_BYTES {
+            body.truncate(MAX_BODY_BYTES);
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Resolve the title and description of a URL so a pasted link can become
+/// `[title](url)`. Results are cached; nothing is fetched in offline mode.
+#[tauri::command]
+pub async fn resolve_url_metadata(
+    url: String,
+    config: State<'_, ConfigState>,
+    cache: State<'_, UrlMetadataCacheState>,
+) -> Result<UrlMetadata, String> {
+    let url = url.trim().to_string();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("Only http and https URLs can be resolved: {}", url));
+    }
+
+    if let Some(cached) = cache.lock().await.get(&url) {
+        log_debug!("URL_METADATA", "Cache hit for {}", url);
+        return Ok(cached.clone());
+    }
+
+    let policy = NetworkPolicy::current(&config).await;
+    let html = fetch_page(policy, &url).await?;
+    let metadata = parse_html_metadata(&url, &html);
+    log_info!("URL_METADATA", "Resolved {} -> {:?}", url, metadata.title);
+
+    let mut cache_lock = cache.lock().await;
+    if cache_lock.len() >= MAX_CACHE_ENTRIES {
+        cache_lock.clear();
+    }
+    cache_lock.insert(url, metadata.clone());
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_graph_wins_over_title() {
+        let html = r#"<html><head>
+            <title>Fallback &amp; title</title>
+            <meta property="og:title" content="Real Title">
+            <meta name='description' content='A  short
+                description'>
+            <meta content="Example" property="og:site_name" />
+        </head></html>"#;
+        let metadata = parse_html_metadata("https://example.com", html);
+
+        assert_eq!(metadata.title.as_deref(), Some("Real Title"));
+        assert_eq!(metadata.description.as_deref(), Some("A short description"));
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+        assert_eq!(metadata.markdown, "[Real Title](https://example.com)");
+    }
+
+    #[test]
+    fn test_title_fallback_and_missing_title() {
+        let metadata = parse_html_metadata("https://a.test", "<TITLE>\n  Docs [v2] &amp; more\n</TITLE>");
+        assert_eq!(metadata.title.as_deref(), Some("Docs [v2] & more"));
+        assert_eq!(metadata.markdown, "[Docs \\[v2\\] & more](https://a.test)");
+
+        let metadata = parse_html_metadata("https://b.test", "<p>no head</p>");
+        assert_eq!(metadata.title, None);
+        assert_eq!(metadata.markdown, "<https://b.test>");
+    }
+}
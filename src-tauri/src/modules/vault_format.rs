@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::modules::database;
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::file_storage::FileStorageManager;
+use crate::modules::maintenance::scan_vault_files;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::config::AppConfig;
+use crate::types::window::DetachedWindow;
+use crate::{log_info, log_warn};
+
+/// Layout this build writes. Bump it and add a step to `MIGRATIONS` whenever
+/// the vault layout changes.
+pub const VAULT_FORMAT_VERSION: u32 = 4;
+
+/// `.blink/version`, holding the format number as text. Vaults from before
+/// versioning have no file and count as format 0.
+const VERSION_FILE: &str = "version";
+
+/// Consolidated window positions, keyed by note ID
+const SPATIAL_FILE_NAME: &str = "spatial_positions.json";
+
+/// Each migration upgrades a vault to `version`. They must be safe to run on
+/// a vault that is already partly upgraded, since a crash can stop the runner
+/// between a step and stamping its version.
+struct Migration {
+    version: u32,
+    name: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "notes.json to markdown files" },
+    Migration { version: 2, name: "index.json to SQLite" },
+    Migration { version: 3, name: "per-note spatial files into spatial_positions.json" },
+    Migration { version: 4, name: "index hashes of note bodies" },
+];
+
+/// What `migrate_vault` did
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub applied: Vec<&'static str>,
+}
+
+/// The vault's format version, 0 when it has never been stamped
+pub fn read_vault_version(dir: &Path) -> Result<u32, String> {
+    let path = dir.join(".blink").join(VERSION_FILE);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read vault version: {}", e))?;
+    text.trim()
+        .parse()
+        .map_err(|_| format!("Vault version file {:?} is not a number: {:?}", path, text.trim()))
+}
+
+fn write_vault_version(dir: &Path, version: u32) -> Result<(), String> {
+    let blink_dir = dir.join(".blink");
+    fs::create_dir_all(&blink_dir).map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+    fs::write(blink_dir.join(VERSION_FILE), format!("{}\n", version))
+        .map_err(|e| format!("Failed to write vault version: {}", e))
+}
+
+/// Fold `spatial_<id>.json` files into `spatial_positions.json`. The per-note
+/// files were written last, so they win over an entry already in the map.
+fn consolidate_spatial_files(dir: &Path) -> Result<(), String> {
+    let target = dir.join(SPATIAL_FILE_NAME);
+    let mut positions: HashMap<String, DetachedWindow> = match fs::read_to_string(&target) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", SPATIAL_FILE_NAME, e))?,
+        Err(_) => HashMap::new(),
+    };
+
+    let mut merged = Vec::new();
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read vault directory: {}", e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(note_id) = name.strip_prefix("spatial_").and_then(|rest| rest.strip_suffix(".json")) else { continue };
+        if name == SPATIAL_FILE_NAME {
+            continue;
+        }
+        let window = fs::read_to_string(entry.path())
+            .ok()
+            .and_then(|json| serde_json::from_str::<DetachedWindow>(&json).ok());
+        match window {
+            Some(window) => {
+                positions.insert(note_id.to_string(), window);
+                merged.push(entry.path());
+            }
+            None => log_warn!("STORAGE", "Leaving unreadable spatial file {:?} in place", entry.path()),
+        }
+    }
+    if merged.is_empty() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string_pretty(&positions).map_err(|e| format!("Failed to serialize window positions: {}", e))?;
+    fs::write(&target, json).map_err(|e| format!("Failed to write {}: {}", SPATIAL_FILE_NAME, e))?;
+    for path in &merged {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+    }
+    log_info!("STORAGE", "Consolidated {} spatial files", merged.len());
+    Ok(())
+}
+
+/// The index used to hash frontmatter and body together; it now hashes the
+/// body alone. Rehash once so the first verification after upgrading doesn't
+/// report every note as edited outside Blink.
+fn rehash_index(dir: &Path, follow_symlinks: bool) -> Result<(), String> {
+    if !database::get_database_path(dir).exists() {
+        return Ok(());
+    }
+    let db = database::initialize_database(dir).map_err(|e| format!("Failed to open index: {}", e))?;
+    let hashes: HashMap<String, String> = scan_vault_files(dir, follow_symlinks)?
+        .into_iter()
+        .map(|file| (file.stem, FileStorageManager::compute_file_hash(&file.body)))
+        .collect();
+
+    let mut rehashed = 0;
+    for mut record in db.get_all_notes().map_err(|e| format!("Failed to read index: {}", e))? {
+        let Some(hash) = hashes.get(&record.id) else { continue };
+        if record.file_hash == *hash {
+            continue;
+        }
+        record.file_hash = hash.clone();
+        db.upsert_note(&record).map_err(|e| format!("Failed to update hash of {}: {}", record.id, e))?;
+        rehashed += 1;
+    }
+    log_info!("STORAGE", "Rehashed {} index entries", rehashed);
+    Ok(())
+}
+
+async fn run_migration(version: u32, dir: &Path, config: &AppConfig) -> Result<(), String> {
+    match version {
+        1 => FileNotesStorage::new(config)?.migrate_if_needed(dir.join("notes.json")).await,
+        // Opening the database imports and backs up index.json
+        2 => database::initialize_database(dir)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to migrate index.json: {}", e)),
+        3 => consolidate_spatial_files(dir),
+        4 => rehash_index(dir, config.storage.follow_symlinks),
+        _ => unreachable!("no migration to vault format {}", version),
+    }
+}
+
+/// Bring the configured vault up to `VAULT_FORMAT_VERSION`, stamping the
+/// version after every step so an interrupted upgrade resumes where it stopped.
+/// Refuses vaults written by a newer build rather than guessing at them.
+pub async fn migrate_vault(config: &AppConfig) -> Result<MigrationReport, String> {
+    let dir = get_configured_notes_directory(config)?;
+    let from = read_vault_version(&dir)?;
+    if from > VAULT_FORMAT_VERSION {
+        return Err(format!(
+            "This vault uses format {}, but this version of Blink only understands up to {}. Update Blink to open it.",
+            from, VAULT_FORMAT_VERSION
+        ));
+    }
+    let mut report = MigrationReport { from, to: from, applied: Vec::new() };
+    if from == VAULT_FORMAT_VERSION {
+        return Ok(report);
+    }
+    if config.read_only {
+        log_info!("STORAGE", "Vault is read-only, leaving format {} as it is", from);
+        return Ok(report);
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from) {
+        log_info!("STORAGE", "Migrating vault to format {}: {}", migration.version, migration.name);
+        run_migration(migration.version, &dir, config)
+            .await
+            .map_err(|e| format!("Vault migration to format {} ({}) failed: {}", migration.version, migration.name, e))?;
+        write_vault_version(&dir, migration.version)?;
+        report.to = migration.version;
+        report.applied.push(migration.name);
+    }
+    Ok(report)
+}
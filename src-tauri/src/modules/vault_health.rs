@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use serde::Serialize;
+use tauri::State;
+
+use crate::modules::attachments::{attachment_usage, count_references};
+use crate::modules::database;
+use crate::modules::maintenance::{list_retained_files, scan_vault_files, verify_vault_at, LAST_MAINTENANCE_KEY, LAST_SYNC_KEY};
+use crate::modules::storage::get_configured_notes_directory;
+use crate::types::window::{ConfigState, DetachedWindowsState, NotesState};
+
+/// A maintenance command and when it last ran
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceRun {
+    pub command: String,
+    pub at: String,
+}
+
+/// Result of `get_vault_health`, everything the settings Health panel shows
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultHealth {
+    pub notes: usize,
+    /// Saved windows and icons for notes that no longer exist
+    pub orphans: usize,
+    /// Note IDs used by more than one file, in different folders
+    pub conflicts: usize,
+    /// Files edited outside Blink, indexed notes without a file, and files the
+    /// index doesn't know about
+    pub index_drift: usize,
+    pub unreferenced_attachments: usize,
+    pub trash_files: usize,
+    pub trash_bytes: u64,
+    pub backups: usize,
+    pub backup_bytes: u64,
+    /// Last time `rebuild_index` reconciled the index with the files on disk
+    pub last_sync: Option<String>,
+    pub last_maintenance: Option<MaintenanceRun>,
+}
+
+/// How many IDs appear more than once
+pub fn count_conflicts<'a>(ids: impl IntoIterator<Item = &'a str>) -> usize {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for id in ids {
+        *seen.entry(id).or_default() += 1;
+    }
+    seen.values().filter(|&&n| n > 1).count()
+}
+
+fn backup_usage(notes_dir: &Path) -> (usize, u64) {
+    let blink_dir = notes_dir.join(".blink");
+    let is_backup = |p: &Path| p.extension().map_or(false, |e| e == "backup");
+    let files: Vec<_> = list_retained_files(&blink_dir.join("backups"), |_| true)
+        .into_iter()
+        .chain(list_retained_files(&blink_dir, is_backup))
+        .chain(list_retained_files(notes_dir, is_backup))
+        .collect();
+    (files.len(), files.iter().map(|f| f.size).sum())
+}
+
+/// Vault statistics gathered from storage, the index, attachments and window state
+#[tauri::command]
+pub async fn get_vault_health(
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<VaultHealth, String> {
+    let (notes_dir, follow_symlinks) = {
+        let config_lock = config.lock().await;
+        (get_configured_notes_directory(&config_lock)?, config_lock.storage.follow_symlinks)
+    };
+    let (note_ids, references) = {
+        let notes_lock = notes.lock().await;
+        let ids: HashSet<String> = notes_lock.keys().cloned().collect();
+        (ids, count_references(notes_lock.values()))
+    };
+
+    let mut health = VaultHealth { notes: note_ids.len(), ..Default::default() };
+
+    health.orphans = detached_windows
+        .lock()
+        .await
+        .values()
+        .filter(|w| !note_ids.contains(&w.note_id))
+        .count();
+
+    let files = scan_vault_files(&notes_dir, follow_symlinks)?;
+    health.conflicts = count_conflicts(files.iter().map(|f| f.stem.as_str()));
+
+    let drift = verify_vault_at(&notes_dir, follow_symlinks)?;
+    health.index_drift = drift.modified.len() + drift.missing_files.len() + drift.unindexed.len();
+
+    health.unreferenced_attachments = attachment_usage(&notes_dir, &references)
+        .iter()
+        .filter(|a| a.references == 0)
+        .count();
+
+    let trash = list_retained_files(&notes_dir.join(".blink").join("trash"), |_| true);
+    health.trash_files = trash.len();
+    health.trash_bytes = trash.iter().map(|f| f.size).sum();
+    (health.backups, health.backup_bytes) = backup_usage(&notes_dir);
+
+    // The index is only read here; a vault without one has no history to report
+    if database::get_database_path(&notes_dir).exists() {
+        let db = database::initialize_database(&notes_dir)
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        health.orphans += db
+            .get_note_icons()
+            .map_err(|e| format!("Failed to read note icons: {}", e))?
+            .iter()
+            .filter(|(id, _)| !note_ids.contains(id))
+            .count();
+        health.last_sync = db
+            .get_metadata(LAST_SYNC_KEY)
+            .map_err(|e| format!("Failed to read metadata: {}", e))?
+            .map(|(_, at)| at);
+        health.last_maintenance = db
+            .get_metadata(LAST_MAINTENANCE_KEY)
+            .map_err(|e| format!("Failed to read metadata: {}", e))?
+            .map(|(command, at)| MaintenanceRun { command, at });
+    }
+
+    Ok(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_conflicts() {
+        assert_eq!(count_conflicts(["a", "b", "c"]), 0);
+        assert_eq!(count_conflicts(["a", "b", "a", "c", "b", "a"]), 2);
+        assert_eq!(count_conflicts(std::iter::empty()), 0);
+    }
+}
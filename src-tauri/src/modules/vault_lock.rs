@@ -0,0 +1,381 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::BlinkError;
+use crate::modules::power_mode::power_scaled;
+use crate::modules::storage::{get_configured_notes_directory, save_config_to_disk};
+use crate::types::window::ConfigState;
+use crate::VaultLockState;
+use crate::{log_error, log_info, log_warn};
+use crate::modules::window_capabilities::{authorize, Capability};
+
+/// Lock file name inside the vault's `.blink` directory
+const VAULT_LOCK_FILE: &str = "vault.lock";
+/// How often the owning process refreshes its heartbeat
+const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+/// Low power mode beats half as often, still well inside `STALE_AFTER_SECS`
+const LOW_POWER_HEARTBEAT_INTERVAL_SECS: u64 = 60;
+/// A lock whose heartbeat is older than this is considered abandoned
+const STALE_AFTER_SECS: i64 = 120;
+
+/// Contents of the advisory lock file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultLockOwner {
+    pub hostname: String,
+    pub pid: u32,
+    pub acquired_at: String,
+    pub heartbeat_at: String,
+}
+
+/// Runtime view of the vault lock, as reported to the frontend
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultLockStatus {
+    /// Whether this process owns the lock and may write to the vault
+    pub held: bool,
+    /// Current owner of the lock file, if any
+    pub owner: Option<VaultLockOwner>,
+    /// Whether the owner's heartbeat has expired
+    pub stale: bool,
+    #[serde(skip)]
+    pub notes_dir: Option<PathBuf>,
+}
+
+fn current_hostname() -> String {
+    if let Ok(name) = std::env::var("HOSTNAME") {
+        if !name.is_empty() {
+            return name;
+        }
+    }
+
+    std::process::Command::new("hostname")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn lock_path(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(".blink").join(VAULT_LOCK_FILE)
+}
+
+fn read_lock(notes_dir: &Path) -> Option<VaultLockOwner> {
+    let content = fs::read_to_string(lock_path(notes_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_lock(notes_dir: &Path, owner: &VaultLockOwner) -> Result<(), String> {
+    let path = lock_path(notes_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(owner)
+        .map_err(|e| format!("Failed to serialize vault lock: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write vault lock: {}", e))
+}
+
+/// Whether a lock's heartbeat is older than the stale threshold
+pub fn is_lock_stale(owner: &VaultLockOwner, now: chrono::DateTime<chrono::Utc>) -> bool {
+    match chrono::DateTime::parse_from_rfc3339(&owner.heartbeat_at) {
+        Ok(heartbeat) => (now - heartbeat.with_timezone(&chrono::Utc)).num_seconds() > STALE_AFTER_SECS,
+        // An unreadable heartbeat can never be refreshed, so treat it as abandoned
+        Err(_) => true,
+    }
+}
+
+fn is_own_lock(owner: &VaultLockOwner) -> bool {
+    owner.hostname == current_hostname() && owner.pid == std::process::id()
+}
+
+/// Try to take the advisory lock for a vault.
+///
+/// Succeeds when there is no lock, the lock is ours, the lock is stale, or `force` is set.
+/// Otherwise the returned status has `held == false` and describes the foreign owner.
+pub fn acquire_vault_lock(notes_dir: &Path, force: bool) -> Result<VaultLockStatus, String> {
+    let now = chrono::Utc::now();
+
+    if let Some(existing) = read_lock(notes_dir) {
+        let stale = is_lock_stale(&existing, now);
+        if !force && !stale && !is_own_lock(&existing) {
+            log_warn!("VAULT_LOCK", "Vault {} is locked by {} (pid {})", notes_dir.display(), existing.hostname, existing.pid);
+            return Ok(VaultLockStatus {
+                held: false,
+                owner: Some(existing),
+                stale,
+                notes_dir: Some(notes_dir.to_path_buf()),
+            });
+        }
+
+        if force && !is_own_lock(&existing) {
+            log_warn!("VAULT_LOCK", "Taking over vault lock from {} (pid {})", existing.hostname, existing.pid);
+        }
+    }
+
+    let owner = VaultLockOwner {
+        hostname: current_hostname(),
+        pid: std::process::id(),
+        acquired_at: now.to_rfc3339(),
+        heartbeat_at: now.to_rfc3339(),
+    };
+    write_lock(notes_dir, &owner)?;
+
+    log_info!("VAULT_LOCK", "Acquired vault lock for {}", notes_dir.display());
+    Ok(VaultLockStatus {
+        held: true,
+        owner: Some(owner),
+        stale: false,
+        notes_dir: Some(notes_dir.to_path_buf()),
+    })
+}
+
+/// Refresh our heartbeat. Returns the new status; `held` becomes false if
+/// another machine has taken the lock in the meantime.
+pub fn refresh_vault_lock(status: &VaultLockStatus) -> Result<VaultLockStatus, String> {
+    let notes_dir = match &status.notes_dir {
+        Some(dir) => dir.clone(),
+        None => return Ok(status.clone()),
+    };
+
+    if !status.held {
+        // Re-check so a lock abandoned by the other machine is picked up automatically
+        return acquire_vault_lock(&notes_dir, false);
+    }
+
+    match read_lock(&notes_dir) {
+        Some(existing) if !is_own_lock(&existing) => {
+            log_warn!("VAULT_LOCK", "Vault lock was taken over by {} (pid {})", existing.hostname, existing.pid);
+            Ok(VaultLockStatus {
+                held: false,
+                stale: is_lock_stale(&existing, chrono::Utc::now()),
+                owner: Some(existing),
+                notes_dir: Some(notes_dir),
+            })
+        }
+        existing => {
+            let mut owner = existing.or_else(|| status.owner.clone()).ok_or("Vault lock owner missing")?;
+            owner.heartbeat_at = chrono::Utc::now().to_rfc3339();
+            write_lock(&notes_dir, &owner)?;
+            Ok(VaultLockStatus {
+                held: true,
+                owner: Some(owner),
+                stale: false,
+                notes_dir: Some(notes_dir),
+            })
+        }
+    }
+}
+
+/// Remove our lock file, leaving foreign locks untouched
+pub fn release_vault_lock(notes_dir: &Path) {
+    if let Some(existing) = read_lock(notes_dir) {
+        if is_own_lock(&existing) {
+            if let Err(e) = fs::remove_file(lock_path(notes_dir)) {
+                log_error!("VAULT_LOCK", "Failed to release vault lock: {}", e);
+            }
+        }
+    }
+}
+
+/// Drop our lock as the app exits, so the next launch elsewhere needn't wait
+/// for the heartbeat to go stale
+pub fn release_vault_lock_on_exit(app: &AppHandle) {
+    let vault_lock = app.state::<VaultLockState>();
+    let mut status = tauri::async_runtime::block_on(vault_lock.lock());
+    if status.held {
+        if let Some(notes_dir) = &status.notes_dir {
+            release_vault_lock(notes_dir);
+            log_info!("VAULT_LOCK", "Released vault lock for {} on exit", notes_dir.display());
+        }
+        status.held = false;
+    }
+}
+
+/// Reject writes when the vault is read-only or another machine owns it
+pub async fn ensure_vault_writable(config: &ConfigState, vault_lock: &VaultLockState) -> Result<(), String> {
+    if config.lock().await.read_only {
+        return Err(BlinkError::ReadOnly.to_string());
+    }
+
+    let status = vault_lock.lock().await;
+    if status.held {
+        return Ok(());
+    }
+
+    match &status.owner {
+        Some(owner) => Err(format!(
+            "Vault is locked by {} (pid {}); open it read-only or take over the lock",
+            owner.hostname, owner.pid
+        )),
+        None => Err("Vault lock has not been acquired yet".to_string()),
+    }
+}
+
+/// Acquire the lock for a (new) notes directory, releasing any lock we hold elsewhere
+pub async fn switch_vault_lock(app: &AppHandle, notes_dir: &Path) -> Result<VaultLockStatus, String> {
+    let vault_lock = app.state::<VaultLockState>();
+    let mut status = vault_lock.lock().await;
+
+    if status.held {
+        if let Some(previous) = &status.notes_dir {
+            if previous != notes_dir {
+                release_vault_lock(previous);
+            }
+        }
+    }
+
+    *status = acquire_vault_lock(notes_dir, false)?;
+    let _ = app.emit("vault-lock-changed", &*status);
+    Ok(status.clone())
+}
+
+/// Take the vault lock on startup and keep its heartbeat alive.
+/// With no directory (read-only session) only the heartbeat task is started.
+pub async fn start_vault_lock(app: AppHandle, notes_dir: Option<PathBuf>) -> Result<(), String> {
+    if let Some(notes_dir) = &notes_dir {
+        switch_vault_lock(&app, notes_dir).await?;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(power_scaled(
+                Duration::from_secs(HEARTBEAT_INTERVAL_SECS),
+                Duration::from_secs(LOW_POWER_HEARTBEAT_INTERVAL_SECS),
+            ))
+            .await;
+
+            let vault_lock = app.state::<VaultLockState>();
+            let mut status = vault_lock.lock().await;
+            let was_held = status.held;
+
+            match refresh_vault_lock(&status) {
+                Ok(new_status) => {
+                    *status = new_status;
+                    if status.held != was_held {
+                        let _ = app.emit("vault-lock-changed", &*status);
+                    }
+                }
+                Err(e) => log_error!("VAULT_LOCK", "Failed to refresh vault lock: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Get the current vault lock status
+#[tauri::command]
+pub async fn get_vault_lock_status(
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<VaultLockStatus, String> {
+    Ok(vault_lock.lock().await.clone())
+}
+
+/// Take over the vault lock from another machine
+#[tauri::command]
+pub async fn force_take_vault_lock(
+    window: tauri::Window,
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<VaultLockStatus, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+
+    let mut status = vault_lock.lock().await;
+    *status = acquire_vault_lock(&notes_dir, true)?;
+    let _ = app.emit("vault-lock-changed", &*status);
+    Ok(status.clone())
+}
+
+/// Switch the vault between read-only and writable at runtime
+#[tauri::command]
+pub async fn set_read_only(
+    window: tauri::Window,
+    app: AppHandle,
+    enabled: bool,
+    persist: Option<bool>,
+    config: State<'_, ConfigState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let (config_clone, notes_dir) = {
+        let mut config_lock = config.lock().await;
+        config_lock.read_only = enabled;
+        (config_lock.clone(), get_configured_notes_directory(&config_lock)?)
+    };
+
+    if persist.unwrap_or(false) {
+        save_config_to_disk(&config_clone).await?;
+    }
+
+    // A read-only session must not hold (or keep refreshing) the lock file
+    if enabled {
+        let vault_lock = app.state::<VaultLockState>();
+        let mut status = vault_lock.lock().await;
+        if status.held {
+            release_vault_lock(&notes_dir);
+            *status = VaultLockStatus::default();
+        }
+    } else {
+        switch_vault_lock(&app, &notes_dir).await?;
+    }
+
+    log_info!("VAULT_LOCK", "Read-only mode {}", if enabled { "enabled" } else { "disabled" });
+    let _ = app.emit("read-only-changed", enabled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn owner(hostname: &str, pid: u32, heartbeat_at: chrono::DateTime<chrono::Utc>) -> VaultLockOwner {
+        VaultLockOwner {
+            hostname: hostname.to_string(),
+            pid,
+            acquired_at: heartbeat_at.to_rfc3339(),
+            heartbeat_at: heartbeat_at.to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_lock_staleness() {
+        let now = chrono::Utc::now();
+        assert!(!is_lock_stale(&owner("a", 1, now), now));
+        assert!(is_lock_stale(&owner("a", 1, now - chrono::Duration::seconds(STALE_AFTER_SECS + 1)), now));
+    }
+
+    #[test]
+    fn test_fresh_foreign_lock_is_refused() {
+        let temp_dir = TempDir::new().unwrap();
+        write_lock(temp_dir.path(), &owner("other-machine", 4242, chrono::Utc::now())).unwrap();
+
+        let status = acquire_vault_lock(temp_dir.path(), false).unwrap();
+        assert!(!status.held);
+        assert_eq!(status.owner.unwrap().hostname, "other-machine");
+
+        let forced = acquire_vault_lock(temp_dir.path(), true).unwrap();
+        assert!(forced.held);
+        assert_eq!(read_lock(temp_dir.path()).unwrap().pid, std::process::id());
+    }
+
+    #[test]
+    fn test_stale_foreign_lock_is_taken() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = chrono::Utc::now() - chrono::Duration::seconds(STALE_AFTER_SECS * 2);
+        write_lock(temp_dir.path(), &owner("other-machine", 4242, old)).unwrap();
+
+        let status = acquire_vault_lock(temp_dir.path(), false).unwrap();
+        assert!(status.held);
+    }
+}
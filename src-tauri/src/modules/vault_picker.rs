@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+use crate::handlers::menu_handler::update_app_menu;
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::file_storage::list_note_files;
+use crate::modules::storage::{get_configured_notes_directory, save_config_to_disk};
+use crate::modules::vault_format::migrate_vault;
+use crate::modules::vault_lock::switch_vault_lock;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::services::window_service::WindowService;
+use crate::types::config::{AppConfig, RecentVault};
+use crate::types::window::{ConfigState, DetachedWindowsState, NotesState};
+use crate::{log_error, log_info, ModifiedStateTrackerState};
+
+/// Label of the vault picker window
+pub const VAULT_PICKER_WINDOW_LABEL: &str = "vault-picker";
+/// Unpinned vaults remembered; pinned ones don't count
+const RECENT_VAULT_LIMIT: usize = 10;
+
+/// A row in the vault picker
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultEntry {
+    pub path: String,
+    pub name: String,
+    pub last_opened: Option<String>,
+    pub pinned: bool,
+    pub current: bool,
+    /// `None` when the folder is gone or can't be read
+    pub note_count: Option<usize>,
+}
+
+/// Move `path` to the top of the recent vaults, keeping its pin, and forget
+/// the oldest unpinned ones past `RECENT_VAULT_LIMIT`
+pub fn remember_vault(recent: &mut Vec<RecentVault>, path: &str, now: &str) {
+    let pinned = recent.iter().any(|v| v.path == path && v.pinned);
+    recent.retain(|v| v.path != path);
+    recent.insert(0, RecentVault { path: path.to_string(), last_opened: now.to_string(), pinned });
+
+    let mut unpinned = 0;
+    recent.retain(|v| {
+        if v.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= RECENT_VAULT_LIMIT
+    });
+}
+
+/// Pinned vaults first, then the most recently opened
+pub fn picker_order(recent: &[RecentVault]) -> Vec<RecentVault> {
+    let mut ordered = recent.to_vec();
+    ordered.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| b.last_opened.cmp(&a.last_opened)));
+    ordered
+}
+
+fn vault_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// Point the app at another notes directory: move the vault lock, upgrade the
+/// vault if needed, load its notes and tell every window to reload. Open note
+/// windows belong to the old vault and are closed.
+pub async fn switch_to_vault(app: &AppHandle, notes_dir: &Path) -> Result<usize, String> {
+    if !notes_dir.is_dir() {
+        return Err(format!("Not a folder: {}", notes_dir.display()));
+    }
+    let path = notes_dir.to_string_lossy().to_string();
+
+    let config = {
+        let config_state = app.state::<ConfigState>();
+        let mut config_lock = config_state.lock().await;
+        config_lock.storage.notes_directory = Some(path.clone());
+        config_lock.storage.use_custom_directory = true;
+        remember_vault(&mut config_lock.storage.recent_vaults, &path, &chrono::Utc::now().to_rfc3339());
+        config_lock.clone()
+    };
+    save_config_to_disk(&config).await?;
+    if !config.read_only {
+        switch_vault_lock(app, notes_dir).await?;
+    }
+
+    let open_notes: Vec<String> =
+        app.state::<DetachedWindowsState>().lock().await.values().map(|w| w.note_id.clone()).collect();
+    let window_service = app.state::<WindowService>();
+    for note_id in open_notes {
+        if let Err(e) = window_service.close_detached_window(&note_id).await {
+            log_error!("VAULT", "Failed to close window for {}: {}", note_id, e);
+        }
+    }
+
+    let count = reload_vault_notes(app, &config).await?;
+    log_info!("VAULT", "Switched to vault {} ({} notes)", path, count);
+    Ok(count)
+}
+
+/// Load the configured vault's notes in place of the ones in memory and tell
+/// every window to reload
+pub async fn reload_vault_notes(app: &AppHandle, config: &AppConfig) -> Result<usize, String> {
+    let notes = load_vault_notes(config).await?;
+    let count = notes.len();
+    {
+        let notes_state = app.state::<NotesState>();
+        let mut notes_lock = notes_state.lock().await;
+        *notes_lock = notes;
+        let modified_tracker = app.state::<ModifiedStateTrackerState>();
+        modified_tracker.clear_all().await;
+        for note in notes_lock.values() {
+            modified_tracker.initialize_note(note).await;
+        }
+    }
+
+    if let Err(e) = update_app_menu(app.clone(), app.state::<DetachedWindowsState>(), app.state::<NotesState>()).await {
+        log_error!("VAULT", "Failed to refresh menu: {}", e);
+    }
+    // Windows reload their notes on this, as after startup
+    let _ = app.emit("data-loaded", ());
+    Ok(count)
+}
+
+async fn load_vault_notes(config: &AppConfig) -> Result<std::collections::HashMap<String, crate::types::note::Note>, String> {
+    let report = migrate_vault(config).await?;
+    if !report.applied.is_empty() {
+        log_info!("VAULT", "Upgraded vault from format {} to {}", report.from, report.to);
+    }
+    FileNotesStorage::new(config)?.load_notes().await
+}
+
+/// Show the vault picker. There is only ever one; later calls focus it.
+#[tauri::command]
+pub async fn open_vault_picker(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(VAULT_PICKER_WINDOW_LABEL) {
+        window.show().map_err(|e| format!("Failed to show vault picker: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus vault picker: {}", e))?;
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(&app, VAULT_PICKER_WINDOW_LABEL, WebviewUrl::App("index.html?vaultPicker=true".into()))
+        .title("Open Vault")
+        .inner_size(460.0, 420.0)
+        .min_inner_size(360.0, 280.0)
+        .center()
+        .minimizable(false)
+        .maximizable(false)
+        .build()
+        .map_err(|e| format!("Failed to create vault picker: {}", e))?;
+    Ok(())
+}
+
+/// Recent vaults for the picker, with note counts. The open vault is always
+/// listed, even before it has been switched to once.
+#[tauri::command]
+pub async fn list_recent_vaults(
+    config: State<'_, ConfigState>,
+    notes: State<'_, NotesState>,
+) -> Result<Vec<VaultEntry>, String> {
+    let (current, mut recent, follow_symlinks) = {
+        let config_lock = config.lock().await;
+        (
+            get_configured_notes_directory(&config_lock)?,
+            config_lock.storage.recent_vaults.clone(),
+            config_lock.storage.follow_symlinks,
+        )
+    };
+    let current_path = current.to_string_lossy().to_string();
+    if !recent.iter().any(|v| v.path == current_path) {
+        recent.push(RecentVault { path: current_path.clone(), last_opened: String::new(), pinned: false });
+    }
+    let current_count = notes.lock().await.len();
+
+    let entries = tauri::async_runtime::spawn_blocking(move || {
+        picker_order(&recent)
+            .into_iter()
+            .map(|vault| {
+                let path = PathBuf::from(&vault.path);
+                let current = vault.path == current_path;
+                let note_count = if current {
+                    Some(current_count)
+                } else {
+                    path.is_dir().then(|| list_note_files(&path, follow_symlinks).ok().map(|files| files.len())).flatten()
+                };
+                VaultEntry {
+                    name: vault_name(&path),
+                    last_opened: (!vault.last_opened.is_empty()).then_some(vault.last_opened),
+                    pinned: vault.pinned,
+                    current,
+                    note_count,
+                    path: vault.path,
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to count notes: {}", e))?;
+    Ok(entries)
+}
+
+/// Switch to another notes directory from the picker, then close it
+#[tauri::command]
+pub async fn switch_vault(window: tauri::Window, app: AppHandle, path: String) -> Result<usize, String> {
+    authorize(&window, Capability::ManageVault)?;
+    let count = switch_to_vault(&app, Path::new(&path)).await?;
+    if let Some(picker) = app.get_webview_window(VAULT_PICKER_WINDOW_LABEL) {
+        let _ = picker.close();
+    }
+    Ok(count)
+}
+
+/// Pin a vault to the top of the picker, or unpin it
+#[tauri::command]
+pub async fn set_vault_pinned(
+    window: tauri::Window,
+    path: String,
+    pinned: bool,
+    config: State<'_, ConfigState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let config_clone = {
+        let mut config_lock = config.lock().await;
+        let recent = &mut config_lock.storage.recent_vaults;
+        match recent.iter_mut().find(|v| v.path == path) {
+            Some(vault) => vault.pinned = pinned,
+            None if pinned => recent.push(RecentVault { path, last_opened: String::new(), pinned }),
+            None => return Ok(()),
+        }
+        config_lock.clone()
+    };
+    save_config_to_disk(&config_clone).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(path: &str, last_opened: &str, pinned: bool) -> RecentVault {
+        RecentVault { path: path.to_string(), last_opened: last_opened.to_string(), pinned }
+    }
+
+    #[test]
+    fn test_remember_vault_keeps_pins_and_limit() {
+        let mut recent = vec![vault("/work", "1", true)];
+        for i in 0..RECENT_VAULT_LIMIT + 3 {
+            remember_vault(&mut recent, &format!("/v{}", i), &format!("{:02}", i + 2));
+        }
+        assert_eq!(recent.len(), RECENT_VAULT_LIMIT + 1);
+        assert!(recent.iter().any(|v| v.path == "/work"));
+        assert!(!recent.iter().any(|v| v.path == "/v0"));
+
+        remember_vault(&mut recent, "/work", "99");
+        assert_eq!(recent[0], vault("/work", "99", true));
+        assert_eq!(recent.iter().filter(|v| v.path == "/work").count(), 1);
+    }
+
+    #[test]
+    fn test_picker_order_puts_pinned_first() {
+        let recent = vec![vault("/a", "2026-03-01", false), vault("/b", "2026-01-01", true), vault("/c", "2026-04-01", false)];
+        let paths: Vec<String> = picker_order(&recent).into_iter().map(|v| v.path).collect();
+        assert_eq!(paths, ["/b", "/c", "/a"]);
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs;
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use regex::Regex;
+use tauri::{AppHandle, State};
+
+use crate::modules::commands::insert_generated_note;
+use crate::modules::database::{self, NoteRecord};
+use crate::modules::date_format::format_datetime;
+use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::config::DateStyle;
+use crate::types::note::{CreateNoteRequest, Note};
+use crate::types::window::{ConfigState, NotesState};
+use crate::VaultLockState;
+use crate::log_info;
+
+/// Tag on generated reviews; reviews never summarize each other
+pub const WEEKLY_REVIEW_TAG: &str = "weekly-review";
+
+/// Monday of the requested week and the Monday after. `week` is an ISO week
+/// (`2024-W03`) or any date in the week (`2024-01-17`); `None` means this week.
+pub fn week_range(week: Option<&str>, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    let day = match week.map(str::trim).filter(|w| !w.is_empty()) {
+        None => today,
+        Some(week) => match week.split_once("-W").or_else(|| week.split_once("-w")) {
+            Some((year, number)) => {
+                let year: i32 = year.parse().map_err(|_| format!("Invalid year in '{}'", week))?;
+                let number: u32 = number.parse().map_err(|_| format!("Invalid week number in '{}'", week))?;
+                NaiveDate::from_isoywd_opt(year, number, Weekday::Mon)
+                    .ok_or_else(|| format!("No week {} in {}", number, year))?
+            }
+            None => NaiveDate::parse_from_str(week, "%Y-%m-%d")
+                .map_err(|_| format!("Expected a week like 2024-W03 or a date like 2024-01-17, got '{}'", week))?,
+        },
+    };
+    let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+    Ok((monday, monday + Duration::days(7)))
+}
+
+/// Text of checked task list items (`- [x] ...`)
+pub fn completed_tasks(content: &str) -> Vec<String> {
+    let task = Regex::new(r"^\s*[-*+]\s+\[[xX]\]\s+(.+?)\s*$").unwrap();
+    content
+        .lines()
+        .filter_map(|line| task.captures(line).map(|c| c[1].to_string()))
+        .collect()
+}
+
+/// ATX headings outside code blocks, without the `#` marks
+pub fn headings(content: &str) -> Vec<String> {
+    let mut in_code = false;
+    let mut result = Vec::new();
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let trimmed = line.trim_start();
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            let text = trimmed[level..].trim().trim_end_matches('#').trim();
+            if !text.is_empty() {
+                result.push(text.to_string());
+            }
+        }
+    }
+    result
+}
+
+/// Local date of an index timestamp
+fn local_date(time: &chrono::DateTime<chrono::Utc>) -> NaiveDate {
+    time.with_timezone(&Local).date_naive()
+}
+
+fn link(record: &NoteRecord) -> String {
+    format!("[{}]({})", record.title.replace(']', "\\]"), record.file_path.replace(' ', "%20"))
+}
+
+/// Build the review from indexed notes and their files. Returns the title and markdown.
+pub fn build_weekly_review(
+    records: &[NoteRecord],
+    read_content: impl Fn(&NoteRecord) -> Option<String>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> (String, String) {
+    let in_range = |date: NaiveDate| date >= start && date < end;
+    let mut active: Vec<&NoteRecord> = records
+        .iter()
+        .filter(|r| !r.tags.iter().any(|t| t.eq_ignore_ascii_case(WEEKLY_REVIEW_TAG)))
+        .filter(|r| in_range(local_date(&r.created_at)) || in_range(local_date(&r.updated_at)))
+        .collect();
+    active.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let (created, edited): (Vec<&NoteRecord>, Vec<&NoteRecord>) =
+        active.iter().copied().partition(|r| in_range(local_date(&r.created_at)));
+
+    let last_day = end - Duration::days(1);
+    let week = start.iso_week();
+    let title = format!("Weekly review {}-W{:02}", week.year(), week.week());
+    let mut content = format!(
+        "# {}\n\n{} – {}\n\n{} notes created, {} edited.\n",
+        title,
+        start.format("%a %-d %b"),
+        last_day.format("%a %-d %b %Y"),
+        created.len(),
+        edited.len()
+    );
+
+    if !created.is_empty() {
+        content.push_str("\n## Created\n\n");
+        for record in &created {
+            content.push_str(&format!("- {} — {}\n", link(record), format_datetime(&record.created_at, Some(DateStyle::Date))));
+        }
+    }
+    if !edited.is_empty() {
+        content.push_str("\n## Edited\n\n");
+        for record in &edited {
+            content.push_str(&format!("- {} — {}\n", link(record), format_datetime(&record.updated_at, Some(DateStyle::Date))));
+        }
+    }
+
+    let mut tasks = String::new();
+    let mut outline = String::new();
+    for record in &active {
+        let Some(body) = read_content(record) else { continue };
+        let done = completed_tasks(&body);
+        if !done.is_empty() {
+            tasks.push_str(&format!("\n### {}\n\n", link(record)));
+            for task in done {
+                tasks.push_str(&format!("- [x] {}\n", task));
+            }
+        }
+        let sections: Vec<String> = headings(&body)
+            .into_iter()
+            .filter(|h| h != &record.title)
+            .collect();
+        if !sections.is_empty() {
+            outline.push_str(&format!("- {}: {}\n", link(record), sections.join(" · ")));
+        }
+    }
+    if !tasks.is_empty() {
+        content.push_str("\n## Completed tasks\n");
+        content.push_str(&tasks);
+    }
+    if !outline.is_empty() {
+        content.push_str("\n## Topics\n\n");
+        content.push_str(&outline);
+    }
+
+    (title, content)
+}
+
+/// Create a review note for a week from the index and note files
+#[tauri::command]
+pub async fn generate_weekly_review(
+    window: tauri::Window,
+    app: AppHandle,
+    week: Option<String>,
+    notes: State<'_, NotesState>,
+    config: State<'_, ConfigState>,
+    modified_tracker: State<'_, ModifiedStateTracker>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<Note, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let (start, end) = week_range(week.as_deref(), Local::now().date_naive())?;
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    let db = database::initialize_database(&notes_dir)
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    let records = db.get_all_notes().map_err(|e| format!("Failed to load notes from database: {}", e))?;
+
+    let (title, content) = build_weekly_review(
+        &records,
+        |record| fs::read_to_string(notes_dir.join(&record.file_path)).ok(),
+        start,
+        end,
+    );
+    let note = insert_generated_note(
+        &app,
+        CreateNoteRequest { title, content, tags: vec![WEEKLY_REVIEW_TAG.to_string()] },
+        None,
+        &notes,
+        &config,
+        &modified_tracker,
+    )
+    .await?;
+
+    log_info!("REVIEW", "Generated {} for {} – {}", note.title, start, end);
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn record(id: &str, created: (u32, u32), updated: (u32, u32), tags: &[&str]) -> NoteRecord {
+        let at = |(month, day): (u32, u32)| Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        NoteRecord {
+            id: id.to_string(),
+            title: id.to_string(),
+            file_path: format!("{}.md", id),
+            created_at: at(created),
+            updated_at: at(updated),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            position: None,
+            file_hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_week_range() {
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let next = NaiveDate::from_ymd_opt(2024, 1, 22).unwrap();
+        assert_eq!(week_range(Some("2024-W03"), monday).unwrap(), (monday, next));
+        assert_eq!(week_range(Some("2024-01-17"), monday).unwrap(), (monday, next));
+        assert_eq!(week_range(None, NaiveDate::from_ymd_opt(2024, 1, 21).unwrap()).unwrap(), (monday, next));
+        assert!(week_range(Some("2024-W60"), monday).is_err());
+    }
+
+    #[test]
+    fn test_build_weekly_review() {
+        let records = vec![
+            record("plan", (1, 16), (1, 16), &[]),
+            record("old", (1, 2), (1, 18), &[]),
+            record("untouched", (1, 2), (1, 3), &[]),
+            record("last-review", (1, 17), (1, 17), &[WEEKLY_REVIEW_TAG]),
+        ];
+        let read = |r: &NoteRecord| match r.id.as_str() {
+            "plan" => Some("# plan\n\n## Launch\n\n- [x] Ship beta\n- [ ] Write docs\n```\n# not a heading\n```".to_string()),
+            _ => Some(String::new()),
+        };
+        let (start, end) = week_range(Some("2024-W03"), NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()).unwrap();
+        let (title, content) = build_weekly_review(&records, read, start, end);
+
+        assert_eq!(title, "Weekly review 2024-W03");
+        assert!(content.contains("1 notes created, 1 edited."));
+        assert!(content.contains("## Created\n\n- [plan](plan.md)"));
+        assert!(content.contains("## Edited\n\n- [old](old.md)"));
+        assert!(content.contains("### [plan](plan.md)\n\n- [x] Ship beta\n"));
+        assert!(content.contains("- [plan](plan.md): Launch\n"));
+        assert!(!content.contains("untouched") && !content.contains("last-review"));
+    }
+}
@@ -0,0 +1,334 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::display_layouts::{current_display_fingerprint, current_monitor_geometries, ensure_window_onscreen};
+use crate::modules::power_mode::power_scaled;
+use crate::modules::storage::get_default_notes_directory;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::services::window_service::WindowService;
+use crate::types::window::{CreateDetachedWindowRequest, DetachedWindow, DetachedWindowsState, NotesState};
+use crate::{log_debug, log_error, log_info};
+
+/// How often the display configuration is checked between display events
+const DISPLAY_POLL: Duration = Duration::from_secs(5);
+const DISPLAY_POLL_LOW_POWER: Duration = Duration::from_secs(30);
+/// Displays report in one by one after a dock is plugged in; wait for them to settle
+const DISPLAY_SETTLE: Duration = Duration::from_secs(2);
+
+/// Main window geometry in physical pixels, as kept in the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowFrame {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The display configuration an arrangement is applied on automatically
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayPreset {
+    pub fingerprint: String,
+    pub monitor_count: usize,
+}
+
+/// A named set of note windows and where they go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowArrangement {
+    pub name: String,
+    pub windows: Vec<DetachedWindow>,
+    pub main_window: Option<WindowFrame>,
+    /// Set for presets like "1 monitor" and "2 monitors"
+    #[serde(default)]
+    pub display: Option<DisplayPreset>,
+    pub saved_at: String,
+}
+
+/// Fingerprint seen last, so only real changes apply a preset
+static LAST_FINGERPRINT: Mutex<Option<String>> = Mutex::new(None);
+
+fn arrangements_path() -> Result<PathBuf, String> {
+    Ok(get_default_notes_directory()?.join("window_arrangements.json"))
+}
+
+pub fn load_window_arrangements() -> Result<Vec<WindowArrangement>, String> {
+    let path = arrangements_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read window arrangements: {}", e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse window arrangements: {}", e))
+}
+
+fn save_window_arrangements(arrangements: &[WindowArrangement]) -> Result<(), String> {
+    let path = arrangements_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(arrangements)
+        .map_err(|e| format!("Failed to serialize window arrangements: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save window arrangements: {}", e))
+}
+
+/// The preset for a display configuration: the one saved on exactly these
+/// displays, otherwise the newest one saved with as many displays
+pub fn preset_for_display<'a>(
+    arrangements: &'a [WindowArrangement],
+    fingerprint: &str,
+    monitor_count: usize,
+) -> Option<&'a WindowArrangement> {
+    let presets = || arrangements.iter().filter_map(|a| a.display.as_ref().map(|d| (a, d)));
+    presets()
+        .find(|(_, d)| d.fingerprint == fingerprint)
+        .or_else(|| presets().filter(|(_, d)| d.monitor_count == monitor_count).max_by(|a, b| a.0.saved_at.cmp(&b.0.saved_at)))
+        .map(|(a, _)| a)
+}
+
+/// Add or replace `arrangement` by name. A display preset takes over its
+/// displays from any other preset, so each configuration has one.
+pub fn upsert_arrangement(arrangements: &mut Vec<WindowArrangement>, arrangement: WindowArrangement) {
+    if let Some(display) = &arrangement.display {
+        for other in arrangements.iter_mut() {
+            if other.display.as_ref().map_or(false, |d| d.fingerprint == display.fingerprint) {
+                other.display = None;
+            }
+        }
+    }
+    arrangements.retain(|a| a.name != arrangement.name);
+    arrangements.push(arrangement);
+}
+
+fn main_window_frame(app: &AppHandle) -> Option<WindowFrame> {
+    let window = app.get_webview_window("main")?;
+    if window.is_minimized().unwrap_or(false) || window.is_fullscreen().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowFrame { x: position.x, y: position.y, width: size.width, height: size.height })
+}
+
+/// Open, move and resize note windows to match `arrangement`. Windows not in
+/// it stay open and are only brought onscreen.
+pub async fn apply_arrangement(app: &AppHandle, arrangement: &WindowArrangement) -> Result<(), String> {
+    let window_service = app.state::<WindowService>();
+    let open: Vec<DetachedWindow> = app.state::<DetachedWindowsState>().lock().await.values().cloned().collect();
+    let existing_notes: Vec<String> = app.state::<NotesState>().lock().await.keys().cloned().collect();
+
+    if let (Some(frame), Some(main)) = (&arrangement.main_window, app.get_webview_window("main")) {
+        let _ = main.set_size(tauri::Size::Physical(tauri::PhysicalSize { width: frame.width, height: frame.height }));
+        let _ = main.set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: frame.x, y: frame.y }));
+    }
+
+    for saved in &arrangement.windows {
+        if !existing_notes.contains(&saved.note_id) {
+            continue;
+        }
+        let Some(current) = open.iter().find(|w| w.note_id == saved.note_id) else {
+            let request = CreateDetachedWindowRequest {
+                note_id: saved.note_id.clone(),
+                x: Some(saved.position.0),
+                y: Some(saved.position.1),
+                width: Some(saved.size.0),
+                height: Some(saved.size.1),
+            };
+            if let Err(e) = window_service.create_detached_window(request).await {
+                log_error!("WINDOW", "Failed to open {} for arrangement {}: {}", saved.note_id, arrangement.name, e);
+            }
+            continue;
+        };
+        // Docked windows stay on their edge
+        if current.dock.is_some() {
+            continue;
+        }
+        let Some(window) = app.get_webview_window(&current.window_label) else { continue };
+        let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: saved.size.0, height: saved.size.1 }));
+        let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: saved.position.0, y: saved.position.1 }));
+        window_service.update_size(&current.window_label, saved.size.0, saved.size.1).await?;
+        window_service.update_position(&current.window_label, saved.position.0, saved.position.1).await?;
+    }
+
+    for window in open.iter().filter(|w| !arrangement.windows.iter().any(|s| s.note_id == w.note_id)) {
+        if let Err(e) = ensure_window_onscreen(app, &window.window_label) {
+            log_debug!("WINDOW", "Failed to check {} is onscreen: {}", window.window_label, e);
+        }
+    }
+    log_info!("WINDOW", "Applied window arrangement {} ({} windows)", arrangement.name, arrangement.windows.len());
+    Ok(())
+}
+
+/// Apply the preset for the current displays if they changed since last time
+pub async fn check_display_change(app: &AppHandle) {
+    let monitor_count = current_monitor_geometries(app).len();
+    // Nothing reports while the displays sleep; that isn't a configuration
+    if monitor_count == 0 {
+        return;
+    }
+    let fingerprint = current_display_fingerprint(app);
+    {
+        let mut last = LAST_FINGERPRINT.lock().unwrap();
+        let first = last.is_none();
+        if last.as_deref() == Some(fingerprint.as_str()) {
+            return;
+        }
+        *last = Some(fingerprint.clone());
+        // Launching isn't a change; windows come back where they were
+        if first {
+            return;
+        }
+    }
+    log_info!("DISPLAY", "Display configuration changed to {} ({} monitors)", fingerprint, monitor_count);
+
+    let arrangements = match load_window_arrangements() {
+        Ok(arrangements) => arrangements,
+        Err(e) => {
+            log_error!("DISPLAY", "Failed to load window arrangements: {}", e);
+            return;
+        }
+    };
+    let Some(preset) = preset_for_display(&arrangements, &fingerprint, monitor_count) else {
+        log_debug!("DISPLAY", "No arrangement preset for {} monitors", monitor_count);
+        return;
+    };
+    match apply_arrangement(app, preset).await {
+        Ok(()) => {
+            let _ = app.emit("window-arrangement-applied", &preset.name);
+        }
+        Err(e) => log_error!("DISPLAY", "Failed to apply arrangement {}: {}", preset.name, e),
+    }
+}
+
+/// A window changed scale factor, which is what macOS reports when displays
+/// are plugged in, unplugged or rearranged
+pub fn on_display_event(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(DISPLAY_SETTLE).await;
+        check_display_change(&app).await;
+    });
+}
+
+/// Follow display changes, including those no window's scale factor shows
+pub fn start_display_watcher(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        check_display_change(&app).await;
+        loop {
+            tokio::time::sleep(power_scaled(DISPLAY_POLL, DISPLAY_POLL_LOW_POWER)).await;
+            check_display_change(&app).await;
+        }
+    });
+}
+
+/// Save the open note windows and main window under `name`. With
+/// `display_preset` it is applied whenever these displays are connected again.
+#[tauri::command]
+pub async fn save_window_arrangement(
+    window: tauri::Window,
+    app: AppHandle,
+    name: String,
+    display_preset: Option<bool>,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<WindowArrangement, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Arrangement name is empty".to_string());
+    }
+    let mut windows: Vec<DetachedWindow> = detached_windows.lock().await.values().cloned().collect();
+    windows.sort_by(|a, b| a.window_label.cmp(&b.window_label));
+    let display = display_preset.unwrap_or(false).then(|| DisplayPreset {
+        fingerprint: current_display_fingerprint(&app),
+        monitor_count: current_monitor_geometries(&app).len(),
+    });
+    let arrangement = WindowArrangement {
+        name,
+        windows,
+        main_window: main_window_frame(&app),
+        display,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let mut arrangements = load_window_arrangements()?;
+    upsert_arrangement(&mut arrangements, arrangement.clone());
+    save_window_arrangements(&arrangements)?;
+    log_info!("WINDOW", "Saved window arrangement {} ({} windows)", arrangement.name, arrangement.windows.len());
+    Ok(arrangement)
+}
+
+#[tauri::command]
+pub async fn apply_window_arrangement(app: AppHandle, name: String) -> Result<(), String> {
+    let arrangements = load_window_arrangements()?;
+    let arrangement = arrangements
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("Window arrangement not found: {}", name))?;
+    apply_arrangement(&app, arrangement).await
+}
+
+#[tauri::command]
+pub async fn list_window_arrangements() -> Result<Vec<WindowArrangement>, String> {
+    load_window_arrangements()
+}
+
+#[tauri::command]
+pub async fn delete_window_arrangement(window: tauri::Window, name: String) -> Result<(), String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let mut arrangements = load_window_arrangements()?;
+    let count = arrangements.len();
+    arrangements.retain(|a| a.name != name);
+    if arrangements.len() == count {
+        return Err(format!("Window arrangement not found: {}", name));
+    }
+    save_window_arrangements(&arrangements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrangement(name: &str, preset: Option<(&str, usize)>, saved_at: &str) -> WindowArrangement {
+        WindowArrangement {
+            name: name.to_string(),
+            windows: Vec::new(),
+            main_window: None,
+            display: preset.map(|(fingerprint, monitor_count)| DisplayPreset {
+                fingerprint: fingerprint.to_string(),
+                monitor_count,
+            }),
+            saved_at: saved_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_preset_prefers_exact_displays_then_monitor_count() {
+        let arrangements = vec![
+            arrangement("Writing", None, "2026-01-01"),
+            arrangement("Laptop", Some(("aaa", 1)), "2026-01-02"),
+            arrangement("Office", Some(("bbb", 2)), "2026-01-03"),
+            arrangement("Home desk", Some(("ccc", 2)), "2026-01-04"),
+        ];
+        assert_eq!(preset_for_display(&arrangements, "bbb", 2).unwrap().name, "Office");
+        assert_eq!(preset_for_display(&arrangements, "ddd", 2).unwrap().name, "Home desk");
+        assert_eq!(preset_for_display(&arrangements, "eee", 1).unwrap().name, "Laptop");
+        assert!(preset_for_display(&arrangements, "fff", 3).is_none());
+    }
+
+    #[test]
+    fn test_upsert_moves_display_to_newest_preset() {
+        let mut arrangements = vec![arrangement("Old", Some(("aaa", 1)), "1"), arrangement("Other", None, "1")];
+        upsert_arrangement(&mut arrangements, arrangement("New", Some(("aaa", 1)), "2"));
+        upsert_arrangement(&mut arrangements, arrangement("Other", None, "3"));
+
+        let names: Vec<&str> = arrangements.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["Old", "New", "Other"]);
+        assert!(arrangements[0].display.is_none());
+        assert_eq!(arrangements[2].saved_at, "3");
+    }
+}
@@ -0,0 +1,107 @@
+use tauri::{Runtime, Window};
+
+use crate::modules::about::ABOUT_WINDOW_LABEL;
+use crate::modules::note_palette::PALETTE_WINDOW_LABEL;
+use crate::modules::reading_mode::READING_WINDOW_PREFIX;
+use crate::modules::settings_window::SETTINGS_WINDOW_LABEL;
+use crate::modules::vault_picker::VAULT_PICKER_WINDOW_LABEL;
+use crate::services::window_service::FOLLOW_WINDOW_LABEL;
+use crate::log_warn;
+
+/// What kind of webview is calling a command, from its window label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowRole {
+    Main,
+    /// Detached note windows, pooled or not, and the follow window
+    Note,
+    Settings,
+    /// About and the note palette
+    Utility,
+    /// Reading mode windows, which show a note but never change it
+    Reader,
+    /// Drag ghosts and hybrid drag windows
+    Ghost,
+    Unknown,
+}
+
+/// Something a command does that not every window may ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    WriteNotes,
+    DeleteNotes,
+    WriteConfig,
+    /// Switching, importing into or repairing the vault
+    ManageVault,
+}
+
+impl WindowRole {
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "main" => WindowRole::Main,
+            // The vault picker switches vaults and pins them, like Settings does
+            SETTINGS_WINDOW_LABEL | VAULT_PICKER_WINDOW_LABEL => WindowRole::Settings,
+            ABOUT_WINDOW_LABEL | PALETTE_WINDOW_LABEL => WindowRole::Utility,
+            FOLLOW_WINDOW_LABEL => WindowRole::Note,
+            _ if label.starts_with("drag-ghost") || label.starts_with("hybrid-drag-") => WindowRole::Ghost,
+            _ if label.starts_with("note-") => WindowRole::Note,
+            _ if label.starts_with(READING_WINDOW_PREFIX) => WindowRole::Reader,
+            _ => WindowRole::Unknown,
+        }
+    }
+
+    /// The capability map. Reading notes is open to every window.
+    pub fn allows(self, capability: Capability) -> bool {
+        match self {
+            WindowRole::Main => true,
+            WindowRole::Note => capability == Capability::WriteNotes,
+            WindowRole::Settings => matches!(capability, Capability::WriteConfig | Capability::ManageVault),
+            WindowRole::Utility | WindowRole::Reader | WindowRole::Ghost | WindowRole::Unknown => false,
+        }
+    }
+}
+
+/// Refuse a command when the calling window's role does not grant `capability`
+pub fn authorize<R: Runtime>(window: &Window<R>, capability: Capability) -> Result<(), String> {
+    let label = window.label();
+    let role = WindowRole::from_label(label);
+    if role.allows(capability) {
+        return Ok(());
+    }
+    log_warn!("SECURITY", "Denied {:?} to window {} ({:?})", capability, label, role);
+    Err(format!("This window is not allowed to do that ({:?})", capability))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_from_labels() {
+        assert_eq!(WindowRole::from_label("main"), WindowRole::Main);
+        assert_eq!(WindowRole::from_label("note-abc"), WindowRole::Note);
+        assert_eq!(WindowRole::from_label("note-pool-3"), WindowRole::Note);
+        assert_eq!(WindowRole::from_label("follow-preview"), WindowRole::Note);
+        assert_eq!(WindowRole::from_label("drag-ghost-1712345678901"), WindowRole::Ghost);
+        assert_eq!(WindowRole::from_label("hybrid-drag-abc"), WindowRole::Ghost);
+        assert_eq!(WindowRole::from_label("palette"), WindowRole::Utility);
+        assert_eq!(WindowRole::from_label("settings"), WindowRole::Settings);
+        assert_eq!(WindowRole::from_label("vault-picker"), WindowRole::Settings);
+        assert_eq!(WindowRole::from_label("reading-abc"), WindowRole::Reader);
+        assert_eq!(WindowRole::from_label("something-else"), WindowRole::Unknown);
+    }
+
+    #[test]
+    fn test_capability_map() {
+        assert!(WindowRole::Main.allows(Capability::DeleteNotes));
+        assert!(WindowRole::Note.allows(Capability::WriteNotes));
+        assert!(!WindowRole::Note.allows(Capability::DeleteNotes));
+        assert!(!WindowRole::Note.allows(Capability::WriteConfig));
+        assert!(WindowRole::Settings.allows(Capability::WriteConfig));
+        assert!(!WindowRole::Settings.allows(Capability::WriteNotes));
+        for capability in [Capability::WriteNotes, Capability::DeleteNotes, Capability::WriteConfig, Capability::ManageVault] {
+            assert!(!WindowRole::Ghost.allows(capability));
+            assert!(!WindowRole::Reader.allows(capability));
+            assert!(!WindowRole::Unknown.allows(capability));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+use image::{ImageFormat, RgbaImage};
+use tauri::{AppHandle, State};
+
+use crate::modules::file_operations::allowed_roots;
+use crate::types::window::ConfigState;
+use crate::utils::paths::ensure_within;
+use crate::log_info;
+
+/// Convert premultiplied BGRA rows, as CoreGraphics hands out window images,
+/// to straight RGBA. Rows may be padded past `width * 4` bytes.
+pub fn bgra_premultiplied_to_rgba(data: &[u8], width: usize, height: usize, bytes_per_row: usize) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for row in data.chunks(bytes_per_row).take(height) {
+        for px in row[..width * 4].chunks_exact(4) {
+            let (b, g, r, a) = (px[0] as u32, px[1] as u32, px[2] as u32, px[3] as u32);
+            let unpremultiply = |c: u32| if a == 0 { 0 } else { ((c * 255 + a / 2) / a).min(255) as u8 };
+            rgba.extend_from_slice(&[unpremultiply(r), unpremultiply(g), unpremultiply(b), a as u8]);
+        }
+    }
+    rgba
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::c_void;
+    use cocoa::foundation::NSRect;
+
+    pub type CGImageRef = *mut c_void;
+    type CFDataRef = *const c_void;
+
+    /// Only the window passed in, without its shadow, at the display's resolution
+    pub const LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+    pub const IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+    pub const IMAGE_BEST_RESOLUTION: u32 = 1 << 3;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        pub static CGRectNull: NSRect;
+        pub fn CGWindowListCreateImage(bounds: NSRect, list_option: u32, window_id: u32, image_option: u32) -> CGImageRef;
+        pub fn CGImageGetWidth(image: CGImageRef) -> usize;
+        pub fn CGImageGetHeight(image: CGImageRef) -> usize;
+        pub fn CGImageGetBytesPerRow(image: CGImageRef) -> usize;
+        pub fn CGImageGetBitsPerPixel(image: CGImageRef) -> usize;
+        pub fn CGImageGetDataProvider(image: CGImageRef) -> *mut c_void;
+        pub fn CGDataProviderCopyData(provider: *mut c_void) -> CFDataRef;
+        pub fn CGImageRelease(image: CGImageRef);
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        pub fn CFDataGetBytePtr(data: CFDataRef) -> *const u8;
+        pub fn CFDataGetLength(data: CFDataRef) -> isize;
+        pub fn CFRelease(object: *const c_void);
+    }
+}
+
+/// Capture one of our windows with `CGWindowListCreateImage`. The window number
+/// is read on the main thread, as AppKit requires.
+#[cfg(target_os = "macos")]
+async fn capture_window(app: &AppHandle, label: &str) -> Result<RgbaImage, String> {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.run_on_main_thread(move || {
+        let number = window.ns_window().map(|ns_window| unsafe {
+            let number: isize = msg_send![ns_window as id, windowNumber];
+            number as u32
+        });
+        let _ = tx.send(number.map_err(|e| format!("No native window: {}", e)));
+    })
+    .map_err(|e| format!("Failed to reach the main thread: {}", e))?;
+    let window_id = rx.await.map_err(|_| "Window went away during capture".to_string())??;
+
+    tauri::async_runtime::spawn_blocking(move || unsafe {
+        use macos::*;
+
+        let image = CGWindowListCreateImage(
+            CGRectNull,
+            LIST_OPTION_INCLUDING_WINDOW,
+            window_id,
+            IMAGE_BOUNDS_IGNORE_FRAMING | IMAGE_BEST_RESOLUTION,
+        );
+        if image.is_null() {
+            return Err("The window could not be captured".to_string());
+        }
+        let (width, height) = (CGImageGetWidth(image), CGImageGetHeight(image));
+        let bytes_per_row = CGImageGetBytesPerRow(image);
+        if CGImageGetBitsPerPixel(image) != 32 || width == 0 || height == 0 {
+            CGImageRelease(image);
+            return Err("Unexpected window image format".to_string());
+        }
+
+        let data = CGDataProviderCopyData(CGImageGetDataProvider(image));
+        let bytes = std::slice::from_raw_parts(CFDataGetBytePtr(data), CFDataGetLength(data) as usize);
+        let rgba = bgra_premultiplied_to_rgba(bytes, width, height, bytes_per_row);
+        CFRelease(data);
+        CGImageRelease(image);
+
+        RgbaImage::from_raw(width as u32, height as u32, rgba).ok_or_else(|| "Window image was truncated".to_string())
+    })
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn capture_window(_app: &AppHandle, _label: &str) -> Result<RgbaImage, String> {
+    Err("Capturing windows is only supported on macOS".to_string())
+}
+
+/// Save a PNG snapshot of a rendered window, e.g. to share a styled note in chat.
+/// Returns the path written; `.png` is added when `path` has no extension.
+#[tauri::command]
+pub async fn capture_note_window(
+    app: AppHandle,
+    label: String,
+    path: String,
+    config: State<'_, ConfigState>,
+) -> Result<String, String> {
+    let mut path = PathBuf::from(path);
+    if path.extension().is_none() {
+        path.set_extension("png");
+    }
+    let path = ensure_within(&path, &allowed_roots(&*config.lock().await))?;
+
+    let image = capture_window(&app, &label).await?;
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log_info!("CAPTURE", "Captured {} ({}x{}) to {}", label, image.width(), image.height(), path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bgra_premultiplied_to_rgba() {
+        // One opaque red pixel and one half-transparent white pixel, rows padded to 12 bytes
+        let row = [0, 0, 255, 255, 128, 128, 128, 128, 9, 9, 9, 9];
+        let data = [row, row].concat();
+        let rgba = bgra_premultiplied_to_rgba(&data, 2, 2, 12);
+        assert_eq!(rgba.len(), 16);
+        assert_eq!(&rgba[..8], &[255, 0, 0, 255, 255, 255, 255, 128]);
+        assert_eq!(&rgba[8..], &rgba[..8]);
+    }
+}
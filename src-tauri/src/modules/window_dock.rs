@@ -0,0 +1,176 @@
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+
+use crate::modules::storage::save_detached_windows_to_disk;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::{DetachedWindow, DetachedWindowsState, DockEdge, WindowDock};
+use crate::utils::window_geometry::{docked_tab_rect, expanded_dock_rect, Rect};
+use crate::log_info;
+
+/// Width of a docked tab across the edge, in logical pixels
+const DOCK_TAB_THICKNESS: f64 = 28.0;
+/// Length of a docked tab along the edge, in logical pixels
+const DOCK_TAB_LENGTH: f64 = 160.0;
+
+/// Move and resize a window, in physical pixels
+fn set_geometry(window: &WebviewWindow, rect: Rect) -> Result<(), String> {
+    window
+        .set_size(tauri::Size::Physical(tauri::PhysicalSize { width: rect.width as u32, height: rect.height as u32 }))
+        .map_err(|e| format!("Failed to resize window: {}", e))?;
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: rect.x as i32, y: rect.y as i32 }))
+        .map_err(|e| format!("Failed to move window: {}", e))
+}
+
+/// The monitor the window is on, in physical pixels
+fn screen_rect(window: &WebviewWindow) -> Result<Rect, String> {
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("Window is not on a monitor")?;
+    Ok(Rect::new(
+        monitor.position().x as f64,
+        monitor.position().y as f64,
+        monitor.size().width as f64,
+        monitor.size().height as f64,
+    ))
+}
+
+fn note_window(app: &AppHandle, window_label: &str) -> Result<WebviewWindow, String> {
+    app.get_webview_window(window_label)
+        .ok_or_else(|| format!("Window {} not found", window_label))
+}
+
+async fn save_and_notify(
+    app: &AppHandle,
+    windows: &std::collections::HashMap<String, DetachedWindow>,
+    window_data: &DetachedWindow,
+) -> Result<(), String> {
+    save_detached_windows_to_disk(windows).await?;
+    app.emit("window-dock-changed", window_data).map_err(|e| e.to_string())
+}
+
+/// Put a recreated window back on its tab. Returns the dock to store, slid in
+/// whatever it was before.
+pub fn restore_dock(window: &WebviewWindow, dock: &WindowDock) -> Result<WindowDock, String> {
+    window.set_resizable(false).map_err(|e| e.to_string())?;
+    set_geometry(window, Rect::new(dock.tab_position.0, dock.tab_position.1, dock.tab_size.0, dock.tab_size.1))?;
+    Ok(WindowDock { expanded: false, ..dock.clone() })
+}
+
+/// Collapse a note window to a thin tab on a screen edge
+#[tauri::command]
+pub async fn dock_window(
+    window: tauri::Window,
+    window_label: String,
+    edge: DockEdge,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<DetachedWindow, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let mut windows_lock = detached_windows.lock().await;
+    let window_data = windows_lock
+        .get_mut(&window_label)
+        .ok_or_else(|| format!("Window data not found for {}", window_label))?;
+    let window = note_window(&app, &window_label)?;
+
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let screen = screen_rect(&window)?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let current = Rect::new(position.x as f64, position.y as f64, size.width as f64, size.height as f64);
+    // Remember the undocked geometry, unless this just moves the tab to another edge
+    if window_data.dock.is_none() {
+        let position = position.to_logical::<f64>(scale);
+        let size = size.to_logical::<f64>(scale);
+        window_data.position = (position.x, position.y);
+        window_data.size = (size.width, size.height);
+    }
+    let tab = docked_tab_rect(edge, screen, current, DOCK_TAB_THICKNESS * scale, DOCK_TAB_LENGTH * scale);
+
+    window.set_resizable(false).map_err(|e| e.to_string())?;
+    set_geometry(&window, tab)?;
+    window_data.dock = Some(WindowDock {
+        edge,
+        expanded: false,
+        tab_position: (tab.x, tab.y),
+        tab_size: (tab.width, tab.height),
+    });
+
+    let window_data = window_data.clone();
+    save_and_notify(&app, &windows_lock, &window_data).await?;
+    log_info!("WINDOW", "Docked {} to the {:?} edge", window_label, edge);
+    Ok(window_data)
+}
+
+/// Slide a docked window out from its tab, or back in. The frontend calls this
+/// on hover and click; the window stays docked either way.
+#[tauri::command]
+pub async fn set_dock_expanded(
+    window: tauri::Window,
+    window_label: String,
+    expanded: bool,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<DetachedWindow, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let mut windows_lock = detached_windows.lock().await;
+    let window_data = windows_lock
+        .get_mut(&window_label)
+        .ok_or_else(|| format!("Window data not found for {}", window_label))?;
+    let logical_size = window_data.size;
+    let dock = window_data.dock.as_mut().ok_or_else(|| format!("{} is not docked", window_label))?;
+    if dock.expanded == expanded {
+        return Ok(window_data.clone());
+    }
+
+    let window = note_window(&app, &window_label)?;
+    let tab = Rect::new(dock.tab_position.0, dock.tab_position.1, dock.tab_size.0, dock.tab_size.1);
+    let rect = if expanded {
+        let scale = window.scale_factor().map_err(|e| e.to_string())?;
+        let size = (logical_size.0 * scale, logical_size.1 * scale);
+        expanded_dock_rect(dock.edge, screen_rect(&window)?, tab, size)
+    } else {
+        tab
+    };
+    set_geometry(&window, rect)?;
+    if expanded {
+        let _ = window.set_focus();
+    }
+    dock.expanded = expanded;
+
+    let window_data = window_data.clone();
+    save_and_notify(&app, &windows_lock, &window_data).await?;
+    Ok(window_data)
+}
+
+/// Turn a docked tab back into a normal window where it was before docking
+#[tauri::command]
+pub async fn undock_window(
+    window: tauri::Window,
+    window_label: String,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<DetachedWindow, String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let mut windows_lock = detached_windows.lock().await;
+    let window_data = windows_lock
+        .get_mut(&window_label)
+        .ok_or_else(|| format!("Window data not found for {}", window_label))?;
+    if window_data.dock.take().is_none() {
+        return Ok(window_data.clone());
+    }
+
+    let window = note_window(&app, &window_label)?;
+    window
+        .set_size(tauri::Size::Logical(tauri::LogicalSize { width: window_data.size.0, height: window_data.size.1 }))
+        .map_err(|e| format!("Failed to resize window: {}", e))?;
+    window
+        .set_position(tauri::Position::Logical(tauri::LogicalPosition { x: window_data.position.0, y: window_data.position.1 }))
+        .map_err(|e| format!("Failed to move window: {}", e))?;
+    window.set_resizable(true).map_err(|e| e.to_string())?;
+
+    let window_data = window_data.clone();
+    save_and_notify(&app, &windows_lock, &window_data).await?;
+    log_info!("WINDOW", "Undocked {}", window_label);
+    Ok(window_data)
+}
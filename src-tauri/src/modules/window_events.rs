@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::storage::get_notes_directory;
+use crate::types::window::DetachedWindow;
+use crate::{log_debug, log_warn};
+
+/// Latest full copy of the window state; the log holds what changed since
+const SNAPSHOT_FILE_NAME: &str = "detached_windows.json";
+const LOG_FILE_NAME: &str = "window_events.jsonl";
+/// The log as it was before the last snapshot, kept for debugging
+const PREVIOUS_LOG_FILE_NAME: &str = "window_events.prev.jsonl";
+
+/// Events appended before the state is folded into a new snapshot
+const SNAPSHOT_EVERY: usize = 500;
+
+/// Something that happened to a detached window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum WindowLogEvent {
+    Opened { window: DetachedWindow },
+    /// Logs from older builds have no note ID on geometry events
+    Moved { label: String, #[serde(default)] note_id: String, position: (f64, f64) },
+    Resized { label: String, #[serde(default)] note_id: String, size: (f64, f64) },
+    /// Anything else changed, such as opacity, shading or docking
+    Updated { window: DetachedWindow },
+    Closed { label: String, note_id: String },
+}
+
+impl WindowLogEvent {
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Opened { window } | Self::Updated { window } => &window.window_label,
+            Self::Moved { label, .. } | Self::Resized { label, .. } | Self::Closed { label, .. } => label,
+        }
+    }
+
+    /// The note shown in the window, empty on geometry events from older logs
+    pub fn note_id(&self) -> &str {
+        match self {
+            Self::Opened { window } | Self::Updated { window } => &window.note_id,
+            Self::Moved { note_id, .. } | Self::Resized { note_id, .. } | Self::Closed { note_id, .. } => note_id,
+        }
+    }
+}
+
+/// One line of the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLogRecord {
+    pub at: String,
+    #[serde(flatten)]
+    pub event: WindowLogEvent,
+}
+
+/// What was last written, so the next save only logs the difference
+struct WindowLog {
+    dir: PathBuf,
+    windows: HashMap<String, DetachedWindow>,
+    pending: usize,
+}
+
+static WINDOW_LOG: Mutex<Option<WindowLog>> = Mutex::new(None);
+
+fn same_window(a: &DetachedWindow, b: &DetachedWindow) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// The events that turn `old` into `new`, ordered by label
+pub fn diff_windows(old: &HashMap<String, DetachedWindow>, new: &HashMap<String, DetachedWindow>) -> Vec<WindowLogEvent> {
+    let mut labels: Vec<&String> = old.keys().chain(new.keys().filter(|l| !old.contains_key(*l))).collect();
+    labels.sort();
+
+    let mut events = Vec::new();
+    for label in labels {
+        match (old.get(label), new.get(label)) {
+            (None, Some(window)) => events.push(WindowLogEvent::Opened { window: window.clone() }),
+            (Some(window), None) => events.push(WindowLogEvent::Closed { label: label.clone(), note_id: window.note_id.clone() }),
+            (Some(before), Some(after)) => {
+                let mut geometry_only = before.clone();
+                geometry_only.position = after.position;
+                geometry_only.size = after.size;
+                if !same_window(&geometry_only, after) {
+                    events.push(WindowLogEvent::Updated { window: after.clone() });
+                    continue;
+                }
+                if before.position != after.position {
+                    events.push(WindowLogEvent::Moved {
+                        label: label.clone(),
+                        note_id: after.note_id.clone(),
+                        position: after.position,
+                    });
+                }
+                if before.size != after.size {
+                    events.push(WindowLogEvent::Resized {
+                        label: label.clone(),
+                        note_id: after.note_id.clone(),
+                        size: after.size,
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    events
+}
+
+/// Apply one event. Events carry absolute values, so replaying one twice is harmless.
+pub fn apply_event(windows: &mut HashMap<String, DetachedWindow>, event: &WindowLogEvent) {
+    match event {
+        WindowLogEvent::Opened { window } | WindowLogEvent::Updated { window } => {
+            windows.insert(window.window_label.clone(), window.clone());
+        }
+        WindowLogEvent::Moved { label, position, .. } => {
+            if let Some(window) = windows.get_mut(label) {
+                window.position = *position;
+            }
+        }
+        WindowLogEvent::Resized { label, size, .. } => {
+            if let Some(window) = windows.get_mut(label) {
+                window.size = *size;
+            }
+        }
+        WindowLogEvent::Closed { label, .. } => {
+            windows.remove(label);
+        }
+    }
+}
+
+/// Read a log, skipping a line torn by a crash mid-write
+fn read_log(path: &Path) -> Vec<WindowLogRecord> {
+    let Ok(contents) = fs::read_to_string(path) else { return Vec::new() };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                log_warn!("WINDOW_LOG", "Skipping unreadable entry in {:?}: {}", path, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn read_snapshot(dir: &Path) -> Result<HashMap<String, DetachedWindow>, String> {
+    let path = dir.join(SNAPSHOT_FILE_NAME);
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read detached windows from disk: {}", e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse detached windows JSON: {}", e))
+}
+
+/// Rebuild the window state from the last snapshot and the events after it
+pub fn replay_window_state(dir: &Path) -> Result<HashMap<String, DetachedWindow>, String> {
+    let mut windows = read_snapshot(dir)?;
+    let records = read_log(&dir.join(LOG_FILE_NAME));
+    for record in &records {
+        apply_event(&mut windows, &record.event);
+    }
+    if !records.is_empty() {
+        log_debug!("WINDOW_LOG", "Replayed {} window events onto the snapshot", records.len());
+    }
+
+    *WINDOW_LOG.lock().unwrap() = Some(WindowLog { dir: dir.to_path_buf(), windows: windows.clone(), pending: records.len() });
+    Ok(windows)
+}
+
+/// Fold the state into a new snapshot and start a fresh log. The snapshot is
+/// written first, so a crash in between only replays events already in it.
+fn write_snapshot(dir: &Path, windows: &HashMap<String, DetachedWindow>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(windows).map_err(|e| format!("Failed to serialize detached windows: {}", e))?;
+    let temp = dir.join(format!("{}.tmp", SNAPSHOT_FILE_NAME));
+    fs::write(&temp, json).map_err(|e| format!("Failed to write detached windows to disk: {}", e))?;
+    fs::rename(&temp, dir.join(SNAPSHOT_FILE_NAME)).map_err(|e| format!("Failed to write detached windows to disk: {}", e))?;
+
+    let log = dir.join(LOG_FILE_NAME);
+    if log.exists() {
+        fs::rename(&log, dir.join(PREVIOUS_LOG_FILE_NAME)).map_err(|e| format!("Failed to rotate window log: {}", e))?;
+    }
+    Ok(())
+}
+
+fn append_events(dir: &Path, events: &[WindowLogEvent]) -> Result<(), String> {
+    let at = chrono::Utc::now().to_rfc3339();
+    let mut lines = String::new();
+    for event in events {
+        let record = WindowLogRecord { at: at.clone(), event: event.clone() };
+        lines.push_str(&serde_json::to_string(&record).map_err(|e| format!("Failed to serialize window event: {}", e))?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE_NAME))
+        .map_err(|e| format!("Failed to open window log: {}", e))?;
+    file.write_all(lines.as_bytes()).map_err(|e| format!("Failed to append to window log: {}", e))
+}
+
+/// Log how `windows` differs from what was last recorded, taking a snapshot
+/// every `SNAPSHOT_EVERY` events
+pub fn record_window_state(dir: &Path, windows: &HashMap<String, DetachedWindow>) -> Result<(), String> {
+    let mut guard = WINDOW_LOG.lock().unwrap();
+    if guard.as_ref().map_or(true, |log| log.dir != dir) {
+        drop(guard);
+        replay_window_state(dir)?;
+        guard = WINDOW_LOG.lock().unwrap();
+    }
+    let log = guard.as_mut().expect("window log was just loaded");
+
+    let events = diff_windows(&log.windows, windows);
+    if events.is_empty() {
+        return Ok(());
+    }
+    append_events(dir, &events)?;
+    log.windows = windows.clone();
+    log.pending += events.len();
+
+    if log.pending >= SNAPSHOT_EVERY {
+        write_snapshot(dir, windows)?;
+        log.pending = 0;
+        log_debug!("WINDOW_LOG", "Wrote window state snapshot");
+    }
+    Ok(())
+}
+
+/// The records about `note_id`. Pooled windows are re-targeted, so labels say
+/// nothing about the note; events without a note ID take the one last seen
+/// for their window.
+pub fn records_for_note(records: Vec<WindowLogRecord>, note_id: &str) -> Vec<WindowLogRecord> {
+    let mut shown: HashMap<String, String> = HashMap::new();
+    records
+        .into_iter()
+        .filter(|record| {
+            let event = &record.event;
+            let current = if event.note_id().is_empty() {
+                shown.get(event.label()).cloned().unwrap_or_default()
+            } else {
+                shown.insert(event.label().to_string(), event.note_id().to_string());
+                event.note_id().to_string()
+            };
+            current == note_id
+        })
+        .collect()
+}
+
+/// Recent window events, newest last, optionally only those for one note.
+/// Covers the current log and the one before the last snapshot.
+#[tauri::command]
+pub async fn get_window_history(note_id: Option<String>, limit: Option<usize>) -> Result<Vec<WindowLogRecord>, String> {
+    let dir = get_notes_directory()?;
+    let mut records: Vec<WindowLogRecord> = read_log(&dir.join(PREVIOUS_LOG_FILE_NAME))
+        .into_iter()
+        .chain(read_log(&dir.join(LOG_FILE_NAME)))
+        .collect();
+    if let Some(note_id) = note_id {
+        records = records_for_note(records, &note_id);
+    }
+    let skip = records.len().saturating_sub(limit.unwrap_or(200));
+    Ok(records.into_iter().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(note_id: &str, position: (f64, f64)) -> DetachedWindow {
+        DetachedWindow {
+            note_id: note_id.to_string(),
+            window_label: format!("note-{}", note_id),
+            position,
+            size: (800.0, 600.0),
+            always_on_top: false,
+            opacity: 1.0,
+            is_shaded: false,
+            original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
+        }
+    }
+
+    fn state(windows: &[DetachedWindow]) -> HashMap<String, DetachedWindow> {
+        windows.iter().map(|w| (w.window_label.clone(), w.clone())).collect()
+    }
+
+    #[test]
+    fn test_diff_windows() {
+        let old = state(&[window("a", (0.0, 0.0)), window("b", (10.0, 10.0))]);
+        let mut moved = window("a", (50.0, 0.0));
+        moved.size = (400.0, 300.0);
+        let mut faded = window("c", (0.0, 0.0));
+        let new = state(&[moved, faded.clone()]);
+
+        let kinds: Vec<String> = diff_windows(&old, &new)
+            .iter()
+            .map(|e| format!("{}:{}", serde_json::to_value(e).unwrap()["event"].as_str().unwrap(), e.label()))
+            .collect();
+        assert_eq!(kinds, ["moved:note-a", "resized:note-a", "closed:note-b", "opened:note-c"]);
+
+        faded.opacity = 0.5;
+        let events = diff_windows(&new, &state(&[window("a", (50.0, 0.0)), faded]));
+        assert!(matches!(&events[..], [WindowLogEvent::Resized { .. }, WindowLogEvent::Updated { window }] if window.opacity == 0.5));
+    }
+
+    #[test]
+    fn test_history_follows_notes_not_labels() {
+        let record = |event: WindowLogEvent| WindowLogRecord { at: String::new(), event };
+        let mut pooled = window("a", (0.0, 0.0));
+        pooled.window_label = "note-pool-1".to_string();
+        let mut retargeted = window("b", (0.0, 0.0));
+        retargeted.window_label = "note-pool-1".to_string();
+        let records = vec![
+            record(WindowLogEvent::Opened { window: pooled }),
+            // From an older log, without a note ID
+            record(serde_json::from_str(r#"{"event":"moved","label":"note-pool-1","position":[5.0,5.0]}"#).unwrap()),
+            record(WindowLogEvent::Closed { label: "note-pool-1".to_string(), note_id: "a".to_string() }),
+            record(WindowLogEvent::Opened { window: retargeted }),
+            record(WindowLogEvent::Resized { label: "note-pool-1".to_string(), note_id: "b".to_string(), size: (1.0, 1.0) }),
+        ];
+
+        let kinds = |records: Vec<WindowLogRecord>| -> Vec<String> {
+            records.iter().map(|r| serde_json::to_value(&r.event).unwrap()["event"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(kinds(records_for_note(records.clone(), "a")), ["opened", "moved", "closed"]);
+        assert_eq!(kinds(records_for_note(records, "b")), ["opened", "resized"]);
+    }
+
+    #[test]
+    fn test_replay_rebuilds_state() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = state(&[window("a", (0.0, 0.0))]);
+        write_snapshot(dir.path(), &first).unwrap();
+
+        let second = state(&[window("a", (20.0, 30.0)), window("b", (5.0, 5.0))]);
+        let third = state(&[window("b", (5.0, 5.0))]);
+        append_events(dir.path(), &diff_windows(&first, &second)).unwrap();
+        append_events(dir.path(), &diff_windows(&second, &third)).unwrap();
+        // A torn final line is ignored
+        fs::OpenOptions::new().append(true).open(dir.path().join(LOG_FILE_NAME)).unwrap().write_all(b"{\"at\":").unwrap();
+
+        let replayed = replay_window_state(dir.path()).unwrap();
+        assert_eq!(replayed.keys().collect::<Vec<_>>(), ["note-b"]);
+        assert!(same_window(&replayed["note-b"], &third["note-b"]));
+
+        // Replaying events already folded into a snapshot changes nothing
+        write_snapshot(dir.path(), &third).unwrap();
+        append_events(dir.path(), &diff_windows(&second, &third)).unwrap();
+        assert_eq!(replay_window_state(dir.path()).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,168 @@
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::modules::window_reveal::{reveal_when_ready, show_when_ready};
+use crate::state::WindowPoolState;
+use crate::types::window::{ConfigState, DetachedWindowsState};
+use crate::{log_debug, log_error, log_info};
+
+/// Label prefix of pooled windows. They keep their label when re-targeted, so
+/// lookups must go through the detached window state rather than the label.
+pub const POOL_LABEL_PREFIX: &str = "note-pool-";
+
+/// Hidden note windows kept warm for reuse
+#[derive(Debug, Default)]
+pub struct WindowPool {
+    pub idle: Vec<String>,
+    next_id: u64,
+}
+
+impl WindowPool {
+    fn next_label(&mut self) -> String {
+        self.next_id += 1;
+        format!("{}{}", POOL_LABEL_PREFIX, self.next_id)
+    }
+}
+
+pub fn is_pool_window(label: &str) -> bool {
+    label.starts_with(POOL_LABEL_PREFIX)
+}
+
+/// Pool size, or `None` when pooling is disabled
+async fn pool_capacity(app: &AppHandle) -> Option<usize> {
+    let config = app.state::<ConfigState>();
+    let config_lock = config.lock().await;
+    let pool = &config_lock.window_pool;
+    (pool.enabled && pool.max_size > 0).then_some(pool.max_size)
+}
+
+fn build_pool_window(app: &AppHandle, label: &str) -> Result<WebviewWindow, String> {
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App("index.html?pooled=true".into()))
+        .title("Note")
+        .inner_size(800.0, 600.0)
+        .visible(false)
+        .resizable(true)
+        .decorations(false)
+        .transparent(true)
+        .shadow(true)
+        .min_inner_size(400.0, 300.0)
+        .build()
+        .map_err(|e| format!("Failed to create pooled window: {}", e))?;
+
+    // The note shown changes over the window's life, so look it up when it goes away
+    let app_for_events = app.clone();
+    let label_for_events = label.to_string();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            let app = app_for_events.clone();
+            let label = label_for_events.clone();
+            tauri::async_runtime::spawn(async move {
+                app.state::<WindowPoolState>().lock().await.idle.retain(|l| l != &label);
+                let note_id = app
+                    .state::<DetachedWindowsState>()
+                    .lock()
+                    .await
+                    .get(&label)
+                    .map(|w| w.note_id.clone());
+                if let Some(note_id) = note_id {
+                    app.emit("window-destroyed", &note_id).unwrap_or_else(|e| {
+                        log_error!("WINDOW_LIFECYCLE", "Failed to emit window-destroyed event: {}", e);
+                    });
+                }
+            });
+        }
+    });
+
+    Ok(window)
+}
+
+/// Top the pool up to its configured size in the background
+pub fn prewarm_window_pool(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(capacity) = pool_capacity(&app).await else { return };
+        let pool_state = app.state::<WindowPoolState>();
+        let mut pool = pool_state.lock().await;
+
+        while pool.idle.len() < capacity {
+            let label = pool.next_label();
+            match build_pool_window(&app, &label) {
+                Ok(_) => {
+                    log_debug!("WINDOW_POOL", "Pre-warmed {}", label);
+                    pool.idle.push(label);
+                }
+                Err(e) => {
+                    log_error!("WINDOW_POOL", "{}", e);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Take a warm window from the pool and point it at `note_id`. Returns `None`
+/// when pooling is disabled or the pool is empty; the caller creates a window as usual.
+pub async fn take_pooled_window(
+    app: &AppHandle,
+    note_id: &str,
+    title: &str,
+    position: (f64, f64),
+    size: (f64, f64),
+) -> Option<WebviewWindow> {
+    pool_capacity(app).await?;
+
+    let window = loop {
+        let label = app.state::<WindowPoolState>().lock().await.idle.pop()?;
+        if let Some(window) = app.get_webview_window(&label) {
+            break window;
+        }
+    };
+
+    // The page reloads for the new note; keep it hidden through that unless told not to
+    let defer_show = show_when_ready(app).await;
+    let url = serde_json::to_string(&format!("index.html?note={}", note_id)).ok()?;
+    let retargeted = window.eval(&format!("window.location.replace({})", url)).is_ok()
+        && window.set_title(title).is_ok()
+        && window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: size.0, height: size.1 })).is_ok()
+        && window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: position.0, y: position.1 })).is_ok()
+        && (defer_show || window.show().is_ok());
+
+    if !retargeted {
+        log_error!("WINDOW_POOL", "Failed to re-target {}, discarding it", window.label());
+        let _ = window.close();
+        return None;
+    }
+
+    if defer_show {
+        reveal_when_ready(app, &window);
+    } else {
+        let _ = window.set_focus();
+    }
+    log_info!("WINDOW_POOL", "Reused {} for note {}", window.label(), note_id);
+
+    // Replace the window we just used
+    prewarm_window_pool(app);
+    Some(window)
+}
+
+/// Return a pooled window to the pool instead of closing it. Returns false when
+/// the window should be closed normally.
+pub async fn recycle_window(app: &AppHandle, label: &str) -> bool {
+    if !is_pool_window(label) {
+        return false;
+    }
+    let Some(capacity) = pool_capacity(app).await else { return false };
+    let Some(window) = app.get_webview_window(label) else { return false };
+
+    let pool_state = app.state::<WindowPoolState>();
+    let mut pool = pool_state.lock().await;
+    if pool.idle.len() >= capacity {
+        return false;
+    }
+
+    if window.hide().is_err() || window.eval("window.location.replace('index.html?pooled=true')").is_err() {
+        return false;
+    }
+    pool.idle.push(label.to_string());
+    log_debug!("WINDOW_POOL", "Recycled {} ({} idle)", label, pool.idle.len());
+    true
+}
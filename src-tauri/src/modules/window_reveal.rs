@@ -0,0 +1,64 @@
+use std::time::Duration;
+use serde::Deserialize;
+use tauri::{AppHandle, Listener, Manager, WebviewWindow};
+
+use crate::types::window::ConfigState;
+use crate::{log_debug, log_error, log_warn, WindowRevealState};
+
+/// How long a hidden window waits for its webview before being shown anyway
+const FRONTEND_READY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Payload of `frontend-ready`, emitted by a note window once its content has rendered
+#[derive(Debug, Deserialize)]
+struct FrontendReady {
+    label: String,
+}
+
+/// Whether new note windows should stay hidden until their content has rendered
+pub async fn show_when_ready(app: &AppHandle) -> bool {
+    !app.state::<ConfigState>().lock().await.instant_show
+}
+
+/// Show a hidden window once its webview reports `frontend-ready`, or after a
+/// timeout so a failed load never leaves it hidden for good
+pub fn reveal_when_ready(app: &AppHandle, window: &WebviewWindow) {
+    let label = window.label().to_string();
+    app.state::<WindowRevealState>().lock().unwrap().insert(label.clone());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FRONTEND_READY_TIMEOUT).await;
+        if reveal(&app, &label) {
+            log_warn!("WINDOW", "{} did not report frontend-ready, showing it anyway", label);
+        }
+    });
+}
+
+/// Show a window waiting on `reveal_when_ready`. Returns false when it was not waiting.
+fn reveal(app: &AppHandle, label: &str) -> bool {
+    if !app.state::<WindowRevealState>().lock().unwrap().remove(label) {
+        return false;
+    }
+    let Some(window) = app.get_webview_window(label) else { return false };
+    if let Err(e) = window.show() {
+        log_error!("WINDOW", "Failed to show {}: {}", label, e);
+        return false;
+    }
+    let _ = window.set_focus();
+    true
+}
+
+/// Route `frontend-ready` from any webview to the window waiting on it
+pub fn listen_for_frontend_ready(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.listen_any("frontend-ready", move |event| {
+        match serde_json::from_str::<FrontendReady>(event.payload()) {
+            Ok(ready) => {
+                if reveal(&app_handle, &ready.label) {
+                    log_debug!("WINDOW", "Showed {} after frontend-ready", ready.label);
+                }
+            }
+            Err(e) => log_error!("WINDOW", "Invalid frontend-ready payload: {}", e),
+        }
+    });
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fs;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, Theme};
+
+use crate::modules::storage::get_notes_directory;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::window::{ConfigState, DetachedWindowsState};
+use crate::{log_error, log_info};
+
+const WINDOW_THEMES_FILE: &str = "window_themes.json";
+
+/// Light or dark appearance, for the system or one window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowTheme {
+    Light,
+    Dark,
+}
+
+impl From<Theme> for WindowTheme {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Light => WindowTheme::Light,
+            _ => WindowTheme::Dark,
+        }
+    }
+}
+
+impl From<WindowTheme> for Theme {
+    fn from(theme: WindowTheme) -> Self {
+        match theme {
+            WindowTheme::Light => Theme::Light,
+            WindowTheme::Dark => Theme::Dark,
+        }
+    }
+}
+
+/// Payload of `theme-changed`
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeChanged {
+    pub theme: WindowTheme,
+}
+
+fn load_window_themes() -> HashMap<String, WindowTheme> {
+    let Ok(notes_dir) = get_notes_directory() else { return HashMap::new() };
+    fs::read_to_string(notes_dir.join(WINDOW_THEMES_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_window_themes(themes: &HashMap<String, WindowTheme>) -> Result<(), String> {
+    let notes_dir = get_notes_directory()?;
+    fs::create_dir_all(&notes_dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+    let json = serde_json::to_string_pretty(themes)
+        .map_err(|e| format!("Failed to serialize window themes: {}", e))?;
+    fs::write(notes_dir.join(WINDOW_THEMES_FILE), json)
+        .map_err(|e| format!("Failed to write window themes: {}", e))
+}
+
+/// Theme a note's window should open with: its override, else the app theme.
+/// `None` follows the system.
+pub async fn initial_window_theme(app: &AppHandle, note_id: &str) -> Option<Theme> {
+    if let Some(theme) = load_window_themes().get(note_id) {
+        return Some((*theme).into());
+    }
+    match app.state::<ConfigState>().lock().await.appearance.theme.as_str() {
+        "light" => Some(Theme::Light),
+        "dark" => Some(Theme::Dark),
+        _ => None,
+    }
+}
+
+/// Forward appearance changes from the main window, which follows the system
+pub fn emit_theme_changed(app: &AppHandle, theme: Theme) {
+    let theme = WindowTheme::from(theme);
+    log_info!("THEME", "System appearance changed to {:?}", theme);
+    app.emit("theme-changed", ThemeChanged { theme }).unwrap_or_else(|e| {
+        log_error!("THEME", "Failed to emit theme-changed event: {}", e);
+    });
+}
+
+/// Current light/dark appearance of the system
+#[tauri::command]
+pub async fn get_system_theme(app: AppHandle) -> Result<WindowTheme, String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    let theme = window.theme().map_err(|e| format!("Failed to read system theme: {}", e))?;
+    Ok(theme.into())
+}
+
+/// The theme override for a note's window, if any
+#[tauri::command]
+pub async fn get_window_theme(note_id: String) -> Result<Option<WindowTheme>, String> {
+    Ok(load_window_themes().get(&note_id).copied())
+}
+
+/// Override the theme of a note's window, or clear the override with `None`.
+/// Applies to the open window now and to windows opened for the note later.
+#[tauri::command]
+pub async fn set_window_theme(
+    window: tauri::Window,
+    note_id: String,
+    theme: Option<WindowTheme>,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<(), String> {
+    authorize(&window, Capability::WriteNotes)?;
+    let mut themes = load_window_themes();
+    match theme {
+        Some(theme) => themes.insert(note_id.clone(), theme),
+        None => themes.remove(&note_id),
+    };
+    save_window_themes(&themes)?;
+
+    let labels: Vec<String> = detached_windows
+        .lock()
+        .await
+        .values()
+        .filter(|w| w.note_id == note_id)
+        .map(|w| w.window_label.clone())
+        .collect();
+    let native_theme = initial_window_theme(&app, &note_id).await;
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            window.set_theme(native_theme).map_err(|e| format!("Failed to set window theme: {}", e))?;
+        }
+    }
+
+    log_info!("THEME", "Theme override for {} set to {:?}", note_id, theme);
+    Ok(())
+}
@@ -1,10 +1,22 @@
-use std::fs;
 use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, Emitter};
 
 use crate::types::{
-    window::{DetachedWindow, DetachedWindowsState, NotesState, ConfigState, ToggleState, CreateDetachedWindowRequest},
+    config::MIN_NOTE_WINDOW_SIZE,
+    note::Note,
+    window::{DetachedWindow, DetachedWindowsState, NotesState, ConfigState, ToggleState, CursorDeployState, CreateDetachedWindowRequest, RevealTarget},
 };
-use crate::modules::storage::{get_configured_notes_directory, save_config_to_disk, save_detached_windows_to_disk, load_detached_windows_from_disk, get_default_notes_directory};
+use crate::modules::storage::{get_configured_notes_directory, save_config_to_disk, save_detached_windows_to_disk, load_detached_windows_from_disk};
+use crate::modules::drag_session::queue_drag_position;
+use crate::modules::settings_window::is_utility_window;
+use crate::modules::confirmation::require_confirmation;
+use crate::state::ConfirmationTokenState;
+use crate::modules::debug_tools::ensure_diagnostics_unlocked;
+use crate::modules::display_layouts::ensure_window_onscreen;
+use crate::modules::recent_notes::note_opened;
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::modules::window_dock::restore_dock;
+use crate::services::window_service::{cursor_window_position, WindowService};
+use crate::handlers::window_handler::{load_spatial_data, save_spatial_data};
 use crate::{log_info, log_error, log_debug};
 
 #[cfg(target_os = "macos")]
@@ -79,20 +91,9 @@ pub async fn force_main_window_visible(app: AppHandle) -> Result<(), String> {
         e.to_string()
     })?;
     
-    // Center the window
-    window.center().map_err(|e| {
-        log_error!("DEBUG", "Failed to center window: {}", e);
-        e.to_string()
-    })?;
-    
-    // Set proper size (match tauri.conf.json defaults)
-    window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
-        width: 1000,
-        height: 700,
-    })).map_err(|e| {
-        log_error!("DEBUG", "Failed to set window size: {}", e);
-        e.to_string()
-    })?;
+    // Put it back where the user left it, nudged into view if that's offscreen
+    let saved = app.state::<ConfigState>().lock().await.window.clone();
+    crate::startup::place_main_window(&app, &window, &saved, true);
     
     // Ensure it's not minimized
     if window.is_minimized().unwrap_or(false) {
@@ -124,6 +125,7 @@ pub async fn force_main_window_visible(app: AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn debug_webview_state(app: AppHandle) -> Result<String, String> {
+    ensure_diagnostics_unlocked()?;
     let window = app.get_webview_window("main").ok_or("Main window not found")?;
     
     let mut debug_info = String::new();
@@ -164,6 +166,7 @@ pub async fn debug_all_windows_state(
     app: AppHandle,
     detached_windows: State<'_, DetachedWindowsState>,
 ) -> Result<String, String> {
+    ensure_diagnostics_unlocked()?;
     let mut debug_info = String::new();
     
     // Get all webview windows from Tauri
@@ -386,9 +389,9 @@ pub async fn recreate_missing_windows(
     
     result.push_str(&format!("=== RECREATING MISSING WINDOWS ===\n"));
     
-    let detached_windows_lock = detached_windows.lock().await;
+    let mut detached_windows_lock = detached_windows.lock().await;
+    let mut docks_restored = false;
     let windows_to_recreate: Vec<_> = detached_windows_lock.iter()
-        .filter(|(label, _)| !label.starts_with("hybrid-drag-")) // Skip hybrid drag windows
         .filter(|(label, _)| !webview_windows.contains_key(*label)) // Only missing windows
         .map(|(label, window_data)| (label.clone(), window_data.clone()))
         .collect();
@@ -402,6 +405,7 @@ pub async fn recreate_missing_windows(
         
         // Create the window URL
         let window_url = format!("/?note={}", window_data.note_id);
+        let window_title = note_window_title(&app, &window_data.note_id).await;
         
         // Create the webview window
         match WebviewWindowBuilder::new(
@@ -409,9 +413,9 @@ pub async fn recreate_missing_windows(
             &label,
             WebviewUrl::App(window_url.into()),
         )
-        .title(&format!("Note - {}", window_data.note_id))
+        .title(&window_title)
         .inner_size(window_data.size.0, window_data.size.1)
-        .position(100.0, 100.0) // Use safe position instead of stored position
+        .position(window_data.position.0, window_data.position.1)
         .visible(true)
         .resizable(true)
         .decorations(false)
@@ -422,6 +426,26 @@ pub async fn recreate_missing_windows(
             Ok(window) => {
                 result.push_str("  ✓ Window created successfully\n");
                 
+                match ensure_window_onscreen(&app, &label) {
+                    Ok(true) => result.push_str("  ✓ Moved back onscreen\n"),
+                    Ok(false) => {}
+                    Err(e) => result.push_str(&format!("  ⚠ Failed to check position: {}\n", e)),
+                }
+                
+                // Docked windows go back to their tab
+                if let Some(dock) = &window_data.dock {
+                    match restore_dock(&window, dock) {
+                        Ok(dock) => {
+                            result.push_str(&format!("  ✓ Docked to the {:?} edge\n", dock.edge));
+                            if let Some(stored) = detached_windows_lock.get_mut(&label) {
+                                stored.dock = Some(dock);
+                                docks_restored = true;
+                            }
+                        }
+                        Err(e) => result.push_str(&format!("  ⚠ Failed to dock window: {}\n", e)),
+                    }
+                }
+                
                 // Show and focus the window
                 if let Err(e) = window.show() {
                     result.push_str(&format!("  ⚠ Failed to show window: {}\n", e));
@@ -456,20 +480,9 @@ pub async fn recreate_missing_windows(
         result.push_str("\n");
     }
     
-    // Clean up hybrid drag windows from state
-    let hybrid_windows: Vec<_> = detached_windows_lock.keys()
-        .filter(|label| label.starts_with("hybrid-drag-"))
-        .cloned()
-        .collect();
-    
-    drop(detached_windows_lock); // Release lock before modification
-    
-    if !hybrid_windows.is_empty() {
-        result.push_str(&format!("Cleaning up {} hybrid drag windows from state\n", hybrid_windows.len()));
-        let mut detached_windows_lock = detached_windows.lock().await;
-        for label in hybrid_windows {
-            detached_windows_lock.remove(&label);
-            result.push_str(&format!("  ✓ Removed hybrid window: {}\n", label));
+    if docks_restored {
+        if let Err(e) = save_detached_windows_to_disk(&detached_windows_lock).await {
+            result.push_str(&format!("⚠ Failed to save dock state: {}\n", e));
         }
     }
     
@@ -478,44 +491,32 @@ pub async fn recreate_missing_windows(
     Ok(result)
 }
 
+/// Close drag windows left behind by an interrupted drag. They are never tracked
+/// in `DetachedWindowsState`; a finished drag becomes a `note-<id>` window.
 #[tauri::command]
-pub async fn cleanup_stale_hybrid_windows(
-    app: AppHandle,
-    detached_windows: State<'_, DetachedWindowsState>,
-) -> Result<String, String> {
+pub async fn cleanup_stale_hybrid_windows(app: AppHandle) -> Result<String, String> {
     let mut result = String::new();
     result.push_str("=== CLEANING UP STALE HYBRID WINDOWS ===\n");
     
-    let mut windows_lock = detached_windows.lock().await;
-    let hybrid_labels: Vec<String> = windows_lock.keys()
-        .filter(|k| k.starts_with("hybrid-drag-"))
-        .cloned()
+    let hybrid_windows: Vec<_> = app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label.starts_with("hybrid-drag-"))
         .collect();
     
-    result.push_str(&format!("Found {} hybrid windows to clean up\n", hybrid_labels.len()));
+    result.push_str(&format!("Found {} hybrid windows to clean up\n", hybrid_windows.len()));
     
-    for window_label in hybrid_labels {
-        // Close the Tauri window
-        if let Some(window) = app.get_webview_window(&window_label) {
-            window.close().map_err(|e| format!("Failed to close window: {}", e))?;
-            result.push_str(&format!("✓ Closed Tauri window: {}\n", window_label));
-        }
-        
-        // Remove from backend state
-        windows_lock.remove(&window_label);
-        result.push_str(&format!("✓ Removed from backend state: {}\n", window_label));
+    for (window_label, window) in hybrid_windows {
+        window.close().map_err(|e| format!("Failed to close window: {}", e))?;
+        result.push_str(&format!("✓ Closed Tauri window: {}\n", window_label));
     }
     
-    // Save state
-    save_detached_windows_to_disk(&windows_lock).await?;
-    result.push_str("✓ Saved state to disk\n");
-    
     result.push_str("=== CLEANUP COMPLETE ===\n");
     Ok(result)
 }
 
 #[tauri::command]
 pub async fn list_all_windows(app: AppHandle) -> Result<Vec<String>, String> {
+    ensure_diagnostics_unlocked()?;
     let webview_windows = app.webview_windows();
     let mut window_list = Vec::new();
     
@@ -587,10 +588,8 @@ pub async fn test_window_events(app: AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn force_create_detached_window(
-    app: AppHandle,
     note_id: String,
-    detached_windows: State<'_, DetachedWindowsState>,
-    notes: State<'_, NotesState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<(), String> {
     log_info!("DEBUG", "Force creating detached window for note: {}", note_id);
     
@@ -602,7 +601,7 @@ pub async fn force_create_detached_window(
         height: Some(400.0),
     };
     
-    create_detached_window(request, app, detached_windows, notes).await.map(|_| ())
+    window_service.create_detached_window(request).await.map(|_| ())
 }
 
 #[tauri::command]
@@ -759,6 +758,8 @@ pub async fn test_detached_window_creation(
                 opacity: 1.0,
                 is_shaded: false,
                 original_height: None,
+                dock: None,
+                visible_on_all_workspaces: false,
             };
             
             let mut detached_windows_lock = detached_windows.lock().await;
@@ -782,6 +783,7 @@ pub async fn get_window_state_truth(
     app: AppHandle,
     detached_windows: State<'_, DetachedWindowsState>,
 ) -> Result<String, String> {
+    ensure_diagnostics_unlocked()?;
     let mut truth = String::new();
     
     truth.push_str("=== WINDOW STATE TRUTH ===\n\n");
@@ -839,8 +841,7 @@ pub async fn get_window_state_truth(
         truth.push_str(&format!("Label: {}\n", label));
         truth.push_str(&format!("  Note ID: {}\n", window_data.note_id));
         truth.push_str(&format!("  Type: {}\n", 
-            if label.starts_with("hybrid-drag-") { "HYBRID" } 
-            else if label.starts_with("note-") { "DETACHED" }
+            if label.starts_with("note-") { "DETACHED" }
             else { "UNKNOWN" }
         ));
         truth.push_str(&format!("  Stored Position: ({}, {})\n", window_data.position.0, window_data.position.1));
@@ -863,7 +864,7 @@ pub async fn get_window_state_truth(
     // Windows in Tauri but not in backend
     let mut tauri_only = Vec::new();
     for label in tauri_windows.keys() {
-        if !backend_windows.contains_key(label) && (label.starts_with("note-") || label.starts_with("hybrid-drag-")) {
+        if !backend_windows.contains_key(label) && label.starts_with("note-") {
             tauri_only.push(label);
         }
     }
@@ -892,21 +893,6 @@ pub async fn get_window_state_truth(
         truth.push_str("\n");
     }
     
-    // Hybrid windows that should be cleaned
-    let mut stale_hybrids = Vec::new();
-    for label in backend_windows.keys() {
-        if label.starts_with("hybrid-drag-") {
-            stale_hybrids.push(label);
-        }
-    }
-    
-    if !stale_hybrids.is_empty() {
-        truth.push_str("Stale hybrid windows in backend:\n");
-        for label in stale_hybrids {
-            truth.push_str(&format!("  - {} (SHOULD BE CLEANED)\n", label));
-        }
-    }
-    
     truth.push_str("\n=== END WINDOW STATE TRUTH ===\n");
     
     log_info!("STATE_TRUTH", "Generated window state truth report");
@@ -941,7 +927,7 @@ pub async fn reload_main_window(app: AppHandle) -> Result<(), String> {
 pub async fn toggle_all_windows_hover(
     app: AppHandle,
     detached_windows: State<'_, DetachedWindowsState>,
-    notes: State<'_, NotesState>,
+    window_service: State<'_, WindowService>,
     toggle_state: State<'_, ToggleState>,
 ) -> Result<bool, String> {
     // Check if a toggle is already in progress
@@ -971,9 +957,9 @@ pub async fn toggle_all_windows_hover(
             log_info!("HOVER", "Hiding all windows...");
             main_window.hide().map_err(|e| format!("Failed to hide main window: {}", e))?;
             
-            // Hide all detached windows
+            // Hide all detached windows; utility windows like Settings stay put
             let windows_lock = detached_windows.lock().await;
-            for (window_label, _) in windows_lock.iter() {
+            for (window_label, _) in windows_lock.iter().filter(|(label, _)| !is_utility_window(label)) {
                 if let Some(window) = app.get_webview_window(window_label) {
                     let _ = window.hide();
                 }
@@ -987,7 +973,11 @@ pub async fn toggle_all_windows_hover(
             
             // Show or restore all detached windows
             let windows_lock = detached_windows.lock().await;
-            let windows_to_restore: Vec<DetachedWindow> = windows_lock.values().cloned().collect();
+            let windows_to_restore: Vec<DetachedWindow> = windows_lock
+                .iter()
+                .filter(|(label, _)| !is_utility_window(label))
+                .map(|(_, w)| w.clone())
+                .collect();
             drop(windows_lock);
             
             for window_data in windows_to_restore {
@@ -1005,7 +995,7 @@ pub async fn toggle_all_windows_hover(
                         width: Some(window_data.size.0),
                         height: Some(window_data.size.1),
                     };
-                    let _ = create_detached_window(request, app.clone(), detached_windows.clone(), notes.clone()).await;
+                    let _ = window_service.create_detached_window(request).await;
                 }
             }
             Ok(true)
@@ -1255,6 +1245,10 @@ pub async fn update_hybrid_drag_position(
     x: f64,
     y: f64,
 ) -> Result<(), String> {
+    // During a drag session the move is coalesced and applied on the next frame
+    if queue_drag_position(&app, &window_label, (x, y)).await {
+        return Ok(());
+    }
     if let Some(window) = app.get_webview_window(&window_label) {
         window.set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: x as i32, y: y as i32 }))
             .map_err(|e| e.to_string())?;
@@ -1262,63 +1256,45 @@ pub async fn update_hybrid_drag_position(
     Ok(())
 }
 
+/// Turn a dropped drag window into a regular detached window. Labels can't be
+/// renamed, so the window is recreated as `note-<id>` with the drag window's geometry.
 #[tauri::command]
 pub async fn finalize_hybrid_drag_window(
     app: AppHandle,
     window_label: String,
     note_id: String,
-    detached_windows: State<'_, DetachedWindowsState>,
-    notes: State<'_, NotesState>,
-) -> Result<(), String> {
+    window_service: State<'_, WindowService>,
+) -> Result<DetachedWindow, String> {
     log_info!("DRAG", "Finalizing hybrid drag window '{}' for note '{}'", window_label, note_id);
     
-    // Instead of closing and recreating, just register this window as a detached window
-    if let Some(window) = app.get_webview_window(&window_label) {
-        // Get current position and size
-        let pos = window.outer_position().map_err(|e| e.to_string())?;
-        let size = window.inner_size().map_err(|e| e.to_string())?;
-        
-        // Change the window label to the standard format
-        let _new_label = format!("note-{}", note_id);
-        
-        // Since we can't rename a window, we'll track it with its current label
-        // but treat it as a detached window
-        let detached_window = DetachedWindow {
-            note_id: note_id.clone(),
-            window_label: window_label.clone(), // Keep the hybrid-drag label
-            position: (pos.x as f64, pos.y as f64),
-            size: (size.width as f64, size.height as f64),
-            always_on_top: false,
-            opacity: 1.0,
-            is_shaded: false,
-            original_height: None,
-        };
-        
-        // Update the window to act like a normal detached window
-        window.set_title(&format!("Note - {}", note_id)).map_err(|e| e.to_string())?;
-        window.set_resizable(true).map_err(|e| e.to_string())?;
-        window.set_always_on_top(false).map_err(|e| e.to_string())?;
-        
-        // Save to state
-        let mut windows_lock = detached_windows.lock().await;
-        windows_lock.insert(window_label.clone(), detached_window.clone());
-        save_detached_windows_to_disk(&windows_lock).await?;
-        
-        // Update the app menu
-        drop(windows_lock);
-        update_app_menu(app.clone(), detached_windows.clone(), notes.clone()).await?;
-        
-        // Note: Window position/size tracking is now handled by the frontend useWindowTracking hook
-        // with proper debouncing to avoid excessive file I/O operations
-        
-        // Emit event to notify frontend
-        app.emit("window-created", note_id.clone()).map_err(|e| e.to_string())?;
-        
-        log_info!("DRAG", "Window finalized in place as detached window");
-        Ok(())
+    let window = app.get_webview_window(&window_label).ok_or("Drag window not found")?;
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    let pos = window.outer_position().map_err(|e| e.to_string())?.to_logical::<f64>(scale_factor);
+    let size = window.inner_size().map_err(|e| e.to_string())?.to_logical::<f64>(scale_factor);
+    window.close().map_err(|e| format!("Failed to close drag window: {}", e))?;
+    
+    // Drag windows smaller than the minimum note window open at the default size
+    let settings = app.state::<ConfigState>().lock().await.new_windows.clone();
+    let (width, height) = if size.width < MIN_NOTE_WINDOW_SIZE.0 || size.height < MIN_NOTE_WINDOW_SIZE.1 {
+        (settings.width, settings.height)
     } else {
-        Err("Drag window not found".to_string())
-    }
+        (size.width, size.height)
+    };
+    
+    let request = CreateDetachedWindowRequest {
+        note_id: note_id.clone(),
+        x: Some(pos.x),
+        y: Some(pos.y),
+        width: Some(width),
+        height: Some(height),
+    };
+    let detached_window = window_service.create_detached_window(request).await?;
+    
+    // Emit event to notify frontend
+    app.emit("window-created", note_id.clone()).map_err(|e| e.to_string())?;
+    
+    log_info!("DRAG", "Drag window '{}' finalized as '{}'", window_label, detached_window.window_label);
+    Ok(detached_window)
 }
 
 #[tauri::command]
@@ -1355,6 +1331,7 @@ pub async fn restore_detached_windows(
                 Ok(visible) => {
                     if !visible {
                         println!("[RESTORE_WINDOWS] Showing hidden window: {}", window_label);
+                        ensure_window_onscreen(&app, window_label)?;
                         window.show().map_err(|e| e.to_string())?;
                         window.set_focus().map_err(|e| e.to_string())?;
                         restored_windows.push(window_label.clone());
@@ -1398,9 +1375,15 @@ pub async fn restore_detached_windows(
 
 #[tauri::command]
 pub async fn clear_all_detached_windows(
+    window: tauri::Window,
     app: AppHandle,
+    confirmation_token: Option<String>,
     detached_windows: State<'_, DetachedWindowsState>,
+    confirmations: State<'_, ConfirmationTokenState>,
 ) -> Result<i32, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    require_confirmation(&confirmations, confirmation_token.as_deref(), "clear_all_detached_windows").await?;
+    
     let mut windows_lock = detached_windows.lock().await;
     let window_count = windows_lock.len() as i32;
     
@@ -1427,329 +1410,156 @@ pub async fn clear_all_detached_windows(
 #[tauri::command]
 pub async fn focus_detached_window(
     note_id: String,
-    app: AppHandle,
-    detached_windows: State<'_, DetachedWindowsState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<bool, String> {
-    let windows_lock = detached_windows.lock().await;
-    println!("[FOCUS_DETACHED_WINDOW] Looking for note: {}", note_id);
-    
-    // Find window by note_id (only in note-* windows, not hybrid-drag)
-    if let Some((window_label, _window_data)) = windows_lock.iter().find(|(label, w)| {
-        label.starts_with("note-") && w.note_id == note_id
-    }) {
-        println!("[FOCUS_DETACHED_WINDOW] Found window in state: {} -> {}", window_label, note_id);
-        
-        if let Some(window) = app.get_webview_window(window_label) {
-            println!("[FOCUS_DETACHED_WINDOW] ✅ Tauri window found, attempting to focus...");
-            
-            // Show and focus the window
-            window.show().map_err(|e| format!("Failed to show window: {}", e))?;
-            window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
-            
-            // If window is minimized, restore it
-            if window.is_minimized().unwrap_or(false) {
-                window.unminimize().map_err(|e| format!("Failed to unminimize window: {}", e))?;
-            }
-            
-            println!("[FOCUS_DETACHED_WINDOW] ✅ Successfully focused window for note: {}", note_id);
-            log_info!("WINDOW", "Focused existing detached window for note: {}", note_id);
-            return Ok(true);
-        } else {
-            println!("[FOCUS_DETACHED_WINDOW] ❌ Window found in state but Tauri window doesn't exist: {}", window_label);
-            println!("[FOCUS_DETACHED_WINDOW] ❌ Window may have been closed but not cleaned up from state");
+    window_service.focus_detached_window(&note_id).await
+}
+
+/// Bring a note to the front: focus its detached window if one is open,
+/// otherwise show the main window and ask it to select the note.
+/// Shared by the menu, tray, shortcuts and any other "show me this note" entry point.
+pub async fn reveal_note_in_app(app: &AppHandle, note_id: &str) -> Result<RevealTarget, String> {
+    let label = {
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let windows_lock = detached_windows.lock().await;
+        windows_lock
+            .iter()
+            .find(|(label, w)| label.starts_with("note-") && w.note_id == note_id)
+            .map(|(label, _)| label.clone())
+    };
+
+    if let Some(window) = label.and_then(|label| app.get_webview_window(&label)) {
+        if window.is_minimized().unwrap_or(false) {
+            window.unminimize().map_err(|e| format!("Failed to unminimize window: {}", e))?;
         }
-    } else {
-        println!("[FOCUS_DETACHED_WINDOW] ❌ No note window found in state for note: {}", note_id);
+        window.show().map_err(|e| format!("Failed to show window: {}", e))?;
+        window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+        log_info!("WINDOW", "Revealed note {} in its detached window", note_id);
+        note_opened(app, note_id).await;
+        return Ok(RevealTarget::Detached);
+    }
+
+    let main_window = app.get_webview_window("main").ok_or("Main window not found")?;
+    if main_window.is_minimized().unwrap_or(false) {
+        main_window.unminimize().map_err(|e| format!("Failed to unminimize main window: {}", e))?;
+    }
+    main_window.show().map_err(|e| format!("Failed to show main window: {}", e))?;
+    main_window.set_focus().map_err(|e| format!("Failed to focus main window: {}", e))?;
+    main_window
+        .emit("reveal-note", note_id)
+        .map_err(|e| format!("Failed to emit reveal-note event: {}", e))?;
+
+    log_info!("WINDOW", "Revealed note {} in the main window", note_id);
+    note_opened(app, note_id).await;
+    Ok(RevealTarget::Main)
+}
+
+/// Show a note wherever it lives (detached window or main list)
+#[tauri::command]
+pub async fn reveal_note(
+    note_id: String,
+    app: AppHandle,
+    notes: State<'_, NotesState>,
+) -> Result<RevealTarget, String> {
+    if !notes.lock().await.contains_key(&note_id) {
+        return Err(format!("Note not found: {}", note_id));
     }
-    
-    println!("[FOCUS_DETACHED_WINDOW] ❌ Failed to focus window for note: {}", note_id);
-    log_info!("WINDOW", "No existing detached window found for note: {}", note_id);
-    Ok(false)
+    reveal_note_in_app(&app, &note_id).await
 }
 
 #[tauri::command]
 pub async fn create_detached_window(
     request: CreateDetachedWindowRequest,
-    app: AppHandle,
-    detached_windows: State<'_, DetachedWindowsState>,
-    notes: State<'_, NotesState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<DetachedWindow, String> {
-    println!("[CREATE_DETACHED_WINDOW] Starting window creation for note: {}", request.note_id);
-    println!("[CREATE_DETACHED_WINDOW] Request params: x={:?}, y={:?}, width={:?}, height={:?}", 
-        request.x, request.y, request.width, request.height);
-    
-    // Clean up any existing drag ghost window first
-    if let Some(ghost_window) = app.get_webview_window("drag-ghost") {
-        println!("[CREATE_DETACHED_WINDOW] Found existing drag ghost window, closing it...");
-        let _ = ghost_window.close();
-    }
-    
-    // Check if note exists
-    {
-        println!("[CREATE_DETACHED_WINDOW] Checking if note exists...");
-        let notes_lock = notes.lock().await;
-        if !notes_lock.contains_key(&request.note_id) {
-            println!("[CREATE_DETACHED_WINDOW] ERROR: Note not found: {}", request.note_id);
-            return Err("Note not found".to_string());
-        }
-        println!("[CREATE_DETACHED_WINDOW] Note exists ✓");
-    }
+    window_service.create_detached_window(request).await
+}
 
-    // Check if window already exists for this note
-    let mut windows_lock = detached_windows.lock().await;
-    println!("[CREATE_DETACHED_WINDOW] Current windows count: {}", windows_lock.len());
-    println!("[CREATE_DETACHED_WINDOW] === BACKEND WINDOWS STATE ===");
-    for (window_label, window) in windows_lock.iter() {
-        println!("[CREATE_DETACHED_WINDOW] Backend window: {} -> note_id: {}, position: ({}, {})", 
-            window_label, window.note_id, window.position.0, window.position.1);
-    }
-    println!("[CREATE_DETACHED_WINDOW] === END BACKEND WINDOWS STATE ===");
-    
-    // Only check for actual note windows (not hybrid-drag windows)
-    let existing_note_window = windows_lock
-        .iter()
-        .find(|(window_label, window)| {
-            window_label.starts_with("note-") && window.note_id == request.note_id
-        });
-    
-    if existing_note_window.is_some() {
-        println!("[CREATE_DETACHED_WINDOW] ERROR: Note window already exists for note: {}", request.note_id);
-        return Err("Window already exists for this note".to_string());
-    }
-    println!("[CREATE_DETACHED_WINDOW] No existing note window for this note ✓");
+/// Deploys within this window of time of each other are cascaded
+const CURSOR_CASCADE_INTERVAL_MS: u128 = 1500;
 
-    let window_label = format!("note-{}", request.note_id);
-    println!("[CREATE_DETACHED_WINDOW] Window label: {}", window_label);
-    
-    // Check if we have a saved position for this note
-    println!("[CREATE_DETACHED_WINDOW] Loading saved spatial data...");
-    let saved_window = load_spatial_data(&request.note_id).await;
-    
-    // Use requested dimensions first, then saved, then defaults
-    let width = request.width.unwrap_or_else(|| saved_window.as_ref().map(|w| w.size.0).unwrap_or(800.0));
-    let height = request.height.unwrap_or_else(|| saved_window.as_ref().map(|w| w.size.1).unwrap_or(600.0));
-    
-    // For position: if provided in request, use it; otherwise use saved position or calculate offset
-    let (mut x, mut y) = if request.x.is_some() && request.y.is_some() {
-        (request.x.unwrap(), request.y.unwrap())
-    } else if let Some(saved) = saved_window.as_ref() {
-        (saved.position.0, saved.position.1)
-    } else {
-        // Calculate position to avoid overlapping with existing windows
-        let offset = windows_lock.len() as f64 * 30.0;
-        (100.0 + offset, 100.0 + offset)
+/// Create a detached window for a note centered under the mouse cursor
+#[tauri::command]
+pub async fn detach_note_at_cursor(
+    note_id: String,
+    app: AppHandle,
+    window_service: State<'_, WindowService>,
+    cascade: State<'_, CursorDeployState>,
+    config: State<'_, ConfigState>,
+) -> Result<DetachedWindow, String> {
+    log_info!("WINDOW", "Detaching note {} at cursor position", note_id);
+    let settings = config.lock().await.new_windows.clone();
+    
+    // Cascade windows deployed in quick succession
+    let step = {
+        let mut cascade_lock = cascade.lock().await;
+        let now = std::time::Instant::now();
+        let is_rapid = cascade_lock.last_deploy
+            .map_or(false, |last| now.duration_since(last).as_millis() < CURSOR_CASCADE_INTERVAL_MS);
+        cascade_lock.step = if is_rapid {
+            (cascade_lock.step + 1) % settings.max_cascade.max(1)
+        } else {
+            0
+        };
+        cascade_lock.last_deploy = Some(now);
+        cascade_lock.step
     };
+    let offset = settings.cascade_offset(step as usize);
     
-    // Check if the position would overlap with existing windows
-    let mut needs_offset = false;
-    for (_, window) in windows_lock.iter() {
-        let dx = (window.position.0 - x).abs();
-        let dy = (window.position.1 - y).abs();
-        // If windows are too close (within 50 pixels), offset the new window
-        if dx < 50.0 && dy < 50.0 {
-            needs_offset = true;
-            break;
-        }
-    }
-    
-    if needs_offset {
-        // Offset by 30 pixels from the requested position
-        x += 30.0;
-        y += 30.0;
-        println!("[CREATE_DETACHED_WINDOW] Offsetting window position to avoid overlap");
-    }
-    
-    println!("[CREATE_DETACHED_WINDOW] Window dimensions: {}x{} at ({}, {})", width, height, x, y);
-
-    // Create the window
-    println!("[CREATE_DETACHED_WINDOW] Creating WebviewWindow...");
-    let window_url = format!("index.html?note={}", request.note_id);
-    println!("[CREATE_DETACHED_WINDOW] Window URL: {}", window_url);
-    
-    // Create window with custom title bar
-    println!("[CREATE_DETACHED_WINDOW] Building window...");
-    let webview_window = WebviewWindowBuilder::new(
-        &app,
-        &window_label,
-        WebviewUrl::App(window_url.into()),
-    )
-    .title(&format!("Note - {}", request.note_id))
-    .inner_size(width, height)
-    .position(x, y)
-    .visible(true)
-    .resizable(true)     // Enable window resizing
-    .decorations(false)  // Disable native decorations for custom title bar
-    .transparent(true)   // Enable transparency for custom window styling
-    .shadow(true)        // Enable window shadow
-    .min_inner_size(400.0, 300.0)  // Minimum size for proper display
-    .build()
-    .map_err(|e| {
-        println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to create window: {:?}", e);
-        format!("Failed to create window: {}", e)
-    })?;
-    
-    println!("[CREATE_DETACHED_WINDOW] WebviewWindow created successfully ✓");
-    
-    // Ensure the window is visible
-    println!("[CREATE_DETACHED_WINDOW] Showing window...");
-    webview_window.show().map_err(|e| {
-        println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to show window: {:?}", e);
-        format!("Failed to show window: {}", e)
-    })?;
-    println!("[CREATE_DETACHED_WINDOW] Window shown ✓");
+    let (width, height) = (settings.width, settings.height);
+    let (x, y) = cursor_window_position(&app, (width, height), offset)?;
     
-    // Set focus to ensure it's brought to front
-    webview_window.set_focus().map_err(|e| {
-        println!("[CREATE_DETACHED_WINDOW] WARNING: Failed to set focus: {:?}", e);
-        e.to_string()
-    }).unwrap_or_else(|e| {
-        println!("[CREATE_DETACHED_WINDOW] Focus warning: {}", e);
-    });
+    log_debug!("WINDOW", "Cascade step {}, placing window at ({}, {})", step, x, y);
     
-    // Verify window is actually visible
-    match webview_window.is_visible() {
-        Ok(visible) => println!("[CREATE_DETACHED_WINDOW] Window visibility check: {}", visible),
-        Err(e) => println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to check visibility: {:?}", e),
-    }
-
-    let detached_window = DetachedWindow {
-        note_id: request.note_id.clone(),
-        window_label: window_label.clone(),
-        position: (x, y),
-        size: (width, height),
-        always_on_top: false,
-        opacity: 1.0,
-        is_shaded: false,
-        original_height: None,
+    let request = CreateDetachedWindowRequest {
+        note_id,
+        x: Some(x),
+        y: Some(y),
+        width: Some(width),
+        height: Some(height),
     };
-    println!("[CREATE_DETACHED_WINDOW] DetachedWindow struct created: {:?}", detached_window);
-
-    println!("[CREATE_DETACHED_WINDOW] Inserting window into state...");
-    windows_lock.insert(window_label.clone(), detached_window.clone());
-    println!("[CREATE_DETACHED_WINDOW] Window inserted into state ✓");
-    
-    println!("[CREATE_DETACHED_WINDOW] Saving detached windows to disk...");
-    save_detached_windows_to_disk(&windows_lock).await.map_err(|e| {
-        println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to save windows to disk: {}", e);
-        e
-    })?;
-    println!("[CREATE_DETACHED_WINDOW] Windows saved to disk ✓");
-    
-    // Update the app menu to include the new window
-    drop(windows_lock);
-    println!("[CREATE_DETACHED_WINDOW] Updating app menu...");
-    update_app_menu(app.clone(), detached_windows.clone(), notes.clone()).await.map_err(|e| {
-        println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to update app menu: {}", e);
-        e
-    })?;
-    println!("[CREATE_DETACHED_WINDOW] App menu updated ✓");
-    
-    // Set up window event listeners for lifecycle tracking
-    let window_label_for_events = window_label.clone();
-    let app_handle_for_events = app.clone();
-    let note_id_for_events = request.note_id.clone();
     
-    webview_window.on_window_event(move |event| {
-        match event {
-            tauri::WindowEvent::Destroyed => {
-                log_info!("WINDOW_LIFECYCLE", "Window {} destroyed via OS", window_label_for_events);
-                let note_id = note_id_for_events.clone();
-                let app = app_handle_for_events.clone();
-                
-                // Simply emit the event - let the frontend handle state cleanup
-                // This avoids the lifetime issue with accessing state in the closure
-                app.emit("window-destroyed", &note_id).unwrap_or_else(|e| {
-                    log_error!("WINDOW_LIFECYCLE", "Failed to emit window-destroyed event: {}", e);
-                });
-                
-                log_info!("WINDOW_LIFECYCLE", "Emitted window-destroyed event for note {}", note_id);
-            },
-            tauri::WindowEvent::CloseRequested { api: _, .. } => {
-                log_info!("WINDOW_LIFECYCLE", "Window {} close requested", window_label_for_events);
-                // Allow the close - the Destroyed event will handle cleanup
-            },
-            _ => {}
-        }
-    });
-    
-    println!("[CREATE_DETACHED_WINDOW] Window lifecycle listeners attached ✓");
-    
-    // Note: Window position/size tracking is now handled by the frontend useWindowTracking hook
-    // with proper debouncing to avoid excessive file I/O operations
-    println!("[CREATE_DETACHED_WINDOW] Window tracking delegated to frontend (debounced) ✓");
-
-    println!("[CREATE_DETACHED_WINDOW] Window creation completed successfully! Returning: {:?}", detached_window);
-    Ok(detached_window)
+    window_service.create_detached_window(request).await
 }
 
 #[tauri::command]
 pub async fn cleanup_destroyed_window(
     note_id: String,
-    detached_windows: State<'_, DetachedWindowsState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<(), String> {
-    let mut windows_lock = detached_windows.lock().await;
-    
-    // Find and remove window by note_id
-    let window_label = format!("note-{}", note_id);
-    if windows_lock.remove(&window_label).is_some() {
-        log_info!("WINDOW_LIFECYCLE", "Cleaned up destroyed window state for note {}", note_id);
-        save_detached_windows_to_disk(&windows_lock).await?;
-    }
-    
-    Ok(())
+    window_service.cleanup_destroyed_window(&note_id).await
 }
 
 #[tauri::command]
 pub async fn close_detached_window(
     note_id: String,
-    app: AppHandle,
-    detached_windows: State<'_, DetachedWindowsState>,
-    notes: State<'_, NotesState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<bool, String> {
-    let mut windows_lock = detached_windows.lock().await;
-    
-    // Find window by note_id
-    let window_label = if let Some((label, _)) = windows_lock.iter().find(|(_, w)| w.note_id == note_id) {
-        label.clone()
-    } else {
-        return Ok(false);
-    };
-
-    // Close the actual window
-    if let Some(window) = app.get_webview_window(&window_label) {
-        window.close().map_err(|e| format!("Failed to close window: {}", e))?;
-    }
-
-    // Remove from state
-    windows_lock.remove(&window_label);
-    save_detached_windows_to_disk(&windows_lock).await?;
-    
-    // Update the app menu to remove the closed window
-    drop(windows_lock);
-    update_app_menu(app.clone(), detached_windows.clone(), notes.clone()).await?;
-    
-    // Emit event to all windows to notify frontend
-    app.emit("window-closed", note_id.clone()).map_err(|e| e.to_string())?;
-    log_info!("WINDOW", "Emitted window-closed event for note: {}", note_id);
+    window_service.close_detached_window(&note_id).await
+}
 
-    Ok(true)
+/// Snap a window the user just stopped dragging to a nearby screen edge or
+/// Blink window, in logical coordinates. Returns where it was moved to.
+#[tauri::command]
+pub async fn snap_detached_window(
+    window_label: String,
+    x: f64,
+    y: f64,
+    window_service: State<'_, WindowService>,
+) -> Result<Option<(f64, f64)>, String> {
+    window_service.snap_window(&window_label, x, y).await
 }
 
+/// Record a detached window's logical position. Returns the snapped position
+/// when the window was close enough to a screen edge or another Blink window.
 #[tauri::command]
 pub async fn update_detached_window_position(
     window_label: String,
     x: f64,
     y: f64,
-    detached_windows: State<'_, DetachedWindowsState>,
-) -> Result<(), String> {
-    let mut windows_lock = detached_windows.lock().await;
-    
-    if let Some(window) = windows_lock.get_mut(&window_label) {
-        window.position = (x, y);
-        save_detached_windows_to_disk(&windows_lock).await?;
-    }
-    
-    Ok(())
+    window_service: State<'_, WindowService>,
+) -> Result<Option<(f64, f64)>, String> {
+    window_service.update_position(&window_label, x, y).await
 }
 
 #[tauri::command]
@@ -1757,16 +1567,9 @@ pub async fn update_detached_window_size(
     window_label: String,
     width: f64,
     height: f64,
-    detached_windows: State<'_, DetachedWindowsState>,
+    window_service: State<'_, WindowService>,
 ) -> Result<(), String> {
-    let mut windows_lock = detached_windows.lock().await;
-    
-    if let Some(window) = windows_lock.get_mut(&window_label) {
-        window.size = (width, height);
-        save_detached_windows_to_disk(&windows_lock).await?;
-    }
-    
-    Ok(())
+    window_service.update_size(&window_label, width, height).await
 }
 
 // ============================================================================
@@ -1821,6 +1624,29 @@ pub async fn toggle_window_shade(
     }
 }
 
+/// Show a detached note on every Space / virtual desktop (macOS `CanJoinAllSpaces`,
+/// the sticky hint on Linux) so reference notes follow the user around
+#[tauri::command]
+pub async fn set_window_visible_on_all_workspaces(
+    window_label: String,
+    visible: bool,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+) -> Result<(), String> {
+    let mut windows_lock = detached_windows.lock().await;
+    let window_data = windows_lock.get_mut(&window_label)
+        .ok_or_else(|| format!("Window data not found for {}", window_label))?;
+    let window = app.get_webview_window(&window_label)
+        .ok_or_else(|| format!("Window {} not found", window_label))?;
+
+    window.set_visible_on_all_workspaces(visible)
+        .map_err(|e| format!("Failed to set workspace visibility: {}", e))?;
+    window_data.visible_on_all_workspaces = visible;
+    log_debug!("WINDOW", "{} visible on all workspaces: {}", window_label, visible);
+
+    save_detached_windows_to_disk(&windows_lock).await
+}
+
 #[tauri::command]
 pub async fn toggle_main_window_shade(
     app: AppHandle,
@@ -1868,50 +1694,46 @@ pub async fn toggle_main_window_shade(
 }
 
 // ============================================================================
-// SPATIAL DATA FUNCTIONS
+// HELPER FUNCTIONS
 // ============================================================================
 
-/// Load spatial data for a specific note
-async fn load_spatial_data(note_id: &str) -> Option<DetachedWindow> {
-    let notes_dir = get_default_notes_directory().ok()?;
-    let spatial_file = notes_dir.join(format!("spatial_{}.json", note_id));
-    
-    if !spatial_file.exists() {
-        return None;
-    }
-    
-    let spatial_json = fs::read_to_string(spatial_file).ok()?;
-    serde_json::from_str(&spatial_json).ok()
+/// Title for a note's window: icon and title, so Mission Control and alt-tab stay readable
+pub(crate) async fn note_window_title(app: &AppHandle, note_id: &str) -> String {
+    app.state::<NotesState>()
+        .lock()
+        .await
+        .get(note_id)
+        .map(|note| note.display_title())
+        .unwrap_or_else(|| "Untitled Note".to_string())
 }
 
-/// Save spatial data for a specific note
-async fn save_spatial_data(note_id: &str, window_data: &DetachedWindow) -> Result<(), String> {
-    let notes_dir = get_default_notes_directory()?;
-    fs::create_dir_all(&notes_dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
-    
-    let spatial_file = notes_dir.join(format!("spatial_{}.json", note_id));
-    let spatial_json = serde_json::to_string_pretty(window_data)
-        .map_err(|e| format!("Failed to serialize spatial data: {}", e))?;
-    
-    fs::write(spatial_file, spatial_json)
-        .map_err(|e| format!("Failed to write spatial data to disk: {}", e))?;
-    
-    Ok(())
+/// Retitle every open window showing `note`, after its title or icon changed
+pub(crate) async fn refresh_note_window_titles(app: &AppHandle, note: &Note) {
+    let title = note.display_title();
+    let labels: Vec<String> = app
+        .state::<DetachedWindowsState>()
+        .lock()
+        .await
+        .values()
+        .filter(|w| w.note_id == note.id)
+        .map(|w| w.window_label.clone())
+        .collect();
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            if let Err(e) = window.set_title(&title) {
+                log_error!("WINDOW", "Failed to retitle {}: {}", label, e);
+            }
+        }
+    }
 }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
-
 /// Update the app menu to include detached windows
 async fn update_app_menu(
-    _app: AppHandle,
-    _detached_windows: State<'_, DetachedWindowsState>,
-    _notes: State<'_, NotesState>,
+    app: AppHandle,
+    detached_windows: State<'_, DetachedWindowsState>,
+    notes: State<'_, NotesState>,
 ) -> Result<(), String> {
-    // For now, just return Ok - menu functionality would be implemented here
-    // This is a placeholder to satisfy the function calls
-    Ok(())
+    crate::handlers::menu_handler::update_app_menu(app, detached_windows, notes).await
 }
 
 // ============================================================================
@@ -1920,10 +1742,10 @@ async fn update_app_menu(
 
 /// Currently unused - position tracking handled by frontend with debouncing
 #[allow(dead_code)]
-async fn save_window_position(note_id: String, x: f64, y: f64) -> Result<(), String> {
-    if let Some(mut window_data) = load_spatial_data(&note_id).await {
+async fn save_window_position(notes_dir: &std::path::Path, note_id: String, x: f64, y: f64) -> Result<(), String> {
+    if let Some(mut window_data) = load_spatial_data(notes_dir, &note_id).await {
         window_data.position = (x, y);
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     } else {
         // Create new spatial data if none exists
         let window_data = DetachedWindow {
@@ -1935,18 +1757,20 @@ async fn save_window_position(note_id: String, x: f64, y: f64) -> Result<(), Str
             opacity: 1.0,
             is_shaded: false,
             original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
         };
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     }
     Ok(())
 }
 
 /// Currently unused - size tracking handled by frontend with debouncing
 #[allow(dead_code)]
-async fn save_window_size(note_id: String, width: f64, height: f64) -> Result<(), String> {
-    if let Some(mut window_data) = load_spatial_data(&note_id).await {
+async fn save_window_size(notes_dir: &std::path::Path, note_id: String, width: f64, height: f64) -> Result<(), String> {
+    if let Some(mut window_data) = load_spatial_data(notes_dir, &note_id).await {
         window_data.size = (width, height);
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     } else {
         // Create new spatial data if none exists
         let window_data = DetachedWindow {
@@ -1958,8 +1782,10 @@ async fn save_window_size(note_id: String, width: f64, height: f64) -> Result<()
             opacity: 1.0,
             is_shaded: false,
             original_height: None,
+            dock: None,
+            visible_on_all_workspaces: false,
         };
-        save_spatial_data(&note_id, &window_data).await?;
+        save_spatial_data(notes_dir, &note_id, &window_data).await?;
     }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::modules::storage::get_configured_notes_directory;
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::types::config::AppConfig;
+use crate::types::window::ConfigState;
+use crate::types::workspace::WorkspaceSettings;
+use crate::VaultLockState;
+use crate::{log_info, log_warn};
+use crate::modules::window_capabilities::{authorize, Capability};
+
+/// AppConfig keys that describe the machine rather than the vault.
+/// A workspace can never override these, so opening a synced vault
+/// doesn't move windows, rebind shortcuts or redirect storage.
+const MACHINE_ONLY_KEYS: &[&str] = &["window", "shortcuts", "storage", "opacity", "alwaysOnTop", "readOnly"];
+
+fn settings_path(notes_dir: &Path) -> PathBuf {
+    notes_dir.join(".blink").join("settings.json")
+}
+
+/// Load `.blink/settings.json`, falling back to empty settings
+pub fn load_workspace_settings(notes_dir: &Path) -> WorkspaceSettings {
+    let path = settings_path(notes_dir);
+    if !path.exists() {
+        return WorkspaceSettings::default();
+    }
+
+    match fs::read_to_string(&path).map(|json| serde_json::from_str(&json)) {
+        Ok(Ok(settings)) => settings,
+        Ok(Err(e)) => {
+            log_warn!("WORKSPACE", "Ignoring invalid workspace settings {:?}: {}", path, e);
+            WorkspaceSettings::default()
+        }
+        Err(e) => {
+            log_warn!("WORKSPACE", "Failed to read workspace settings {:?}: {}", path, e);
+            WorkspaceSettings::default()
+        }
+    }
+}
+
+/// Write `.blink/settings.json`
+pub fn save_workspace_settings(notes_dir: &Path, settings: &WorkspaceSettings) -> Result<(), String> {
+    let path = settings_path(notes_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .blink directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize workspace settings: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write workspace settings: {}", e))
+}
+
+/// Recursively merge `overlay` into `base`; objects merge key by key, everything else replaces
+fn deep_merge(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base_map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Apply workspace overrides on top of the app-level config.
+///
+/// Precedence, lowest to highest: built-in defaults, app config (`config.json`),
+/// workspace `configOverrides`. Machine-only keys are always taken from the app config,
+/// and overrides that would produce an invalid config are ignored as a whole.
+pub fn merge_workspace_config(app_config: &AppConfig, settings: &WorkspaceSettings) -> AppConfig {
+    if settings.config_overrides.is_empty() {
+        return app_config.clone();
+    }
+
+    let mut overrides = settings.config_overrides.clone();
+    for key in MACHINE_ONLY_KEYS {
+        overrides.remove(*key);
+    }
+
+    let mut merged = match serde_json::to_value(app_config) {
+        Ok(value) => value,
+        Err(_) => return app_config.clone(),
+    };
+    deep_merge(&mut merged, &Value::Object(overrides));
+
+    serde_json::from_value(merged).unwrap_or_else(|e| {
+        log_warn!("WORKSPACE", "Ignoring workspace config overrides: {}", e);
+        app_config.clone()
+    })
+}
+
+/// App config with the current vault's workspace overrides applied
+pub fn effective_config(app_config: &AppConfig) -> AppConfig {
+    match get_configured_notes_directory(app_config) {
+        Ok(notes_dir) => merge_workspace_config(app_config, &load_workspace_settings(&notes_dir)),
+        Err(_) => app_config.clone(),
+    }
+}
+
+/// Get the settings stored with the current vault
+#[tauri::command]
+pub async fn get_workspace_settings(config: State<'_, ConfigState>) -> Result<WorkspaceSettings, String> {
+    let config_lock = config.lock().await;
+    let notes_dir = get_configured_notes_directory(&config_lock)?;
+    Ok(load_workspace_settings(&notes_dir))
+}
+
+/// Replace the settings stored with the current vault
+#[tauri::command]
+pub async fn update_workspace_settings(
+    window: tauri::Window,
+    app: AppHandle,
+    settings: WorkspaceSettings,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<WorkspaceSettings, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    ensure_vault_writable(&config, &vault_lock).await?;
+
+    let notes_dir = {
+        let config_lock = config.lock().await;
+        get_configured_notes_directory(&config_lock)?
+    };
+    save_workspace_settings(&notes_dir, &settings)?;
+    log_info!("WORKSPACE", "Workspace settings updated in {:?}", notes_dir);
+
+    let _ = app.emit("workspace-settings-changed", &settings);
+    // Saved filters are listed in the Notes menu
+    if let Err(e) = crate::handlers::menu_handler::update_app_menu(app.clone(), app.state(), app.state()).await {
+        log_warn!("WORKSPACE", "Failed to refresh saved filters in the menu: {}", e);
+    }
+    Ok(settings)
+}
+
+/// Get the app config with workspace overrides applied
+#[tauri::command]
+pub async fn get_effective_config(config: State<'_, ConfigState>) -> Result<AppConfig, String> {
+    let config_lock = config.lock().await;
+    Ok(effective_config(&config_lock))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn overrides(value: Value) -> WorkspaceSettings {
+        WorkspaceSettings {
+            config_overrides: value.as_object().unwrap().clone(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_workspace_overrides_app_config() {
+        let app_config = AppConfig::default();
+        let settings = overrides(json!({
+            "formatting": { "ensureFinalNewline": true },
+            "appearance": { "theme": "light" }
+        }));
+
+        let merged = merge_workspace_config(&app_config, &settings);
+        assert!(merged.formatting.ensure_final_newline);
+        assert!(!merged.formatting.trim_trailing_whitespace);
+        assert_eq!(merged.appearance.theme, "light");
+        // Untouched nested values survive the merge
+        assert_eq!(merged.appearance.font_size, app_config.appearance.font_size);
+    }
+
+    #[test]
+    fn test_machine_keys_cannot_be_overridden() {
+        let app_config = AppConfig::default();
+        let settings = overrides(json!({
+            "window": { "width": 10.0 },
+            "readOnly": true,
+            "storage": { "notesDirectory": "/elsewhere", "useCustomDirectory": true }
+        }));
+
+        let merged = merge_workspace_config(&app_config, &settings);
+        assert_eq!(merged.window.width, app_config.window.width);
+        assert!(!merged.read_only);
+        assert!(merged.storage.notes_directory.is_none());
+    }
+
+    #[test]
+    fn test_invalid_overrides_are_ignored() {
+        let app_config = AppConfig::default();
+        let settings = overrides(json!({ "appearance": { "fontSize": "huge" } }));
+
+        let merged = merge_workspace_config(&app_config, &settings);
+        assert_eq!(merged.appearance.font_size, app_config.appearance.font_size);
+    }
+}
@@ -84,6 +84,7 @@ impl NoteService {
             updated_at: now,
             tags: request.tags,
             position: None,
+            icon: None,
         };
         
         // Save to file system
@@ -1,273 +1,672 @@
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tauri::{AppHandle, Manager};
-
-use crate::modules::file_storage::FileStorageManager;
-use crate::types::{
-    workspace::{WindowState, WorkspaceState},
-    config::AppConfig,
-    window::DetachedWindow,
+use tauri::{AppHandle, Emitter, LogicalPosition, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::handlers::menu_handler::update_app_menu;
+use crate::modules::display_layouts::{
+    current_monitor_geometries, ensure_window_onscreen, load_display_position, monitor_at, save_display_position,
+    MonitorGeometry,
 };
+use crate::modules::note_bootstrap::note_bootstrap_script;
+use crate::modules::note_command_guard::NoteCommand;
+use crate::modules::note_styles::{note_css, note_css_script};
+use crate::modules::sticky_notes::{is_sticky, STICKY_SIZE};
+use crate::modules::storage::{get_configured_notes_directory, save_detached_windows_to_disk};
+use crate::modules::window_pool::{recycle_window, take_pooled_window};
+use crate::modules::window_reveal::{reveal_when_ready, show_when_ready};
+use crate::modules::window_theme::initial_window_theme;
+use crate::handlers::window_handler::{load_spatial_data, save_spatial_data};
+use crate::state::{NoteCommandGuardState, RecentNotesState};
+use crate::types::config::{NewWindowConfig, SpawnAnchor, MIN_NOTE_WINDOW_SIZE};
+use crate::types::window::{ConfigState, CreateDetachedWindowRequest, DetachedWindow, DetachedWindowsState, NotesState};
+use crate::utils::window_geometry::{keep_within, snap_window_position, Rect};
 use crate::{log_info, log_error, log_debug};
 
-/// Service for managing window state with persistent storage
+/// Distance (logical px) within which a dragged window snaps to an edge
+const SNAP_THRESHOLD_PX: f64 = 16.0;
+
+/// Top left for a `size` window centred on the cursor and moved `offset`
+/// along the cascade, kept on the monitor under the cursor. Positions are
+/// logical, in that monitor's scale.
+pub fn cursor_window_position(app: &AppHandle, size: (f64, f64), offset: f64) -> Result<(f64, f64), String> {
+    let cursor = app.cursor_position().map_err(|e| format!("Failed to get cursor position: {}", e))?;
+    let monitors = current_monitor_geometries(app);
+    let monitor = monitor_at(&monitors, cursor.x, cursor.y)
+        .or_else(|| monitors.first())
+        .ok_or_else(|| "No monitor to place the window on".to_string())?;
+    let (x, y) = (cursor.x / monitor.scale_factor, cursor.y / monitor.scale_factor);
+    let window = Rect::new(x - size.0 / 2.0 + offset, y - size.1 / 2.0 + offset, size.0, size.1);
+    Ok(keep_within(window, monitor.logical_bounds()))
+}
+
+/// Top left corner for a window that has no requested or saved position: the
+/// configured anchor, moved along the cascade by `cascade_index` steps
+pub fn spawn_position(app: &AppHandle, settings: &NewWindowConfig, size: (f64, f64), cascade_index: usize) -> (f64, f64) {
+    let offset = settings.cascade_offset(cascade_index);
+    if matches!(settings.spawn_at, SpawnAnchor::Cursor) {
+        if let Ok(position) = cursor_window_position(app, size, offset) {
+            return position;
+        }
+    }
+    let anchor = match settings.spawn_at {
+        SpawnAnchor::MainWindow => app.get_webview_window("main").and_then(|main| {
+            let scale_factor = main.scale_factor().ok()?;
+            let pos = main.outer_position().ok()?.to_logical::<f64>(scale_factor);
+            Some((pos.x + 40.0, pos.y + 40.0))
+        }),
+        _ => None,
+    };
+    let (x, y) = anchor.unwrap_or((100.0, 100.0));
+    let window = Rect::new(x + offset, y + offset, size.0, size.1);
+
+    // Stay on the monitor the anchor is on, which may sit left of or above the primary
+    let screens: Vec<Rect> = current_monitor_geometries(app).iter().map(MonitorGeometry::logical_bounds).collect();
+    match screens.iter().find(|s| s.contains(x, y)).or_else(|| screens.first()) {
+        Some(screen) => keep_within(window, *screen),
+        None => (window.x, window.y),
+    }
+}
+
+/// Owns the lifecycle of detached note windows. Managed as app state; commands,
+/// menus and shortcuts all go through it so window state has one writer.
 pub struct WindowService {
-    storage: Arc<Mutex<FileStorageManager>>,
-    app_handle: AppHandle,
-    active_windows: Arc<Mutex<HashMap<String, DetachedWindow>>>,
+    app: AppHandle,
 }
 
 impl WindowService {
-    pub fn new(config: &AppConfig, app_handle: AppHandle) -> Result<Self, String> {
-        let storage = FileStorageManager::new(config)?;
-        
-        Ok(Self {
-            storage: Arc::new(Mutex::new(storage)),
-            app_handle,
-            active_windows: Arc::new(Mutex::new(HashMap::new())),
-        })
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
     }
-    
-    /// Initialize the service and load window states
-    pub async fn initialize(&self) -> Result<(), String> {
-        log_info!("WINDOW_SERVICE", "Initializing window service...");
-        
-        // Load workspace state
-        let storage = self.storage.lock().await;
-        let workspace = storage.load_workspace_state().await?;
-        
-        // Restore detached windows
-        let mut restored_count = 0;
-        for (note_id, window_state) in workspace.window_states {
-            if window_state.is_detached {
-                match self.restore_window(&note_id, &window_state).await {
-                    Ok(true) => restored_count += 1,
-                    Ok(false) => log_debug!("WINDOW_SERVICE", "Skipped restoring window for note: {}", note_id),
-                    Err(e) => log_error!("WINDOW_SERVICE", "Failed to restore window for note {}: {}", note_id, e),
-                }
-            }
+
+    /// Open a note in its own window, reusing a pooled window when it can
+    pub async fn create_detached_window(&self, request: CreateDetachedWindowRequest) -> Result<DetachedWindow, String> {
+        let app = self.app.clone();
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let notes = app.state::<NotesState>();
+        println!("[CREATE_DETACHED_WINDOW] Starting window creation for note: {}", request.note_id);
+        println!("[CREATE_DETACHED_WINDOW] Request params: x={:?}, y={:?}, width={:?}, height={:?}", 
+            request.x, request.y, request.width, request.height);
+
+        // A double-click storm sends several of these; only the first goes through
+        let guards = app.state::<NoteCommandGuardState>().inner().clone();
+        let Some(_guard) = guards.begin(&request.note_id, NoteCommand::CreateWindow).await else {
+            log_debug!("WINDOW", "Dropping duplicate create for note {}", request.note_id);
+            return Err("A window for this note is already being created".to_string());
+        };
+
+        // Clean up any existing drag ghost window first
+        if let Some(ghost_window) = app.get_webview_window("drag-ghost") {
+            println!("[CREATE_DETACHED_WINDOW] Found existing drag ghost window, closing it...");
+            let _ = ghost_window.close();
         }
-        
-        log_info!("WINDOW_SERVICE", "Window service initialized, restored {} windows", restored_count);
-        
-        Ok(())
-    }
-    
-    /// Create a detached window
-    pub async fn create_detached_window(
-        &self,
-        note_id: &str,
-        x: Option<f64>,
-        y: Option<f64>,
-        width: Option<f64>,
-        height: Option<f64>,
-        grid_position: Option<u8>,
-    ) -> Result<DetachedWindow, String> {
-        log_info!("WINDOW_SERVICE", "Creating detached window for note: {}", note_id);
-        
-        // Check if window already exists
-        let active_windows = self.active_windows.lock().await;
-        if active_windows.contains_key(note_id) {
+
+        // Check if note exists
+        let (window_title, note) = {
+            println!("[CREATE_DETACHED_WINDOW] Checking if note exists...");
+            let notes_lock = notes.lock().await;
+            let Some(note) = notes_lock.get(&request.note_id) else {
+                println!("[CREATE_DETACHED_WINDOW] ERROR: Note not found: {}", request.note_id);
+                return Err("Note not found".to_string());
+            };
+            println!("[CREATE_DETACHED_WINDOW] Note exists ✓");
+            (note.display_title(), note.clone())
+        };
+        let window_theme = initial_window_theme(&app, &request.note_id).await;
+        let custom_css = note_css(&app, &request.note_id).await;
+        let defer_show = show_when_ready(&app).await;
+
+        // Check if window already exists for this note
+        let mut windows_lock = detached_windows.lock().await;
+        println!("[CREATE_DETACHED_WINDOW] Current windows count: {}", windows_lock.len());
+        println!("[CREATE_DETACHED_WINDOW] === BACKEND WINDOWS STATE ===");
+        for (window_label, window) in windows_lock.iter() {
+            println!("[CREATE_DETACHED_WINDOW] Backend window: {} -> note_id: {}, position: ({}, {})", 
+                window_label, window.note_id, window.position.0, window.position.1);
+        }
+        println!("[CREATE_DETACHED_WINDOW] === END BACKEND WINDOWS STATE ===");
+
+        let existing_note_window = windows_lock
+            .iter()
+            .find(|(_, window)| window.note_id == request.note_id);
+
+        if existing_note_window.is_some() {
+            println!("[CREATE_DETACHED_WINDOW] ERROR: Note window already exists for note: {}", request.note_id);
             return Err("Window already exists for this note".to_string());
         }
-        drop(active_windows);
-        
-        // Create window state
-        let window_state = WindowState {
-            note_id: note_id.to_string(),
-            grid_position,
-            custom_position: if x.is_some() && y.is_some() {
-                Some((x.unwrap(), y.unwrap()))
-            } else {
-                None
+        println!("[CREATE_DETACHED_WINDOW] No existing note window for this note ✓");
+
+        let window_label = format!("note-{}", request.note_id);
+        println!("[CREATE_DETACHED_WINDOW] Window label: {}", window_label);
+
+        // Check if we have a saved position for this note, preferring the layout
+        // recorded for the current display configuration
+        println!("[CREATE_DETACHED_WINDOW] Loading saved spatial data...");
+        let saved_window = match load_display_position(&app, &request.note_id).await {
+            Some(saved) => Some(saved),
+            None => match get_configured_notes_directory(&*app.state::<ConfigState>().lock().await) {
+                Ok(notes_dir) => load_spatial_data(&notes_dir, &request.note_id).await,
+                Err(_) => None,
             },
-            size: (width.unwrap_or(800.0), height.unwrap_or(600.0)),
-            last_focused: chrono::Utc::now().to_rfc3339(),
-            is_detached: true,
-            always_on_top: false,
-            opacity: 1.0,
         };
-        
-        // Create the actual Tauri window
-        let window_label = format!("note-{}", note_id);
-        let webview_url = format!("/?note={}", note_id);
-        
-        let _window = tauri::WebviewWindowBuilder::new(
-            &self.app_handle,
-            &window_label,
-            tauri::WebviewUrl::App(webview_url.parse().unwrap()),
-        )
-        .title("Blink Note")
-        .inner_size(window_state.size.0, window_state.size.1)
-        .position(
-            window_state.custom_position.unwrap_or((100.0, 100.0)).0,
-            window_state.custom_position.unwrap_or((100.0, 100.0)).1,
-        )
-        .resizable(true)
-        .transparent(true)
-        .decorations(false)
-        .shadow(false)
-        .build()
-        .map_err(|e| format!("Failed to create window: {}", e))?;
-        
-        // Create detached window info
+
+        // Use requested dimensions first, then saved, then defaults
+        // Stickies are fixed-size paper on top of everything else
+        let settings = app.state::<ConfigState>().lock().await.new_windows.clone();
+        let sticky = is_sticky(&note);
+        let (width, height) = if sticky {
+            STICKY_SIZE
+        } else {
+            (
+                request.width.unwrap_or_else(|| saved_window.as_ref().map(|w| w.size.0).unwrap_or(settings.width)),
+                request.height.unwrap_or_else(|| saved_window.as_ref().map(|w| w.size.1).unwrap_or(settings.height)),
+            )
+        };
+        let min_size = if sticky { STICKY_SIZE } else { MIN_NOTE_WINDOW_SIZE };
+        let all_workspaces = saved_window.as_ref().map_or(false, |w| w.visible_on_all_workspaces);
+
+        // For position: if provided in request, use it; otherwise use saved position or calculate offset
+        let (mut x, mut y) = if request.x.is_some() && request.y.is_some() {
+            (request.x.unwrap(), request.y.unwrap())
+        } else if let Some(saved) = saved_window.as_ref() {
+            (saved.position.0, saved.position.1)
+        } else {
+            // Cascade from the configured anchor to avoid overlapping existing windows
+            spawn_position(&app, &settings, (width, height), windows_lock.len())
+        };
+
+        // Check if the position would overlap with existing windows
+        let mut needs_offset = false;
+        for (_, window) in windows_lock.iter() {
+            let dx = (window.position.0 - x).abs();
+            let dy = (window.position.1 - y).abs();
+            // If windows are too close (within 50 pixels), offset the new window
+            if dx < 50.0 && dy < 50.0 {
+                needs_offset = true;
+                break;
+            }
+        }
+
+        if needs_offset {
+            // Offset by one cascade step from the requested position
+            x += settings.cascade_step;
+            y += settings.cascade_step;
+            println!("[CREATE_DETACHED_WINDOW] Offsetting window position to avoid overlap");
+        }
+
+        println!("[CREATE_DETACHED_WINDOW] Window dimensions: {}x{} at ({}, {})", width, height, x, y);
+
+        // Reuse a pre-warmed window when one is available. Pooled windows were built
+        // without the note's initialization scripts, so styled notes get a fresh one.
+        let pooled_window = if custom_css.is_some() || sticky {
+            None
+        } else {
+            take_pooled_window(&app, &request.note_id, &window_title, (x, y), (width, height)).await
+        };
+        let is_pooled = pooled_window.is_some();
+        let webview_window = if let Some(window) = pooled_window {
+            println!("[CREATE_DETACHED_WINDOW] Reusing pooled window {} ✓", window.label());
+            let _ = window.set_theme(window_theme);
+            let _ = window.set_visible_on_all_workspaces(all_workspaces);
+            window
+        } else {
+            // Create the window
+            println!("[CREATE_DETACHED_WINDOW] Creating WebviewWindow...");
+            let window_url = format!("index.html?note={}", request.note_id);
+            println!("[CREATE_DETACHED_WINDOW] Window URL: {}", window_url);
+
+            // Create window with custom title bar
+            println!("[CREATE_DETACHED_WINDOW] Building window...");
+            let mut builder = WebviewWindowBuilder::new(
+                &app,
+                &window_label,
+                WebviewUrl::App(window_url.into()),
+            );
+            // Embed the note so it renders on first paint instead of after a fetch
+            if let Some(script) = note_bootstrap_script(&app, &note).await {
+                builder = builder.initialization_script(&script);
+            }
+            if let Some(css) = custom_css.as_deref() {
+                builder = builder.initialization_script(&note_css_script(Some(css)));
+            }
+            let webview_window = builder
+            .title(&window_title)
+            .theme(window_theme)
+            .inner_size(width, height)
+            .position(x, y)
+            .visible(!defer_show)  // Hidden until the webview has rendered the note
+            .resizable(!sticky)  // Enable window resizing
+            .always_on_top(sticky)
+            .visible_on_all_workspaces(all_workspaces)
+            .decorations(false)  // Disable native decorations for custom title bar
+            .transparent(true)   // Enable transparency for custom window styling
+            .shadow(true)        // Enable window shadow
+            .min_inner_size(min_size.0, min_size.1)  // Minimum size for proper display
+            .build()
+            .map_err(|e| {
+                println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to create window: {:?}", e);
+                format!("Failed to create window: {}", e)
+            })?;
+
+            println!("[CREATE_DETACHED_WINDOW] WebviewWindow created successfully ✓");
+
+            if defer_show {
+                println!("[CREATE_DETACHED_WINDOW] Waiting for frontend-ready before showing window");
+                reveal_when_ready(&app, &webview_window);
+            } else {
+                // Ensure the window is visible
+                println!("[CREATE_DETACHED_WINDOW] Showing window...");
+                webview_window.show().map_err(|e| {
+                    println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to show window: {:?}", e);
+                    format!("Failed to show window: {}", e)
+                })?;
+                println!("[CREATE_DETACHED_WINDOW] Window shown ✓");
+
+                // Set focus to ensure it's brought to front
+                webview_window.set_focus().map_err(|e| {
+                    println!("[CREATE_DETACHED_WINDOW] WARNING: Failed to set focus: {:?}", e);
+                    e.to_string()
+                }).unwrap_or_else(|e| {
+                    println!("[CREATE_DETACHED_WINDOW] Focus warning: {}", e);
+                });
+
+                // Verify window is actually visible
+                match webview_window.is_visible() {
+                    Ok(visible) => println!("[CREATE_DETACHED_WINDOW] Window visibility check: {}", visible),
+                    Err(e) => println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to check visibility: {:?}", e),
+                }
+            }
+            webview_window
+        };
+        let window_label = webview_window.label().to_string();
+        // Saved and requested positions may belong to a display that is gone
+        match ensure_window_onscreen(&app, &window_label) {
+            Ok(true) => {
+                if let (Ok(pos), Ok(scale)) = (webview_window.outer_position(), webview_window.scale_factor()) {
+                    let pos = pos.to_logical::<f64>(scale);
+                    (x, y) = (pos.x, pos.y);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => log_error!("WINDOW", "Failed to check {} is onscreen: {}", window_label, e),
+        }
+
         let detached_window = DetachedWindow {
-            note_id: note_id.to_string(),
+            note_id: request.note_id.clone(),
             window_label: window_label.clone(),
-            position: window_state.custom_position.unwrap_or((100.0, 100.0)),
-            size: window_state.size,
-            always_on_top: window_state.always_on_top,
-            opacity: window_state.opacity,
+            position: (x, y),
+            size: (width, height),
+            always_on_top: sticky,
+            opacity: 1.0,
             is_shaded: false,
-            original_height: Some(window_state.size.1),
+            original_height: None,
+            dock: None,
+            visible_on_all_workspaces: all_workspaces,
         };
-        
-        // Store in active windows
-        let mut active_windows = self.active_windows.lock().await;
-        active_windows.insert(note_id.to_string(), detached_window.clone());
-        
-        // Save window state to disk
-        let storage = self.storage.lock().await;
-        storage.save_window_state(note_id, &window_state).await?;
-        
-        log_info!("WINDOW_SERVICE", "Created detached window: {}", window_label);
-        
+        println!("[CREATE_DETACHED_WINDOW] DetachedWindow struct created: {:?}", detached_window);
+
+        println!("[CREATE_DETACHED_WINDOW] Inserting window into state...");
+        windows_lock.insert(window_label.clone(), detached_window.clone());
+        println!("[CREATE_DETACHED_WINDOW] Window inserted into state ✓");
+
+        println!("[CREATE_DETACHED_WINDOW] Saving detached windows to disk...");
+        save_detached_windows_to_disk(&windows_lock).await.map_err(|e| {
+            println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to save windows to disk: {}", e);
+            e
+        })?;
+        println!("[CREATE_DETACHED_WINDOW] Windows saved to disk ✓");
+
+        // Opening a note puts it at the top of Open Recent; the menu update lists it
+        app.state::<RecentNotesState>().lock().unwrap().record(&request.note_id);
+
+        // Update the app menu to include the new window
+        drop(windows_lock);
+        println!("[CREATE_DETACHED_WINDOW] Updating app menu...");
+        update_app_menu(app.clone(), detached_windows.clone(), notes.clone()).await.map_err(|e| {
+            println!("[CREATE_DETACHED_WINDOW] ERROR: Failed to update app menu: {}", e);
+            e
+        })?;
+        println!("[CREATE_DETACHED_WINDOW] App menu updated ✓");
+
+        // Set up window event listeners for lifecycle tracking
+        let window_label_for_events = window_label.clone();
+        let app_handle_for_events = app.clone();
+        let note_id_for_events = request.note_id.clone();
+
+        // Pooled windows get a listener that follows the note they currently show
+        if !is_pooled {
+            webview_window.on_window_event(move |event| {
+                match event {
+                    tauri::WindowEvent::Destroyed => {
+                        log_info!("WINDOW_LIFECYCLE", "Window {} destroyed via OS", window_label_for_events);
+                        let note_id = note_id_for_events.clone();
+                        let app = app_handle_for_events.clone();
+
+                        // Simply emit the event - let the frontend handle state cleanup
+                        // This avoids the lifetime issue with accessing state in the closure
+                        app.emit("window-destroyed", &note_id).unwrap_or_else(|e| {
+                            log_error!("WINDOW_LIFECYCLE", "Failed to emit window-destroyed event: {}", e);
+                        });
+
+                        log_info!("WINDOW_LIFECYCLE", "Emitted window-destroyed event for note {}", note_id);
+                    },
+                    tauri::WindowEvent::CloseRequested { api: _, .. } => {
+                        log_info!("WINDOW_LIFECYCLE", "Window {} close requested", window_label_for_events);
+                        // Allow the close - the Destroyed event will handle cleanup
+                    },
+                    _ => {}
+                }
+            });
+        }
+
+        println!("[CREATE_DETACHED_WINDOW] Window lifecycle listeners attached ✓");
+
+        // Note: Window position/size tracking is now handled by the frontend useWindowTracking hook
+        // with proper debouncing to avoid excessive file I/O operations
+        println!("[CREATE_DETACHED_WINDOW] Window tracking delegated to frontend (debounced) ✓");
+
+        println!("[CREATE_DETACHED_WINDOW] Window creation completed successfully! Returning: {:?}", detached_window);
         Ok(detached_window)
     }
-    
-    /// Close a detached window
+
+    /// Close a note's window, or hand it back to the pool. Returns false when
+    /// the note has no window.
     pub async fn close_detached_window(&self, note_id: &str) -> Result<bool, String> {
-        log_info!("WINDOW_SERVICE", "Closing detached window for note: {}", note_id);
-        
-        let window_label = format!("note-{}", note_id);
-        
-        // Close the Tauri window
-        if let Some(window) = self.app_handle.get_webview_window(&window_label) {
-            window.close().map_err(|e| format!("Failed to close window: {}", e))?;
+        let app = self.app.clone();
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let notes = app.state::<NotesState>();
+        let guards = app.state::<NoteCommandGuardState>().inner().clone();
+        let Some(_guard) = guards.begin(note_id, NoteCommand::CloseWindow).await else {
+            log_debug!("WINDOW", "Dropping duplicate close for note {}", note_id);
+            return Ok(false);
+        };
+        let notes_dir = get_configured_notes_directory(&*app.state::<ConfigState>().lock().await);
+        let mut windows_lock = detached_windows.lock().await;
+
+        // Find window by note_id
+        let window_label = if let Some((label, _)) = windows_lock.iter().find(|(_, w)| w.note_id == note_id) {
+            label.clone()
+        } else {
+            return Ok(false);
+        };
+
+        // Close the actual window, or hand it back to the pool for reuse
+        if let Some(window) = app.get_webview_window(&window_label) {
+            if !recycle_window(&app, &window_label).await {
+                window.close().map_err(|e| format!("Failed to close window: {}", e))?;
+            }
         }
-        
-        // Remove from active windows
-        let mut active_windows = self.active_windows.lock().await;
-        active_windows.remove(note_id);
-        
-        // Update window state on disk
-        let storage = self.storage.lock().await;
-        if let Ok(Some(mut window_state)) = storage.load_window_state(note_id).await {
-            window_state.is_detached = false;
-            storage.save_window_state(note_id, &window_state).await?;
+
+        // Remove from state, remembering where it was for when the note is opened again
+        if let (Some(closed), Ok(notes_dir)) = (windows_lock.remove(&window_label), &notes_dir) {
+            if let Err(e) = save_spatial_data(notes_dir, note_id, &closed).await {
+                log_error!("WINDOW", "Failed to save spatial data for {}: {}", note_id, e);
+            }
         }
-        
-        log_info!("WINDOW_SERVICE", "Closed detached window: {}", window_label);
-        
+        save_detached_windows_to_disk(&windows_lock).await?;
+
+        // Update the app menu to remove the closed window
+        drop(windows_lock);
+        update_app_menu(app.clone(), detached_windows.clone(), notes.clone()).await?;
+
+        // Emit event to all windows to notify frontend
+        app.emit("window-closed", note_id).map_err(|e| e.to_string())?;
+        log_info!("WINDOW", "Emitted window-closed event for note: {}", note_id);
+
         Ok(true)
     }
-    
-    /// Focus a detached window
+
+    /// Bring a note's window to the front. Returns false when it has none.
     pub async fn focus_detached_window(&self, note_id: &str) -> Result<bool, String> {
-        log_debug!("WINDOW_SERVICE", "Focusing detached window for note: {}", note_id);
-        
-        let window_label = format!("note-{}", note_id);
-        
-        if let Some(window) = self.app_handle.get_webview_window(&window_label) {
-            window.show().map_err(|e| format!("Failed to show window: {}", e))?;
-            window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
-            
-            // If window is minimized, restore it
-            if window.is_minimized().unwrap_or(false) {
-                window.unminimize().map_err(|e| format!("Failed to unminimize window: {}", e))?;
-            }
-            
-            // Update last focused time
-            let storage = self.storage.lock().await;
-            if let Ok(Some(mut window_state)) = storage.load_window_state(note_id).await {
-                window_state.last_focused = chrono::Utc::now().to_rfc3339();
-                storage.save_window_state(note_id, &window_state).await?;
+        let app = self.app.clone();
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let windows_lock = detached_windows.lock().await;
+        println!("[FOCUS_DETACHED_WINDOW] Looking for note: {}", note_id);
+
+        // Find window by note_id
+        if let Some((window_label, _window_data)) = windows_lock.iter().find(|(_, w)| w.note_id == note_id) {
+            println!("[FOCUS_DETACHED_WINDOW] Found window in state: {} -> {}", window_label, note_id);
+
+            if let Some(window) = app.get_webview_window(window_label) {
+                println!("[FOCUS_DETACHED_WINDOW] ✅ Tauri window found, attempting to focus...");
+
+                // Show and focus the window
+                window.show().map_err(|e| format!("Failed to show window: {}", e))?;
+                window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+
+                // If window is minimized, restore it
+                if window.is_minimized().unwrap_or(false) {
+                    window.unminimize().map_err(|e| format!("Failed to unminimize window: {}", e))?;
+                }
+
+                println!("[FOCUS_DETACHED_WINDOW] ✅ Successfully focused window for note: {}", note_id);
+                log_info!("WINDOW", "Focused existing detached window for note: {}", note_id);
+                return Ok(true);
+            } else {
+                println!("[FOCUS_DETACHED_WINDOW] ❌ Window found in state but Tauri window doesn't exist: {}", window_label);
+                println!("[FOCUS_DETACHED_WINDOW] ❌ Window may have been closed but not cleaned up from state");
             }
-            
-            log_debug!("WINDOW_SERVICE", "Focused detached window: {}", window_label);
-            return Ok(true);
+        } else {
+            println!("[FOCUS_DETACHED_WINDOW] ❌ No note window found in state for note: {}", note_id);
         }
-        
-        log_debug!("WINDOW_SERVICE", "No detached window found for note: {}", note_id);
+
+        println!("[FOCUS_DETACHED_WINDOW] ❌ Failed to focus window for note: {}", note_id);
+        log_info!("WINDOW", "No existing detached window found for note: {}", note_id);
         Ok(false)
     }
-    
-    /// Get all active detached windows
-    pub async fn get_detached_windows(&self) -> Result<Vec<DetachedWindow>, String> {
-        let active_windows = self.active_windows.lock().await;
-        Ok(active_windows.values().cloned().collect())
+
+    /// Forget a window the OS has already destroyed
+    pub async fn cleanup_destroyed_window(&self, note_id: &str) -> Result<(), String> {
+        let detached_windows = self.app.state::<DetachedWindowsState>();
+        let mut windows_lock = detached_windows.lock().await;
+
+        // Entries are keyed by the real label, which for pooled windows isn't
+        // `note-{id}`; drop whichever of the note's windows is actually gone
+        let before = windows_lock.len();
+        windows_lock.retain(|label, window| window.note_id != note_id || self.app.get_webview_window(label).is_some());
+        if windows_lock.len() != before {
+            log_info!("WINDOW_LIFECYCLE", "Cleaned up destroyed window state for note {}", note_id);
+            save_detached_windows_to_disk(&windows_lock).await?;
+        }
+
+        Ok(())
     }
-    
-    /// Restore a window from saved state
-    async fn restore_window(&self, note_id: &str, window_state: &WindowState) -> Result<bool, String> {
-        // Only restore if the window should be detached
-        if !window_state.is_detached {
-            return Ok(false);
+
+    /// Where a window at logical `(x, y)` snaps to: a monitor edge or another
+    /// detached window within `SNAP_THRESHOLD_PX`. Docked windows don't snap.
+    fn snap_target(&self, windows: &HashMap<String, DetachedWindow>, window_label: &str, x: f64, y: f64) -> Option<(f64, f64)> {
+        // While docked the window sits at its tab; keep the undocked geometry
+        let size = match windows.get(window_label) {
+            Some(window) if window.dock.is_none() => window.size,
+            _ => return None,
+        };
+
+        let screens: Vec<Rect> = current_monitor_geometries(&self.app)
+            .iter()
+            .map(MonitorGeometry::logical_bounds)
+            .collect();
+        let others: Vec<Rect> = windows
+            .iter()
+            .filter(|(label, _)| **label != window_label)
+            .map(|(_, w)| Rect::new(w.position.0, w.position.1, w.size.0, w.size.1))
+            .collect();
+
+        snap_window_position(Rect::new(x, y, size.0, size.1), &screens, &others, SNAP_THRESHOLD_PX)
+    }
+
+    /// Move the window to its snapped position, unless it is already there
+    fn move_to_snapped(&self, window_label: &str, (x, y): (f64, f64), snapped: (f64, f64)) -> Result<(), String> {
+        if (snapped.0 - x).abs() < 0.5 && (snapped.1 - y).abs() < 0.5 {
+            return Ok(());
         }
-        
-        // Create the detached window
-        self.create_detached_window(
-            note_id,
-            window_state.custom_position.map(|p| p.0),
-            window_state.custom_position.map(|p| p.1),
-            Some(window_state.size.0),
-            Some(window_state.size.1),
-            window_state.grid_position,
-        ).await?;
-        
-        Ok(true)
+        let window = self.app.get_webview_window(window_label).ok_or_else(|| format!("Window {} not found", window_label))?;
+        window
+            .set_position(LogicalPosition::new(snapped.0, snapped.1))
+            .map_err(|e| format!("Failed to snap window: {}", e))?;
+        log_debug!("WINDOW", "Snapped {} from ({}, {}) to ({}, {})", window_label, x, y, snapped.0, snapped.1);
+        Ok(())
     }
-    
-    /// Update window position
-    pub async fn update_window_position(&self, note_id: &str, x: f64, y: f64) -> Result<(), String> {
-        // Update active windows
-        let mut active_windows = self.active_windows.lock().await;
-        if let Some(window) = active_windows.get_mut(note_id) {
-            window.position = (x, y);
+
+    /// Snap a window that has just been dragged to logical `(x, y)`, moving it
+    /// to the edge it was close to. Nothing is saved; the position is recorded
+    /// by `update_position` once the window has settled.
+    pub async fn snap_window(&self, window_label: &str, x: f64, y: f64) -> Result<Option<(f64, f64)>, String> {
+        let detached_windows = self.app.state::<DetachedWindowsState>();
+        let windows_lock = detached_windows.lock().await;
+        let snapped = self.snap_target(&windows_lock, window_label, x, y);
+        if let Some(position) = snapped {
+            self.move_to_snapped(window_label, (x, y), position)?;
         }
-        
-        // Update persistent state
-        let storage = self.storage.lock().await;
-        if let Ok(Some(mut window_state)) = storage.load_window_state(note_id).await {
-            window_state.custom_position = Some((x, y));
-            storage.save_window_state(note_id, &window_state).await?;
+        Ok(snapped)
+    }
+
+    /// Record a detached window's logical position, snapping it first when it
+    /// is close enough to a screen edge or another Blink window. Returns the
+    /// snapped position.
+    pub async fn update_position(&self, window_label: &str, x: f64, y: f64) -> Result<Option<(f64, f64)>, String> {
+        let app = self.app.clone();
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let mut windows_lock = detached_windows.lock().await;
+
+        let snapped = self.snap_target(&windows_lock, window_label, x, y);
+        let (final_x, final_y) = snapped.unwrap_or((x, y));
+        if let Some(position) = snapped {
+            self.move_to_snapped(window_label, (x, y), position)?;
         }
-        
-        Ok(())
+
+        if let Some(window) = windows_lock.get_mut(window_label).filter(|w| w.dock.is_none()) {
+            window.position = (final_x, final_y);
+            let window_data = window.clone();
+            save_detached_windows_to_disk(&windows_lock).await?;
+            save_display_position(&app, &window_data).await?;
+        }
+
+        Ok(snapped)
     }
-    
-    /// Assign a window to a grid position
-    pub async fn assign_grid_position(&self, note_id: &str, grid_position: u8) -> Result<(), String> {
-        let storage = self.storage.lock().await;
-        
-        // Load current workspace state
-        let mut workspace = storage.load_workspace_state().await?;
-        
-        // Remove any existing assignment for this grid position
-        workspace.grid_assignments.retain(|_, id| id != note_id);
-        
-        // Add new assignment
-        workspace.grid_assignments.insert(grid_position, note_id.to_string());
-        
-        // Update window state
-        if let Some(window_state) = workspace.window_states.get_mut(note_id) {
-            window_state.grid_position = Some(grid_position);
+
+    /// Record a detached window's size; docked windows keep their undocked size
+    pub async fn update_size(&self, window_label: &str, width: f64, height: f64) -> Result<(), String> {
+        let app = self.app.clone();
+        let detached_windows = app.state::<DetachedWindowsState>();
+        let mut windows_lock = detached_windows.lock().await;
+
+        if let Some(window) = windows_lock.get_mut(window_label).filter(|w| w.dock.is_none()) {
+            window.size = (width, height);
+            let window_data = window.clone();
+            save_detached_windows_to_disk(&windows_lock).await?;
+            save_display_position(&app, &window_data).await?;
         }
-        
-        // Save workspace state
-        storage.save_workspace_state(&workspace).await?;
-        
-        log_info!("WINDOW_SERVICE", "Assigned note {} to grid position {}", note_id, grid_position);
-        
+
         Ok(())
     }
-    
-    /// Get note ID assigned to a grid position
-    pub async fn get_grid_assignment(&self, grid_position: u8) -> Result<Option<String>, String> {
-        let storage = self.storage.lock().await;
-        let workspace = storage.load_workspace_state().await?;
-        
-        Ok(workspace.grid_assignments.get(&grid_position).cloned())
+}
+
+/// Label of the always-on-top mini window that mirrors the main window's selection
+pub const FOLLOW_WINDOW_LABEL: &str = "follow-preview";
+
+const FOLLOW_WINDOW_SIZE: (f64, f64) = (360.0, 420.0);
+
+/// Selection in the main window and the note the follow window shows
+#[derive(Debug, Default)]
+pub struct FollowMode {
+    pub selected: Option<String>,
+    pub shown: Option<String>,
+}
+
+/// Payload of `note-selected`, emitted by the main window
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteSelected {
+    note_id: Option<String>,
+}
+
+fn follow_url(note_id: &str) -> String {
+    format!("index.html?note={}&follow=1", urlencoding::encode(note_id))
+}
+
+async fn follow_title(app: &AppHandle, note_id: &str) -> String {
+    let notes = app.state::<crate::NotesState>();
+    let title = notes.lock().await.get(note_id).map(|n| n.display_title());
+    title.unwrap_or_else(|| "Blink".to_string())
+}
+
+/// Point the follow window at `note_id`, if it is open and showing something else
+async fn follow_note(app: &AppHandle, note_id: &str) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(FOLLOW_WINDOW_LABEL) else { return Ok(()) };
+    {
+        let state = app.state::<crate::FollowModeState>();
+        let mut follow = state.lock().unwrap();
+        if follow.shown.as_deref() == Some(note_id) {
+            return Ok(());
+        }
+        follow.shown = Some(note_id.to_string());
+    }
+
+    let url = serde_json::to_string(&follow_url(note_id)).map_err(|e| e.to_string())?;
+    window
+        .eval(&format!("window.location.replace({})", url))
+        .map_err(|e| format!("Failed to load note in follow window: {}", e))?;
+    let _ = window.set_title(&follow_title(app, note_id).await);
+    log_debug!("WINDOW_SERVICE", "Follow window now shows {}", note_id);
+    Ok(())
+}
+
+/// Route the main window's selection to the follow window
+pub fn listen_for_note_selection(app: &AppHandle) {
+    use tauri::Listener;
+
+    let app_handle = app.clone();
+    app.listen_any("note-selected", move |event| {
+        let selected = match serde_json::from_str::<NoteSelected>(event.payload()) {
+            Ok(selected) => selected,
+            Err(e) => {
+                log_error!("WINDOW_SERVICE", "Invalid note-selected payload: {}", e);
+                return;
+            }
+        };
+        app_handle.state::<crate::FollowModeState>().lock().unwrap().selected = selected.note_id.clone();
+
+        let Some(note_id) = selected.note_id else { return };
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = follow_note(&app, &note_id).await {
+                log_error!("WINDOW_SERVICE", "{}", e);
+            }
+        });
+    });
+}
+
+/// Open or close the follow window. It opens on the main window's current selection.
+pub async fn set_follow_mode(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let existing = app.get_webview_window(FOLLOW_WINDOW_LABEL);
+    if !enabled {
+        if let Some(window) = existing {
+            window.close().map_err(|e| format!("Failed to close follow window: {}", e))?;
+        }
+        app.state::<crate::FollowModeState>().lock().unwrap().shown = None;
+        log_info!("WINDOW_SERVICE", "Follow mode off");
+        return Ok(());
+    }
+    if existing.is_some() {
+        return Ok(());
     }
-}
\ No newline at end of file
+
+    let selected = app.state::<crate::FollowModeState>().lock().unwrap().selected.clone();
+    let note_id = selected.ok_or("Select a note in the main window first")?;
+    let url = follow_url(&note_id);
+    let title = follow_title(app, &note_id).await;
+
+    tauri::WebviewWindowBuilder::new(app, FOLLOW_WINDOW_LABEL, tauri::WebviewUrl::App(url.into()))
+        .title(&title)
+        .inner_size(FOLLOW_WINDOW_SIZE.0, FOLLOW_WINDOW_SIZE.1)
+        .always_on_top(true)
+        .resizable(true)
+        .decorations(false)
+        .transparent(true)
+        .shadow(true)
+        .skip_taskbar(true)
+        .build()
+        .map_err(|e| format!("Failed to create follow window: {}", e))?;
+
+    app.state::<crate::FollowModeState>().lock().unwrap().shown = Some(note_id);
+    log_info!("WINDOW_SERVICE", "Follow mode on");
+    Ok(())
+}
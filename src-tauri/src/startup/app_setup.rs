@@ -1,7 +1,20 @@
 use crate::error::BlinkResult;
-use crate::handlers::{build_app_menu, handle_menu_event, register_global_shortcuts, handle_global_shortcut};
+use crate::handlers::{build_app_menu, handle_menu_event, refresh_window_menu, register_global_shortcuts, handle_global_shortcut};
 use crate::handlers::window_handler::apply_initial_window_settings;
 use crate::startup::data_loader::load_application_data;
+use crate::startup::main_window::{apply_startup_behavior, schedule_main_geometry_save};
+use crate::modules::storage::load_config_from_disk;
+use crate::modules::note_locks::release_window_edit_locks;
+use crate::modules::drag_session::drop_drag_session;
+use crate::modules::attachments::attach_dropped_files;
+use crate::modules::file_operations::{import_dropped_files, is_droppable_file};
+use crate::modules::window_reveal::listen_for_frontend_ready;
+use crate::modules::autosave::start_autosave_ticker;
+use crate::modules::power_mode::start_power_monitor;
+use crate::modules::window_arrangements::{on_display_event, start_display_watcher};
+use crate::modules::safe_mode::is_safe_mode;
+use crate::modules::window_theme::emit_theme_changed;
+use crate::services::window_service::WindowService;
 use crate::types::config::AppConfig;
 use crate::types::window::{DetachedWindowsState, NotesState, ToggleState};
 use crate::{log_error, log_info};
@@ -13,6 +26,9 @@ use tauri_plugin_global_shortcut::ShortcutState;
 pub fn setup_app(app: &mut App) -> BlinkResult<()> {
     let app_handle = app.handle().clone();
 
+    // Every detached window is created, moved and closed through this service
+    app.manage(WindowService::new(app_handle.clone()));
+
     // Get states for menu building
     let notes_state = app.state::<NotesState>();
     let detached_windows_state = app.state::<DetachedWindowsState>();
@@ -22,20 +38,37 @@ pub fn setup_app(app: &mut App) -> BlinkResult<()> {
     tauri::async_runtime::block_on(async {
         let notes_lock = notes_state.lock().await;
         let windows_lock = detached_windows_state.lock().await;
-        if let Ok(menu) = build_app_menu(&app_handle_for_menu, &*windows_lock, &*notes_lock) {
+        if let Ok(menu) = build_app_menu(&app_handle_for_menu, &*windows_lock, &*notes_lock, &[], &[], &[]) {
             let _ = app_handle_for_menu.set_menu(menu);
         }
     });
 
-    // Register global shortcuts
-    register_global_shortcuts(&app_handle)?;
+    // Register global shortcuts, unless one of them is what keeps crashing
+    if is_safe_mode() {
+        log_info!("STARTUP", "Safe mode, not registering global shortcuts");
+    } else {
+        register_global_shortcuts(&app_handle)?;
+    }
+
+    // Note windows created hidden are shown once their webview reports in
+    listen_for_frontend_ready(&app_handle);
+    // Before anything low power mode slows down
+    start_power_monitor(&app_handle);
+    start_autosave_ticker(&app_handle);
+    start_display_watcher(&app_handle);
+    crate::services::window_service::listen_for_note_selection(&app_handle);
 
-    // Apply config settings synchronously
+    // Apply config settings synchronously. The config state is filled in later by
+    // `load_application_data`, so read the saved config for startup behavior.
     let config_state_ref = app.state::<crate::ConfigState>();
     let config_for_init = tauri::async_runtime::block_on(async {
-        config_state_ref.lock().await.clone()
+        match load_config_from_disk().await {
+            Ok(config) => config,
+            Err(_) => config_state_ref.lock().await.clone(),
+        }
     });
 
+    apply_startup_behavior(&app_handle, &config_for_init);
     apply_initial_window_settings(&app_handle, &config_for_init);
 
     // Load data asynchronously after app starts
@@ -71,4 +104,65 @@ pub fn build_menu_handler() -> impl Fn(&tauri::AppHandle, tauri::menu::MenuEvent
         let menu_id = event.id();
         handle_menu_event(app, menu_id.0.as_str());
     }
-}
\ No newline at end of file
+}
+
+/// Notes dropped anywhere are imported. Other files dropped onto a note
+/// window are attached to that note.
+async fn route_dropped_files(app: &tauri::AppHandle, label: &str, paths: Vec<std::path::PathBuf>) {
+    let note_id = app.state::<DetachedWindowsState>().lock().await.get(label).map(|w| w.note_id.clone());
+    let Some(note_id) = note_id else {
+        import_dropped_files(app, label, paths).await;
+        return;
+    };
+    let (notes, others): (Vec<_>, Vec<_>) = paths.into_iter().partition(|p| is_droppable_file(p));
+    if !notes.is_empty() {
+        import_dropped_files(app, label, notes).await;
+    }
+    if !others.is_empty() {
+        attach_dropped_files(app, label, &note_id, others).await;
+    }
+}
+
+/// Build the window event handler
+pub fn build_window_event_handler() -> impl Fn(&tauri::Window, &tauri::WindowEvent) + Send + Sync + 'static {
+    |window, event| match event {
+        tauri::WindowEvent::Destroyed => {
+            // Closed windows must not keep notes locked for editing
+            let app_handle = window.app_handle().clone();
+            let label = window.label().to_string();
+            tauri::async_runtime::spawn(async move {
+                release_window_edit_locks(&app_handle, &label).await;
+                drop_drag_session(&app_handle, &label).await;
+                // Closed note windows leave the Window menu
+                refresh_window_menu(&app_handle).await;
+            });
+        }
+        tauri::WindowEvent::Focused(true) => {
+            // Keep the Window menu's checkmark on the focused note window, and
+            // clear it when main or another window takes focus
+            let app_handle = window.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                refresh_window_menu(&app_handle).await;
+            });
+        }
+        tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+            let app_handle = window.app_handle().clone();
+            let label = window.label().to_string();
+            let paths = paths.clone();
+            tauri::async_runtime::spawn(async move {
+                route_dropped_files(&app_handle, &label, paths).await;
+            });
+        }
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) if window.label() == "main" => {
+            schedule_main_geometry_save(window.app_handle());
+        }
+        tauri::WindowEvent::ScaleFactorChanged { .. } => {
+            // Displays were plugged in, unplugged or rearranged
+            on_display_event(window.app_handle());
+        }
+        tauri::WindowEvent::ThemeChanged(theme) if window.label() == "main" => {
+            emit_theme_changed(window.app_handle(), *theme);
+        }
+        _ => {}
+    }
+}
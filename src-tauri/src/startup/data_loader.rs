@@ -1,5 +1,13 @@
 use crate::error::{BlinkError, BlinkResult};
 use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::vault_lock::start_vault_lock;
+use crate::modules::maintenance::run_vault_verification;
+use crate::modules::power_mode::{defer_in_low_power, DeferredJob};
+use crate::modules::drag_preview::warm_drag_previews;
+use crate::modules::window_pool::prewarm_window_pool;
+use crate::modules::safe_mode::is_safe_mode;
+use crate::handlers::menu_handler::update_app_menu;
+use crate::startup::onboarding::{emit_onboarding_start, is_first_launch, seed_default_vault};
 use crate::ModifiedStateTrackerState;
 use crate::modules::storage::{
     load_config_from_disk as load_config_from_disk_storage,
@@ -18,24 +26,105 @@ pub async fn load_application_data(app_handle: AppHandle) -> BlinkResult<()> {
     // Load config first (needed for notes directory)
     let config = load_config(app_handle.clone()).await?;
 
+    // A missing config means this is the first launch: set up a vault to start from
+    let first_launch = is_first_launch();
+    crate::startup::main_window::enable_main_geometry_tracking();
+    let welcome_note_id = if first_launch {
+        log_info!("STARTUP", "First launch, preparing default vault");
+        seed_default_vault(&config).await.unwrap_or_else(|e| {
+            log_error!("STARTUP", "Failed to seed default vault: {}", e);
+            None
+        })
+    } else {
+        None
+    };
+
+    // Take the vault lock before anything can write to the notes directory
+    let lock_dir = if config.read_only {
+        log_info!("STARTUP", "Vault opened read-only, skipping vault lock");
+        None
+    } else {
+        Some(crate::modules::storage::get_configured_notes_directory(&config)
+            .map_err(BlinkError::Storage)?)
+    };
+    if let Err(e) = start_vault_lock(app_handle.clone(), lock_dir).await {
+        log_error!("STARTUP", "Failed to acquire vault lock: {}", e);
+    }
+
+    // Upgrade older vault layouts before checking or reading them. A vault this
+    // build can't open is left unloaded rather than misread.
+    let migrated = crate::modules::vault_format::migrate_vault(&config)
+        .await
+        .map_err(BlinkError::Storage);
+    match &migrated {
+        Ok(report) if !report.applied.is_empty() => {
+            log_info!("STARTUP", "✅ Upgraded vault from format {} to {}", report.from, report.to);
+        }
+        Ok(_) => {}
+        Err(e) => log_error!("STARTUP", "Failed to upgrade vault: {}", e),
+    }
+
+    // Check the index against the files before loading rewrites it. In low
+    // power mode the check waits for normal mode.
+    if migrated.is_ok() && !defer_in_low_power(DeferredJob::VaultVerification) {
+        if let Err(e) = run_vault_verification(&app_handle, &config, false) {
+            log_error!("STARTUP", "Vault integrity check failed: {}", e);
+        }
+    }
+
     // Load notes and windows in parallel
     let (notes_result, windows_result) = tokio::join!(
-        load_notes(app_handle.clone(), &config),
+        async {
+            migrated?;
+            load_notes(app_handle.clone(), &config).await
+        },
         load_detached_windows()
     );
 
     // Update notes state
     if let Ok(notes) = notes_result {
         update_notes_state(&app_handle, notes).await?;
+        warm_drag_previews(&app_handle);
     }
 
-    // Update windows state
-    if let Ok(windows) = windows_result {
+    // Update windows state. Safe mode leaves the saved windows on disk for the next
+    // normal launch but doesn't bring them back.
+    if is_safe_mode() {
+        log_info!("STARTUP", "Safe mode, not restoring detached windows");
+    } else if let Ok(windows) = windows_result {
         update_windows_state(&app_handle, windows).await?;
     }
 
+    // Age-based archive policies run once the notes are in memory, or once
+    // low power mode ends
+    if !defer_in_low_power(DeferredJob::ArchivePolicies) {
+        crate::modules::archive_policy::run_archive_policies_at_startup(&app_handle).await;
+    }
+
+    // Pick up edits to config.json made outside the app
+    if is_safe_mode() {
+        log_info!("STARTUP", "Safe mode, not watching the config file");
+    } else if let Err(e) = crate::modules::config_reload::start_config_watcher(app_handle.clone()) {
+        log_error!("STARTUP", "Failed to watch config file: {}", e);
+    }
+
     // Notify frontend that data is loaded
     let _ = app_handle.emit("data-loaded", ());
+    if first_launch {
+        emit_onboarding_start(&app_handle, &config, welcome_note_id);
+    }
+
+    // The menu was built before notes were loaded
+    if let Err(e) = update_app_menu(
+        app_handle.clone(),
+        app_handle.state::<DetachedWindowsState>(),
+        app_handle.state::<NotesState>(),
+    ).await {
+        log_error!("STARTUP", "Failed to refresh menu: {}", e);
+    }
+
+    // Warm spare note windows once startup work is done
+    prewarm_window_pool(&app_handle);
 
     log_info!("STARTUP", "✅ All data loaded successfully");
     Ok(())
@@ -54,6 +143,9 @@ async fn load_config(app_handle: AppHandle) -> BlinkResult<AppConfig> {
     } else {
         AppConfig::default()
     };
+    crate::modules::date_format::set_date_format(&config.date_format);
+    crate::modules::file_storage::set_note_extensions(&config.storage);
+    crate::modules::logging::set_log_levels(&config.logging);
 
     Ok(config)
 }
@@ -66,15 +158,6 @@ async fn load_notes(
     let file_storage = FileNotesStorage::new(config)
         .map_err(|e| BlinkError::Storage(format!("Failed to create file storage: {}", e)))?;
 
-    // Run migration if needed
-    let notes_dir = crate::modules::storage::get_notes_directory()
-        .map_err(|e| BlinkError::Storage(e))?;
-    let json_path = notes_dir.join("notes.json");
-    file_storage
-        .migrate_if_needed(json_path)
-        .await
-        .map_err(|e| BlinkError::Storage(e))?;
-
     // Load notes from files
     file_storage
         .load_notes()
@@ -0,0 +1,203 @@
+use crate::modules::display_layouts::ensure_window_onscreen;
+use crate::modules::storage::save_config_to_disk;
+use crate::types::config::{AppConfig, WindowConfig};
+use crate::ConfigState;
+use crate::{log_debug, log_error, log_info};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_autostart::ManagerExt;
+
+/// Main window size when there is nothing to restore (matches tauri.conf.json)
+const DEFAULT_MAIN_WINDOW_SIZE: (u32, u32) = (1000, 700);
+const TRAY_ID: &str = "blink-tray";
+/// Moves and resizes settle for this long before the geometry is saved
+const GEOMETRY_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// A main window this short is shaded; its height is not worth keeping
+const SHADED_MAX_HEIGHT: u32 = 50;
+
+/// Set once the saved config is loaded, so startup placement can't overwrite it
+static GEOMETRY_TRACKING: AtomicBool = AtomicBool::new(false);
+/// Bumped on every move/resize; only the latest scheduled save runs
+static GEOMETRY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Place the main window and show it, or keep it hidden, per `config.startup`
+pub fn apply_startup_behavior(app: &AppHandle, config: &AppConfig) {
+    let startup = &config.startup;
+    log_info!(
+        "STARTUP",
+        "Startup behavior: hidden={}, tray={}, restoreGeometry={}, launchAtLogin={}",
+        startup.start_hidden,
+        startup.start_in_tray,
+        startup.restore_geometry,
+        startup.launch_at_login
+    );
+
+    sync_launch_at_login(app, startup.launch_at_login);
+
+    if startup.start_in_tray {
+        if let Err(e) = create_tray_icon(app) {
+            log_error!("STARTUP", "Failed to create menu bar icon: {}", e);
+        } else {
+            // Only hide the Dock icon once there is another way back to the app
+            #[cfg(target_os = "macos")]
+            if let Err(e) = app.set_activation_policy(tauri::ActivationPolicy::Accessory) {
+                log_error!("STARTUP", "Failed to hide Dock icon: {}", e);
+            }
+        }
+    }
+
+    let Some(window) = app.get_webview_window("main") else {
+        log_error!("STARTUP", "❌ Could not find main window!");
+        return;
+    };
+    place_main_window(app, &window, &config.window, config.startup.restore_geometry);
+
+    if startup.start_hidden || startup.start_in_tray {
+        if let Err(e) = window.hide() {
+            log_error!("STARTUP", "Failed to hide window: {}", e);
+        }
+        log_info!("STARTUP", "🙈 Main window starts hidden");
+    } else {
+        if let Err(e) = window.show() {
+            log_error!("STARTUP", "Failed to show window: {}", e);
+        }
+        if let Err(e) = window.set_focus() {
+            log_error!("STARTUP", "Failed to set window focus: {}", e);
+        }
+    }
+}
+
+/// Restore the saved main window geometry, or center it at the default size
+/// when `restore` is off. Offscreen positions are nudged back into view.
+pub fn place_main_window(app: &AppHandle, window: &WebviewWindow, saved: &WindowConfig, restore: bool) {
+    let (width, height) = if restore {
+        (saved.width as u32, saved.height as u32)
+    } else {
+        DEFAULT_MAIN_WINDOW_SIZE
+    };
+    if let Err(e) = window.set_size(tauri::Size::Physical(tauri::PhysicalSize { width, height })) {
+        log_error!("STARTUP", "Failed to set window size: {}", e);
+    }
+
+    let position = match (restore, saved.x, saved.y) {
+        (true, Some(x), Some(y)) => Some((x, y)),
+        _ => None,
+    };
+    let placed = match position {
+        Some((x, y)) => window.set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: x as i32, y: y as i32 })),
+        None => window.center(),
+    };
+    if let Err(e) = placed {
+        log_error!("STARTUP", "Failed to position window: {}", e);
+        return;
+    }
+    log_info!("STARTUP", "📍 Main window {}x{} at {:?} (centered when None)", width, height, position);
+
+    // The saved position may be on a display that is no longer connected
+    if let Err(e) = ensure_window_onscreen(app, window.label()) {
+        log_error!("STARTUP", "Failed to check main window is onscreen: {}", e);
+    }
+}
+
+/// Start saving main window moves and resizes into the config
+pub fn enable_main_geometry_tracking() {
+    GEOMETRY_TRACKING.store(true, Ordering::SeqCst);
+}
+
+/// Save the main window's geometry once it stops moving. Called for every
+/// `Moved`/`Resized` event of the main window.
+pub fn schedule_main_geometry_save(app: &AppHandle) {
+    if !GEOMETRY_TRACKING.load(Ordering::SeqCst) {
+        return;
+    }
+    let generation = GEOMETRY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(GEOMETRY_SAVE_DEBOUNCE).await;
+        if GEOMETRY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(e) = save_main_geometry(&app).await {
+            log_error!("WINDOW", "Failed to save main window geometry: {}", e);
+        }
+    });
+}
+
+async fn save_main_geometry(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else { return Ok(()) };
+    if window.is_minimized().unwrap_or(false) || window.is_fullscreen().unwrap_or(false) {
+        return Ok(());
+    }
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+
+    let config_state = app.state::<ConfigState>();
+    let mut config_lock = config_state.lock().await;
+    let geometry = &mut config_lock.window;
+    let unchanged = geometry.x == Some(position.x as f64) && geometry.y == Some(position.y as f64);
+    geometry.x = Some(position.x as f64);
+    geometry.y = Some(position.y as f64);
+    // Shading only changes the height; keep the unshaded one
+    let unchanged = if size.height > SHADED_MAX_HEIGHT {
+        let same_size = geometry.width == size.width as f64 && geometry.height == size.height as f64;
+        geometry.width = size.width as f64;
+        geometry.height = size.height as f64;
+        unchanged && same_size
+    } else {
+        unchanged
+    };
+    if unchanged {
+        return Ok(());
+    }
+
+    save_config_to_disk(&config_lock).await?;
+    log_debug!("WINDOW", "Saved main window geometry {}x{} at ({}, {})", size.width, size.height, position.x, position.y);
+    Ok(())
+}
+
+/// Menu bar icon for `startInTray`, the way back to a hidden main window
+fn create_tray_icon(app: &AppHandle) -> tauri::Result<()> {
+    if app.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+    let show = MenuItem::with_id(app, "tray-show", "Show Blink", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "tray-quit", "Quit Blink", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &quit])?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Blink")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "tray-show" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "tray-quit" => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+/// Register or remove the login item to match `launchAtLogin`
+pub fn sync_launch_at_login(app: &AppHandle, enabled: bool) {
+    let autolaunch = app.autolaunch();
+    let result = match autolaunch.is_enabled() {
+        Ok(current) if current == enabled => Ok(()),
+        Ok(_) if enabled => autolaunch.enable(),
+        Ok(_) => autolaunch.disable(),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => log_info!("STARTUP", "Launch at login: {}", enabled),
+        Err(e) => log_error!("STARTUP", "Failed to update login item: {}", e),
+    }
+}
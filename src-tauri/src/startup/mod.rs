@@ -1,5 +1,9 @@
 pub mod app_setup;
 pub mod data_loader;
+pub mod main_window;
+pub mod onboarding;
 
 pub use app_setup::*;
-pub use data_loader::*;
\ No newline at end of file
+pub use data_loader::*;
+pub use main_window::*;
+pub use onboarding::*;
\ No newline at end of file
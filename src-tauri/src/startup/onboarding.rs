@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::storage::{get_configured_notes_directory, get_notes_directory, save_config_to_disk};
+use crate::modules::vault_lock::ensure_vault_writable;
+use crate::modules::vault_picker::{reload_vault_notes, switch_to_vault};
+use crate::modules::window_capabilities::{authorize, Capability};
+use crate::types::config::AppConfig;
+use crate::types::note::Note;
+use crate::types::window::ConfigState;
+use crate::VaultLockState;
+use crate::{log_error, log_info};
+
+/// ID of the note seeded into a new vault
+pub const WELCOME_NOTE_ID: &str = "welcome-to-blink";
+
+const WELCOME_NOTE_CONTENT: &str = "# Welcome to Blink
+
+Blink keeps quick notes one shortcut away.
+
+## Shortcuts
+
+- **Cmd+Ctrl+Alt+Shift+N** creates a new note from anywhere
+- **Cmd+Ctrl+Alt+Shift+H** shows or hides Blink
+- **Cmd+Shift+O** opens any note by name
+- Drag a note out of the list to give it its own floating window
+
+Notes are plain markdown files in your notes folder, so you can edit them
+with any other app too. Delete this note whenever you like.
+";
+
+/// Sent with `onboarding-start` so the frontend can show the setup flow
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingInfo {
+    pub notes_directory: String,
+    pub welcome_note_id: Option<String>,
+}
+
+/// First launch means no config has ever been saved
+pub fn is_first_launch() -> bool {
+    get_notes_directory()
+        .map(|dir| !dir.join("config.json").exists())
+        .unwrap_or(false)
+}
+
+/// Create the default vault and seed the welcome note, unless the vault already has notes
+pub async fn seed_default_vault(config: &AppConfig) -> Result<Option<String>, String> {
+    let notes_dir = get_configured_notes_directory(config)?;
+    fs::create_dir_all(&notes_dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let storage = FileNotesStorage::new(config)?;
+    if !storage.load_notes().await?.is_empty() {
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let note = Note {
+        id: WELCOME_NOTE_ID.to_string(),
+        title: "Welcome to Blink".to_string(),
+        content: WELCOME_NOTE_CONTENT.to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+        tags: vec![],
+        position: Some(0),
+        icon: None,
+    };
+    storage.save_note(&note).await?;
+    log_info!("ONBOARDING", "Seeded welcome note in {:?}", notes_dir);
+    Ok(Some(note.id))
+}
+
+/// Tell the frontend to start onboarding
+pub fn emit_onboarding_start(app: &AppHandle, config: &AppConfig, welcome_note_id: Option<String>) {
+    let info = OnboardingInfo {
+        notes_directory: get_configured_notes_directory(config)
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        welcome_note_id,
+    };
+    if let Err(e) = app.emit("onboarding-start", &info) {
+        log_error!("ONBOARDING", "Failed to emit onboarding-start: {}", e);
+    }
+}
+
+/// The chosen notes directory must be absolute and writable; it is created if missing
+pub fn validate_notes_directory(dir: &Path) -> Result<PathBuf, String> {
+    if !dir.is_absolute() {
+        return Err(format!("Notes directory must be an absolute path: {}", dir.display()));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(format!("Notes directory is a file: {}", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create notes directory: {}", e))?;
+
+    let probe = dir.join(".blink-write-test");
+    fs::write(&probe, b"").map_err(|e| format!("Notes directory is not writable: {}", e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(dir.to_path_buf())
+}
+
+/// Shortcuts must parse, e.g. `CommandOrControl+Shift+H`
+pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
+    Shortcut::from_str(shortcut)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))
+}
+
+/// Finish onboarding with the directory and shortcuts the user picked. Saving the
+/// config marks onboarding as done. A custom directory is opened like any other
+/// vault, then seeded with the welcome note if it has no notes.
+#[tauri::command]
+pub async fn complete_onboarding(
+    window: tauri::Window,
+    app: AppHandle,
+    onboarding_config: AppConfig,
+    config: State<'_, ConfigState>,
+    vault_lock: State<'_, VaultLockState>,
+) -> Result<AppConfig, String> {
+    authorize(&window, Capability::WriteConfig)?;
+    let custom_dir = if onboarding_config.storage.use_custom_directory {
+        authorize(&window, Capability::ManageVault)?;
+        let dir = onboarding_config
+            .storage
+            .notes_directory
+            .as_deref()
+            .ok_or("Choose a notes directory")?;
+        Some(validate_notes_directory(Path::new(dir))?)
+    } else {
+        None
+    };
+    validate_shortcut(&onboarding_config.shortcuts.toggle_visibility)?;
+
+    {
+        let mut config_lock = config.lock().await;
+        let mut storage = onboarding_config.storage;
+        if custom_dir.is_some() {
+            // `switch_to_vault` moves the directory over, with the lock and notes
+            storage.notes_directory = config_lock.storage.notes_directory.clone();
+            storage.use_custom_directory = config_lock.storage.use_custom_directory;
+            storage.recent_vaults = config_lock.storage.recent_vaults.clone();
+        }
+        config_lock.storage = storage;
+        config_lock.shortcuts = onboarding_config.shortcuts;
+        save_config_to_disk(&config_lock).await?;
+    }
+    if let Some(dir) = &custom_dir {
+        switch_to_vault(&app, dir).await?;
+    }
+
+    // A custom directory may be empty; give it the welcome note too
+    ensure_vault_writable(&config, &vault_lock).await?;
+    let new_config = config.lock().await.clone();
+    let welcome_note_id = seed_default_vault(&new_config).await?;
+    if welcome_note_id.is_some() {
+        reload_vault_notes(&app, &new_config).await?;
+    }
+
+    log_info!("ONBOARDING", "Onboarding complete (welcome note: {:?})", welcome_note_id);
+    let _ = app.emit("onboarding-complete", &new_config);
+    Ok(new_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_notes_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("vault").join("notes");
+        assert!(validate_notes_directory(&nested).is_ok());
+        assert!(nested.is_dir());
+
+        assert!(validate_notes_directory(Path::new("relative/notes")).is_err());
+
+        let file = temp_dir.path().join("file.md");
+        fs::write(&file, "").unwrap();
+        assert!(validate_notes_directory(&file).is_err());
+    }
+
+    #[test]
+    fn test_validate_shortcut() {
+        assert!(validate_shortcut("CommandOrControl+Shift+H").is_ok());
+        assert!(validate_shortcut("Shift+NotAKey").is_err());
+    }
+}
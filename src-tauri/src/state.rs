@@ -1,8 +1,23 @@
+use crate::handlers::menu_handler::{NotesMenu, OpenRecentMenu, WindowMenu};
+use crate::handlers::window_chord::WindowChord;
+use crate::modules::autosave::PendingSave;
+use crate::modules::undo::UndoHistory;
+use crate::modules::reading_mode::ReadingMode;
+use crate::modules::recent_notes::RecentNotes;
+use crate::modules::audio_capture::AudioCapture;
+use crate::modules::drag_preview::DragPreview;
+use crate::modules::confirmation::PendingConfirmation;
+use crate::modules::drag_session::DragSession;
 use crate::modules::modified_state_tracker::ModifiedStateTracker;
+use crate::modules::note_command_guard::NoteCommandGuards;
+use crate::modules::url_metadata::UrlMetadata;
+use crate::modules::vault_lock::VaultLockStatus;
+use crate::modules::window_pool::WindowPool;
+use crate::services::window_service::FollowMode;
 use crate::types::config::AppConfig;
 use crate::types::note::Note;
-use crate::types::window::DetachedWindow;
-use std::collections::HashMap;
+use crate::types::window::{CursorDeployCascade, DetachedWindow, NoteEditLock};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -54,4 +69,35 @@ pub type NotesState = tokio::sync::Mutex<HashMap<String, Note>>;
 pub type ConfigState = tokio::sync::Mutex<AppConfig>;
 pub type DetachedWindowsState = tokio::sync::Mutex<HashMap<String, DetachedWindow>>;
 pub type ToggleState = tokio::sync::Mutex<bool>;
-pub type ModifiedStateTrackerState = ModifiedStateTracker;
\ No newline at end of file
+pub type CursorDeployState = tokio::sync::Mutex<CursorDeployCascade>;
+pub type NoteEditLockState = tokio::sync::Mutex<HashMap<String, NoteEditLock>>;
+pub type ModifiedStateTrackerState = ModifiedStateTracker;
+pub type DragPreviewCacheState = tokio::sync::Mutex<HashMap<String, DragPreview>>;
+pub type DragSessionState = tokio::sync::Mutex<HashMap<String, DragSession>>;
+pub type WindowPoolState = tokio::sync::Mutex<WindowPool>;
+pub type ConfirmationTokenState = tokio::sync::Mutex<HashMap<String, PendingConfirmation>>;
+pub type UrlMetadataCacheState = tokio::sync::Mutex<HashMap<String, UrlMetadata>>;
+/// Plain mutex: the menu is built from sync code
+pub type NotesMenuState = std::sync::Mutex<Option<NotesMenu>>;
+pub type WindowMenuState = std::sync::Mutex<Option<WindowMenu>>;
+pub type OpenRecentMenuState = std::sync::Mutex<Option<OpenRecentMenu>>;
+pub type VaultLockState = tokio::sync::Mutex<VaultLockStatus>;
+/// Plain mutex: the recording is only swapped in and out, never held across awaits
+pub type AudioCaptureState = std::sync::Mutex<Option<AudioCapture>>;
+/// Labels of windows kept hidden until their webview sends `frontend-ready`
+pub type WindowRevealState = std::sync::Mutex<HashSet<String>>;
+pub type NoteCommandGuardState = NoteCommandGuards;
+/// A config edited on disk that waits for the user to confirm it
+pub type ConfigReloadState = std::sync::Mutex<Option<AppConfig>>;
+/// Plain mutex: updated from event listeners
+pub type FollowModeState = std::sync::Mutex<FollowMode>;
+/// Plain mutex: edits waiting for autosave, keyed by note ID
+pub type AutosaveState = std::sync::Mutex<HashMap<String, PendingSave>>;
+/// Plain mutex: operations are only pushed and popped
+pub type UndoHistoryState = std::sync::Mutex<UndoHistory>;
+/// Plain mutex: armed and completed from the sync shortcut handler
+pub type WindowChordState = std::sync::Mutex<WindowChord>;
+/// Plain mutex: pages are turned from the sync shortcut handler
+pub type ReadingModeState = std::sync::Mutex<ReadingMode>;
+/// Plain mutex: also read while building the menu
+pub type RecentNotesState = std::sync::Mutex<RecentNotes>;
@@ -1,3 +1,4 @@
 pub mod position_bug_tests;
 pub mod simplified_position_test;
-pub mod slug_test;
\ No newline at end of file
+pub mod slug_test;
+pub mod vault_migration_tests;
@@ -28,6 +28,7 @@ mod test_utils {
             updated_at: now,
             tags: vec![],
             position,
+            icon: None,
         }
     }
 
@@ -22,6 +22,7 @@ fn create_test_note(id: &str, title: &str, content: &str, position: Option<i32>)
         updated_at: now,
         tags: vec![],
         position,
+        icon: None,
     }
 }
 
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::modules::file_notes_storage::FileNotesStorage;
+use crate::modules::maintenance::verify_vault_at;
+use crate::modules::vault_format::{migrate_vault, read_vault_version, VAULT_FORMAT_VERSION};
+use crate::types::{config::AppConfig, window::DetachedWindow};
+
+/// Snapshots of vault layouts written by older builds
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/vaults");
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// A scratch copy of a fixture vault, so tests never upgrade the fixture itself
+fn open_fixture(name: &str) -> (TempDir, AppConfig) {
+    let temp_dir = TempDir::new().unwrap();
+    copy_dir(&Path::new(FIXTURES).join(name), temp_dir.path());
+
+    let mut config = AppConfig::default();
+    config.storage.notes_directory = Some(temp_dir.path().to_string_lossy().to_string());
+    config.storage.use_custom_directory = true;
+    (temp_dir, config)
+}
+
+async fn load_contents(config: &AppConfig) -> HashMap<String, String> {
+    let notes = FileNotesStorage::new(config).unwrap().load_notes().await.unwrap();
+    notes.into_iter().map(|(id, note)| (id, note.content)).collect()
+}
+
+#[tokio::test]
+async fn test_notes_json_vault_upgrades_without_losing_notes() {
+    let (dir, config) = open_fixture("v0-notes-json");
+    let original: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("notes.json")).unwrap()).unwrap();
+
+    let report = migrate_vault(&config).await.unwrap();
+    assert_eq!((report.from, report.to), (0, VAULT_FORMAT_VERSION));
+    assert_eq!(report.applied.len(), VAULT_FORMAT_VERSION as usize);
+    assert_eq!(read_vault_version(dir.path()).unwrap(), VAULT_FORMAT_VERSION);
+    assert!(!dir.path().join("notes.json").exists());
+    assert!(dir.path().join("notes.json.backup").exists());
+
+    let contents = load_contents(&config).await;
+    assert_eq!(contents.len(), original.len());
+    for (id, note) in &original {
+        assert_eq!(contents[id], note["content"].as_str().unwrap(), "content of {}", id);
+    }
+}
+
+#[tokio::test]
+async fn test_index_json_vault_upgrades_without_losing_notes() {
+    let (dir, config) = open_fixture("v1-index-json");
+
+    migrate_vault(&config).await.unwrap();
+    assert!(!dir.path().join(".blink/index.json").exists());
+    assert!(dir.path().join(".blink/index.json.backup").exists());
+    assert!(dir.path().join(".blink/notes.db").exists());
+
+    let contents = load_contents(&config).await;
+    let mut ids: Vec<&String> = contents.keys().collect();
+    ids.sort();
+    assert_eq!(ids, ["alpha", "beta"]);
+    assert!(contents["alpha"].contains("before the SQLite index"));
+
+    // The per-note spatial file is folded into the shared one, next to what was there
+    assert!(!dir.path().join("spatial_alpha.json").exists());
+    let spatial: HashMap<String, DetachedWindow> =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("spatial_positions.json")).unwrap()).unwrap();
+    assert_eq!(spatial["alpha"].position, (120.0, 80.0));
+    assert_eq!(spatial["beta"].opacity, 0.9);
+}
+
+#[tokio::test]
+async fn test_upgraded_index_matches_the_files() {
+    // The fixture's index predates body hashes
+    let (dir, config) = open_fixture("v1-index-json");
+
+    migrate_vault(&config).await.unwrap();
+    let report = verify_vault_at(dir.path(), config.storage.follow_symlinks).unwrap();
+    assert!(report.is_clean(), "{:?}", report);
+    assert_eq!(report.checked, 2);
+}
+
+#[tokio::test]
+async fn test_upgraded_vault_is_left_alone() {
+    let (_dir, config) = open_fixture("v1-index-json");
+    migrate_vault(&config).await.unwrap();
+
+    let again = migrate_vault(&config).await.unwrap();
+    assert_eq!((again.from, again.to), (VAULT_FORMAT_VERSION, VAULT_FORMAT_VERSION));
+    assert!(again.applied.is_empty());
+}
+
+#[tokio::test]
+async fn test_vault_from_newer_build_is_refused() {
+    let (dir, config) = open_fixture("v1-index-json");
+    fs::write(dir.path().join(".blink/version"), format!("{}\n", VAULT_FORMAT_VERSION + 1)).unwrap();
+
+    let err = migrate_vault(&config).await.unwrap_err();
+    assert!(err.contains("Update Blink"));
+    // Nothing was touched
+    assert!(dir.path().join(".blink/index.json").exists());
+    assert!(dir.path().join("spatial_alpha.json").exists());
+}
@@ -11,6 +11,43 @@ pub struct AppConfig {
     pub appearance: AppearanceConfig,
     #[serde(default = "default_storage")]
     pub storage: StorageConfig,
+    #[serde(rename = "readOnly")]
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default = "default_formatting")]
+    pub formatting: FormattingConfig,
+    #[serde(rename = "windowPool")]
+    #[serde(default = "default_window_pool")]
+    pub window_pool: WindowPoolConfig,
+    /// Never make network requests; enforced by `NetworkPolicy`
+    #[serde(default)]
+    pub offline: bool,
+    #[serde(default = "default_attachments")]
+    pub attachments: AttachmentsConfig,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assistant: Option<AssistantConfig>,
+    /// Speech-to-text for voice memos; memos are only attached when unset
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcription: Option<TranscriptionConfig>,
+    #[serde(default = "default_startup")]
+    pub startup: StartupConfig,
+    /// Show note windows as soon as they are created instead of once their
+    /// content has rendered
+    #[serde(rename = "instantShow")]
+    #[serde(default)]
+    pub instant_show: bool,
+    #[serde(rename = "dateFormat")]
+    #[serde(default = "default_date_format")]
+    pub date_format: DateFormatConfig,
+    #[serde(default = "default_logging")]
+    pub logging: LoggingConfig,
+    #[serde(default = "default_reading")]
+    pub reading: ReadingConfig,
+    #[serde(rename = "newWindows")]
+    #[serde(default = "default_new_windows")]
+    pub new_windows: NewWindowConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -34,6 +71,226 @@ pub struct StorageConfig {
     pub notes_directory: Option<String>,
     #[serde(rename = "useCustomDirectory")]
     pub use_custom_directory: bool,
+    /// Load notes through symlinked files and folders. Links back into folders
+    /// already scanned are skipped either way.
+    #[serde(rename = "followSymlinks")]
+    #[serde(default = "default_follow_symlinks")]
+    pub follow_symlinks: bool,
+    /// File extensions loaded as notes, without the dot
+    #[serde(rename = "noteExtensions")]
+    #[serde(default = "default_note_extensions")]
+    pub note_extensions: Vec<String>,
+    /// Extension for new notes; existing notes keep theirs
+    #[serde(rename = "defaultExtension")]
+    #[serde(default = "default_note_extension")]
+    pub default_extension: String,
+    /// Keep the text of notes written by Blink in the index, so a cold start
+    /// can skip reading files that haven't changed since. Off by default, as
+    /// it stores a second copy of those notes.
+    #[serde(rename = "contentCache")]
+    #[serde(default)]
+    pub content_cache: bool,
+    /// Notes directories opened before, for the vault picker
+    #[serde(rename = "recentVaults")]
+    #[serde(default)]
+    pub recent_vaults: Vec<RecentVault>,
+}
+
+/// A notes directory listed in the vault picker
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RecentVault {
+    pub path: String,
+    #[serde(rename = "lastOpened")]
+    pub last_opened: String,
+    /// Pinned vaults stay listed, first, however long ago they were opened
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Format-on-save transforms applied to note content
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FormattingConfig {
+    #[serde(rename = "trimTrailingWhitespace")]
+    pub trim_trailing_whitespace: bool,
+    #[serde(rename = "normalizeHeadingSpacing")]
+    pub normalize_heading_spacing: bool,
+    #[serde(rename = "convertSmartQuotes")]
+    pub convert_smart_quotes: bool,
+    #[serde(rename = "ensureFinalNewline")]
+    pub ensure_final_newline: bool,
+    /// Hard-wrap prose longer than this many characters; unset leaves lines alone
+    #[serde(rename = "wrapColumn", default)]
+    pub wrap_column: Option<usize>,
+}
+
+/// Hidden, pre-warmed note windows reused for new detached windows
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WindowPoolConfig {
+    pub enabled: bool,
+    #[serde(rename = "maxSize")]
+    pub max_size: usize,
+}
+
+/// How the main window comes up at launch
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct StartupConfig {
+    /// Keep the main window hidden until the toggle shortcut shows it
+    #[serde(rename = "startHidden")]
+    pub start_hidden: bool,
+    /// Start hidden behind a menu bar icon, without a Dock icon
+    #[serde(rename = "startInTray")]
+    pub start_in_tray: bool,
+    /// Reopen at the last size and position instead of centered at the default size
+    #[serde(rename = "restoreGeometry")]
+    pub restore_geometry: bool,
+    /// Register Blink as a login item
+    #[serde(rename = "launchAtLogin")]
+    pub launch_at_login: bool,
+}
+
+/// How pasted images are stored
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AttachmentsConfig {
+    /// Longest side of a pasted image in pixels; larger images are scaled down
+    #[serde(rename = "maxImageDimension")]
+    pub max_image_dimension: u32,
+    #[serde(rename = "jpegQuality")]
+    pub jpeg_quality: u8,
+    /// Recognize text in pasted images so search can find them
+    #[serde(default)]
+    pub ocr: bool,
+    /// Link files dropped onto a note where they are instead of copying them
+    /// into the vault. The link is relative, so it only survives moving the
+    /// vault when the files move with it.
+    #[serde(rename = "linkDroppedFiles")]
+    #[serde(default)]
+    pub link_dropped_files: bool,
+}
+
+/// How timestamps are shown in menus and exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateStyle {
+    /// "5 minutes ago", falling back to the date after a week
+    Relative,
+    /// Date and time in the user's locale
+    Absolute,
+    /// Date only, in the user's locale
+    Date,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DateFormatConfig {
+    pub style: DateStyle,
+    /// Locale such as `de_DE`; the system locale when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// Most verbose messages written to the log files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LoggingConfig {
+    pub level: LogLevel,
+    /// Overrides for one category such as `WINDOW`, or a specific one like `WINDOW_POOL`
+    #[serde(default)]
+    pub categories: std::collections::HashMap<String, LogLevel>,
+}
+
+/// Where a new note window opens when it has no saved position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpawnAnchor {
+    /// Near the top left of the screen
+    Screen,
+    /// Centered under the mouse cursor
+    Cursor,
+    /// Just inside the main window's top left corner
+    MainWindow,
+}
+
+/// Size and placement of new detached note windows
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct NewWindowConfig {
+    pub width: f64,
+    pub height: f64,
+    /// Offset between windows opened one after another
+    #[serde(rename = "cascadeStep")]
+    pub cascade_step: f64,
+    /// Cascade steps before wrapping back to the first position
+    #[serde(rename = "maxCascade")]
+    pub max_cascade: u32,
+    #[serde(rename = "spawnAt")]
+    pub spawn_at: SpawnAnchor,
+}
+
+/// Smallest note window; also the windows' minimum size
+pub const MIN_NOTE_WINDOW_SIZE: (f64, f64) = (400.0, 300.0);
+
+impl NewWindowConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.width >= MIN_NOTE_WINDOW_SIZE.0 && self.height >= MIN_NOTE_WINDOW_SIZE.1) {
+            return Err(format!(
+                "newWindows size must be at least {}x{}, got {}x{}",
+                MIN_NOTE_WINDOW_SIZE.0, MIN_NOTE_WINDOW_SIZE.1, self.width, self.height
+            ));
+        }
+        if !(0.0..=200.0).contains(&self.cascade_step) {
+            return Err(format!("newWindows.cascadeStep must be 0-200, got {}", self.cascade_step));
+        }
+        if self.max_cascade == 0 {
+            return Err("newWindows.maxCascade must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Offset of the `index`th window of a cascade
+    pub fn cascade_offset(&self, index: usize) -> f64 {
+        (index % self.max_cascade.max(1) as usize) as f64 * self.cascade_step
+    }
+}
+
+/// Reading mode windows. They keep their own look instead of taking the
+/// opacity and shade of note windows.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReadingConfig {
+    pub opacity: f64,
+    /// Open collapsed to the title bar
+    pub shaded: bool,
+    pub width: f64,
+    pub height: f64,
+    /// Characters per page, roughly; pages break between paragraphs
+    #[serde(rename = "pageChars")]
+    pub page_chars: usize,
+}
+
+/// An OpenAI-compatible chat completions endpoint used by the assistant
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssistantConfig {
+    pub endpoint: String,
+    #[serde(rename = "apiKey")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+/// An OpenAI-compatible `audio/transcriptions` endpoint
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TranscriptionConfig {
+    pub endpoint: String,
+    #[serde(rename = "apiKey")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub model: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -83,10 +340,96 @@ pub struct AppearanceConfig {
 }
 
 // Default constructors
+pub fn default_follow_symlinks() -> bool {
+    true
+}
+
+pub fn default_note_extensions() -> Vec<String> {
+    vec!["md".to_string()]
+}
+
+pub fn default_note_extension() -> String {
+    "md".to_string()
+}
+
 pub fn default_storage() -> StorageConfig {
     StorageConfig {
         notes_directory: None,
         use_custom_directory: false,
+        follow_symlinks: default_follow_symlinks(),
+        note_extensions: default_note_extensions(),
+        default_extension: default_note_extension(),
+        content_cache: false,
+        recent_vaults: Vec::new(),
+    }
+}
+
+pub fn default_formatting() -> FormattingConfig {
+    FormattingConfig {
+        trim_trailing_whitespace: false,
+        normalize_heading_spacing: false,
+        convert_smart_quotes: false,
+        ensure_final_newline: false,
+        wrap_column: None,
+    }
+}
+
+pub fn default_window_pool() -> WindowPoolConfig {
+    WindowPoolConfig {
+        enabled: true,
+        max_size: 2,
+    }
+}
+
+pub fn default_attachments() -> AttachmentsConfig {
+    AttachmentsConfig {
+        max_image_dimension: 2048,
+        jpeg_quality: 85,
+        ocr: false,
+        link_dropped_files: false,
+    }
+}
+
+pub fn default_startup() -> StartupConfig {
+    StartupConfig {
+        start_hidden: false,
+        start_in_tray: false,
+        restore_geometry: true,
+        launch_at_login: false,
+    }
+}
+
+pub fn default_date_format() -> DateFormatConfig {
+    DateFormatConfig {
+        style: DateStyle::Relative,
+        locale: None,
+    }
+}
+
+pub fn default_logging() -> LoggingConfig {
+    LoggingConfig {
+        level: LogLevel::Info,
+        categories: std::collections::HashMap::new(),
+    }
+}
+
+pub fn default_new_windows() -> NewWindowConfig {
+    NewWindowConfig {
+        width: 800.0,
+        height: 600.0,
+        cascade_step: 30.0,
+        max_cascade: 8,
+        spawn_at: SpawnAnchor::Screen,
+    }
+}
+
+pub fn default_reading() -> ReadingConfig {
+    ReadingConfig {
+        opacity: 1.0,
+        shaded: false,
+        width: 640.0,
+        height: 760.0,
+        page_chars: 1800,
     }
 }
 
@@ -127,6 +470,19 @@ impl Default for AppConfig {
             },
             appearance: default_appearance(),
             storage: default_storage(),
+            read_only: false,
+            formatting: default_formatting(),
+            window_pool: default_window_pool(),
+            offline: false,
+            attachments: default_attachments(),
+            assistant: None,
+            transcription: None,
+            startup: default_startup(),
+            instant_show: false,
+            date_format: default_date_format(),
+            logging: default_logging(),
+            reading: default_reading(),
+            new_windows: default_new_windows(),
         }
     }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -9,6 +11,117 @@ pub struct Note {
     pub updated_at: String,
     pub tags: Vec<String>,
     pub position: Option<i32>, // Manual ordering position
+    /// Emoji shown before the title in menus, window titles and the palette
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl Note {
+    /// Title as shown outside the editor, with the icon in front
+    pub fn display_title(&self) -> String {
+        let title = if self.title.trim().is_empty() { "Untitled Note" } else { self.title.as_str() };
+        match self.icon.as_deref() {
+            Some(icon) => format!("{} {}", icon, title),
+            None => title.to_string(),
+        }
+    }
+}
+
+/// The order notes are listed in: notes with a position first, by position;
+/// then the rest, most recently updated first. The id breaks any tie, so the
+/// order never depends on map iteration.
+pub fn note_order(a: &Note, b: &Note) -> Ordering {
+    match (a.position, b.position) {
+        (Some(pa), Some(pb)) => pa.cmp(&pb),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => b.updated_at.cmp(&a.updated_at),
+    }
+    .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Give every note an explicit position, 0..n in `note_order`.
+/// Returns the IDs of notes whose position changed.
+pub fn assign_positions(notes: &mut HashMap<String, Note>) -> Vec<String> {
+    let mut ordered: Vec<&Note> = notes.values().collect();
+    ordered.sort_by(|a, b| note_order(a, b));
+    let ids: Vec<String> = ordered.into_iter().map(|n| n.id.clone()).collect();
+
+    let mut changed = Vec::new();
+    for (index, id) in ids.into_iter().enumerate() {
+        let note = notes.get_mut(&id).expect("id came from the map");
+        if note.position != Some(index as i32) {
+            note.position = Some(index as i32);
+            changed.push(id);
+        }
+    }
+    changed
+}
+
+/// Spacing between positions given out by `move_positions`, so later moves can
+/// usually slot notes in between their neighbours without renumbering
+pub const POSITION_GAP: i32 = 1024;
+
+/// New positions for the selection placed between `before` and `after`, or
+/// `None` when there's no room and the list has to be renumbered
+fn positions_between(before: Option<i32>, after: Option<i32>, count: usize) -> Option<Vec<i32>> {
+    let count = count as i64;
+    let (low, high) = match (before, after) {
+        (Some(low), Some(high)) => (low as i64, high as i64),
+        (Some(low), None) => (low as i64, low as i64 + (count + 1) * POSITION_GAP as i64),
+        (None, Some(high)) => (high as i64 - (count + 1) * POSITION_GAP as i64, high as i64),
+        (None, None) => (-(POSITION_GAP as i64), count * POSITION_GAP as i64),
+    };
+    let step = (high - low) / (count + 1);
+    if step < 1 || low + step * count > i32::MAX as i64 || low + step < 0 {
+        return None;
+    }
+    Some((1..=count).map(|i| (low + step * i) as i32).collect())
+}
+
+/// Move `ids` to `target_index` in the list of the other notes, keeping their
+/// relative order, whether or not they were next to each other. Only the moved
+/// notes get new positions when there's room between their new neighbours;
+/// otherwise everything is renumbered `POSITION_GAP` apart.
+/// Returns the IDs of notes whose position changed.
+pub fn move_positions(notes: &mut HashMap<String, Note>, ids: &[String], target_index: usize) -> Vec<String> {
+    let mut ordered: Vec<&Note> = notes.values().collect();
+    ordered.sort_by(|a, b| note_order(a, b));
+    let (selection, rest): (Vec<String>, Vec<String>) = ordered
+        .into_iter()
+        .map(|n| n.id.clone())
+        .partition(|id| ids.contains(id));
+    if selection.is_empty() {
+        return Vec::new();
+    }
+    let target = target_index.min(rest.len());
+
+    // Notes without a position sort last; moving among them renumbers the list
+    let before = target.checked_sub(1).map(|i| notes[&rest[i]].position);
+    let after = rest.get(target).map(|id| notes[id].position);
+    let slots = match (before, after) {
+        (Some(None), _) | (_, Some(None)) => None,
+        (before, after) => positions_between(before.flatten(), after.flatten(), selection.len()),
+    };
+
+    let updates: Vec<(String, i32)> = match slots {
+        Some(slots) => selection.into_iter().zip(slots).collect(),
+        None => {
+            let mut order = rest;
+            order.splice(target..target, selection);
+            order.into_iter().enumerate().map(|(i, id)| (id, i as i32 * POSITION_GAP)).collect()
+        }
+    };
+
+    let mut changed = Vec::new();
+    for (id, position) in updates {
+        let note = notes.get_mut(&id).expect("id came from the map");
+        if note.position != Some(position) {
+            note.position = Some(position);
+            changed.push(id);
+        }
+    }
+    changed
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -23,6 +136,12 @@ pub struct UpdateNoteRequest {
     pub title: Option<String>,
     pub content: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Hash of the content the editor started from, used to detect concurrent edits
+    #[serde(default)]
+    pub base_hash: Option<String>,
+    /// Save content over the size and line limits anyway
+    #[serde(default, rename = "allowLarge")]
+    pub allow_large: bool,
 }
 
 // Internal type for parsing frontmatter
@@ -35,4 +154,102 @@ pub struct NoteFrontmatter {
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<i32>,
-}
\ No newline at end of file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, position: Option<i32>, updated_at: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: updated_at.to_string(),
+            tags: vec![],
+            position,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_note_order_is_total() {
+        let mut notes = vec![
+            note("old", None, "2024-01-01T00:00:00Z"),
+            note("b", Some(1), "2024-01-01T00:00:00Z"),
+            note("new-2", None, "2024-03-01T00:00:00Z"),
+            note("a", Some(1), "2024-01-01T00:00:00Z"),
+            note("first", Some(0), "2024-01-01T00:00:00Z"),
+            note("new-1", None, "2024-03-01T00:00:00Z"),
+        ];
+        notes.sort_by(note_order);
+        let ids: Vec<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["first", "a", "b", "new-1", "new-2", "old"]);
+    }
+
+    #[test]
+    fn test_assign_positions() {
+        let mut notes: HashMap<String, Note> = [
+            note("x", Some(5), "2024-01-01T00:00:00Z"),
+            note("y", Some(1), "2024-01-01T00:00:00Z"),
+            note("z", None, "2024-01-01T00:00:00Z"),
+        ]
+        .into_iter()
+        .map(|n| (n.id.clone(), n))
+        .collect();
+
+        let changed = assign_positions(&mut notes);
+        assert_eq!(changed, ["y", "x", "z"]);
+        assert_eq!(notes["y"].position, Some(0));
+        assert_eq!(notes["x"].position, Some(1));
+        assert_eq!(notes["z"].position, Some(2));
+        assert!(assign_positions(&mut notes).is_empty());
+    }
+
+    fn ordered_ids(notes: &HashMap<String, Note>) -> Vec<String> {
+        let mut ordered: Vec<&Note> = notes.values().collect();
+        ordered.sort_by(|a, b| note_order(a, b));
+        ordered.into_iter().map(|n| n.id.clone()).collect()
+    }
+
+    fn positioned(ids: &[&str], gap: i32) -> HashMap<String, Note> {
+        ids.iter()
+            .enumerate()
+            .map(|(i, id)| (id.to_string(), note(id, Some(i as i32 * gap), "2024-01-01T00:00:00Z")))
+            .collect()
+    }
+
+    fn strings(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_move_positions_fills_gaps() {
+        let mut notes = positioned(&["a", "b", "c", "d", "e"], POSITION_GAP);
+
+        // Non-contiguous selection dropped between "c" and "e"
+        let changed = move_positions(&mut notes, &strings(&["d", "a"]), 2);
+        assert_eq!(ordered_ids(&notes), ["b", "c", "a", "d", "e"]);
+        assert_eq!(changed, ["a", "d"]);
+
+        // To the front and to the end, still without touching anything else
+        assert_eq!(move_positions(&mut notes, &strings(&["e"]), 0), ["e"]);
+        assert_eq!(move_positions(&mut notes, &strings(&["b"]), 10), ["b"]);
+        assert_eq!(ordered_ids(&notes), ["e", "c", "a", "d", "b"]);
+    }
+
+    #[test]
+    fn test_move_positions_renumbers_when_full() {
+        let mut notes = positioned(&["a", "b", "c"], 1);
+        notes.insert("z".to_string(), note("z", None, "2024-01-01T00:00:00Z"));
+
+        let changed = move_positions(&mut notes, &strings(&["c"]), 1);
+        assert_eq!(ordered_ids(&notes), ["a", "c", "b", "z"]);
+        assert_eq!(changed, ["c", "b", "z"]);
+        assert_eq!(notes["z"].position, Some(3 * POSITION_GAP));
+
+        assert!(move_positions(&mut notes, &strings(&["missing"]), 0).is_empty());
+    }
+}
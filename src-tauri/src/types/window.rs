@@ -15,6 +15,43 @@ pub struct DetachedWindow {
     pub is_shaded: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_height: Option<f64>,
+    /// Set while the window is collapsed to a tab on a screen edge. `position`
+    /// and `size` keep the undocked geometry meanwhile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dock: Option<WindowDock>,
+    /// Shown on every Space / virtual desktop rather than only the one it was opened on
+    #[serde(default)]
+    pub visible_on_all_workspaces: bool,
+}
+
+/// Screen edge a note window can be docked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Geometry of a window docked as a tab, in physical pixels
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WindowDock {
+    pub edge: DockEdge,
+    /// Whether the window is slid out from its tab (hovered or clicked)
+    pub expanded: bool,
+    pub tab_position: (f64, f64),
+    pub tab_size: (f64, f64),
+}
+
+/// Where `reveal_note` surfaced a note
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevealTarget {
+    /// An existing detached window was focused
+    Detached,
+    /// The note was selected in the main window
+    Main,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -26,8 +63,24 @@ pub struct CreateDetachedWindowRequest {
     pub height: Option<f64>,
 }
 
+/// Tracks rapid successive cursor deploys so new windows cascade instead of stacking
+#[derive(Debug, Default)]
+pub struct CursorDeployCascade {
+    pub last_deploy: Option<std::time::Instant>,
+    pub step: u32,
+}
+
+/// Soft editing lock held by the window that is currently editing a note
+#[derive(Debug, Clone)]
+pub struct NoteEditLock {
+    pub holder: String,
+    pub last_active: std::time::Instant,
+}
+
 // State type aliases for cleaner code
 pub type NotesState = Mutex<HashMap<String, Note>>;
 pub type ConfigState = Mutex<AppConfig>;
 pub type DetachedWindowsState = Mutex<HashMap<String, DetachedWindow>>;
-pub type ToggleState = Mutex<bool>;
\ No newline at end of file
+pub type ToggleState = Mutex<bool>;
+pub type CursorDeployState = Mutex<CursorDeployCascade>;
+pub type NoteEditLockState = Mutex<HashMap<String, NoteEditLock>>;
\ No newline at end of file
@@ -24,6 +24,105 @@ pub struct WindowState {
     pub opacity: f64,
 }
 
+/// Settings stored in the vault's `.blink/settings.json` so they travel with the notes
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct WorkspaceSettings {
+    /// How the notes list is ordered, e.g. "manual", "updated", "title"
+    #[serde(rename = "sortOrder")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+    /// Note templates by name
+    #[serde(default)]
+    pub templates: HashMap<String, String>,
+    /// Pinned note IDs, in display order
+    #[serde(rename = "pinnedNotes")]
+    #[serde(default)]
+    pub pinned_notes: Vec<String>,
+    /// Partial AppConfig merged over the machine's config
+    #[serde(rename = "configOverrides")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub config_overrides: serde_json::Map<String, serde_json::Value>,
+    /// Tag → folder rules applied when notes are saved
+    #[serde(rename = "tagRouting")]
+    #[serde(default)]
+    pub tag_routing: Vec<TagRoutingRule>,
+    /// Searches listed in the Notes menu with their matching notes
+    #[serde(rename = "savedFilters")]
+    #[serde(default)]
+    pub saved_filters: Vec<SavedFilter>,
+    /// Rules for archiving notes nobody has touched in a while
+    #[serde(rename = "archivePolicies")]
+    #[serde(default)]
+    pub archive_policies: Vec<ArchivePolicy>,
+    /// CSS injected into a note's window, by note ID
+    #[serde(rename = "noteStyles")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub note_styles: HashMap<String, String>,
+}
+
+/// What an archive policy does with the notes it matches
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveAction {
+    /// Tag the notes `archived`; tag routing can then file them away
+    Archive,
+    /// Only tell the frontend, which can offer to archive them
+    #[default]
+    Suggest,
+}
+
+/// Notes with `tag` not updated for `olderThanDays` days are archived or suggested
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ArchivePolicy {
+    /// Tag the note must have, with or without `#`; any note when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(rename = "olderThanDays")]
+    pub older_than_days: u32,
+    #[serde(default)]
+    pub action: ArchiveAction,
+    #[serde(default = "default_policy_enabled")]
+    pub enabled: bool,
+}
+
+fn default_policy_enabled() -> bool {
+    true
+}
+
+/// A saved search. Every condition that is set must match.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct SavedFilter {
+    pub name: String,
+    /// Words to find in the title or content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Tags the note must all have, with or without `#`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only notes without any tags
+    #[serde(default)]
+    pub untagged: bool,
+    /// Folder relative to the vault root, including its subfolders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// Only notes edited in the last this many days
+    #[serde(rename = "updatedWithinDays")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_within_days: Option<u32>,
+    /// How many notes the menu lists; defaults to `SAVED_FILTER_MENU_LIMIT`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Notes with `tag` are filed into `folder` (relative to the vault root)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TagRoutingRule {
+    pub tag: String,
+    pub folder: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct NotesIndex {
     pub notes: HashMap<String, NoteIndexEntry>,
@@ -1,5 +1,7 @@
+pub mod paths;
 pub mod slug;
 pub mod uuid_from_slug;
+pub mod window_geometry;
 
-pub use slug::{generate_slug, generate_unique_slug};
+pub use slug::{generate_slug, generate_unique_slug, sanitize_filename};
 pub use uuid_from_slug::uuid_from_slug;
\ No newline at end of file
@@ -0,0 +1,156 @@
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// Why a path was refused
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PathError {
+    #[error("{} is outside the folders Blink may use", .0.display())]
+    OutsideRoots(PathBuf),
+    #[error("Absolute paths are not allowed here: {0}")]
+    Absolute(String),
+    #[error("{0} points outside its folder")]
+    Traversal(String),
+    #[error("Cannot resolve {}: {reason}", .path.display())]
+    Unresolvable { path: PathBuf, reason: String },
+}
+
+impl From<PathError> for String {
+    fn from(err: PathError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Resolve `.` and `..` without touching the filesystem. `..` never climbs
+/// above the root of an absolute path.
+pub fn lexical_normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() && !out.has_root() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// A link or stored path relative to some folder, as it would resolve there.
+/// Refuses absolute paths and anything that climbs out of the folder.
+pub fn normalize_relative(relative: &str) -> Result<PathBuf, PathError> {
+    let path = Path::new(relative);
+    if path.has_root() || path.is_absolute() {
+        return Err(PathError::Absolute(relative.to_string()));
+    }
+    let normalized = lexical_normalize(path);
+    if normalized.components().next() == Some(Component::ParentDir) {
+        return Err(PathError::Traversal(relative.to_string()));
+    }
+    Ok(normalized)
+}
+
+/// Canonicalize a path that may not exist yet: the deepest existing ancestor is
+/// canonicalized, so symlinks are followed, and the rest is appended.
+pub fn canonicalize_lenient(path: &Path) -> Result<PathBuf, PathError> {
+    let unresolvable = |reason: String| PathError::Unresolvable { path: path.to_path_buf(), reason };
+    if !path.is_absolute() {
+        return Err(unresolvable("not an absolute path".to_string()));
+    }
+
+    let normalized = lexical_normalize(path);
+    let mut existing = normalized.as_path();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        rest.push(existing.file_name().ok_or_else(|| unresolvable("no existing parent".to_string()))?);
+        existing = existing.parent().ok_or_else(|| unresolvable("no existing parent".to_string()))?;
+    }
+
+    let mut resolved = existing.canonicalize().map_err(|e| unresolvable(e.to_string()))?;
+    resolved.extend(rest.into_iter().rev());
+    Ok(resolved)
+}
+
+/// The canonical form of `path`, provided it lies inside one of `roots`
+pub fn ensure_within(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, PathError> {
+    let resolved = canonicalize_lenient(path)?;
+    let inside = roots
+        .iter()
+        .filter_map(|root| canonicalize_lenient(root).ok())
+        .any(|root| resolved.starts_with(root));
+    if inside {
+        Ok(resolved)
+    } else {
+        Err(PathError::OutsideRoots(path.to_path_buf()))
+    }
+}
+
+/// Join a relative link onto `root` and make sure the result, symlinks
+/// included, stays inside it
+pub fn resolve_inside(root: &Path, relative: &str) -> Result<PathBuf, PathError> {
+    let joined = root.join(normalize_relative(relative)?);
+    ensure_within(&joined, &[root.to_path_buf()])
+}
+
+/// Where imports may be read from and exports written to: the user's home and
+/// temp folders, mounted volumes, and the vault itself (which may live elsewhere)
+pub fn user_roots(notes_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = dirs::home_dir().into_iter().collect();
+    roots.push(std::env::temp_dir());
+    if cfg!(target_os = "macos") {
+        roots.push(PathBuf::from("/Volumes"));
+    } else if cfg!(target_os = "linux") {
+        roots.extend(["/media", "/mnt", "/run/media"].map(PathBuf::from));
+    }
+    roots.extend(notes_dir.map(Path::to_path_buf));
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_relative() {
+        assert_eq!(normalize_relative("attachments/./a.png").unwrap(), PathBuf::from("attachments/a.png"));
+        assert_eq!(normalize_relative("a/../b.png").unwrap(), PathBuf::from("b.png"));
+        assert_eq!(normalize_relative("../secret").unwrap_err(), PathError::Traversal("../secret".to_string()));
+        assert!(matches!(normalize_relative("a/../../b"), Err(PathError::Traversal(_))));
+        assert!(matches!(normalize_relative("/etc/passwd"), Err(PathError::Absolute(_))));
+    }
+
+    #[test]
+    fn test_resolve_inside() {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir(root.path().join("attachments")).unwrap();
+
+        let inside = resolve_inside(root.path(), "attachments/new.png").unwrap();
+        assert!(inside.ends_with("attachments/new.png"));
+        assert!(resolve_inside(root.path(), "attachments/../../x").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_out_of_the_root_are_refused() {
+        let root = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.path().join("escape")).unwrap();
+
+        assert!(matches!(
+            resolve_inside(root.path(), "escape/file.txt"),
+            Err(PathError::OutsideRoots(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_within() {
+        let root = TempDir::new().unwrap();
+        let roots = vec![root.path().to_path_buf()];
+        assert!(ensure_within(&root.path().join("exports/notes.json"), &roots).is_ok());
+        assert!(ensure_within(&root.path().join("../elsewhere.json"), &roots).is_err());
+        assert!(matches!(ensure_within(Path::new("relative.json"), &roots), Err(PathError::Unresolvable { .. })));
+    }
+}
@@ -1,23 +1,45 @@
 use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+
+/// Longest slug/filename stem in bytes, leaving room for a collision suffix and
+/// extension under the common 255-byte filename limit
+const MAX_SLUG_BYTES: usize = 200;
+
+/// Names Windows refuses as filenames regardless of extension
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul",
+    "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Truncate to at most `max_bytes` without splitting a character
+fn truncate_to_bytes(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
 
 /// Generate a slug from a title with explicit rules:
-/// 1. Convert to lowercase
-/// 2. Replace spaces with single hyphen
-/// 3. Replace multiple consecutive spaces with single hyphen
-/// 4. Allow only: a-z, 0-9, hyphen, underscore
-/// 5. Replace any other character with hyphen
-/// 6. Collapse multiple consecutive hyphens into one
-/// 7. Trim hyphens from start and end
+/// 1. Normalize to Unicode NFC (macOS hands back decomposed filenames)
+/// 2. Convert to lowercase
+/// 3. Keep letters and digits from any script (e.g. "café", "日本語")
+/// 4. Replace any other character (spaces, punctuation, emoji) with hyphen
+/// 5. Collapse multiple consecutive hyphens into one
+/// 6. Trim hyphens from start and end
+/// 7. Cap the length at `MAX_SLUG_BYTES`
 pub fn generate_slug(title: &str) -> String {
     let slug = title
         .trim()
+        .nfc()
+        .collect::<String>()
         .to_lowercase()
         .chars()
-        .map(|c| match c {
-            'a'..='z' | '0'..='9' => c,
-            ' ' | '-' | '_' => '-',  // spaces and special chars become hyphens
-            _ => '-',  // any other character becomes hyphen
-        })
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
         .collect::<String>();
     
     // Collapse multiple hyphens and trim
@@ -30,7 +52,8 @@ pub fn generate_slug(title: &str) -> String {
         // If title was all special characters, generate a default
         "untitled".to_string()
     } else {
-        parts.join("-")
+        let joined = parts.join("-");
+        truncate_to_bytes(&joined, MAX_SLUG_BYTES).trim_end_matches('-').to_string()
     }
 }
 
@@ -54,6 +77,34 @@ pub fn generate_unique_slug(title: &str, existing_ids: &HashSet<String>) -> Stri
     }
 }
 
+/// Make a title safe to use as a filename on macOS, Windows and Linux while
+/// keeping it readable. Unlike slugs this keeps case, spaces and emoji.
+pub fn sanitize_filename(title: &str) -> String {
+    let cleaned: String = title
+        .nfc()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    
+    // Leading dots hide files; trailing dots and spaces are stripped by Windows
+    let trimmed = cleaned.trim().trim_start_matches('.').trim_end_matches(['.', ' ']);
+    let truncated = truncate_to_bytes(trimmed, MAX_SLUG_BYTES).trim_end_matches(['.', ' ']);
+    
+    if truncated.is_empty() {
+        return "untitled".to_string();
+    }
+    
+    let stem = truncated.split('.').next().unwrap_or_default().to_lowercase();
+    if WINDOWS_RESERVED_NAMES.contains(&stem.as_str()) {
+        return format!("_{}", truncated);
+    }
+    
+    truncated.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +128,48 @@ mod tests {
         assert_eq!(generate_unique_slug("Hello World", &existing), "hello-world-3");
         assert_eq!(generate_unique_slug("New Title", &existing), "new-title");
     }
+
+    #[test]
+    fn test_unicode_slugs() {
+        assert_eq!(generate_slug("日本語のメモ"), "日本語のメモ");
+        assert_eq!(generate_slug("Café Notes"), "café-notes");
+        assert_eq!(generate_slug("Привет Мир"), "привет-мир");
+        assert_eq!(generate_slug("🚀 Launch plan"), "launch-plan");
+        assert_eq!(generate_slug("🚀🎉"), "untitled");
+    }
+
+    #[test]
+    fn test_decomposed_input_matches_composed() {
+        // "é" as e + combining acute, as returned by HFS+
+        assert_eq!(generate_slug("Cafe\u{301}"), generate_slug("Caf\u{e9}"));
+    }
+
+    #[test]
+    fn test_unicode_collisions_get_suffixes() {
+        let mut existing = HashSet::new();
+        existing.insert(generate_slug("🚀"));
+        existing.insert(generate_slug("会議"));
+
+        assert_eq!(generate_unique_slug("🎉", &existing), "untitled-2");
+        assert_eq!(generate_unique_slug("会議", &existing), "会議-2");
+    }
+
+    #[test]
+    fn test_long_titles_are_truncated_on_char_boundary() {
+        let title = "語".repeat(100);
+        let slug = generate_slug(&title);
+        assert!(slug.len() <= MAX_SLUG_BYTES);
+        assert!(slug.chars().all(|c| c == '語'));
+    }
+
+    #[test]
+    fn test_sanitize_filename_cross_platform() {
+        assert_eq!(sanitize_filename("a/b\\c:d"), "a-b-c-d");
+        assert_eq!(sanitize_filename("  .hidden note. "), "hidden note");
+        assert_eq!(sanitize_filename("CON"), "_CON");
+        assert_eq!(sanitize_filename("nul.txt"), "_nul.txt");
+        assert_eq!(sanitize_filename("🚀 Launch"), "🚀 Launch");
+        assert_eq!(sanitize_filename("日本語"), "日本語");
+        assert_eq!(sanitize_filename("..."), "untitled");
+    }
 }
\ No newline at end of file
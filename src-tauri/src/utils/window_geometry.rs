@@ -0,0 +1,267 @@
+use crate::types::window::DockEdge;
+
+/// Axis-aligned rectangle in screen coordinates, physical or logical
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    /// Whether the two rectangles overlap on the vertical axis
+    fn overlaps_vertically(&self, other: &Rect) -> bool {
+        self.y < other.bottom() && other.y < self.bottom()
+    }
+
+    /// Whether the two rectangles overlap on the horizontal axis
+    fn overlaps_horizontally(&self, other: &Rect) -> bool {
+        self.x < other.right() && other.x < self.right()
+    }
+}
+
+/// Find the smallest adjustment that aligns one of `edges` with one of `targets`
+fn closest_snap(edges: &[f64], targets: &[f64], threshold: f64) -> Option<f64> {
+    let mut best: Option<f64> = None;
+    for edge in edges {
+        for target in targets {
+            let delta = target - edge;
+            if delta.abs() <= threshold && best.map_or(true, |b| delta.abs() < b.abs()) {
+                best = Some(delta);
+            }
+        }
+    }
+    best
+}
+
+/// Snap a window rectangle to screen edges and to the edges of other windows.
+///
+/// Returns the corrected top-left position if either axis moved, or `None` when
+/// the window is not within `threshold` pixels of any edge.
+pub fn snap_window_position(
+    window: Rect,
+    screens: &[Rect],
+    others: &[Rect],
+    threshold: f64,
+) -> Option<(f64, f64)> {
+    let mut x_targets = Vec::new();
+    let mut y_targets = Vec::new();
+
+    for screen in screens {
+        x_targets.extend([screen.x, screen.right()]);
+        y_targets.extend([screen.y, screen.bottom()]);
+    }
+
+    // Only align against windows that are beside/above us, otherwise
+    // every window on screen would pull at the dragged one
+    for other in others {
+        if window.overlaps_vertically(other) {
+            x_targets.extend([other.x, other.right()]);
+        }
+        if window.overlaps_horizontally(other) {
+            y_targets.extend([other.y, other.bottom()]);
+        }
+    }
+
+    let dx = closest_snap(&[window.x, window.right()], &x_targets, threshold);
+    let dy = closest_snap(&[window.y, window.bottom()], &y_targets, threshold);
+
+    if dx.is_none() && dy.is_none() {
+        return None;
+    }
+
+    Some((window.x + dx.unwrap_or(0.0), window.y + dy.unwrap_or(0.0)))
+}
+
+/// How much of a window's top edge must be on a screen for it to be grabbable
+const MIN_VISIBLE: f64 = 48.0;
+
+/// Distance from a point to the nearest point of a rectangle
+fn distance_to(rect: &Rect, (x, y): (f64, f64)) -> f64 {
+    let dx = (rect.x - x).max(x - rect.right()).max(0.0);
+    let dy = (rect.y - y).max(y - rect.bottom()).max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Move a window that can't be reached on any screen back into view, keeping its
+/// size. A window counts as reachable while a strip of its title bar is on a screen.
+///
+/// Returns the new top-left, or `None` when the window can stay where it is.
+pub fn nudge_onscreen(window: Rect, screens: &[Rect]) -> Option<(f64, f64)> {
+    let reachable = screens.iter().any(|s| {
+        let visible_width = window.right().min(s.right()) - window.x.max(s.x);
+        window.y >= s.y && window.y <= s.bottom() - MIN_VISIBLE && visible_width >= MIN_VISIBLE.min(window.width)
+    });
+    if reachable {
+        return None;
+    }
+
+    let center = (window.x + window.width / 2.0, window.y + window.height / 2.0);
+    let screen = screens
+        .iter()
+        .min_by(|a, b| distance_to(a, center).total_cmp(&distance_to(b, center)))?;
+    Some((
+        clamp_span(window.x, window.width, screen.x, screen.right()),
+        clamp_span(window.y, window.height, screen.y, screen.bottom()),
+    ))
+}
+
+/// Keep a span of `len` starting at `start` inside `min..max`
+fn clamp_span(start: f64, len: f64, min: f64, max: f64) -> f64 {
+    start.min(max - len).max(min)
+}
+
+/// Top-left that keeps `window` inside `screen`, pinning the top-left corner
+/// when the window is larger than the screen
+pub fn keep_within(window: Rect, screen: Rect) -> (f64, f64) {
+    (
+        clamp_span(window.x, window.width, screen.x, screen.right()),
+        clamp_span(window.y, window.height, screen.y, screen.bottom()),
+    )
+}
+
+/// Where the tab of a window docked to `edge` goes: flush with the edge, level
+/// with where the window was
+pub fn docked_tab_rect(edge: DockEdge, screen: Rect, window: Rect, thickness: f64, length: f64) -> Rect {
+    match edge {
+        DockEdge::Left | DockEdge::Right => {
+            let x = if edge == DockEdge::Left { screen.x } else { screen.right() - thickness };
+            Rect::new(x, clamp_span(window.y, length, screen.y, screen.bottom()), thickness, length)
+        }
+        DockEdge::Top | DockEdge::Bottom => {
+            let y = if edge == DockEdge::Top { screen.y } else { screen.bottom() - thickness };
+            Rect::new(clamp_span(window.x, length, screen.x, screen.right()), y, length, thickness)
+        }
+    }
+}
+
+/// A docked window slid out from its tab at full size, still against the edge
+pub fn expanded_dock_rect(edge: DockEdge, screen: Rect, tab: Rect, size: (f64, f64)) -> Rect {
+    let (width, height) = (size.0.min(screen.width), size.1.min(screen.height));
+    match edge {
+        DockEdge::Left | DockEdge::Right => {
+            let x = if edge == DockEdge::Left { screen.x } else { screen.right() - width };
+            Rect::new(x, clamp_span(tab.y, height, screen.y, screen.bottom()), width, height)
+        }
+        DockEdge::Top | DockEdge::Bottom => {
+            let y = if edge == DockEdge::Top { screen.y } else { screen.bottom() - height };
+            Rect::new(clamp_span(tab.x, width, screen.x, screen.right()), y, width, height)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Rect = Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+
+    #[test]
+    fn test_snaps_to_screen_edges() {
+        let window = Rect::new(8.0, 500.0, 400.0, 300.0);
+        assert_eq!(snap_window_position(window, &[SCREEN], &[], 16.0), Some((0.0, 500.0)));
+
+        let window = Rect::new(1510.0, 775.0, 400.0, 300.0);
+        assert_eq!(snap_window_position(window, &[SCREEN], &[], 16.0), Some((1520.0, 780.0)));
+    }
+
+    #[test]
+    fn test_no_snap_outside_threshold() {
+        let window = Rect::new(200.0, 200.0, 400.0, 300.0);
+        assert_eq!(snap_window_position(window, &[SCREEN], &[], 16.0), None);
+    }
+
+    #[test]
+    fn test_snaps_beside_other_window() {
+        let other = Rect::new(100.0, 100.0, 400.0, 300.0);
+        let window = Rect::new(510.0, 120.0, 400.0, 300.0);
+        // Only the facing edges line up; the tops are 20px apart, past the threshold
+        assert_eq!(snap_window_position(window, &[SCREEN], &[other], 16.0), Some((500.0, 120.0)));
+    }
+
+    #[test]
+    fn test_ignores_windows_not_adjacent() {
+        // Same x edge but far below: should not pull horizontally
+        let other = Rect::new(100.0, 700.0, 400.0, 200.0);
+        let window = Rect::new(505.0, 100.0, 400.0, 300.0);
+        assert_eq!(snap_window_position(window, &[SCREEN], &[other], 16.0), None);
+    }
+
+    /// Laptop screen with an external display to its right
+    const SCREENS: [Rect; 2] = [
+        Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 },
+        Rect { x: 1920.0, y: 0.0, width: 1440.0, height: 900.0 },
+    ];
+
+    #[test]
+    fn test_nudge_leaves_reachable_windows() {
+        assert_eq!(nudge_onscreen(Rect::new(200.0, 200.0, 400.0, 300.0), &SCREENS), None);
+        // Straddling both displays
+        assert_eq!(nudge_onscreen(Rect::new(1800.0, 100.0, 400.0, 300.0), &SCREENS), None);
+        // Mostly off the left edge, title bar still grabbable
+        assert_eq!(nudge_onscreen(Rect::new(-300.0, 100.0, 400.0, 300.0), &SCREENS), None);
+        assert_eq!(nudge_onscreen(Rect::new(200.0, 200.0, 400.0, 300.0), &[]), None);
+    }
+
+    #[test]
+    fn test_nudge_onto_nearest_screen() {
+        // Left behind by a disconnected display to the far right
+        assert_eq!(nudge_onscreen(Rect::new(5000.0, 100.0, 400.0, 300.0), &SCREENS), Some((2960.0, 100.0)));
+        // Title bar above the top of the screen
+        assert_eq!(nudge_onscreen(Rect::new(100.0, -200.0, 400.0, 300.0), &SCREENS), Some((100.0, 0.0)));
+        // Below the shorter external display
+        assert_eq!(nudge_onscreen(Rect::new(2500.0, 1000.0, 400.0, 300.0), &SCREENS), Some((2500.0, 600.0)));
+    }
+
+    #[test]
+    fn test_nudge_keeps_size_of_large_windows() {
+        // Larger than any screen: pin the top-left corner so the title bar shows
+        assert_eq!(nudge_onscreen(Rect::new(-3000.0, 0.0, 2500.0, 1200.0), &SCREENS), Some((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_keep_within_screens_left_of_the_primary() {
+        let left = Rect::new(-1440.0, -200.0, 1440.0, 900.0);
+        assert_eq!(keep_within(Rect::new(-800.0, 100.0, 400.0, 300.0), left), (-800.0, 100.0));
+        assert_eq!(keep_within(Rect::new(-1500.0, -250.0, 400.0, 300.0), left), (-1440.0, -200.0));
+        assert_eq!(keep_within(Rect::new(-200.0, 500.0, 400.0, 300.0), left), (-400.0, 400.0));
+    }
+
+    #[test]
+    fn test_docked_tab_rect() {
+        let window = Rect::new(600.0, 900.0, 400.0, 300.0);
+        assert_eq!(docked_tab_rect(DockEdge::Left, SCREEN, window, 24.0, 160.0), Rect::new(0.0, 900.0, 24.0, 160.0));
+        assert_eq!(docked_tab_rect(DockEdge::Right, SCREEN, window, 24.0, 160.0), Rect::new(1896.0, 900.0, 24.0, 160.0));
+        assert_eq!(docked_tab_rect(DockEdge::Bottom, SCREEN, window, 24.0, 160.0), Rect::new(600.0, 1056.0, 160.0, 24.0));
+
+        // Tabs stay on screen even when the window hangs off it
+        let window = Rect::new(-50.0, 1000.0, 400.0, 300.0);
+        assert_eq!(docked_tab_rect(DockEdge::Left, SCREEN, window, 24.0, 160.0), Rect::new(0.0, 920.0, 24.0, 160.0));
+    }
+
+    #[test]
+    fn test_expanded_dock_rect() {
+        let tab = Rect::new(1896.0, 900.0, 24.0, 160.0);
+        assert_eq!(expanded_dock_rect(DockEdge::Right, SCREEN, tab, (400.0, 300.0)), Rect::new(1520.0, 780.0, 400.0, 300.0));
+
+        let tab = Rect::new(100.0, 0.0, 160.0, 24.0);
+        assert_eq!(expanded_dock_rect(DockEdge::Top, SCREEN, tab, (400.0, 300.0)), Rect::new(100.0, 0.0, 400.0, 300.0));
+    }
+}
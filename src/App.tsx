@@ -1,11 +1,16 @@
 import { useState, useCallback } from 'react';
 import { 
+  AboutWindow,
   DetachedNoteWindow, 
-  DragGhost 
+  DragGhost,
+  NotePaletteWindow,
+  ReadingWindow,
+  VaultPickerWindow
 } from './components/windows';
 import { 
   SettingsPanel, 
-  SettingsNavigation 
+  SettingsNavigation,
+  SettingsWindow
 } from './components/settings';
 import { DevToolbar } from './components/dev/DevToolbar';
 import { 
@@ -19,7 +24,8 @@ import {
   EditorArea 
 } from './components/notes';
 import { 
-  ChordHint 
+  ChordHint,
+  OnboardingPrompt
 } from './components/common';
 import { 
   useDetachedWindowsStore,
@@ -39,7 +45,8 @@ import {
   useContextMenu,
   useChordShortcuts,
   useWindowManager,
-  useGlobalEventListeners
+  useGlobalEventListeners,
+  useOnboarding
 } from './hooks';
 import { getThemeById } from './types';
 import { getWordCount } from './lib/utils';
@@ -54,7 +61,7 @@ function App() {
   const [selectedSettingsSection, setSelectedSettingsSection] = useState<'general' | 'appearance' | 'shortcuts' | 'editor' | 'advanced'>('appearance');
 
   // Window detection from hook
-  const { isDetachedWindow, detachedNoteId, isDragGhost, dragGhostTitle } = useWindowManager();
+  const { isDetachedWindow, detachedNoteId, isDragGhost, dragGhostTitle, isNotePalette, isAboutWindow, isVaultPicker, isPooledWindow, isSettingsWindow, settingsSection, readingNoteId, readingOpacity } = useWindowManager();
   
   // App initialization
   useAppInitialization({ isDetachedWindow });
@@ -171,7 +178,7 @@ function App() {
   });
 
   // Chord shortcuts hook for advanced keyboard combinations
-  const { chordMode, showChordHint, startWindowMode } = useChordShortcuts({
+  const { chordMode, showChordHint, startWindowMode, clearChordMode } = useChordShortcuts({
     notes: notes.map(note => ({ id: note.id, title: note.title })),
     onSelectNote: selectNote,
     onCreateNewNote: createNewNote,
@@ -216,8 +223,12 @@ function App() {
     notes,
     onCreateNewNote: createNewNote,
     onStartWindowMode: startWindowMode,
+    onEndWindowMode: clearChordMode,
   });
   
+  // First-launch setup, shown over the main window
+  const onboarding = useOnboarding();
+
   // Debug logging
   // console.log('Config loaded:', config);
   // console.log('Focus mode:', config.appearance?.focusMode);
@@ -245,6 +256,11 @@ function App() {
     }
   };
 
+  // Idle pooled windows stay blank until they're given a note
+  if (isPooledWindow) {
+    return <div className="w-full h-full" />;
+  }
+
   // If this is a detached window, render the detached note component
   if (isDetachedWindow && detachedNoteId) {
     return <DetachedNoteWindow noteId={detachedNoteId} />;
@@ -255,6 +271,31 @@ function App() {
     return <DragGhost noteTitle={dragGhostTitle} distance={100} threshold={60} />;
   }
 
+  // If this is the "Open Note…" palette, render only the palette
+  if (isNotePalette) {
+    return <NotePaletteWindow />;
+  }
+
+  // If this is the About window, render only the about details
+  if (isAboutWindow) {
+    return <AboutWindow />;
+  }
+
+  // If this is the vault picker, render only the vault list
+  if (isVaultPicker) {
+    return <VaultPickerWindow />;
+  }
+
+  // If this is a reading window, render only the current page
+  if (readingNoteId) {
+    return <ReadingWindow opacity={readingOpacity} />;
+  }
+
+  // If this is the settings window, render only the settings
+  if (isSettingsWindow) {
+    return <SettingsWindow initialSection={settingsSection} />;
+  }
+
   // Calculate word count for current content
   const wordCount = getWordCount(currentContent);
   
@@ -373,6 +414,13 @@ function App() {
         visible={showChordHint}
         notes={notes.map(note => ({ id: note.id, title: note.title }))}
       />
+
+      {/* First-launch setup */}
+      <OnboardingPrompt
+        info={onboarding.onboarding}
+        error={onboarding.error}
+        onComplete={onboarding.complete}
+      />
       
       {/* Dev toolbar - only show in development */}
       {process.env.NODE_ENV === 'development' && !isDetachedWindow && <DevToolbar />}
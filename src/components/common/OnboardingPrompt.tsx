@@ -0,0 +1,71 @@
+import { useState } from 'react';
+import type { OnboardingInfo } from '../../hooks/use-onboarding';
+
+interface OnboardingPromptProps {
+  info: OnboardingInfo | null;
+  error: string | null;
+  onComplete: (notesDirectory: string | null) => void;
+}
+
+export function OnboardingPrompt({
+  info,
+  error,
+  onComplete,
+}: OnboardingPromptProps) {
+  const [customDirectory, setCustomDirectory] = useState('');
+
+  if (!info) return null;
+
+  const directory = customDirectory.trim();
+
+  return (
+    <div className="fixed inset-0 bg-background/80 backdrop-blur-sm z-50 flex items-center justify-center p-4">
+      <div className="bg-card border border-border/30 rounded-2xl shadow-xl max-w-md w-full p-6">
+        {/* Header */}
+        <div className="mb-4">
+          <h2 className="text-lg font-semibold text-foreground">
+            Welcome to Blink
+          </h2>
+          <p className="text-sm text-muted-foreground">
+            Choose where your notes live
+          </p>
+        </div>
+
+        {/* Content */}
+        <div className="space-y-4 mb-6">
+          <p className="text-sm text-muted-foreground leading-relaxed">
+            Notes are plain markdown files. By default they are kept in
+            <code className="block mt-1 px-1.5 py-0.5 bg-background border border-border/30 rounded-xl text-xs break-all">
+              {info.notesDirectory}
+            </code>
+          </p>
+
+          <label className="block text-sm text-muted-foreground">
+            Or use another folder
+            <input
+              type="text"
+              value={customDirectory}
+              onChange={(e) => setCustomDirectory(e.target.value)}
+              placeholder="/Users/you/Notes"
+              className="mt-1 w-full px-3 py-2 bg-background border border-border/30 rounded-2xl text-sm text-foreground"
+            />
+          </label>
+
+          {error && (
+            <p className="text-xs text-red-400 leading-relaxed">{error}</p>
+          )}
+        </div>
+
+        {/* Actions */}
+        <div className="flex gap-3">
+          <button
+            onClick={() => onComplete(directory === '' ? null : directory)}
+            className="flex-1 bg-primary text-primary-foreground hover:bg-primary/90 px-4 py-2 rounded-2xl text-sm font-medium transition-colors"
+          >
+            Get Started
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
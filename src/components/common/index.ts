@@ -2,5 +2,6 @@ export { ChordHint } from './ChordHint';
 export { CommandPalette } from './CommandPalette';
 export { ContextMenu } from './ContextMenu';
 export { MarkdownRenderer } from './MarkdownRenderer';
+export { OnboardingPrompt } from './OnboardingPrompt';
 export { PermissionPrompt } from './PermissionPrompt';
 export { Sidebar } from './Sidebar';
\ No newline at end of file
@@ -574,7 +574,8 @@ export function DevToolbar() {
               <button
                 onClick={async () => {
                   if (confirm('Clear all detached windows?')) {
-                    const count = await DetachedWindowsAPI.clearAllDetachedWindows();
+                    const token = await DetachedWindowsAPI.requestConfirmationToken('clear_all_detached_windows');
+                    const count = await DetachedWindowsAPI.clearAllDetachedWindows(token);
                     console.log(`[DEV] Cleared ${count} windows`);
                     await refreshWindows();
                   }
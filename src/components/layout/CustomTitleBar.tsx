@@ -65,7 +65,7 @@ export function CustomTitleBar({
         if (isMainWindow) {
           await DetachedWindowsAPI.toggleMainWindowShade();
         } else if (noteId) {
-          // Get the current window label - it might be note-* or hybrid-drag-*
+          // Shade whichever note window this title bar belongs to
           const currentWindow = getCurrentWebviewWindow();
           const windowLabel = currentWindow.label;
           await DetachedWindowsAPI.toggleWindowShade(windowLabel);
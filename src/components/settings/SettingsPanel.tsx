@@ -1019,28 +1019,31 @@ function calculateMetrics(data) {
                 {shortcutStatus === 'registering' ? 'registering...' : 're-register shortcuts'}
               </button>
               
-              <button
-                onClick={async () => {
-                  console.log('[BLINK] [SETTINGS] Testing event emission...');
-                  try {
-                    const result = await invoke<string>('test_emit_new_note');
-                    console.log('[BLINK] [SETTINGS] Test result:', result);
-                    setShortcutMessage('Test event emitted successfully');
-                    setShortcutStatus('success');
-                    setTimeout(() => {
-                      setShortcutStatus('idle');
-                      setShortcutMessage('');
-                    }, 3000);
-                  } catch (error: any) {
-                    console.error('[BLINK] [SETTINGS] Test failed:', error);
-                    setShortcutMessage('Test failed: ' + error.toString());
-                    setShortcutStatus('error');
-                  }
-                }}
-                className="px-3 py-1.5 text-xs bg-background/40 border border-border/40 hover:bg-background/60 rounded transition-all font-mono"
-              >
-                test event
-              </button>
+              {/* test_emit_new_note is only registered with the debug-tools feature */}
+              {process.env.NODE_ENV === 'development' && (
+                <button
+                  onClick={async () => {
+                    console.log('[BLINK] [SETTINGS] Testing event emission...');
+                    try {
+                      const result = await invoke<string>('test_emit_new_note');
+                      console.log('[BLINK] [SETTINGS] Test result:', result);
+                      setShortcutMessage('Test event emitted successfully');
+                      setShortcutStatus('success');
+                      setTimeout(() => {
+                        setShortcutStatus('idle');
+                        setShortcutMessage('');
+                      }, 3000);
+                    } catch (error: any) {
+                      console.error('[BLINK] [SETTINGS] Test failed:', error);
+                      setShortcutMessage('Test failed: ' + error.toString());
+                      setShortcutStatus('error');
+                    }
+                  }}
+                  className="px-3 py-1.5 text-xs bg-background/40 border border-border/40 hover:bg-background/60 rounded transition-all font-mono"
+                >
+                  test event
+                </button>
+              )}
               
               <button
                 onClick={async () => {
@@ -0,0 +1,30 @@
+import { useState } from 'react';
+import { SettingsNavigation } from './SettingsNavigation';
+import { SettingsPanel } from './SettingsPanel';
+
+type SettingsSection = 'general' | 'appearance' | 'shortcuts' | 'editor' | 'advanced';
+
+const SECTIONS: SettingsSection[] = ['general', 'appearance', 'shortcuts', 'editor', 'advanced'];
+
+interface SettingsWindowProps {
+  initialSection?: string | null;
+}
+
+export function SettingsWindow({ initialSection }: SettingsWindowProps) {
+  const [selectedSection, setSelectedSection] = useState<SettingsSection>(
+    SECTIONS.find((s) => s === initialSection) ?? 'appearance'
+  );
+
+  return (
+    <div className="w-full h-full flex min-h-0 overflow-hidden bg-background text-foreground">
+      <SettingsNavigation
+        sidebarVisible={true}
+        selectedSection={selectedSection}
+        onSectionChange={setSelectedSection}
+      />
+      <div className="flex-1 flex flex-col min-h-0 overflow-hidden">
+        <SettingsPanel selectedSection={selectedSection} />
+      </div>
+    </div>
+  );
+}
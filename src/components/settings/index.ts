@@ -1,5 +1,6 @@
 export { SettingsMenu } from './SettingsMenu';
 export { SettingsNavigation } from './SettingsNavigation';
 export { SettingsPanel } from './SettingsPanel';
+export { SettingsWindow } from './SettingsWindow';
 export { ThemeSelector } from './ThemeSelector';
 export { TransparencyControls } from './TransparencyControls';
\ No newline at end of file
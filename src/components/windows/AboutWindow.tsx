@@ -0,0 +1,74 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+interface AboutInfo {
+  version: string;
+  buildHash: string;
+  notesDirectory: string;
+  noteCount: number;
+  databaseBytes: number;
+  logFile: string;
+  platform: string;
+  arch: string;
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export function AboutWindow() {
+  const [info, setInfo] = useState<AboutInfo | null>(null);
+  const [copied, setCopied] = useState(false);
+
+  useEffect(() => {
+    invoke<AboutInfo>('get_about_info')
+      .then(setInfo)
+      .catch((error) => console.error('[ABOUT] Failed to load about info:', error));
+  }, []);
+
+  const copyDiagnostics = async () => {
+    try {
+      await invoke('copy_diagnostics');
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    } catch (error) {
+      console.error('[ABOUT] Failed to copy diagnostics:', error);
+    }
+  };
+
+  const rows: [string, string][] = info
+    ? [
+        ['Notes directory', info.notesDirectory],
+        ['Notes', String(info.noteCount)],
+        ['Database', formatBytes(info.databaseBytes)],
+        ['Platform', `${info.platform} ${info.arch}`],
+      ]
+    : [];
+
+  return (
+    <div className="w-full h-full flex flex-col items-center px-6 py-8 bg-background text-foreground">
+      <h1 className="text-xl font-semibold">Blink</h1>
+      {info && (
+        <p className="mt-1 text-xs text-muted-foreground">
+          Version {info.version} ({info.buildHash})
+        </p>
+      )}
+      <dl className="mt-6 w-full space-y-2 text-xs">
+        {rows.map(([label, value]) => (
+          <div key={label} className="flex justify-between gap-4">
+            <dt className="text-muted-foreground">{label}</dt>
+            <dd className="text-right truncate" title={value}>{value}</dd>
+          </div>
+        ))}
+      </dl>
+      <button
+        onClick={copyDiagnostics}
+        className="mt-auto px-3 py-1.5 text-xs rounded-md border border-border/40 hover:bg-white/10"
+      >
+        {copied ? 'Copied' : 'Copy Diagnostics'}
+      </button>
+    </div>
+  );
+}
@@ -1,17 +1,20 @@
-import { useState, useEffect, useRef, useCallback } from 'react';
+import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
-import { listen } from '@tauri-apps/api/event';
+import { emit, listen } from '@tauri-apps/api/event';
 import { useDetachedWindowsStore } from '../../stores/detached-windows-store';
 import { useConfigStore } from '../../stores/config-store';
 import { useSaveStatus } from '../../hooks/use-save-status';
 import { useModifiedState } from '../../hooks/use-modified-state';
 import { useWindowShade } from '../../hooks/use-window-shade';
+import { useWindowDock } from '../../hooks/use-window-dock';
 import { useWindowTracking } from '../../hooks/use-window-tracking';
-import { noteSyncService, useNoteSync } from '../../services/note-sync';
+import { useNoteSync } from '../../services/note-sync';
+import { notesApi, isNoteTooLargeError, type AutosavePending, type DroppedAttachment } from '../../services/tauri-api';
 import { CustomTitleBar } from '../layout/CustomTitleBar';
 import { WindowWrapper } from '../layout/WindowWrapper';
 import { extractTitleFromContent, getWordCount } from '../../lib/utils';
+import { getNoteBootstrap } from '../../lib/note-bootstrap';
 import { NoteEditor, VimModeIndicator, type VimStatus, type EditorConfig } from '../editor/NoteEditor';
 
 import { Note } from '../../types';
@@ -22,10 +25,11 @@ interface DetachedNoteWindowProps {
 
 export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
   const { config, loadConfig } = useConfigStore();
-  const [note, setNote] = useState<Note | null>(null);
-  const [content, setContent] = useState('');
+  const bootstrap = getNoteBootstrap(noteId);
+  const [note, setNote] = useState<Note | null>(bootstrap?.note ?? null);
+  const [content, setContent] = useState(bootstrap?.note.content ?? '');
   const [isPreviewMode, setIsPreviewMode] = useState(false);
-  const [loading, setLoading] = useState(true);
+  const [loading, setLoading] = useState(!bootstrap);
   const [error, setError] = useState<string | null>(null);
   const [vimStatus, setVimStatus] = useState<VimStatus>({ mode: 'NORMAL' });
 
@@ -34,7 +38,8 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
   const saveStatus = useSaveStatus();
   const modifiedState = useModifiedState();
   const isShaded = useWindowShade();
-  const saveTimeoutRef = useRef<NodeJS.Timeout>();
+  const { dock, dockTo, undock } = useWindowDock();
+  const [autosaveRemainingMs, setAutosaveRemainingMs] = useState<number | null>(null);
   
   // Track window position/size changes with proper debouncing
   useWindowTracking(noteId);
@@ -57,6 +62,31 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
           setNote(event.payload);
           setContent(event.payload.content);
           modifiedState.markSaved(event.payload.content);
+          setAutosaveRemainingMs(null);
+          saveStatus.saveSuccess();
+        }
+      });
+
+      const unlistenAutosavePending = await listen<AutosavePending>('autosave-pending', (event) => {
+        if (event.payload.noteId === noteId) {
+          setAutosaveRemainingMs(event.payload.remainingMs);
+        }
+      });
+
+      const unlistenAutosaveFailed = await listen<{ noteId: string; error: string }>('autosave-failed', (event) => {
+        if (event.payload.noteId === noteId) {
+          setAutosaveRemainingMs(null);
+          // Oversized notes are refused unless the user asks for them to be saved
+          if (isNoteTooLargeError(event.payload.error) && window.confirm(`${event.payload.error}\n\nSave it anyway?`)) {
+            notesApi.scheduleAutosave(noteId, { content: contentRef.current, allowLarge: true })
+              .then(() => notesApi.flushNote(noteId))
+              .catch((error) => {
+                console.error('Failed to save large note:', error);
+                saveStatus.setSaveError('Failed to save note');
+              });
+            return;
+          }
+          saveStatus.setSaveError('Failed to save note');
         }
       });
       
@@ -70,41 +100,47 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
       return () => {
         unlistenNoteUpdated();
         unlistenNoteDeleted();
+        unlistenAutosavePending();
+        unlistenAutosaveFailed();
       };
     };
     
     let cleanup: (() => void) | undefined;
     setupListeners().then(fn => { cleanup = fn; });
     
-    // Cleanup function to clear save timeout on unmount
     return () => {
-      if (saveTimeoutRef.current) {
-        clearTimeout(saveTimeoutRef.current);
-      }
       if (cleanup) {
         cleanup();
       }
     };
   }, [noteId]);
 
+  // The backend keeps new windows hidden until this, so they never flash blank
   useEffect(() => {
-    // Update window title when content changes
-    if (note) {
-      const title = extractTitleFromContent(content);
-      appWindow.setTitle(title);
-    }
-  }, [content, note, appWindow]);
+    if (loading) return;
+    const frame = requestAnimationFrame(() => {
+      emit('frontend-ready', { label: appWindow.label });
+    });
+    return () => cancelAnimationFrame(frame);
+  }, [loading]);
 
   const loadNote = async () => {
-    setLoading(true);
+    // With a bootstrap the note is already on screen; fetch only to revalidate it
+    const snapshot = getNoteBootstrap(noteId)?.note;
+    if (!snapshot) {
+      setLoading(true);
+    }
     setError(null);
     
     try {
       const loadedNote = await invoke<Note>('get_note', { id: noteId });
       
       if (loadedNote) {
-        setNote(loadedNote);
-        setContent(loadedNote.content);
+        // Keep the embedded snapshot unless the note was saved since it was taken
+        if (loadedNote.updated_at !== snapshot?.updated_at) {
+          setNote(loadedNote);
+          setContent(loadedNote.content);
+        }
       } else {
         setError('Note not found');
       }
@@ -118,55 +154,56 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
   const updateNoteContent = useCallback((newContent: string) => {
     setContent(newContent);
     
-    // Clear existing timeout
-    if (saveTimeoutRef.current) {
-      clearTimeout(saveTimeoutRef.current);
-    }
-    
     // Mark as modified when content changes
     if (note && newContent !== note.content) {
       modifiedState.markModified();
     }
     
-    // Show saving indicator after a short delay to prevent flickering
-    const savingIndicatorTimeout = setTimeout(() => {
-      saveStatus.startSaving();
-    }, 300);
-    
-    // Debounce the actual save operation to 1 second
-    saveTimeoutRef.current = setTimeout(async () => {
-      clearTimeout(savingIndicatorTimeout);
-      
-      if (note) {
-        try {
-          saveStatus.startSaving();
-          
-          const updatedNote = await invoke<Note>('update_note', {
-            id: noteId,
-            request: {
-              content: newContent
-            }
-          });
-          
-          if (updatedNote) {
-            setNote(updatedNote);
-            saveStatus.saveSuccess();
-            modifiedState.markSaved(newContent);
-            
-            // Notify other windows of the update
-            noteSyncService.noteUpdated(updatedNote);
-          }
-        } catch (error) {
-          console.error('Failed to update note:', error);
-          saveStatus.setSaveError('Failed to save note');
-        }
-      }
-    }, 30000); // 30 second save interval, same as main window
+    // The backend saves once the note has been idle for 30 seconds and counts
+    // down with `autosave-pending` meanwhile
+    if (note) {
+      notesApi.scheduleAutosave(noteId, { content: newContent }).catch((error) => {
+        console.error('Failed to schedule autosave:', error);
+        saveStatus.setSaveError('Failed to save note');
+      });
+    }
   }, [note, noteId, saveStatus]);
 
+  // Files other than notes dropped on this window come back as attachments
+  const contentRef = useRef(content);
+  contentRef.current = content;
+  const updateContentRef = useRef(updateNoteContent);
+  updateContentRef.current = updateNoteContent;
+
+  useEffect(() => {
+    const unlisten = listen<{
+      windowLabel: string;
+      noteId: string;
+      attachments: DroppedAttachment[];
+      failed: string[];
+    }>('attachments-dropped', (event) => {
+      if (event.payload.windowLabel !== appWindow.label) return;
+      if (event.payload.failed.length > 0) {
+        console.error('[DETACHED-WINDOW] Failed to attach:', event.payload.failed);
+      }
+      if (event.payload.attachments.length === 0) return;
+      const links = event.payload.attachments.map((attachment) => attachment.markdown).join('\n');
+      const current = contentRef.current;
+      const separator = current === '' || current.endsWith('\n') ? '' : '\n';
+      updateContentRef.current(`${current}${separator}${links}\n`);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [noteId]);
+
   const handleCloseWindow = async () => {
     console.log('[DETACHED-WINDOW] Closing window for note:', noteId);
     try {
+      // Write any edit still counting down before the window goes away
+      await notesApi.flushNote(noteId).catch((error) => {
+        console.error('[DETACHED-WINDOW] Failed to flush pending save:', error);
+      });
       // Update the detached windows store to remove this window
       console.log('[DETACHED-WINDOW] Updating store...');
       await closeWindow(noteId);
@@ -301,6 +338,23 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
     );
   }
 
+  // Docked and slid in: only the tab is visible
+  if (dock && !dock.expanded) {
+    const vertical = dock.edge === 'left' || dock.edge === 'right';
+    return (
+      <WindowWrapper className="detached-note-window">
+        <div className="w-full h-full flex items-center justify-center bg-background/90 text-foreground text-xs cursor-pointer select-none overflow-hidden">
+          <span
+            className="truncate"
+            style={vertical ? { writingMode: 'vertical-rl' } : undefined}
+          >
+            {note.icon ? `${note.icon} ` : ''}{extractTitleFromContent(content)}
+          </span>
+        </div>
+      </WindowWrapper>
+    );
+  }
+
   // Mode toggle component for the title bar
   const modeToggle = (
     <div className="flex items-center bg-background/40 border border-border/30 rounded-xl">
@@ -335,6 +389,27 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
     </div>
   );
 
+  // Collapse to a tab on the nearer side of the screen, or back to a window
+  const dockToggle = (
+    <button
+      onClick={() => {
+        if (dock) {
+          undock();
+        } else {
+          const center = window.screenX + window.outerWidth / 2;
+          dockTo(center < window.screen.width / 2 ? 'left' : 'right');
+        }
+      }}
+      className="w-5 h-4 flex items-center justify-center rounded-2xl text-muted-foreground/60 hover:text-foreground hover:bg-white/5 transition-all duration-200"
+      title={dock ? 'Undock window' : 'Dock to screen edge'}
+    >
+      <svg width="8" height="8" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+        <rect x="3" y="3" width="18" height="18" rx="2"/>
+        <path d={dock ? 'M9 3v18' : 'M15 3v18'}/>
+      </svg>
+    </button>
+  );
+
   // Calculate word count
   const wordCount = getWordCount(content);
 
@@ -359,7 +434,12 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
       <CustomTitleBar 
         title={extractTitleFromContent(content)}
         noteId={noteId}
-        rightContent={!isShaded ? modeToggle : undefined}
+        rightContent={!isShaded ? (
+          <div className="flex items-center gap-1">
+            {dockToggle}
+            {modeToggle}
+          </div>
+        ) : undefined}
         onClose={handleCloseWindow}
         isShaded={isShaded}
         stats={{
@@ -404,7 +484,9 @@ export function DetachedNoteWindow({ noteId }: DetachedNoteWindowProps) {
                     </>
                   ) : modifiedState.isModified ? (
                     <>
-                      <span className="text-xs text-muted-foreground/50" style={{ fontSize: '10px' }}>Modified</span>
+                      <span className="text-xs text-muted-foreground/50" style={{ fontSize: '10px' }}>
+                        {autosaveRemainingMs !== null ? `Saving in ${Math.ceil(autosaveRemainingMs / 1000)}s` : 'Modified'}
+                      </span>
                       <div className="w-1 h-1 bg-orange-500/60 rounded-full"></div>
                     </>
                   ) : saveStatus.lastSaved ? (
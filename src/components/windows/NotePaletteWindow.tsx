@@ -0,0 +1,126 @@
+import { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+
+interface NoteMatch {
+  noteId: string;
+  title: string;
+  icon?: string;
+  score: number;
+  matchedIndices: number[];
+  isOpen: boolean;
+}
+
+function HighlightedTitle({ title, indices }: { title: string; indices: number[] }) {
+  const matched = new Set(indices);
+  return (
+    <span>
+      {Array.from(title || 'Untitled Note').map((char, i) => (
+        <span key={i} className={matched.has(i) ? 'text-blue-400 font-semibold' : undefined}>
+          {char}
+        </span>
+      ))}
+    </span>
+  );
+}
+
+export function NotePaletteWindow() {
+  const [query, setQuery] = useState('');
+  const [results, setResults] = useState<NoteMatch[]>([]);
+  const [selected, setSelected] = useState(0);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  // Results are ranked by the backend on every keystroke
+  useEffect(() => {
+    let cancelled = false;
+    invoke<NoteMatch[]>('fuzzy_find_notes', { query, limit: 50 })
+      .then((matches) => {
+        if (!cancelled) {
+          setResults(matches);
+          setSelected(0);
+        }
+      })
+      .catch((error) => console.error('[NOTE_PALETTE] Search failed:', error));
+    return () => {
+      cancelled = true;
+    };
+  }, [query]);
+
+  // The window is reused, so start fresh whenever it is shown again
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        setQuery('');
+        inputRef.current?.focus();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const open = async (match: NoteMatch | undefined) => {
+    if (!match) return;
+    try {
+      await invoke('select_palette_note', { noteId: match.noteId });
+    } catch (error) {
+      console.error('[NOTE_PALETTE] Failed to open note:', error);
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'ArrowDown' || (e.ctrlKey && e.key === 'n')) {
+      e.preventDefault();
+      setSelected((i) => Math.min(i + 1, results.length - 1));
+    } else if (e.key === 'ArrowUp' || (e.ctrlKey && e.key === 'p')) {
+      e.preventDefault();
+      setSelected((i) => Math.max(i - 1, 0));
+    } else if (e.key === 'Enter') {
+      e.preventDefault();
+      open(results[selected]);
+    } else if (e.key === 'Escape') {
+      e.preventDefault();
+      getCurrentWebviewWindow().hide();
+    }
+  };
+
+  return (
+    <div
+      className="w-full h-full flex flex-col overflow-hidden border border-border/40"
+      style={{ background: 'rgba(18, 19, 23, 0.98)', borderRadius: '12px' }}
+    >
+      <input
+        ref={inputRef}
+        autoFocus
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder="Open note…"
+        className="w-full px-4 py-3 bg-transparent border-b border-border/30 text-sm text-foreground outline-none"
+      />
+      <div className="flex-1 overflow-y-auto py-1">
+        {results.length === 0 ? (
+          <div className="px-4 py-3 text-xs text-muted-foreground">No matching notes</div>
+        ) : (
+          results.map((match, i) => (
+            <div
+              key={match.noteId}
+              ref={i === selected ? (el) => el?.scrollIntoView({ block: 'nearest' }) : undefined}
+              onMouseEnter={() => setSelected(i)}
+              onClick={() => open(match)}
+              className={`flex items-center justify-between px-4 py-2 text-sm cursor-pointer ${
+                i === selected ? 'bg-white/10' : ''
+              }`}
+            >
+              <span className="flex items-center gap-2 min-w-0">
+                {match.icon && <span>{match.icon}</span>}
+                <HighlightedTitle title={match.title} indices={match.matchedIndices} />
+              </span>
+              {match.isOpen && <span className="text-xs text-muted-foreground">Open</span>}
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,45 @@
+import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { MarkdownRenderer } from '../common/MarkdownRenderer';
+import { notesApi, ReadingPage } from '../../services/tauri-api';
+
+interface ReadingWindowProps {
+  opacity: number;
+}
+
+export function ReadingWindow({ opacity }: ReadingWindowProps) {
+  const [page, setPage] = useState<ReadingPage | null>(null);
+
+  // The backend paginates and turns pages; this window only shows them
+  useEffect(() => {
+    notesApi
+      .getReadingPage()
+      .then(setPage)
+      .catch((error) => console.error('[READING] Failed to load page:', error));
+
+    const unlisten = listen<ReadingPage>('reading-page', (event) => setPage(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      className="w-full h-full flex flex-col bg-background text-foreground rounded-lg overflow-hidden"
+      style={{ opacity }}
+    >
+      <div data-tauri-drag-region className="h-12 shrink-0" />
+      {page && (
+        <>
+          <MarkdownRenderer
+            content={page.content}
+            className="flex-1 overflow-hidden px-10 prose prose-invert max-w-none"
+          />
+          <div className="py-3 text-center text-xs text-muted-foreground select-none">
+            {page.page + 1} / {page.total}
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
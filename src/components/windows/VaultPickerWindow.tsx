@@ -0,0 +1,87 @@
+import { useState, useEffect, useCallback } from 'react';
+import { notesApi, type VaultEntry } from '../../services/tauri-api';
+
+function formatOpened(lastOpened: string | null): string {
+  if (!lastOpened) return 'Never opened here';
+  const date = new Date(lastOpened);
+  return isNaN(date.getTime()) ? lastOpened : `Opened ${date.toLocaleString()}`;
+}
+
+export function VaultPickerWindow() {
+  const [vaults, setVaults] = useState<VaultEntry[]>([]);
+  const [switching, setSwitching] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(() => {
+    notesApi
+      .listRecentVaults()
+      .then(setVaults)
+      .catch((error) => console.error('[VAULT_PICKER] Failed to list vaults:', error));
+  }, []);
+
+  useEffect(load, [load]);
+
+  const open = async (vault: VaultEntry) => {
+    if (vault.current || switching) return;
+    setSwitching(vault.path);
+    setError(null);
+    try {
+      // The picker closes once the switch is done
+      await notesApi.switchVault(vault.path);
+    } catch (error) {
+      setError(String(error));
+      setSwitching(null);
+    }
+  };
+
+  const togglePin = async (vault: VaultEntry) => {
+    try {
+      await notesApi.setVaultPinned(vault.path, !vault.pinned);
+      load();
+    } catch (error) {
+      console.error('[VAULT_PICKER] Failed to pin vault:', error);
+    }
+  };
+
+  return (
+    <div className="w-full h-full flex flex-col bg-background text-foreground">
+      <h1 className="px-4 pt-4 pb-2 text-sm font-semibold">Open Vault</h1>
+      <ul className="flex-1 overflow-y-auto px-2 pb-2 space-y-1">
+        {vaults.map((vault) => (
+          <li key={vault.path}>
+            <div
+              role="button"
+              onClick={() => open(vault)}
+              className={`flex items-center gap-3 px-3 py-2 rounded-md text-xs ${
+                vault.current ? 'bg-white/10' : 'hover:bg-white/5 cursor-pointer'
+              } ${vault.noteCount === null ? 'opacity-50' : ''}`}
+            >
+              <div className="flex-1 min-w-0">
+                <div className="font-medium truncate">
+                  {vault.name}
+                  {vault.current && <span className="ml-2 text-muted-foreground">(open)</span>}
+                </div>
+                <div className="text-muted-foreground truncate" title={vault.path}>{vault.path}</div>
+                <div className="text-muted-foreground">
+                  {vault.noteCount === null ? 'Folder not found' : `${vault.noteCount} notes`} · {formatOpened(vault.lastOpened)}
+                </div>
+              </div>
+              {switching === vault.path && <span className="text-muted-foreground">Opening…</span>}
+              <button
+                onClick={(event) => {
+                  event.stopPropagation();
+                  togglePin(vault);
+                }}
+                title={vault.pinned ? 'Unpin' : 'Pin to top'}
+                className={`px-2 py-1 rounded ${vault.pinned ? 'text-foreground' : 'text-muted-foreground hover:text-foreground'}`}
+              >
+                {vault.pinned ? '★' : '☆'}
+              </button>
+            </div>
+          </li>
+        ))}
+      </ul>
+      {error && <p className="px-4 pb-3 text-xs text-red-400">{error}</p>}
+    </div>
+  );
+}
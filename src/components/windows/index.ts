@@ -1,4 +1,8 @@
+export { AboutWindow } from './AboutWindow';
 export { DetachedNoteWindow } from './DetachedNoteWindow';
 export { DragCancelEffect } from './DragCancelEffect';
 export { DragGhost } from './DragGhost';
-export { ResizablePanel } from './ResizablePanel';
\ No newline at end of file
+export { NotePaletteWindow } from './NotePaletteWindow';
+export { ReadingWindow } from './ReadingWindow';
+export { ResizablePanel } from './ResizablePanel';
+export { VaultPickerWindow } from './VaultPickerWindow';
\ No newline at end of file
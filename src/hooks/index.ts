@@ -7,11 +7,13 @@ export { useGlobalEventListeners } from './use-global-event-listeners';
 export { useKeyboardShortcuts } from './use-keyboard-shortcuts';
 export { useNoteManagement } from './use-note-management';
 export { useNotes } from './use-notes';
+export { useOnboarding } from './use-onboarding';
 export { usePermissions } from './use-permissions';
 export { useSaveStatus } from './use-save-status';
 export { useModifiedState } from './use-modified-state';
 export { useTypewriterMode } from './use-typewriter-mode';
 export { useWindowManager } from './use-window-manager';
+export { useWindowDock } from './use-window-dock';
 export { useWindowShade } from './use-window-shade';
 export { useWindowTracking } from './use-window-tracking';
 export { useWindowTransparency } from './use-window-transparency';
\ No newline at end of file
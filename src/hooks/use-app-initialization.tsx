@@ -3,6 +3,14 @@ import { useConfigStore } from '../stores/config-store';
 import { useDetachedWindowsStore } from '../stores/detached-windows-store';
 import { applyTheme, getThemeById } from '../types';
 import { listen } from '@tauri-apps/api/event';
+import { configApi } from '../services/config-api';
+import { notesApi } from '../services/tauri-api';
+import type { AppConfig } from '../types/config';
+
+interface ConfigReloadError {
+  path: string;
+  error: string;
+}
 
 interface AppInitializationProps {
   isDetachedWindow: boolean;
@@ -38,9 +46,34 @@ export function useAppInitialization({ isDetachedWindow }: AppInitializationProp
         loadWindows();
       });
       
+      // config.json was edited outside the app and applied by the backend
+      const unlistenReloaded = await listen<AppConfig>('config-reloaded', async (event) => {
+        const previous = useConfigStore.getState().config;
+        await loadConfig();
+        if (!isDetachedWindow && previous.storage?.notesDirectory !== event.payload.storage?.notesDirectory) {
+          await notesApi.reloadNotesFromDirectory();
+        }
+      });
+      const unlistenReloadFailed = await listen<ConfigReloadError>('config-reload-failed', (event) => {
+        if (!isDetachedWindow) {
+          alert(`Your changes to ${event.payload.path} were not applied:\n\n${event.payload.error}`);
+        }
+      });
+      // Moving the vault needs the user's go-ahead
+      const unlistenReloadPending = await listen<{ changes: string[] }>('config-reload-pending', async (event) => {
+        if (isDetachedWindow) return;
+        const accept = confirm(
+          `config.json was edited and changes ${event.payload.changes.join(', ')}. Apply these changes now?`
+        );
+        await configApi.resolveConfigReload(accept).catch(err => console.warn('[BLINK] Config reload failed:', err));
+      });
+
       // Clean up listener on unmount
       return () => {
         unlisten();
+        unlistenReloaded();
+        unlistenReloadFailed();
+        unlistenReloadPending();
       };
     };
     
@@ -290,13 +290,14 @@ export function useDragToDetach({ onDrop, dragThreshold = 5 }: UseDragToDetachOp
       
       if (dragState.noteId && dragRef.current.realWindowLabel) {
         if (dragState.isDragging && isOutsideSidebar) {
-          // Actually dragged and dropped outside sidebar - finalize the window in place
+          // Actually dragged and dropped outside sidebar - the backend swaps the drag
+          // window for a regular note window at the same spot
           try {
-            await invoke('finalize_hybrid_drag_window', {
+            const detached = await invoke<{ position: [number, number]; size: [number, number] }>('finalize_hybrid_drag_window', {
               windowLabel: dragRef.current.realWindowLabel,
               noteId: dragState.noteId,
             });
-            console.log('[DRAG] Window finalized in place, updating frontend store directly');
+            console.log('[DRAG] Window finalized, updating frontend store directly');
             
             // Import and update the window positions store directly
             const { useWindowPositionsStore } = await import('../stores/window-positions-store');
@@ -305,8 +306,8 @@ export function useDragToDetach({ onDrop, dragThreshold = 5 }: UseDragToDetachOp
             // Add the window to our positions map
             const newPositions = new Map(store.windowPositions);
             newPositions.set(dragState.noteId, {
-              position: [dragRef.current.lastMousePosition.x - 200, dragRef.current.lastMousePosition.y - 20],
-              size: [800, 600] // Default size
+              position: detached.position,
+              size: detached.size
             });
             
             // Update the store
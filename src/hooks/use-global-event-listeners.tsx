@@ -9,22 +9,33 @@ interface GlobalEventListenersProps {
   notes: Note[];
   onCreateNewNote: () => void;
   onStartWindowMode: () => void;
+  onEndWindowMode: () => void;
+}
+
+/** Payload of `window-chord`; the backend captures the chord's second key */
+interface WindowChordEvent {
+  phase: 'armed' | 'completed' | 'cancelled' | 'timedOut';
+  action: string | null;
+  timeoutMs: number;
 }
 
 export function useGlobalEventListeners({
   notes,
   onCreateNewNote,
   onStartWindowMode,
+  onEndWindowMode,
 }: GlobalEventListenersProps) {
   // Keep stable references to current values for event listeners
   const notesRef = useRef(notes);
   const onCreateNewNoteRef = useRef(onCreateNewNote);
   const onStartWindowModeRef = useRef(onStartWindowMode);
+  const onEndWindowModeRef = useRef(onEndWindowMode);
   
   // Update refs when props change
   notesRef.current = notes;
   onCreateNewNoteRef.current = onCreateNewNote;
   onStartWindowModeRef.current = onStartWindowMode;
+  onEndWindowModeRef.current = onEndWindowMode;
 
   useEffect(() => {
     const setupListeners = async () => {
@@ -39,15 +50,18 @@ export function useGlobalEventListeners({
         });
         unlisteners.push(unlistenNewNote);
         
-        // Listen for chord window mode event
-        console.log('[BLINK] [FRONTEND] Setting up chord-window-mode listener...');
-        const unlistenChordWindow = await listen('chord-window-mode', async (event) => {
-          console.log('[BLINK] [FRONTEND] 🔥🔥🔥 RECEIVED CHORD-WINDOW-MODE EVENT! 🔥🔥🔥', event);
-          console.log('[CHORD] Starting window mode from global shortcut');
-          onStartWindowModeRef.current();
+        // The backend runs the Hyperkey+B chord; only show and hide the hint here
+        console.log('[BLINK] [FRONTEND] Setting up window-chord listener...');
+        const unlistenChordWindow = await listen<WindowChordEvent>('window-chord', async (event) => {
+          console.log('[CHORD] Window chord', event.payload.phase, event.payload.action ?? '');
+          if (event.payload.phase === 'armed') {
+            onStartWindowModeRef.current();
+          } else {
+            onEndWindowModeRef.current();
+          }
         });
         unlisteners.push(unlistenChordWindow);
-        console.log('[BLINK] [FRONTEND] ✅ chord-window-mode listener set up successfully');
+        console.log('[BLINK] [FRONTEND] ✅ window-chord listener set up successfully');
         
         // Listen for direct note deployment events
         console.log('[BLINK] [FRONTEND] Setting up deploy-note-window listener...');
@@ -1,7 +1,9 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
+import { emit, listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { noteSyncService, useNoteSync } from '../services/note-sync';
+import { isNoteTooLargeError } from '../services/tauri-api';
 import { Note } from '../types';
 import { extractTitleFromContent } from '../lib/utils';
 
@@ -46,6 +48,15 @@ export function useNoteManagement(options?: UseNoteManagementOptions): UseNoteMa
   // Update ref when selectedNoteId changes
   selectedNoteIdRef.current = selectedNoteId;
 
+  // The backend mirrors the main window's selection in the follow window, when
+  // it is open. Other windows run this hook too but must not steer it.
+  useEffect(() => {
+    if (getCurrentWebviewWindow().label !== 'main') return;
+    emit('note-selected', { noteId: selectedNoteId }).catch(err =>
+      console.warn('[BLINK] Failed to emit note-selected:', err)
+    );
+  }, [selectedNoteId]);
+
   const selectedNote = notes.find(note => note.id === selectedNoteId);
 
   // Real-time sync for selected note
@@ -286,14 +297,25 @@ export function useNoteManagement(options?: UseNoteManagementOptions): UseNoteMa
       const title = extractTitleFromContent(currentContent);
       
       // Update in backend
-      const updatedNote = await invoke<Note>('update_note', {
+      const save = (allowLarge: boolean) => invoke<Note>('update_note', {
         id: selectedNoteId,
         request: {
           title,
           content: currentContent,
-          tags: undefined // Keep existing tags
+          tags: undefined, // Keep existing tags
+          allowLarge
         }
       });
+      let updatedNote: Note;
+      try {
+        updatedNote = await save(false);
+      } catch (error) {
+        // Oversized notes are refused unless the user asks for them to be saved
+        if (!isNoteTooLargeError(error) || !window.confirm(`${error}\n\nSave it anyway?`)) {
+          throw error;
+        }
+        updatedNote = await save(true);
+      }
 
       console.log('[BLINK] Note saved immediately:', updatedNote.id);
       
@@ -0,0 +1,50 @@
+import { useState, useEffect, useCallback } from 'react';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { listen } from '@tauri-apps/api/event';
+import { configApi } from '../services/config-api';
+import { useConfigStore } from '../stores/config-store';
+
+/** Sent with `onboarding-start` on first launch */
+export interface OnboardingInfo {
+  notesDirectory: string;
+  welcomeNoteId: string | null;
+}
+
+export function useOnboarding() {
+  const [onboarding, setOnboarding] = useState<OnboardingInfo | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    // Only the main window runs the setup flow
+    if (getCurrentWebviewWindow().label !== 'main') return;
+
+    const unlisten = listen<OnboardingInfo>('onboarding-start', (event) => {
+      setOnboarding(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  /** Save the picked directory, `null` keeping the default one */
+  const complete = useCallback(async (notesDirectory: string | null) => {
+    const { config } = useConfigStore.getState();
+    setError(null);
+    try {
+      const newConfig = await configApi.completeOnboarding({
+        ...config,
+        storage: {
+          ...config.storage,
+          notesDirectory: notesDirectory ?? config.storage?.notesDirectory,
+          useCustomDirectory: notesDirectory !== null,
+        },
+      });
+      useConfigStore.setState({ config: newConfig });
+      setOnboarding(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, []);
+
+  return { onboarding, error, complete };
+}
@@ -0,0 +1,81 @@
+import { useState, useEffect, useCallback } from 'react';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { listen } from '@tauri-apps/api/event';
+import { DetachedWindowsAPI, type DetachedWindow, type DockEdge, type WindowDock } from '../services/detached-windows-api';
+
+/**
+ * Dock state of the current note window, with actions to dock and undock it.
+ * While docked, hovering the tab slides the window out and leaving it slides
+ * it back in.
+ */
+export function useWindowDock() {
+  const [dock, setDock] = useState<WindowDock | null>(null);
+
+  useEffect(() => {
+    // Only run in Tauri context
+    if (typeof window === 'undefined' || !window.__TAURI__) {
+      return;
+    }
+
+    const label = getCurrentWebviewWindow().label;
+
+    DetachedWindowsAPI.getDetachedWindows()
+      .then(windows => setDock(windows.find(w => w.window_label === label)?.dock ?? null))
+      .catch(error => console.error('Failed to load dock state:', error));
+
+    const unlisten = listen<DetachedWindow>('window-dock-changed', (event) => {
+      if (event.payload.window_label === label) {
+        setDock(event.payload.dock ?? null);
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!dock) {
+      return;
+    }
+
+    const label = getCurrentWebviewWindow().label;
+    const setExpanded = (expanded: boolean) => {
+      DetachedWindowsAPI.setDockExpanded(label, expanded).catch(error => {
+        console.error('Failed to slide docked window:', error);
+      });
+    };
+    const expand = () => setExpanded(true);
+    const collapse = () => setExpanded(false);
+
+    const root = document.documentElement;
+    root.addEventListener('mouseenter', expand);
+    root.addEventListener('mouseleave', collapse);
+    root.addEventListener('click', expand);
+    return () => {
+      root.removeEventListener('mouseenter', expand);
+      root.removeEventListener('mouseleave', collapse);
+      root.removeEventListener('click', expand);
+    };
+  }, [dock !== null]);
+
+  const dockTo = useCallback(async (edge: DockEdge) => {
+    try {
+      const windowData = await DetachedWindowsAPI.dockWindow(getCurrentWebviewWindow().label, edge);
+      setDock(windowData.dock ?? null);
+    } catch (error) {
+      console.error('Failed to dock window:', error);
+    }
+  }, []);
+
+  const undock = useCallback(async () => {
+    try {
+      await DetachedWindowsAPI.undockWindow(getCurrentWebviewWindow().label);
+      setDock(null);
+    } catch (error) {
+      console.error('Failed to undock window:', error);
+    }
+  }, []);
+
+  return { dock, dockTo, undock };
+}
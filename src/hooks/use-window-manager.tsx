@@ -5,6 +5,15 @@ interface WindowDetectionResult {
   detachedNoteId: string | null;
   isDragGhost: boolean;
   dragGhostTitle: string;
+  isNotePalette: boolean;
+  isAboutWindow: boolean;
+  isVaultPicker: boolean;
+  /** A hidden, pre-warmed window waiting in the pool for a note */
+  isPooledWindow: boolean;
+  isSettingsWindow: boolean;
+  settingsSection: string | null;
+  readingNoteId: string | null;
+  readingOpacity: number;
 }
 
 export function useWindowManager(): WindowDetectionResult {
@@ -13,6 +22,14 @@ export function useWindowManager(): WindowDetectionResult {
   const [detachedNoteId, setDetachedNoteId] = useState<string | null>(null);
   const [isDragGhost, setIsDragGhost] = useState(false);
   const [dragGhostTitle, setDragGhostTitle] = useState<string>('');
+  const [isNotePalette, setIsNotePalette] = useState(false);
+  const [isAboutWindow, setIsAboutWindow] = useState(false);
+  const [isVaultPicker, setIsVaultPicker] = useState(false);
+  const [isPooledWindow, setIsPooledWindow] = useState(false);
+  const [isSettingsWindow, setIsSettingsWindow] = useState(false);
+  const [settingsSection, setSettingsSection] = useState<string | null>(null);
+  const [readingNoteId, setReadingNoteId] = useState<string | null>(null);
+  const [readingOpacity, setReadingOpacity] = useState(1);
 
   // Detect if this is a detached window or drag ghost
   useEffect(() => {
@@ -20,6 +37,12 @@ export function useWindowManager(): WindowDetectionResult {
     const noteParam = urlParams.get('note');
     const ghostParam = urlParams.get('ghost');
     const titleParam = urlParams.get('title');
+    const paletteParam = urlParams.get('palette');
+    const aboutParam = urlParams.get('about');
+    const vaultPickerParam = urlParams.get('vaultPicker');
+    const pooledParam = urlParams.get('pooled');
+    const settingsParam = urlParams.get('settings');
+    const readingParam = urlParams.get('reading');
     
     if (noteParam) {
       setIsDetachedWindow(true);
@@ -27,6 +50,21 @@ export function useWindowManager(): WindowDetectionResult {
     } else if (ghostParam === 'true' && titleParam) {
       setIsDragGhost(true);
       setDragGhostTitle(decodeURIComponent(titleParam));
+    } else if (paletteParam === 'true') {
+      setIsNotePalette(true);
+    } else if (aboutParam === 'true') {
+      setIsAboutWindow(true);
+    } else if (vaultPickerParam === 'true') {
+      setIsVaultPicker(true);
+    } else if (pooledParam === 'true') {
+      // Taking the window from the pool navigates it to `?note=`
+      setIsPooledWindow(true);
+    } else if (settingsParam === 'true') {
+      setIsSettingsWindow(true);
+      setSettingsSection(urlParams.get('section'));
+    } else if (readingParam) {
+      setReadingNoteId(readingParam);
+      setReadingOpacity(Number(urlParams.get('opacity') ?? 1));
     }
   }, []);
 
@@ -35,5 +73,13 @@ export function useWindowManager(): WindowDetectionResult {
     detachedNoteId,
     isDragGhost,
     dragGhostTitle,
+    isNotePalette,
+    isAboutWindow,
+    isVaultPicker,
+    isPooledWindow,
+    isSettingsWindow,
+    settingsSection,
+    readingNoteId,
+    readingOpacity,
   };
 }
\ No newline at end of file
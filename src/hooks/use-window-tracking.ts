@@ -1,14 +1,18 @@
 import { useEffect, useRef } from 'react';
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { invoke } from '@tauri-apps/api/core';
+import { DetachedWindowsAPI } from '../services/detached-windows-api';
 
 // Debounce intervals - much less frequent as requested
 const POSITION_UPDATE_DELAY = process.env.NODE_ENV === 'production' ? 60000 : 10000; // 60s prod, 10s dev
 const SIZE_UPDATE_DELAY = process.env.NODE_ENV === 'production' ? 60000 : 10000; // 60s prod, 10s dev
+// How long the window has to stop moving before it snaps to a nearby edge
+const SNAP_SETTLE_DELAY = 150;
 
 export function useWindowTracking(noteId: string) {
   // Refs to store debounce timers and last known values
   const positionTimerRef = useRef<NodeJS.Timeout>();
+  const snapTimerRef = useRef<NodeJS.Timeout>();
   const sizeTimerRef = useRef<NodeJS.Timeout>();
   const lastPositionRef = useRef<{ x: number; y: number }>();
   const lastSizeRef = useRef<{ width: number; height: number }>();
@@ -45,6 +49,18 @@ export function useWindowTracking(noteId: string) {
       }, POSITION_UPDATE_DELAY);
     };
     
+    // Snap once the drag settles; the position itself is saved later
+    const snapWhenSettled = (position: { x: number; y: number }) => {
+      if (snapTimerRef.current) {
+        clearTimeout(snapTimerRef.current);
+      }
+      snapTimerRef.current = setTimeout(() => {
+        DetachedWindowsAPI.snapWindow(appWindow.label, position.x, position.y).catch((error) => {
+          console.error('[WINDOW-TRACKING] Failed to snap window:', error);
+        });
+      }, SNAP_SETTLE_DELAY);
+    };
+    
     // Debounced size update function
     const debouncedSizeUpdate = (size: { width: number; height: number }) => {
       // Clear existing timer
@@ -72,17 +88,21 @@ export function useWindowTracking(noteId: string) {
       }, SIZE_UPDATE_DELAY);
     };
     
-    // Listen for window move events
-    const unlistenMove = appWindow.onMoved(({ payload: position }) => {
+    // Listen for window move events. The backend keeps logical coordinates,
+    // while the events report physical ones.
+    const unlistenMove = appWindow.onMoved(async ({ payload }) => {
+      const position = payload.toLogical(await appWindow.scaleFactor());
       // Only log occasionally to avoid spam
       if (Math.random() < 0.1) { // Log ~10% of moves
         console.log('[WINDOW-TRACKING] Window move detected (debouncing):', position);
       }
+      snapWhenSettled(position);
       debouncedPositionUpdate(position);
     });
     
     // Listen for window resize events
-    const unlistenResize = appWindow.onResized(({ payload: size }) => {
+    const unlistenResize = appWindow.onResized(async ({ payload }) => {
+      const size = payload.toLogical(await appWindow.scaleFactor());
       console.log('[WINDOW-TRACKING] Window resize detected (debouncing):', size);
       debouncedSizeUpdate(size);
     });
@@ -90,6 +110,9 @@ export function useWindowTracking(noteId: string) {
     // Cleanup
     return () => {
       // Clear any pending timers
+      if (snapTimerRef.current) {
+        clearTimeout(snapTimerRef.current);
+      }
       if (positionTimerRef.current) {
         clearTimeout(positionTimerRef.current);
       }
@@ -0,0 +1,32 @@
+import type { AppConfig } from '../types/config';
+import type { Note } from '../types/note';
+
+/**
+ * Snapshot embedded by the backend when it creates a note window
+ * (see `modules/note_bootstrap.rs`), so the note renders on first paint.
+ */
+export interface NoteBootstrap {
+  noteId: string;
+  note: Note;
+  appearance: AppConfig['appearance'];
+}
+
+declare global {
+  interface Window {
+    __BLINK_BOOTSTRAP__?: NoteBootstrap;
+  }
+}
+
+/**
+ * The bootstrap for `noteId`, if this window was created with one. Pooled
+ * windows are re-targeted to other notes, so the id has to match.
+ */
+export function getNoteBootstrap(noteId: string): NoteBootstrap | null {
+  const bootstrap = window.__BLINK_BOOTSTRAP__;
+  return bootstrap && bootstrap.noteId === noteId ? bootstrap : null;
+}
+
+/** Appearance settings embedded in this window, before the config is fetched */
+export function getBootstrapAppearance(): AppConfig['appearance'] | null {
+  return window.__BLINK_BOOTSTRAP__?.appearance ?? null;
+}
@@ -40,6 +40,15 @@ export const configApi = {
     }
   },
 
+  async resolveConfigReload(accept: boolean): Promise<void> {
+    try {
+      await invoke('resolve_config_reload', { accept });
+    } catch (error) {
+      console.error('[BLINK] Error resolving config reload:', error);
+      throw error;
+    }
+  },
+
   async toggleWindowVisibility(): Promise<boolean> {
     try {
       return await invoke('toggle_window_visibility');
@@ -48,4 +57,9 @@ export const configApi = {
       throw error;
     }
   },
-};
\ No newline at end of file
+
+  /** Finish first-launch setup; the backend opens the chosen directory as the vault */
+  async completeOnboarding(onboardingConfig: AppConfig): Promise<AppConfig> {
+    return await invoke<AppConfig>('complete_onboarding', { onboardingConfig });
+  },
+};
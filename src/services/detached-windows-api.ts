@@ -9,6 +9,17 @@ export interface DetachedWindow {
   opacity: number;
   is_shaded?: boolean;
   original_height?: number;
+  dock?: WindowDock;
+  visible_on_all_workspaces?: boolean;
+}
+
+export type DockEdge = 'left' | 'right' | 'top' | 'bottom';
+
+export interface WindowDock {
+  edge: DockEdge;
+  expanded: boolean;
+  tab_position: [number, number];
+  tab_size: [number, number];
 }
 
 export interface CreateDetachedWindowRequest {
@@ -19,6 +30,14 @@ export interface CreateDetachedWindowRequest {
   height?: number;
 }
 
+export type WindowLogEvent =
+  | { event: 'opened' | 'updated'; window: DetachedWindow }
+  | { event: 'moved'; label: string; position: [number, number] }
+  | { event: 'resized'; label: string; size: [number, number] }
+  | { event: 'closed'; label: string; note_id: string };
+
+export type WindowLogRecord = WindowLogEvent & { at: string };
+
 export class DetachedWindowsAPI {
   static async createDetachedWindow(request: CreateDetachedWindowRequest): Promise<DetachedWindow> {
     return await invoke<DetachedWindow>('create_detached_window', { request });
@@ -28,10 +47,43 @@ export class DetachedWindowsAPI {
     return await invoke<boolean>('close_detached_window', { noteId });
   }
 
+  /** Open or close the always-on-top window that mirrors the selected note */
+  static async setFollowActiveNote(enabled: boolean): Promise<void> {
+    await invoke('set_follow_active_note', { enabled });
+  }
+
+  static async getFollowActiveNote(): Promise<boolean> {
+    return await invoke<boolean>('get_follow_active_note');
+  }
+
+  /** Save a PNG snapshot of a rendered window (macOS). Resolves to the path written. */
+  static async captureNoteWindow(label: string, path: string): Promise<string> {
+    return await invoke<string>('capture_note_window', { label, path });
+  }
+
+  /** CSS injected into a note's window; applied to open windows right away. Pass null to remove it. */
+  static async setNoteCss(noteId: string, css: string | null): Promise<void> {
+    await invoke('set_note_css', { noteId, css });
+  }
+
+  static async getNoteCss(noteId: string): Promise<string | null> {
+    return await invoke<string | null>('get_note_css', { noteId });
+  }
+
+  /** Open a new always-on-top sticky note; without a color the next paper color is used */
+  static async createSticky(color?: string, text?: string): Promise<DetachedWindow> {
+    return await invoke<DetachedWindow>('create_sticky', { color, text });
+  }
+
+  /** Recent open/move/resize/close events, oldest first, for tracking down lost windows */
+  static async getWindowHistory(noteId?: string, limit?: number): Promise<WindowLogRecord[]> {
+    return await invoke<WindowLogRecord[]>('get_window_history', { noteId, limit });
+  }
+
   static async getDetachedWindows(): Promise<DetachedWindow[]> {
     const result = await invoke<{[key: string]: DetachedWindow}>('get_detached_windows');
     
-    // Convert HashMap to array and filter out hybrid-drag windows (just in case backend didn't filter)
+    // Convert HashMap to array, keeping only note windows
     const windowsArray = Object.values(result).filter(window => 
       window.window_label.startsWith('note-')
     );
@@ -39,8 +91,14 @@ export class DetachedWindowsAPI {
     return windowsArray;
   }
 
-  static async updateWindowPosition(windowLabel: string, x: number, y: number): Promise<void> {
-    return await invoke('update_detached_window_position', { windowLabel, x, y });
+  /** Record a window's logical position; resolves to where it snapped, if anywhere */
+  static async updateWindowPosition(windowLabel: string, x: number, y: number): Promise<[number, number] | null> {
+    return await invoke<[number, number] | null>('update_detached_window_position', { windowLabel, x, y });
+  }
+
+  /** Snap a window that just stopped moving, without saving its position */
+  static async snapWindow(windowLabel: string, x: number, y: number): Promise<[number, number] | null> {
+    return await invoke<[number, number] | null>('snap_detached_window', { windowLabel, x, y });
   }
 
   static async updateWindowSize(windowLabel: string, width: number, height: number): Promise<void> {
@@ -51,6 +109,22 @@ export class DetachedWindowsAPI {
     return await invoke<boolean>('toggle_window_shade', { windowLabel });
   }
 
+  static async setVisibleOnAllWorkspaces(windowLabel: string, visible: boolean): Promise<void> {
+    return await invoke('set_window_visible_on_all_workspaces', { windowLabel, visible });
+  }
+
+  static async dockWindow(windowLabel: string, edge: DockEdge): Promise<DetachedWindow> {
+    return await invoke<DetachedWindow>('dock_window', { windowLabel, edge });
+  }
+
+  static async setDockExpanded(windowLabel: string, expanded: boolean): Promise<DetachedWindow> {
+    return await invoke<DetachedWindow>('set_dock_expanded', { windowLabel, expanded });
+  }
+
+  static async undockWindow(windowLabel: string): Promise<DetachedWindow> {
+    return await invoke<DetachedWindow>('undock_window', { windowLabel });
+  }
+
   static async toggleMainWindowShade(): Promise<boolean> {
     return await invoke<boolean>('toggle_main_window_shade');
   }
@@ -63,7 +137,14 @@ export class DetachedWindowsAPI {
     return await invoke<string[]>('restore_detached_windows');
   }
 
-  static async clearAllDetachedWindows(): Promise<number> {
-    return await invoke<number>('clear_all_detached_windows');
+  /** Ask for the token a destructive action needs; only once the user has confirmed it */
+  static async requestConfirmationToken(action: string): Promise<string> {
+    const { token } = await invoke<{ token: string }>('request_confirmation_token', { action });
+    return token;
+  }
+
+  /** Destructive: pass a token from `requestConfirmationToken('clear_all_detached_windows')` */
+  static async clearAllDetachedWindows(confirmationToken: string): Promise<number> {
+    return await invoke<number>('clear_all_detached_windows', { confirmationToken });
   }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 export { configApi } from './config-api';
 export { DetachedWindowsAPI } from './detached-windows-api';
+export { themeApi } from './theme-api';
 export * from './note-sync';
 export * from './tauri-api';
\ No newline at end of file
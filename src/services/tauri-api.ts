@@ -1,5 +1,167 @@
 import { invoke } from '@tauri-apps/api/core';
 import { Note, CreateNoteRequest, UpdateNoteRequest } from '../types/note';
+import type { DetachedWindow } from './detached-windows-api';
+
+/** Payload of `autosave-pending`, emitted every second while an edit waits */
+export interface AutosavePending {
+  noteId: string;
+  remainingMs: number;
+}
+
+export interface TagSuggestion {
+  name: string;
+  count: number;
+  lastUsed: string | null;
+}
+
+/** A delete or reorder that `undoLastOperation` can reverse */
+export interface UndoSummary {
+  kind: 'delete' | 'reorder' | 'replace';
+  description: string;
+  at: string;
+  noteIds: string[];
+}
+
+/** One page of a reading window, also the payload of `reading-page` */
+export interface ReadingPage {
+  noteId: string;
+  page: number;
+  total: number;
+  content: string;
+}
+
+/** A file dropped onto a note, as returned by `attachDroppedFile` */
+export interface DroppedAttachment {
+  markdown: string;
+  relativePath: string;
+  linked: boolean;
+}
+
+/** A note scored by shared tags, links and words, for the Related panel */
+export interface RelatedNote {
+  noteId: string;
+  title: string;
+  score: number;
+  sharedTags: string[];
+  linked: boolean;
+  sharedTerms: string[];
+}
+
+/** What `captureScreenToNote` captures; region and window are picked interactively */
+export type CaptureMode = 'region' | 'window' | 'full';
+
+/** A screenshot stored as an attachment and linked from a note */
+export interface ScreenCapture {
+  noteId: string;
+  createdNote: boolean;
+  markdown: string;
+  attachment: { hash: string; relativePath: string; size: number; deduplicated: boolean };
+  width: number;
+  height: number;
+}
+
+/** What `exportTaggedNotes` wrote */
+export interface TagExportReport {
+  files: string[];
+  attachments: string[];
+  rewrittenLinks: number;
+  droppedLinks: number;
+}
+
+export interface FindReplaceOptions {
+  regex?: boolean;
+  caseSensitive?: boolean;
+  /** Write the changes; without it only the preview comes back */
+  apply?: boolean;
+  noteIds?: string[];
+}
+
+export interface NoteMatches {
+  noteId: string;
+  title: string;
+  matches: number;
+  lines: { line: number; before: string; after: string }[];
+}
+
+/** What `findAndReplace` found, and changed when applied */
+export interface FindReplaceReport {
+  applied: boolean;
+  notes: NoteMatches[];
+  totalMatches: number;
+  skipped: string[];
+  snapshots: string[];
+}
+
+export interface AnnotationComment {
+  id: string;
+  author: string | null;
+  body: string;
+  createdAt: string;
+}
+
+/** A comment thread kept in `.blink/annotations`, outside the note's markdown */
+export interface Annotation {
+  id: string;
+  anchor: { quote: string; prefix: string; suffix: string; offset: number };
+  comments: AnnotationComment[];
+  resolved: boolean;
+  resolvedAt: string | null;
+  createdAt: string;
+  /** UTF-16 range of the quote in the note now; null once the text is gone */
+  range: [number, number] | null;
+}
+
+/** Saved note window layout; with `display` set it is applied when those displays connect */
+export interface WindowArrangement {
+  name: string;
+  windows: DetachedWindow[];
+  mainWindow: { x: number; y: number; width: number; height: number } | null;
+  display: { fingerprint: string; monitorCount: number } | null;
+  savedAt: string;
+}
+
+/** A row in the vault picker; noteCount is null when the folder is gone */
+export interface VaultEntry {
+  path: string;
+  name: string;
+  lastOpened: string | null;
+  pinned: boolean;
+  current: boolean;
+  noteCount: number | null;
+}
+
+/** What `exportNoteNormalized` produced */
+export interface NormalizedExport {
+  markdown: string;
+  outputPath: string | null;
+  attachments: string[];
+  missingAttachments: string[];
+  referencesResolved: number;
+}
+
+export interface CommandParam {
+  /** The key to pass to `invoke` */
+  name: string;
+  rustType: string;
+  tsType: string;
+  optional: boolean;
+}
+
+/** A registered command, from `getCommandManifest` */
+export interface CommandSpec {
+  name: string;
+  params: CommandParam[];
+  returns: string;
+  tsReturns: string;
+}
+
+/** `low` pauses the config watcher, stretches autosave and defers maintenance */
+export type PowerMode = 'normal' | 'low';
+
+/** Whether a save was refused for being over the note size limits; resend with `allowLarge` to save anyway */
+export function isNoteTooLargeError(error: unknown): boolean {
+  return String(error).includes('Note is too large');
+}
 
 export const notesApi = {
   async getNotes(): Promise<Note[]> {
@@ -21,10 +183,39 @@ export const notesApi = {
     return await invoke('update_note', { id, request });
   },
 
+  /** Queue an edit for autosave; the countdown restarts with every call */
+  async scheduleAutosave(id: string, request: UpdateNoteRequest): Promise<AutosavePending> {
+    return await invoke('schedule_autosave', { id, request });
+  },
+
+  /** Save a queued edit now. Resolves to null when nothing was waiting. */
+  async flushNote(noteId: string): Promise<Note | null> {
+    return await invoke('flush_note', { noteId });
+  },
+
   async deleteNote(id: string): Promise<boolean> {
     return await invoke('delete_note', { id });
   },
 
+  /** Move the selected notes to `targetIndex` among the others. Resolves to the notes whose position changed. */
+  async moveNotes(noteIds: string[], targetIndex: number): Promise<string[]> {
+    return await invoke('move_notes', { noteIds, targetIndex });
+  },
+
+  /** Reverse the most recent delete or reorder. Resolves to null when there is nothing to undo. */
+  async undoLastOperation(): Promise<UndoSummary | null> {
+    return await invoke('undo_last_operation');
+  },
+
+  async getUndoHistory(): Promise<UndoSummary[]> {
+    return await invoke('get_undo_history');
+  },
+
+  /** Tags starting with `prefix`, ranked by how often and how recently they're used */
+  async suggestTags(prefix: string, limit?: number): Promise<TagSuggestion[]> {
+    return await invoke('suggest_tags', { prefix, limit });
+  },
+
   // File import/export operations
   async importNotesFromDirectory(directoryPath: string): Promise<Note[]> {
     return await invoke('import_notes_from_directory', { directoryPath });
@@ -34,12 +225,13 @@ export const notesApi = {
     return await invoke('import_single_file', { filePath });
   },
 
-  async exportNoteToFile(noteId: string, filePath: string): Promise<void> {
-    return await invoke('export_note_to_file', { noteId, filePath });
+  // wrapColumn hard-wraps prose for tools that want wrapped plain text
+  async exportNoteToFile(noteId: string, filePath: string, wrapColumn?: number): Promise<void> {
+    return await invoke('export_note_to_file', { noteId, filePath, wrapColumn });
   },
 
-  async exportAllNotesToDirectory(directoryPath: string): Promise<string[]> {
-    return await invoke('export_all_notes_to_directory', { directoryPath });
+  async exportAllNotesToDirectory(directoryPath: string, wrapColumn?: number): Promise<string[]> {
+    return await invoke('export_all_notes_to_directory', { directoryPath, wrapColumn });
   },
 
   // Notes directory management
@@ -64,4 +256,122 @@ export const notesApi = {
   async openDirectoryInFinder(directoryPath: string): Promise<void> {
     return await invoke('open_directory_in_finder', { directoryPath });
   },
+
+  // True when launched with --safe-mode or BLINK_SAFE_MODE=1
+  async getSafeMode(): Promise<boolean> {
+    return await invoke<boolean>('get_safe_mode');
+  },
+
+  // Copy (or link, with attachments.linkDroppedFiles) a dropped file; the markdown link is relative
+  async attachDroppedFile(noteId: string, path: string): Promise<DroppedAttachment> {
+    return await invoke<DroppedAttachment>('attach_dropped_file', { noteId, path });
+  },
+
+  async getRelatedNotes(noteId: string, limit?: number): Promise<RelatedNote[]> {
+    return await invoke<RelatedNote[]>('get_related_notes', { noteId, limit });
+  },
+
+  // Also switched automatically when a Mac goes on or off battery
+  async setPowerMode(mode: PowerMode): Promise<PowerMode> {
+    return await invoke<PowerMode>('set_power_mode', { mode });
+  },
+
+  async getPowerMode(): Promise<PowerMode> {
+    return await invoke<PowerMode>('get_power_mode');
+  },
+
+  // One file per tagged note; links to notes outside the tag are reduced to their text
+  async exportTaggedNotes(tag: string, outputDir: string, format: 'md' | 'html'): Promise<TagExportReport> {
+    return await invoke<TagExportReport>('export_tagged_notes', { tag, outputDir, format });
+  },
+
+  // Preview first, then call again with apply (and the noteIds kept) to write
+  async findAndReplace(query: string, replacement: string, options?: FindReplaceOptions): Promise<FindReplaceReport> {
+    return await invoke<FindReplaceReport>('find_and_replace', { query, replacement, options });
+  },
+
+  // Offsets are UTF-16, as in the editor. Pass threadId instead of a range to reply.
+  async addAnnotation(
+    noteId: string,
+    body: string,
+    range: { start?: number; end?: number; threadId?: string },
+    author?: string
+  ): Promise<Annotation> {
+    return await invoke<Annotation>('add_annotation', { noteId, body, ...range, author });
+  },
+
+  async resolveAnnotation(noteId: string, annotationId: string, resolved = true): Promise<Annotation> {
+    return await invoke<Annotation>('resolve_annotation', { noteId, annotationId, resolved });
+  },
+
+  async deleteAnnotation(noteId: string, annotationId: string): Promise<void> {
+    await invoke('delete_annotation', { noteId, annotationId });
+  },
+
+  async listAnnotations(noteId: string, includeResolved = false): Promise<Annotation[]> {
+    return await invoke<Annotation[]>('list_annotations', { noteId, includeResolved });
+  },
+
+  // displayPreset binds it to the current displays, e.g. a "2 monitors" preset
+  async saveWindowArrangement(name: string, displayPreset = false): Promise<WindowArrangement> {
+    return await invoke<WindowArrangement>('save_window_arrangement', { name, displayPreset });
+  },
+
+  async applyWindowArrangement(name: string): Promise<void> {
+    await invoke('apply_window_arrangement', { name });
+  },
+
+  async listWindowArrangements(): Promise<WindowArrangement[]> {
+    return await invoke<WindowArrangement[]>('list_window_arrangements');
+  },
+
+  async deleteWindowArrangement(name: string): Promise<void> {
+    await invoke('delete_window_arrangement', { name });
+  },
+
+  async openVaultPicker(): Promise<void> {
+    await invoke('open_vault_picker');
+  },
+
+  // Pinned vaults first, then by last opened
+  async listRecentVaults(): Promise<VaultEntry[]> {
+    return await invoke<VaultEntry[]>('list_recent_vaults');
+  },
+
+  // Resolves to the number of notes loaded; every window gets `data-loaded`
+  async switchVault(path: string): Promise<number> {
+    return await invoke<number>('switch_vault', { path });
+  },
+
+  async setVaultPinned(path: string, pinned: boolean): Promise<void> {
+    await invoke('set_vault_pinned', { path, pinned });
+  },
+
+  // Markdown for pandoc and the like. 'bundle' copies attachments next to outputPath.
+  async exportNoteNormalized(
+    noteId: string,
+    outputPath?: string,
+    attachments?: 'absolute' | 'bundle',
+  ): Promise<NormalizedExport> {
+    return await invoke<NormalizedExport>('export_note_normalized', { noteId, outputPath, attachments });
+  },
+
+  async getCommandManifest(): Promise<CommandSpec[]> {
+    return await invoke<CommandSpec[]>('get_command_manifest');
+  },
+
+  // macOS only. Appends to the note, or creates one without a noteId; null when cancelled.
+  async captureScreenToNote(mode: CaptureMode, noteId?: string): Promise<ScreenCapture | null> {
+    return await invoke<ScreenCapture | null>('capture_screen_to_note', { mode, noteId });
+  },
+
+  // Read-only, paginated window; pages turn with the arrow, page and space keys
+  async openReadingMode(noteId: string): Promise<void> {
+    await invoke('open_reading_mode', { noteId });
+  },
+
+  // The page the calling reading window is on
+  async getReadingPage(): Promise<ReadingPage> {
+    return await invoke<ReadingPage>('get_reading_page');
+  },
 };
\ No newline at end of file
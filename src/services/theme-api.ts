@@ -0,0 +1,24 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type WindowTheme = 'light' | 'dark';
+
+export const themeApi = {
+  async getSystemTheme(): Promise<WindowTheme> {
+    return await invoke<WindowTheme>('get_system_theme');
+  },
+
+  async getWindowTheme(noteId: string): Promise<WindowTheme | null> {
+    return await invoke<WindowTheme | null>('get_window_theme', { noteId });
+  },
+
+  /** Override a note window's theme; `null` follows the app theme again */
+  async setWindowTheme(noteId: string, theme: WindowTheme | null): Promise<void> {
+    await invoke('set_window_theme', { noteId, theme });
+  },
+
+  /** Called whenever the system switches between light and dark */
+  async onSystemThemeChanged(callback: (theme: WindowTheme) => void): Promise<UnlistenFn> {
+    return await listen<{ theme: WindowTheme }>('theme-changed', (event) => callback(event.payload.theme));
+  },
+};
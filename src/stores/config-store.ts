@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { AppConfig, defaultConfig, migrateConfig } from '../types/config';
 import { configApi } from '../services/config-api';
 import { emit } from '@tauri-apps/api/event';
+import { getBootstrapAppearance } from '../lib/note-bootstrap';
 
 interface ConfigStore {
   config: AppConfig;
@@ -18,8 +19,17 @@ interface ConfigStore {
   updateTheme: (theme: 'dark' | 'light' | 'system') => Promise<void>;
 }
 
+// Note windows start with the appearance embedded at creation, so the first
+// paint already uses the right theme and fonts
+const initialConfig = (): AppConfig => {
+  const appearance = getBootstrapAppearance();
+  return appearance
+    ? { ...defaultConfig, appearance: { ...defaultConfig.appearance, ...appearance } }
+    : defaultConfig;
+};
+
 export const useConfigStore = create<ConfigStore>((set, get) => ({
-  config: defaultConfig,
+  config: initialConfig(),
   isLoading: false,
   error: null,
 
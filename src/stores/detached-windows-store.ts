@@ -131,13 +131,13 @@ export const useDetachedWindowsStore = create<DetachedWindowsState>((set, get) =
     const { windows } = get();
     
     try {
-      await DetachedWindowsAPI.updateWindowPosition(windowLabel, x, y);
+      const snapped = await DetachedWindowsAPI.updateWindowPosition(windowLabel, x, y);
       
       // Update local state
       set({
         windows: windows.map(w => 
           w.window_label === windowLabel 
-            ? { ...w, position: [x, y] as [number, number] }
+            ? { ...w, position: snapped ?? [x, y] as [number, number] }
             : w
         )
       });
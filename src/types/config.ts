@@ -55,9 +55,51 @@ export interface AppConfig {
   storage?: {
     notesDirectory?: string; // Custom directory for notes, defaults to app data directory
     useCustomDirectory?: boolean; // Whether to use custom directory or default
+    followSymlinks?: boolean; // Load notes through symlinked files and folders (default true)
+    noteExtensions?: string[]; // Extensions loaded as notes, without the dot (default ['md'])
+    defaultExtension?: string; // Extension for new notes, one of noteExtensions (default 'md')
+    contentCache?: boolean; // Keep the text of saved notes in the index for faster cold starts (default false)
+    recentVaults?: { path: string; lastOpened: string; pinned: boolean }[]; // Listed in the vault picker
+  };
+  startup?: {
+    startHidden: boolean; // Keep the main window hidden until the toggle shortcut
+    startInTray: boolean; // Menu bar icon only, no Dock icon
+    restoreGeometry: boolean; // Reopen at the last size and position
+    launchAtLogin: boolean;
+  };
+  instantShow?: boolean; // Show note windows before their content has rendered
+  dateFormat?: {
+    style: 'relative' | 'absolute' | 'date';
+    locale?: string; // e.g. 'de_DE'; the system locale when unset
+  };
+  logging?: {
+    level: LogLevel;
+    categories?: Record<string, LogLevel>; // e.g. { WINDOW: 'debug' }
+  };
+  attachments?: {
+    maxImageDimension: number; // Longest side of a pasted image in pixels
+    jpegQuality: number;
+    ocr?: boolean;
+    linkDroppedFiles?: boolean; // Link dropped files where they are instead of copying them
+  };
+  newWindows?: {
+    width: number; // At least 400x300
+    height: number;
+    cascadeStep: number; // Offset between windows opened one after another, 0-200
+    maxCascade: number; // Steps before wrapping back to the first position
+    spawnAt: 'screen' | 'cursor' | 'mainWindow'; // Where windows without a saved position open
+  };
+  reading?: {
+    opacity: number; // Reading windows, separate from note window opacity
+    shaded: boolean; // Open collapsed to the title bar
+    width: number;
+    height: number;
+    pageChars: number; // Roughly, pages break between paragraphs
   };
 }
 
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug';
+
 export const defaultConfig: AppConfig = {
   opacity: 1,
   alwaysOnTop: false,
@@ -6,6 +6,7 @@ export interface Note {
   updated_at: string;
   tags: string[];
   position?: number; // Manual ordering position
+  icon?: string; // Emoji shown before the title
 }
 
 export interface CreateNoteRequest {
@@ -18,4 +19,6 @@ export interface UpdateNoteRequest {
   title?: string;
   content?: string;
   tags?: string[];
+  /** Save content over the backend's size and line limits anyway */
+  allowLarge?: boolean;
 }
\ No newline at end of file